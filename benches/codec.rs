@@ -0,0 +1,69 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks of the codecs and of the letter case steganography on book-length inputs. Run with `cargo bench`.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use bacon_cipher::codecs::char_codec::{CharCodec, CharCodecV2};
+use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+use bacon_cipher::{BaconCodec, Steganographer};
+
+const PARAGRAPH: &str = "It is a truth universally acknowledged, that a single man in possession of a good fortune, \
+must be in want of a wife. However little known the feelings or views of such a man may be on his first entering a \
+neighbourhood, this truth is so well fixed in the minds of the surrounding families, that he is considered the \
+rightful property of some one or other of their daughters. ";
+
+// A text of about the length of a novel
+fn book(chars: usize) -> Vec<char> {
+    PARAGRAPH.chars().cycle().take(chars).collect()
+}
+
+fn codecs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("codec");
+    for chars in [10_000, 500_000] {
+        let secret = book(chars);
+        group.throughput(Throughput::Elements(chars as u64));
+
+        let v1 = CharCodec::new('a', 'b');
+        group.bench_with_input(BenchmarkId::new("encode v1", chars), &secret, |b, secret| b.iter(|| v1.encode(black_box(secret))));
+        let encoded = v1.encode(&secret);
+        group.bench_with_input(BenchmarkId::new("decode v1", chars), &encoded, |b, encoded| b.iter(|| v1.decode(black_box(encoded))));
+
+        let v2 = CharCodecV2::new(false, true);
+        group.bench_with_input(BenchmarkId::new("encode v2", chars), &secret, |b, secret| b.iter(|| v2.encode(black_box(secret))));
+        let encoded = v2.encode(&secret);
+        group.bench_with_input(BenchmarkId::new("decode v2", chars), &encoded, |b, encoded| b.iter(|| v2.decode(black_box(encoded))));
+    }
+    group.finish();
+}
+
+fn letter_case(c: &mut Criterion) {
+    let mut group = c.benchmark_group("letter case");
+    let codec = CharCodec::new('a', 'b');
+    let s = LetterCaseSteganographer::new();
+    let public = book(500_000);
+    // The secret may contain only letters and spaces
+    let secret: Vec<char> = book(50_000).into_iter().filter(|c| c.is_alphabetic() || *c == ' ').collect();
+    group.throughput(Throughput::Elements(public.len() as u64));
+    group.bench_function("disguise", |b| b.iter(|| s.disguise(black_box(&secret), black_box(&public), &codec).unwrap()));
+    let disguised = s.disguise(&secret, &public, &codec).unwrap();
+    group.bench_function("reveal", |b| b.iter(|| s.reveal(black_box(&disguised), &codec).unwrap()));
+    let disguised: String = disguised.into_iter().collect();
+    group.bench_function("reveal bytes", |b| b.iter(|| s.reveal_bytes(black_box(disguised.as_bytes()), &codec).unwrap()));
+    group.bench_function("reveal lazy", |b| b.iter(|| s.reveal_lazy(black_box(&disguised), &codec).count()));
+    group.finish();
+}
+
+criterion_group!(benches, codecs, letter_case);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks of the marker search of the markdown steganography on megabyte-scale inputs, whose time should grow
+//! linearly with their size. Run with `cargo bench --bench markdown`, with and without the feature `fast-marker-search`.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use bacon_cipher::codecs::char_codec::CharCodec;
+use bacon_cipher::stega::markdown::{Marker, MarkdownSteganographer};
+use bacon_cipher::Steganographer;
+
+// An input of about `megabytes` megabytes, half of which are marked segments and half unclosed markers, which make a
+// naive search scan the rest of the input again and again.
+fn input(megabytes: usize) -> Vec<char> {
+    let repeats = megabytes * 1_000_000 / 2;
+    let mut input = "[He](#He)**llo** ".repeat(repeats / 17);
+    input.push_str(&"[world".repeat(repeats / 6));
+    input.chars().collect()
+}
+
+fn marker_search(c: &mut Criterion) {
+    let search = if cfg!(feature = "fast-marker-search") { "aho-corasick" } else { "default" };
+    let mut group = c.benchmark_group(format!("markdown ({} marker search)", search));
+    group.sample_size(10);
+    let codec = CharCodec::new('a', 'b');
+    let s = MarkdownSteganographer::new(
+        Marker::template("[{}](#{})").unwrap(),
+        Marker::new(Some("**"), Some("**"))).unwrap();
+    for megabytes in [1, 2, 4] {
+        let input = input(megabytes);
+        group.throughput(Throughput::Elements(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("reveal", format!("{} MB", megabytes)), &input,
+                               |b, input| b.iter(|| s.reveal(black_box(input), &codec).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, marker_search);
+criterion_main!(benches);
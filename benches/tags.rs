@@ -0,0 +1,45 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks of the tag steganography on large HTML pages. Run with `cargo bench --features extended-steganography`.
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use bacon_cipher::codecs::char_codec::CharCodec;
+use bacon_cipher::stega::tags::SimpleTagSteganographer;
+use bacon_cipher::Steganographer;
+
+// A page of about a megabyte, with the secret disguised in its paragraphs
+fn page(s: &SimpleTagSteganographer, codec: &CharCodec<char>) -> Vec<char> {
+    let paragraph: Vec<char> = "It is a truth universally acknowledged, that a single man in possession of a good fortune, must be in want of a wife."
+        .chars()
+        .collect();
+    let secret: Vec<char> = "Bacon".chars().collect();
+    let disguised: String = s.disguise(&secret, &paragraph, codec).unwrap().into_iter().collect();
+    let body = format!("<p>{}</p>\n", disguised).repeat(5_000);
+    format!("<html><head><title>Page</title></head><body>{}</body></html>", body).chars().collect()
+}
+
+fn reveal(c: &mut Criterion) {
+    let codec = CharCodec::new('a', 'b');
+    let s = SimpleTagSteganographer::default();
+    let input = page(&s, &codec);
+    let mut group = c.benchmark_group("tags");
+    group.throughput(Throughput::Elements(input.len() as u64));
+    group.sample_size(10);
+    group.bench_function("reveal", |b| b.iter(|| s.reveal(black_box(&input), &codec).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, reveal);
+criterion_main!(benches);
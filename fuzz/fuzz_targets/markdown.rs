@@ -0,0 +1,44 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use bacon_cipher::codecs::char_codec::CharCodec;
+use bacon_cipher::Steganographer;
+use bacon_cipher::stega::markdown::{EscapePolicy, MarkdownSteganographer, Marker};
+
+// The first byte selects the markers and the escape policy. The rest of the data is the input.
+fuzz_target!(|data: &[u8]| {
+    let (selector, input) = match data.split_first() {
+        Some((selector, rest)) => (*selector, rest),
+        None => return,
+    };
+    let input = match std::str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    let (a_marker, b_marker) = match selector % 5 {
+        0 => (Marker::new(Some("*"), Some("*")), Marker::new(Some("__"), Some("__"))),
+        1 => (Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))),
+        2 => (Marker::template("[{}](#{})").unwrap(), Marker::empty()),
+        3 => (Marker::empty(), Marker::new(Some("{"), Some("}"))),
+        _ => (Marker::link("https://example.com"), Marker::image("é.png")),
+    };
+    let escape_policy = match selector / 5 % 3 {
+        0 => EscapePolicy::Escape,
+        1 => EscapePolicy::Strip,
+        _ => EscapePolicy::Error,
+    };
+    let steganographer = MarkdownSteganographer::new(a_marker, b_marker).unwrap().with_escape_policy(escape_policy);
+
+    let segments = steganographer.parse_str(input).unwrap();
+    for segment in segments {
+        if let Some(range) = segment.range() {
+            assert_eq!(&input[range], segment.text());
+        }
+    }
+    let chars: Vec<char> = input.chars().collect();
+    let codec = CharCodec::new('a', 'b');
+    steganographer.reveal(&chars, &codec).unwrap();
+    steganographer.strip(&chars).unwrap();
+    steganographer.mapping(&chars).unwrap();
+});
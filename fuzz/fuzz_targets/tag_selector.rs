@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use bacon_cipher::stega::tags::TagSelector;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(start_node) = std::str::from_utf8(data) {
+        if let Some(selector) = TagSelector::parse(start_node) {
+            assert!(!selector.name().is_empty());
+        }
+    }
+});
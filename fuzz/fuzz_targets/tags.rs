@@ -0,0 +1,32 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use bacon_cipher::codecs::char_codec::CharCodec;
+use bacon_cipher::Steganographer;
+use bacon_cipher::stega::tags::{ParseMode, SimpleTagSteganographer};
+
+// The first byte selects the parse mode. The rest of the data is the input.
+fuzz_target!(|data: &[u8]| {
+    let (selector, input) = match data.split_first() {
+        Some((selector, rest)) => (*selector, rest),
+        None => return,
+    };
+    let input = match std::str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    let parse_mode = match selector % 3 {
+        0 => ParseMode::Document,
+        1 => ParseMode::Fragment,
+        _ => ParseMode::Xml,
+    };
+    let steganographer = SimpleTagSteganographer::default().with_parse_mode(parse_mode);
+
+    // The parsers accept any input. The errors are the rejected inputs, like deeply nested ones.
+    let _ = steganographer.parse_str(input);
+    let chars: Vec<char> = input.chars().collect();
+    let codec = CharCodec::new('a', 'b');
+    let _ = steganographer.reveal(&chars, &codec);
+    let _ = steganographer.clone().embed_in_document().strip(&chars);
+});
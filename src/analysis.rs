@@ -0,0 +1,703 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detects hidden Bacon's messages, by revealing an input with candidate schemes and scoring the revealed messages
+//! as English text.
+//!
+//! ```rust
+//! use bacon_cipher::analysis;
+//!
+//! let input: Vec<char> = "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one".chars().collect();
+//! let results = analysis::detect(&input, &analysis::default_schemes());
+//! assert_eq!(results[0].scheme(), "letter-case");
+//! assert_eq!(results[0].message(), "MYSECRET");
+//! ```
+//!
+//! The other way around, the [conspicuousness](fn.conspicuousness.html) of a disguised text scores how visible its
+//! changes are, so that the stealthiest scheme for a public text can be picked, as [recommend](fn.recommend.html) does.
+use crate::BaconCodec;
+use crate::changes::{ChangeKind, ChangeReport};
+use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+use crate::registry::{Codec, Registry, Scheme};
+use crate::stega::math_alphanumeric::LetterStyle;
+use crate::stats::Histogram;
+
+// The common Markdown markers, with the longer ones first, so that they win the ties of the scores.
+const MARKDOWN_MARKERS: &[&str] = &["**", "__", "~~", "*", "_", "`"];
+
+// Common English words, used to segment the revealed messages, which do not contain spaces.
+const COMMON_WORDS: &[&str] = &[
+    "THE", "OF", "AND", "TO", "IN", "IS", "YOU", "THAT", "IT", "HE", "WAS", "FOR", "ON", "ARE", "AS", "WITH", "HIS",
+    "THEY", "AT", "BE", "THIS", "HAVE", "FROM", "OR", "ONE", "HAD", "BY", "WORD", "BUT", "NOT", "WHAT", "ALL", "WERE",
+    "WE", "WHEN", "YOUR", "CAN", "SAID", "THERE", "USE", "AN", "EACH", "WHICH", "SHE", "DO", "HOW", "THEIR", "IF",
+    "WILL", "UP", "OTHER", "ABOUT", "OUT", "MANY", "THEN", "THEM", "THESE", "SO", "SOME", "HER", "WOULD", "MAKE",
+    "LIKE", "HIM", "INTO", "TIME", "HAS", "LOOK", "TWO", "MORE", "WRITE", "GO", "SEE", "NUMBER", "NO", "WAY", "COULD",
+    "PEOPLE", "MY", "THAN", "FIRST", "WATER", "BEEN", "CALL", "WHO", "OIL", "ITS", "NOW", "FIND", "LONG", "DOWN",
+    "DAY", "DID", "GET", "COME", "MADE", "MAY", "PART", "ME", "US", "AM", "OUR", "NIGHT", "DAWN", "NOON", "MEET",
+    "HERE", "HELP", "SEND", "KEEP", "STOP", "WAIT", "LEAVE", "ATTACK", "RUN", "HIDE", "SAFE", "HOUSE", "DOOR", "KEY",
+    "CODE", "SECRET", "MESSAGE", "HELLO", "WORLD", "YES", "TOMORROW", "TODAY", "AFTER", "BEFORE", "UNDER", "OVER",
+    "BRIDGE", "CITY", "RIVER", "NORTH", "SOUTH", "EAST", "WEST", "GOOD", "BAD", "LOVE", "FLAG", "PLEASE", "THANK",
+];
+
+/// The result of revealing an input with a scheme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionResult {
+    scheme: String,
+    message: String,
+    frequency_score: f64,
+    dictionary_score: f64,
+    confidence: f64,
+}
+
+impl DetectionResult {
+    /// The configuration string of the scheme.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The revealed message, without the content of an incomplete trailing group.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// See [frequency_score](fn.frequency_score.html).
+    pub fn frequency_score(&self) -> f64 {
+        self.frequency_score
+    }
+
+    /// See [dictionary_score](fn.dictionary_score.html).
+    pub fn dictionary_score(&self) -> f64 {
+        self.dictionary_score
+    }
+
+    /// The ratio of the groups of the reveal that are valid. See [RevealReport](../report/struct.RevealReport.html).
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    /// The overall score, between 0.0 and 1.0. Higher scores indicate that the message is more likely to be English text.
+    pub fn score(&self) -> f64 {
+        (0.3 * self.frequency_score + 0.7 * self.dictionary_score) * self.confidence
+    }
+}
+
+/// Scores how close the letter frequencies of the text are to the ones of English text, between 0.0 and 1.0.
+///
+/// The score is 1.0 minus the total variation distance of the two distributions. Characters other than the letters
+/// `A` to `Z` are ignored, regardless of their case.
+pub fn frequency_score(text: &str) -> f64 {
+    1.0 - Histogram::of(text).distance_from_english()
+}
+
+/// Scores the ratio of the letters of the text that belong to common English words, between 0.0 and 1.0.
+///
+/// The text does not need to contain spaces: each run of letters is segmented greedily, using the longest common word
+/// that starts at each position.
+pub fn dictionary_score(text: &str) -> f64 {
+    let upper = text.to_uppercase();
+    let mut total = 0;
+    let mut covered = 0;
+    for run in upper.split(|c: char| !c.is_ascii_alphabetic()).filter(|run| !run.is_empty()) {
+        total += run.len();
+        let mut position = 0;
+        while position < run.len() {
+            let rest = &run[position..];
+            match COMMON_WORDS.iter().filter(|word| rest.starts_with(*word)).map(|word| word.len()).max() {
+                Some(len) => {
+                    covered += len;
+                    position += len;
+                }
+                None => position += 1,
+            }
+        }
+    }
+    if total == 0 { 0.0 } else { covered as f64 / total as f64 }
+}
+
+/// The alphabet of a [CharCodec](../codecs/char_codec/struct.CharCodec.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alphabet {
+    /// The classic alphabet of 24 letters, where I and J, as well as U and V, share their codes. This is the first
+    /// version of the Bacon's cipher.
+    Classic24,
+    /// The alphabet of 26 letters of the second version of the Bacon's cipher.
+    Full26,
+}
+
+impl Alphabet {
+    fn codec(&self, inverted: bool) -> Codec {
+        let codec: Codec = match self {
+            Alphabet::Classic24 => Box::new(CharCodec::new('a', 'b')),
+            Alphabet::Full26 => Box::new(CharCodecV2::new('a', 'b')),
+        };
+        if inverted { Box::new(Inverted(codec)) } else { codec }
+    }
+}
+
+// A codec whose elements A and B swap their roles in the steganographers.
+struct Inverted(Codec);
+
+impl BaconCodec for Inverted {
+    type ABTYPE = char;
+    type CONTENT = char;
+
+    fn encode_elem(&self, elem: &char) -> Vec<char> {
+        self.0.encode_elem(elem)
+    }
+
+    fn decode_elems(&self, elems: &[char]) -> char {
+        self.0.decode_elems(elems)
+    }
+
+    fn a(&self) -> char {
+        self.0.b()
+    }
+
+    fn b(&self) -> char {
+        self.0.a()
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        self.0.encoded_group_size()
+    }
+
+    fn is_a(&self, elem: &char) -> bool {
+        self.0.is_b(elem)
+    }
+
+    fn is_b(&self, elem: &char) -> bool {
+        self.0.is_a(elem)
+    }
+}
+
+/// A result of the [brute_force](fn.brute_force.html), along with the codec that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    alphabet: Alphabet,
+    inverted: bool,
+    result: DetectionResult,
+}
+
+impl Candidate {
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    /// Whether the elements A and B are swapped. For the `letter-case` scheme, this means that the capital letters
+    /// carry the element A.
+    pub fn inverted(&self) -> bool {
+        self.inverted
+    }
+
+    pub fn result(&self) -> &DetectionResult {
+        &self.result
+    }
+}
+
+// The configurations of the schemes that are tried by default.
+fn default_configs() -> Vec<&'static str> {
+    let mut configs = vec![
+        "letter-case", "markdown:*", "markdown:**", "markdown:_", "markdown:__", "markdown:`", "markdown:*/**",
+        "html-entity", "math-alphanumeric", "punctuation", "whitespace", "zero-width",
+    ];
+    if cfg!(feature = "extended-steganography") {
+        configs.push("tag:<b>/<i>");
+    }
+    configs
+}
+
+fn schemes(alphabet: Alphabet, inverted: bool) -> Vec<Scheme> {
+    let registry = Registry::default();
+    default_configs().into_iter()
+        .filter_map(|config| registry.scheme_with_codec(config, alphabet.codec(inverted)).ok())
+        .collect()
+}
+
+/// The schemes of the default [Registry](../registry/struct.Registry.html), with the markdown schemes of the common
+/// markers and, with the feature `extended-steganography`, the tag scheme of `<b>` and `<i>`.
+pub fn default_schemes() -> Vec<Scheme> {
+    schemes(Alphabet::Classic24, false)
+}
+
+/// Reveals the input with the [default schemes](fn.default_schemes.html), using both alphabets and both orientations
+/// of the elements A and B, and returns all the candidates, ordered by descending score.
+pub fn brute_force(input: &[char]) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for alphabet in [Alphabet::Classic24, Alphabet::Full26] {
+        for inverted in [false, true] {
+            candidates.extend(detect(input, &schemes(alphabet, inverted)).into_iter()
+                .map(|result| Candidate { alphabet, inverted, result }));
+        }
+    }
+    candidates.sort_by(|c1, c2| c2.result.score().total_cmp(&c1.result.score()));
+    candidates
+}
+
+/// Reveals the input with each one of the schemes and returns the results, ordered by descending score.
+///
+/// The schemes that fail to reveal the input, or reveal an empty message, are omitted.
+pub fn detect(input: &[char], schemes: &[Scheme]) -> Vec<DetectionResult> {
+    let mut results: Vec<DetectionResult> = schemes.iter()
+        .filter_map(|scheme| {
+            let report = scheme.reveal_report(input).ok()?;
+            let message: String = report.message().iter().collect();
+            if message.is_empty() {
+                None
+            } else {
+                Some(DetectionResult {
+                    scheme: scheme.name().to_string(),
+                    frequency_score: frequency_score(&message),
+                    dictionary_score: dictionary_score(&message),
+                    confidence: report.confidence(),
+                    message,
+                })
+            }
+        })
+        .collect();
+    results.sort_by(|r1, r2| r2.score().total_cmp(&r1.score()));
+    results
+}
+
+/// Discovers the markers of a suspected Markdown carrier.
+///
+/// The common markers (`**`, `__`, `~~`, `*`, `_` and backticks) that are found in the input are tried, each one
+/// for the element B with the element A unmarked and vice versa, as well as each pair of them for the elements A and B.
+/// The results are ordered by descending score.
+pub fn discover_markdown_markers(input: &[char]) -> Vec<DetectionResult> {
+    let text: String = input.iter().collect();
+    let found: Vec<&str> = MARKDOWN_MARKERS.iter()
+        .filter(|marker| text.contains(*marker))
+        .cloned()
+        .collect();
+    // The pairs are tried first, so that they win the ties with the single markers that reveal the same message
+    let mut configs = Vec::new();
+    for a_marker in &found {
+        for b_marker in found.iter().filter(|b_marker| b_marker != &a_marker) {
+            configs.push(format!("markdown:{}/{}", a_marker, b_marker));
+        }
+    }
+    for marker in &found {
+        configs.push(format!("markdown:{}", marker));
+        configs.push(format!("markdown:{}/", marker));
+    }
+    let registry = Registry::default();
+    let schemes: Vec<Scheme> = configs.iter()
+        .filter_map(|config| registry.scheme(config).ok())
+        .collect();
+    detect(input, &schemes)
+}
+
+/// How visible the changes of a disguise are, as measured by [conspicuousness](fn.conspicuousness.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    words: usize,
+    visible: usize,
+    case_flips: usize,
+    markup: usize,
+    invisible: usize,
+    whitespace: usize,
+}
+
+impl Score {
+    /// The number of the words of the public text.
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    /// The number of the letters whose case is flipped.
+    pub fn case_flips(&self) -> usize {
+        self.case_flips
+    }
+
+    pub fn case_flips_per_word(&self) -> f64 {
+        per(self.case_flips, self.words)
+    }
+
+    /// The number of the visible characters that are inserted, removed or replaced, like markers, entities or
+    /// punctuation marks.
+    pub fn markup(&self) -> usize {
+        self.markup
+    }
+
+    /// The ratio of the visible characters of the disguised text that are markup.
+    pub fn markup_density(&self) -> f64 {
+        per(self.markup, self.visible)
+    }
+
+    /// The number of the inserted or replaced characters that are not rendered, like zero-width characters.
+    pub fn invisible(&self) -> usize {
+        self.invisible
+    }
+
+    /// The number of the whitespace characters that are inserted, removed or replaced.
+    pub fn whitespace(&self) -> usize {
+        self.whitespace
+    }
+
+    /// The overall score, between 0.0 for an unchanged text and 1.0. Lower scores indicate stealthier disguises.
+    ///
+    /// The case flips, the invisible characters and the whitespace changes per word, as well as the markup density,
+    /// are combined like the probabilities of independent events, so that the score is the chance that a reader notices
+    /// at least one of them.
+    pub fn value(&self) -> f64 {
+        let unnoticed = [
+            self.case_flips_per_word(),
+            self.markup_density(),
+            per(self.invisible, self.words),
+            per(self.whitespace, self.words),
+        ].iter().map(|rate| 1.0 - rate.min(1.0)).product::<f64>();
+        1.0 - unnoticed
+    }
+}
+
+fn per(count: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { count as f64 / total as f64 }
+}
+
+// Characters that are not rendered, like the zero-width characters and the directional marks.
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{00AD}' | '\u{034F}' | '\u{061C}' | '\u{180E}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}'
+        | '\u{2060}'..='\u{2064}' | '\u{2066}'..='\u{206F}' | '\u{FE00}'..='\u{FE0F}' | '\u{FEFF}'
+        | '\u{E0000}'..='\u{E007F}')
+}
+
+/// Scores how visually different the disguised text is from the public text that it was disguised in.
+///
+/// The texts are compared like in a [ChangeReport](../changes/struct.ChangeReport.html) and every changed character
+/// counts as a case flip, markup, an invisible character or whitespace, so that the schemes can be compared on the
+/// same public text.
+///
+/// ```rust
+/// use bacon_cipher::analysis;
+///
+/// let public: Vec<char> = "This is a public message".chars().collect();
+/// let case: Vec<char> = "tHiS IS a public message".chars().collect();
+/// let markdown: Vec<char> = "T*h*i*s* *is* a public message".chars().collect();
+/// let score = analysis::conspicuousness(&public, &case);
+/// assert_eq!(score.case_flips(), 5);
+/// assert_eq!(score.case_flips_per_word(), 1.0);
+/// assert!(analysis::conspicuousness(&public, &markdown).value() < score.value());
+/// ```
+pub fn conspicuousness(cover_before: &[char], cover_after: &[char]) -> Score {
+    let words = cover_before.split(|c| !c.is_alphanumeric()).filter(|word| !word.is_empty()).count();
+    let visible = cover_after.iter().filter(|c| !c.is_whitespace() && !is_invisible(**c)).count();
+    let mut score = Score { words, visible, case_flips: 0, markup: 0, invisible: 0, whitespace: 0 };
+    for change in ChangeReport::new(cover_before, cover_after).changes() {
+        if change.kind() == ChangeKind::CaseFlip {
+            score.case_flips += 1;
+            continue;
+        }
+        // The removed characters are visible by their absence, unlike the removed invisible characters
+        let removed = change.original().chars().filter(|c| !is_invisible(*c));
+        for c in change.replacement().chars().chain(removed) {
+            if is_invisible(c) {
+                score.invisible += 1;
+            } else if c.is_whitespace() {
+                score.whitespace += 1;
+            } else {
+                score.markup += 1;
+            }
+        }
+    }
+    score
+}
+
+/// A scheme that can carry a secret in a public text, as suggested by [recommend](fn.recommend.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemeSuggestion {
+    scheme: String,
+    capacity: usize,
+    required: usize,
+    conspicuousness: Score,
+    robustness: f64,
+}
+
+impl SchemeSuggestion {
+    /// The configuration string of the scheme.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The number of Bacon's elements that the public text can carry with the scheme.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of Bacon's elements of the encoded secret.
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    /// The conspicuousness of the disguised text.
+    pub fn conspicuousness(&self) -> &Score {
+        &self.conspicuousness
+    }
+
+    /// The ratio of the common transformations of a text, like the normalization of its whitespace or the rendering of
+    /// its markup, that the secret survives, between 0.0 and 1.0.
+    pub fn robustness(&self) -> f64 {
+        self.robustness
+    }
+
+    /// The overall score, between 0.0 and 1.0. Higher scores indicate stealthier and more robust schemes.
+    pub fn score(&self) -> f64 {
+        (1.0 - self.conspicuousness.value()) * (0.5 + 0.5 * self.robustness)
+    }
+}
+
+type Transformation = fn(&[char]) -> Vec<char>;
+
+// Transformations that a text commonly undergoes on its way to a reader, like copy-pasting or rendering.
+const TRANSFORMATIONS: &[Transformation] = &[
+    collapse_whitespace,
+    straighten_punctuation,
+    render_markdown,
+    decode_entities,
+    unstyle_letters,
+];
+
+fn collapse_whitespace(input: &[char]) -> Vec<char> {
+    let mut output: Vec<char> = Vec::with_capacity(input.len());
+    for c in input {
+        if !(*c == ' ' && output.last() == Some(&' ')) {
+            output.push(*c);
+        }
+    }
+    output
+}
+
+fn straighten_punctuation(input: &[char]) -> Vec<char> {
+    input.iter()
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' => '\'',
+            '\u{201C}' | '\u{201D}' => '"',
+            '\u{2013}' | '\u{2014}' => '-',
+            other => *other,
+        })
+        .collect()
+}
+
+// Removes the emphasis markers, as they are rendered.
+fn render_markdown(input: &[char]) -> Vec<char> {
+    input.iter().filter(|c| !MARKDOWN_MARKERS.iter().any(|marker| marker.starts_with(**c))).cloned().collect()
+}
+
+// Replaces the numeric character references with their characters, as they are rendered.
+fn decode_entities(input: &[char]) -> Vec<char> {
+    let text: String = input.iter().collect();
+    let mut output = Vec::with_capacity(input.len());
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("&#") {
+        output.extend(rest[..start].chars());
+        let reference = &rest[start + 2..];
+        let decoded = reference.find(';').and_then(|end| {
+            let number = &reference[..end];
+            let code = match number.strip_prefix('x').or_else(|| number.strip_prefix('X')) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => number.parse().ok(),
+            };
+            code.and_then(char::from_u32).map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                output.push(c);
+                rest = &reference[end + 1..];
+            }
+            None => {
+                output.extend("&#".chars());
+                rest = reference;
+            }
+        }
+    }
+    output.extend(rest.chars());
+    output
+}
+
+// Replaces the styled letters with plain ones, like the compatibility normalization of Unicode.
+fn unstyle_letters(input: &[char]) -> Vec<char> {
+    input.iter().map(|c| LetterStyle::classify(*c).map(|(_, plain)| plain).unwrap_or(*c)).collect()
+}
+
+/// Suggests the [default schemes](fn.default_schemes.html) that have enough capacity to disguise the _secret_ in the
+/// _public_ text, ordered by descending [score](struct.SchemeSuggestion.html#method.score).
+///
+/// Every scheme disguises the _secret_, so that the [conspicuousness](fn.conspicuousness.html) of its output is
+/// measured, and its robustness is the ratio of the common transformations of the output, like the collapse of runs
+/// of spaces or the rendering of Markdown, from which the _secret_ is still revealed.
+///
+/// ```rust
+/// use bacon_cipher::analysis;
+///
+/// let secret: Vec<char> = "Hi".chars().collect();
+/// let public: Vec<char> = "This is a public message that contains a secret one".chars().collect();
+/// let suggestions = analysis::recommend(&secret, &public);
+/// assert!(suggestions.iter().all(|suggestion| suggestion.capacity() >= suggestion.required()));
+/// assert!(suggestions.iter().any(|suggestion| suggestion.scheme() == "letter-case"));
+/// assert!(analysis::recommend(&"A much longer secret".chars().collect::<Vec<char>>(), &public).is_empty());
+/// ```
+pub fn recommend(secret: &[char], public: &[char]) -> Vec<SchemeSuggestion> {
+    let hidden = secret.iter().filter(|c| c.is_alphabetic()).count();
+    let mut suggestions: Vec<SchemeSuggestion> = default_schemes().iter()
+        .filter_map(|scheme| {
+            let capacity = scheme.capacity(public).ok()?;
+            let required = scheme.required_capacity(secret);
+            if capacity < required {
+                return None;
+            }
+            let disguised = scheme.disguise(secret, public).ok()?;
+            let revealed = scheme.reveal(&disguised).ok()?;
+            let expected = &revealed[..hidden.min(revealed.len())];
+            let survived = TRANSFORMATIONS.iter()
+                .filter(|transform| {
+                    scheme.reveal(&transform(&disguised))
+                        .map(|revealed| revealed.starts_with(expected))
+                        .unwrap_or(false)
+                })
+                .count();
+            Some(SchemeSuggestion {
+                scheme: scheme.name().to_string(),
+                capacity,
+                required,
+                conspicuousness: conspicuousness(public, &disguised),
+                robustness: survived as f64 / TRANSFORMATIONS.len() as f64,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|s1, s2| s2.score().total_cmp(&s1.score()));
+    suggestions
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    #[test]
+    fn scores() {
+        assert!(frequency_score("the quick brown fox jumps over the lazy dog") > frequency_score("qxzjqxzjvvkk"));
+        assert_eq!(frequency_score("42"), 0.0);
+        assert_eq!(dictionary_score("MYSECRET"), 1.0);
+        assert_eq!(dictionary_score("MY SECRETQQ"), 0.8);
+        assert_eq!(dictionary_score(""), 0.0);
+    }
+
+    #[test]
+    fn score_the_conspicuousness() {
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let registry = Registry::default();
+        let scores: Vec<Score> = ["letter-case", "markdown:*", "whitespace"].iter()
+            .map(|config| {
+                let disguised = registry.scheme(config).unwrap().disguise(&['H'], &public).unwrap();
+                conspicuousness(&public, &disguised)
+            })
+            .collect();
+        // H = AABBB, so the capital T is flipped too
+        assert_eq!((scores[0].case_flips(), scores[0].markup(), scores[0].whitespace()), (4, 0, 0));
+        assert_eq!((scores[1].case_flips(), scores[1].markup(), scores[1].whitespace()), (0, 4, 0));
+        assert_eq!((scores[2].case_flips(), scores[2].markup(), scores[2].whitespace()), (0, 0, 3));
+        assert!(scores[1].value() < scores[2].value());
+
+        let hidden = Vec::from_iter("This is\u{200B} a public\u{200C} message".chars());
+        let score = conspicuousness(&Vec::from_iter("This is a public message".chars()), &hidden);
+        assert_eq!((score.invisible(), score.markup()), (2, 0));
+        assert_eq!(score.value(), 0.4);
+        assert_eq!(conspicuousness(&public, &public).value(), 0.0);
+    }
+
+    #[test]
+    fn recommend_schemes() {
+        let secret = Vec::from_iter("Hi".chars());
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let suggestions = recommend(&secret, &public);
+        let suggestion = |scheme: &str| suggestions.iter().find(|suggestion| suggestion.scheme() == scheme).unwrap();
+        assert_eq!(suggestion("letter-case").robustness(), 1.0);
+        assert_eq!(suggestion("letter-case").required(), 10);
+        assert_eq!(suggestion("letter-case").capacity(), 42);
+        assert_eq!(suggestion("markdown:*").robustness(), 0.8);
+        assert!(suggestions.windows(2).all(|pair| pair[0].score() >= pair[1].score()));
+        // The 9 spaces between the words of the public text cannot carry the 10 elements
+        assert!(suggestions.iter().all(|suggestion| suggestion.scheme() != "whitespace"));
+        let public = Vec::from_iter("This is a public message that contains a secret one, or maybe two".chars());
+        let whitespace = recommend(&secret, &public).into_iter().find(|suggestion| suggestion.scheme() == "whitespace").unwrap();
+        assert!(whitespace.robustness() < 1.0);
+    }
+
+    #[test]
+    fn transform_the_disguised_texts() {
+        let input = Vec::from_iter("A  *b* &#99;&#x64; \u{2019}\u{1D41E}&#;".chars());
+        assert_eq!(String::from_iter(collapse_whitespace(&input)), "A *b* &#99;&#x64; \u{2019}\u{1D41E}&#;");
+        assert_eq!(String::from_iter(render_markdown(&input)), "A  b &#99;&#x64; \u{2019}\u{1D41E}&#;");
+        assert_eq!(String::from_iter(decode_entities(&input)), "A  *b* cd \u{2019}\u{1D41E}&#;");
+        assert_eq!(String::from_iter(straighten_punctuation(&input)), "A  *b* &#99;&#x64; '\u{1D41E}&#;");
+        assert_eq!(String::from_iter(unstyle_letters(&input)), "A  *b* &#99;&#x64; \u{2019}e&#;");
+    }
+
+    #[test]
+    fn detect_markdown() {
+        let input = Vec::from_iter("T*h*i*s* *is* a *pu*b*l*ic m*e*ss*a*ge tha*t* c*o*ntains *a* se*c*re*t* one".chars());
+        let results = detect(&input, &default_schemes());
+        assert_eq!(results[0].scheme(), "markdown:*");
+        assert_eq!(results[0].message(), "MYSECRET");
+        assert_eq!(results[0].confidence(), 1.0);
+        assert!(results[0].score() > results[1].score());
+
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let disguised = Registry::default().scheme("zero-width").unwrap()
+            .disguise(&Vec::from_iter("My secret".chars()), &public).unwrap();
+        let results = detect(&disguised, &default_schemes());
+        assert_eq!(results[0].scheme(), "zero-width");
+        assert_eq!(results[0].message(), "MYSECRET");
+    }
+
+    #[test]
+    fn discover_the_markers() {
+        let input = Vec::from_iter("T*h*i*s* *is* a *pu*b*l*ic m*e*ss*a*ge tha*t* c*o*ntains *a* se*c*re*t* one".chars());
+        let results = discover_markdown_markers(&input);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].scheme(), "markdown:*");
+        assert_eq!(results[0].message(), "MYSECRET");
+
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let scheme = Registry::default().scheme("markdown:__/`").unwrap();
+        let disguised = scheme.disguise(&Vec::from_iter("My secret".chars()), &public).unwrap();
+        let results = discover_markdown_markers(&disguised);
+        assert_eq!(results[0].scheme(), "markdown:__/`");
+        assert_eq!(results[0].message(), "MYSECRET");
+        assert!(discover_markdown_markers(&public).is_empty());
+    }
+
+    #[test]
+    fn brute_force_the_codec() {
+        let input = Vec::from_iter("tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one".chars());
+        let candidates = brute_force(&input);
+        assert_eq!((candidates[0].alphabet(), candidates[0].inverted()), (Alphabet::Classic24, false));
+        assert_eq!(candidates[0].result().message(), "MYSECRET");
+
+        let public = Vec::from_iter("The meeting with the others is planned for a day in the next week".chars());
+        let scheme = Registry::default().scheme_with_codec("letter-case", Alphabet::Full26.codec(true)).unwrap();
+        let disguised = scheme.disguise(&Vec::from_iter("meet at dawn".chars()), &public).unwrap();
+        let candidates = brute_force(&disguised);
+        assert_eq!((candidates[0].alphabet(), candidates[0].inverted()), (Alphabet::Full26, true));
+        assert_eq!(candidates[0].result().scheme(), "letter-case");
+        assert_eq!(candidates[0].result().message(), "MEETATDAWN");
+
+        let scheme = Registry::default().scheme_with_codec("zero-width", Alphabet::Full26.codec(true)).unwrap();
+        let disguised = scheme.disguise(&Vec::from_iter("meet at dawn".chars()), &public).unwrap();
+        let candidates = brute_force(&disguised);
+        assert_eq!((candidates[0].alphabet(), candidates[0].inverted()), (Alphabet::Full26, true));
+        assert_eq!(candidates[0].result().scheme(), "zero-width");
+        assert_eq!(candidates[0].result().message(), "MEETATDAWN");
+    }
+}
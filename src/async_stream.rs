@@ -0,0 +1,236 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Asynchronous versions of the [streams](../stream/index.html), based on the tokio `AsyncRead` and `AsyncWrite` traits.
+//!
+//! Needs the feature `async`.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{BaconCodec, errors};
+use crate::stega::letter_case::LetterCaseSteganographer;
+use crate::stream::{DisguiseState, RevealState};
+
+/// The asynchronous version of the [BaconDisguiseWriter](../stream/struct.BaconDisguiseWriter.html).
+///
+/// Shutting the writer down verifies that the secret is fully disguised.
+pub struct AsyncBaconDisguiseWriter<W: AsyncWrite + Unpin> {
+    inner: W,
+    state: DisguiseState,
+    // The disguised bytes that are not written to the inner writer yet
+    pending: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncBaconDisguiseWriter<W> {
+    pub fn new<AB>(inner: W, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<AsyncBaconDisguiseWriter<W>> {
+        Ok(AsyncBaconDisguiseWriter {
+            inner,
+            state: DisguiseState::new(secret, codec)?,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Sets the steganographer that defines the carrier characters.
+    pub fn with_steganographer(mut self, steganographer: LetterCaseSteganographer) -> Self {
+        self.set_steganographer(steganographer);
+        self
+    }
+
+    pub fn set_steganographer(&mut self, steganographer: LetterCaseSteganographer) {
+        self.state.set_steganographer(steganographer);
+    }
+
+    /// Returns the number of Bacon's elements that are not disguised yet.
+    pub fn remaining(&self) -> usize {
+        self.state.remaining()
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.pending.is_empty() {
+            match Pin::new(&mut self.inner).poll_write(cx, &self.pending) {
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero))),
+                Poll::Ready(Ok(written)) => {
+                    self.pending.drain(..written);
+                }
+                Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncBaconDisguiseWriter<W> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match this.poll_write_pending(cx) {
+            Poll::Ready(Ok(())) => {}
+            other => return other.map(|result| result.map(|_| 0)),
+        }
+        let disguised = this.state.disguise(buf)?;
+        this.pending.extend_from_slice(disguised.as_bytes());
+        // The disguised bytes are written now if possible, or else by the next call
+        if let Poll::Ready(Err(error)) = this.poll_write_pending(cx) {
+            return Poll::Ready(Err(error));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match this.poll_write_pending(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.inner).poll_flush(cx),
+            other => other,
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        // The last unit is disguised once, as the pending characters are drained
+        let disguised = this.state.flush()?;
+        this.pending.extend_from_slice(disguised.as_bytes());
+        match this.poll_write_pending(cx) {
+            Poll::Ready(Ok(())) => {
+                this.state.finish()?;
+                Pin::new(&mut this.inner).poll_shutdown(cx)
+            }
+            other => other,
+        }
+    }
+}
+
+/// The asynchronous version of the [BaconRevealReader](../stream/struct.BaconRevealReader.html).
+///
+/// A stream of the characters of the secret that is hidden in the text of an inner reader.
+pub struct BaconRevealStream<'a, R: AsyncRead + Unpin, AB> {
+    inner: R,
+    state: RevealState<'a, AB>,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl<'a, R: AsyncRead + Unpin, AB> BaconRevealStream<'a, R, AB> {
+    pub fn new(inner: R, codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> BaconRevealStream<'a, R, AB> {
+        BaconRevealStream {
+            inner,
+            state: RevealState::new(codec),
+            buffer: vec![0; 8192],
+            finished: false,
+        }
+    }
+
+    /// Sets the steganographer that defines the carrier characters.
+    pub fn with_steganographer(mut self, steganographer: LetterCaseSteganographer) -> Self {
+        self.set_steganographer(steganographer);
+        self
+    }
+
+    pub fn set_steganographer(&mut self, steganographer: LetterCaseSteganographer) {
+        self.state.set_steganographer(steganographer);
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+// The stream is never pinned structurally, so the Bacon's elements of the incomplete group do not need to be Unpin.
+impl<'a, R: AsyncRead + Unpin, AB> Unpin for BaconRevealStream<'a, R, AB> {}
+
+impl<'a, R: AsyncRead + Unpin, AB> Stream for BaconRevealStream<'a, R, AB> {
+    type Item = io::Result<char>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<io::Result<char>>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(c) = this.state.next_revealed() {
+                return Poll::Ready(Some(Ok(c)));
+            } else if this.finished {
+                return Poll::Ready(None);
+            }
+            let mut buffer = ReadBuf::new(&mut this.buffer);
+            let result = match Pin::new(&mut this.inner).poll_read(cx, &mut buffer) {
+                Poll::Ready(Ok(())) if buffer.filled().is_empty() => {
+                    this.finished = true;
+                    this.state.finish()
+                }
+                Poll::Ready(Ok(())) => this.state.reveal(buffer.filled()),
+                Poll::Ready(Err(error)) => Err(error),
+                Poll::Pending => return Poll::Pending,
+            };
+            if let Err(error) = result {
+                this.finished = true;
+                return Poll::Ready(Some(Err(error)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod async_stream_tests {
+    use std::future::poll_fn;
+
+    use tokio::io::AsyncWriteExt;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    async fn collect<'a, R: AsyncRead + Unpin, AB>(mut stream: BaconRevealStream<'a, R, AB>) -> io::Result<String> {
+        let mut revealed = String::new();
+        while let Some(c) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            revealed.push(c?);
+        }
+        Ok(revealed)
+    }
+
+    #[tokio::test]
+    async fn disguise_and_reveal_a_secret_asynchronously() {
+        let codec = CharCodec::new('a', 'b');
+        let mut writer = AsyncBaconDisguiseWriter::new(Vec::new(), &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'], &codec).unwrap();
+        for chunk in "This is a public message that contains a secret one".as_bytes().chunks(7) {
+            writer.write_all(chunk).await.unwrap();
+        }
+        writer.shutdown().await.unwrap();
+        let disguised = writer.into_inner();
+        assert_eq!(String::from_utf8(disguised.clone()).unwrap(), "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+
+        let revealed = collect(BaconRevealStream::new(disguised.as_slice(), &codec)).await;
+        assert_eq!(revealed.unwrap(), "MYSECRET");
+    }
+
+    #[tokio::test]
+    async fn disguise_and_reveal_asynchronously_failure() {
+        let codec = CharCodec::new('a', 'b');
+        let mut writer = AsyncBaconDisguiseWriter::new(Vec::new(), &['H'], &codec).unwrap();
+        writer.write_all(b"Hell").await.unwrap();
+        // The last unit may continue in the next chunk, so it is disguised by the shutdown
+        assert_eq!(writer.remaining(), 2);
+        assert!(writer.shutdown().await.is_err());
+
+        let revealed = collect(BaconRevealStream::new(&[b'h', 0xff][..], &codec)).await;
+        assert!(revealed.is_err());
+    }
+}
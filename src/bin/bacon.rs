@@ -0,0 +1,277 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `bacon` command line tool. Needs the feature `cli`.
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use bacon_cipher::errors;
+use bacon_cipher::codecs::char_codec::{CharCodec, CharCodecV2};
+use bacon_cipher::codecs::format::GroupFormat;
+use bacon_cipher::errors::BaconError;
+use bacon_cipher::registry::{Capabilities, Codec, Registry, Scheme};
+
+/// Encodes, decodes, disguises and reveals messages using the Bacon's cipher.
+#[derive(Parser, Debug)]
+#[command(name = "bacon", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// The input file. The standard input is read if it is not defined.
+    #[arg(short, long, global = true)]
+    input: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Encodes the input to Bacon codes.
+    Encode {
+        #[command(flatten)]
+        codec: CodecArgs,
+        /// The character that separates the groups, like a space or `/`.
+        #[arg(long)]
+        separator: Option<char>,
+        /// Starts a new line after every that many groups.
+        #[arg(long)]
+        groups_per_line: Option<usize>,
+    },
+    /// Decodes the Bacon codes of the input. Characters other than the A and B elements are ignored.
+    Decode {
+        #[command(flatten)]
+        codec: CodecArgs,
+    },
+    /// Disguises a secret in the public text of the input.
+    Disguise {
+        /// The secret to disguise.
+        #[arg(short, long)]
+        secret: String,
+        #[command(flatten)]
+        steganography: SteganographyArgs,
+    },
+    /// Reveals the secret that is hidden in the input.
+    Reveal {
+        #[command(flatten)]
+        steganography: SteganographyArgs,
+    },
+    /// Lists the schemes that can be used with `--scheme`, along with their capabilities. Does not read any input.
+    Schemes,
+}
+
+#[derive(Args, Debug)]
+struct CodecArgs {
+    /// The version of the Bacon's cipher.
+    #[arg(long = "codec", value_enum, default_value_t = CodecVersion::V1)]
+    version: CodecVersion,
+    /// The substitution element A.
+    #[arg(short = 'a', long = "elem-a", default_value_t = 'A')]
+    elem_a: char,
+    /// The substitution element B.
+    #[arg(short = 'b', long = "elem-b", default_value_t = 'B')]
+    elem_b: char,
+    /// Accepts the other case of the A and B elements, e.g. `a` along with `A`.
+    #[arg(long)]
+    ignore_case: bool,
+}
+
+impl CodecArgs {
+    fn codec(&self) -> errors::Result<Codec> {
+        if self.elem_a == self.elem_b {
+            return Err(BaconError::CodecError("The A and B elements should be different".to_string()));
+        }
+        Ok(match (self.version, self.ignore_case) {
+            (CodecVersion::V1, false) => Box::new(CharCodec::new(self.elem_a, self.elem_b)),
+            (CodecVersion::V1, true) => Box::new(CharCodec::new(self.elem_a, self.elem_b).case_insensitive()),
+            (CodecVersion::V2, false) => Box::new(CharCodecV2::new(self.elem_a, self.elem_b)),
+            (CodecVersion::V2, true) => Box::new(CharCodecV2::new(self.elem_a, self.elem_b).case_insensitive()),
+        })
+    }
+}
+
+#[derive(Args, Debug)]
+struct SteganographyArgs {
+    /// The steganographer that disguises or reveals the secret.
+    #[arg(long, value_enum, default_value_t = SteganographerChoice::LetterCase)]
+    steganographer: SteganographerChoice,
+    /// The scheme that disguises or reveals the secret, like `markdown:*/**` or `tag:<b>/<i>`.
+    /// Overrides the steganographer. See the registry module for the available schemes.
+    #[arg(long, conflicts_with = "steganographer")]
+    scheme: Option<String>,
+    #[command(flatten)]
+    codec: CodecArgs,
+}
+
+impl SteganographyArgs {
+    fn scheme(&self) -> errors::Result<Scheme> {
+        let config = match self.scheme {
+            Some(ref scheme) => scheme.clone(),
+            None => self.steganographer.to_possible_value().unwrap().get_name().to_string(),
+        };
+        Registry::default().scheme_with_codec(&config, self.codec.codec()?)
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum CodecVersion {
+    V1,
+    V2,
+}
+
+/// The steganographers that do not need any arguments.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum SteganographerChoice {
+    LetterCase,
+    /// Unmarked characters for A and bold (`**`) characters for B.
+    Markdown,
+    HtmlEntity,
+    MathAlphanumeric,
+    Punctuation,
+    Whitespace,
+}
+
+// Executes the command on the input and returns the output.
+fn run(command: &Command, input: &str) -> errors::Result<String> {
+    let input: Vec<char> = input.chars().collect();
+    match command {
+        Command::Encode { codec, separator, groups_per_line } => {
+            let codec = codec.codec()?;
+            let mut format = GroupFormat::new().with_groups_per_line(groups_per_line.unwrap_or(0));
+            format.set_separator(*separator);
+            Ok(format.encode(&input, codec.as_ref()))
+        }
+        Command::Decode { codec } => {
+            let codec = codec.codec()?;
+            Ok(codec.decode_lenient(&input).into_iter().collect())
+        }
+        Command::Disguise { secret, steganography } => {
+            let secret: Vec<char> = secret.chars().collect();
+            Ok(steganography.scheme()?.disguise(&secret, &input)?.into_iter().collect())
+        }
+        Command::Reveal { steganography } => {
+            Ok(steganography.scheme()?.reveal(&input)?.into_iter().collect())
+        }
+        Command::Schemes => {
+            let registry = Registry::default();
+            let lines: Vec<String> = registry.names().into_iter()
+                .map(|name| match registry.capabilities(name) {
+                    Some(capabilities) if capabilities != Capabilities::new() => format!("{} ({})", name, capabilities),
+                    _ => name.to_string(),
+                })
+                .collect();
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+fn read_input(path: &Option<PathBuf>) -> io::Result<String> {
+    match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let input = match cli.command {
+        Command::Schemes => String::new(),
+        _ => read_input(&cli.input).unwrap_or_else(|error| {
+            eprintln!("Could not read the input: {}", error);
+            process::exit(2);
+        }),
+    };
+    match run(&cli.command, &input) {
+        // The disguised text keeps the line endings of the public input
+        Ok(output) if matches!(cli.command, Command::Disguise { .. }) => print!("{}", output),
+        Ok(output) => println!("{}", output),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod bacon_tests {
+    use super::*;
+
+    fn run_args(args: &[&str], input: &str) -> errors::Result<String> {
+        let cli = Cli::try_parse_from(args).unwrap();
+        run(&cli.command, input)
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let encoded = run_args(&["bacon", "encode"], "My secret\n").unwrap();
+        assert_eq!(encoded, "ABABBBABBABAAABAABAAAAABABAAAAAABAABAABA");
+        let decoded = run_args(&["bacon", "decode"], &encoded).unwrap();
+        assert_eq!(decoded, "MYSECRET");
+
+        let encoded = run_args(&["bacon", "encode", "--codec", "v2", "-a", "0", "-b", "1"], "j").unwrap();
+        assert_eq!(encoded, "01001");
+        let decoded = run_args(&["bacon", "decode", "--codec", "v2", "-a", "0", "-b", "1"], "0 1 0 0 1").unwrap();
+        assert_eq!(decoded, "J");
+
+        let encoded = run_args(&["bacon", "encode", "--separator", " ", "--groups-per-line", "4"], "My secret").unwrap();
+        assert_eq!(encoded, "ABABB BABBA BAAAB AABAA\nAAABA BAAAA AABAA BAABA");
+        let decoded = run_args(&["bacon", "decode"], &encoded).unwrap();
+        assert_eq!(decoded, "MYSECRET");
+        let decoded = run_args(&["bacon", "decode", "--ignore-case"], &encoded.to_lowercase()).unwrap();
+        assert_eq!(decoded, "MYSECRET");
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let public = "This is a public message that contains a secret one";
+        for steganographer in &["letter-case", "markdown", "html-entity", "math-alphanumeric"] {
+            let disguised = run_args(&["bacon", "disguise", "--secret", "My secret", "--steganographer", steganographer], public).unwrap();
+            let revealed = run_args(&["bacon", "reveal", "--steganographer", steganographer], &disguised).unwrap();
+            assert!(revealed.starts_with("MYSECRET"));
+        }
+        let disguised = run_args(&["bacon", "disguise", "-s", "My secret"], public).unwrap();
+        assert_eq!(disguised, "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_a_scheme() {
+        // H = AABBB
+        let disguised = run_args(&["bacon", "disguise", "-s", "h", "--scheme", "markdown:*/**"], "Hello").unwrap();
+        assert_eq!(disguised, "*He***llo**");
+        let revealed = run_args(&["bacon", "reveal", "--scheme", "markdown:*/**"], &disguised).unwrap();
+        assert_eq!(revealed, "H");
+        assert!(Cli::try_parse_from(["bacon", "reveal", "--scheme", "markdown", "--steganographer", "markdown"]).is_err());
+        assert!(run_args(&["bacon", "reveal", "--scheme", "unknown"], "Hello").is_err());
+    }
+
+    #[test]
+    fn list_the_schemes() {
+        let schemes = run_args(&["bacon", "schemes"], "").unwrap();
+        assert!(schemes.starts_with("letter-case (mapping)\nmarkdown (mapping, markup, argument)\n"));
+        assert!(schemes.contains("\nwhitespace"));
+    }
+
+    #[test]
+    fn failures() {
+        assert!(Cli::try_parse_from(["bacon", "disguise"]).is_err());
+        assert!(Cli::try_parse_from(["bacon", "reveal", "--steganographer", "unknown"]).is_err());
+        assert!(run_args(&["bacon", "encode", "-a", "x", "-b", "x"], "abc").is_err());
+        assert!(run_args(&["bacon", "disguise", "--secret", "My secret"], "Too short").is_err());
+    }
+}
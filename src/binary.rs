@@ -0,0 +1,111 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Steganographers of binary media, like images, audio or documents.
+//!
+//! The [Steganographer](../trait.Steganographer.html) hides a secret of the same type as its input, which suits the
+//! texts. A [ByteSteganographer](trait.ByteSteganographer.html) hides a secret of `char`s in a medium of bytes and
+//! reveals it again, with the codecs of the texts. It is implemented for every
+//! [Carrier](../carrier/trait.Carrier.html) of bytes, so that a binary format needs only to read and write the
+//! elements of its medium.
+//!
+//! ```rust
+//! use bacon_cipher::binary::ByteSteganographer;
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::lsb::LsbSteganographer;
+//!
+//! // The samples of a WAV file, after its 44 bytes of header
+//! let mut wav = vec![0u8; 44];
+//! wav.extend((0..100u8).map(|sample| sample.wrapping_mul(37)));
+//!
+//! let s = LsbSteganographer::new().with_offset(44);
+//! let codec = CharCodec::new('a', 'b');
+//! let disguised = s.disguise_str("Hi", &wav, &codec).unwrap();
+//! assert_eq!(disguised.len(), wav.len());
+//! assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("HI"));
+//! ```
+use crate::{BaconCodec, carrier, errors};
+use crate::carrier::Carrier;
+
+/// Hides a secret of `char`s in a medium of bytes, like [Steganographer](../trait.Steganographer.html) does in a text.
+///
+/// The methods are implemented for every [Carrier](../carrier/trait.Carrier.html) of bytes.
+pub trait ByteSteganographer: Carrier<Unit=u8> {
+    /// Disguises the _secret_ in the _public_ medium and returns the disguised medium.
+    fn disguise<AB>(&self, secret: &[char], public: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<u8>> {
+        carrier::embed(self, secret, public, codec)
+    }
+
+    /// Reveals the _secret_ that is hidden in the medium.
+    fn reveal<AB>(&self, input: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        carrier::extract(self, input, codec)
+    }
+
+    /// Disguises the _secret_ string in the _public_ medium and returns the disguised medium.
+    fn disguise_str<AB>(&self, secret: &str, public: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<u8>> {
+        let secret: Vec<char> = secret.chars().collect();
+        ByteSteganographer::disguise(self, &secret, public, codec)
+    }
+
+    /// Reveals the _secret_ that is hidden in the medium as a string.
+    fn reveal_str<AB>(&self, input: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<String> {
+        ByteSteganographer::reveal(self, input, codec)
+            .map(|revealed| revealed.into_iter().collect())
+    }
+}
+
+impl<C: Carrier<Unit=u8> + ?Sized> ByteSteganographer for C {}
+
+#[cfg(test)]
+mod binary_tests {
+    use crate::codecs::char_codec::CharCodec;
+    use crate::errors::BaconError;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::jpeg::JpegSteganographer;
+    use crate::stega::lsb::LsbSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn disguise_and_reveal_in_any_byte_carrier() {
+        let codec = CharCodec::new('a', 'b');
+        let carriers: Vec<Box<dyn Carrier<Unit=u8>>> = vec![
+            Box::new(LsbSteganographer::new()),
+            Box::new(LsbSteganographer::new().with_offset(3)),
+        ];
+        let public: Vec<u8> = (0..=255).collect();
+        for s in carriers.iter() {
+            let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+            assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I']);
+        }
+        match carriers[0].disguise_str("Hi", &public[..9], &codec) {
+            Err(BaconError::InsufficientCapacity { required, available, .. }) => assert_eq!((required, available), (10, 9)),
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn share_the_trait_with_the_image_carriers() {
+        let codec = CharCodec::new('a', 'b');
+        let s = JpegSteganographer::new(LetterCaseSteganographer::new());
+        // The comment segment, followed by a truncated scan
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xFE, 0, 13];
+        jpeg.extend_from_slice(b"Hello world");
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0, 8, 1, 1, 0, 0, 63, 0, 0x12, 0x34, 0xFF, 0xD9]);
+        let disguised = ByteSteganographer::disguise_str(&s, "H", &jpeg, &codec).unwrap();
+        // H = AABBB
+        assert_eq!(s.text(&disguised).unwrap().unwrap(), "heLLO world");
+        assert_eq!(ByteSteganographer::reveal(&s, &disguised, &codec).unwrap()[0], 'H');
+    }
+}
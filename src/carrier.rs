@@ -0,0 +1,360 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Carriers of Bacon's elements and the classification of a carrier input, as it is done by a steganographer before
+//! decoding.
+//!
+//! A [Carrier](trait.Carrier.html) hides a stream of elements in a medium, regardless of the codec that produces them,
+//! so that any codec composes with any medium. Every steganographer of `char`s is a carrier of texts.
+//!
+//! Steganographers that parse their input, expose the classified [Segment](struct.Segment.html)s, so that they can be
+//! visualized, audited, or post-processed before decoding.
+//!
+//! ```rust
+//! use bacon_cipher::carrier::{self, Carrier};
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//!
+//! let s = LetterCaseSteganographer::new();
+//! let public: Vec<char> = "Hello world".chars().collect();
+//! assert_eq!(s.capacity(&public).unwrap(), 10);
+//! let written = s.write_symbols(&[true, false, true], &public).unwrap();
+//! assert_eq!(written.iter().collect::<String>(), "hEllo world");
+//! assert_eq!(&s.read_symbols(&written).unwrap()[..3], &[true, false, true]);
+//!
+//! // H = AABBB
+//! let codec = CharCodec::new('a', 'b');
+//! let disguised = carrier::embed(&s, &['H'], &public, &codec).unwrap();
+//! assert_eq!(carrier::extract(&s, &disguised, &codec).unwrap()[0], 'H');
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::stega::alphabetic_units;
+
+/// A medium that carries a stream of Bacon's elements, like a text or an image.
+///
+/// The elements are `true` for the element A and `false` for the element B.
+pub trait Carrier {
+    /// The unit of the medium, like `char` for texts and `u8` for binary media.
+    type Unit;
+
+    /// The number of elements that the _public_ medium can carry. It is `usize::MAX` for carriers that generate their
+    /// output, without being limited by the medium.
+    fn capacity(&self, public: &[Self::Unit]) -> errors::Result<usize>;
+
+    /// Writes the elements to the _public_ medium.
+    fn write_symbols(&self, symbols: &[bool], public: &[Self::Unit]) -> errors::Result<Vec<Self::Unit>>;
+
+    /// Reads all the elements that the input carries, including the ones that follow the written elements.
+    fn read_symbols(&self, input: &[Self::Unit]) -> errors::Result<Vec<bool>>;
+}
+
+impl<S: Steganographer<T=char>> Carrier for S {
+    type Unit = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Steganographer::capacity(self, public)
+    }
+
+    fn write_symbols(&self, symbols: &[bool], public: &[char]) -> errors::Result<Vec<char>> {
+        // Any alphabetic secret of the same length, since the codec ignores it
+        let secret = vec!['a'; symbols.len()];
+        self.disguise(&secret, public, &Symbols { symbols, read: RefCell::new(Vec::new()) })
+    }
+
+    fn read_symbols(&self, input: &[char]) -> errors::Result<Vec<bool>> {
+        let symbols = Symbols { symbols: &[], read: RefCell::new(Vec::new()) };
+        self.reveal(input, &symbols)?;
+        Ok(symbols.read.into_inner())
+    }
+}
+
+/// Encodes the _secret_ with the codec and writes its elements to the _public_ medium of the carrier.
+pub fn embed<C, AB>(carrier: &C, secret: &[char], public: &[C::Unit], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<C::Unit>>
+    where C: Carrier + ?Sized {
+    BaconError::check_secret(secret)?;
+    let symbols: Vec<bool> = codec.encode(secret).iter().map(|elem| codec.is_a(elem)).collect();
+    let available = carrier.capacity(public)?;
+    if available < symbols.len() {
+        return Err(BaconError::InsufficientCapacity { required: symbols.len(), available, unit: CapacityUnit::Carriers });
+    }
+    carrier.write_symbols(&symbols, public)
+}
+
+/// Reads the elements of the input of the carrier and decodes them with the codec.
+pub fn extract<C, AB>(carrier: &C, input: &[C::Unit], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>>
+    where C: Carrier + ?Sized {
+    let encoded: Vec<AB> = carrier.read_symbols(input)?.into_iter()
+        .map(|is_a| if is_a { codec.a() } else { codec.b() })
+        .collect();
+    Ok(codec.decode(&encoded))
+}
+
+/// Reads the elements of the input of the carrier once and decodes them with every codec, in the order of the codecs.
+///
+/// Returns the index of every codec along with its decoding, so that the expensive classification of the input is
+/// shared when the codec of a secret is not known.
+pub fn reveal_with_any<C, AB>(carrier: &C, input: &[C::Unit], codecs: &[&dyn BaconCodec<ABTYPE=AB, CONTENT=char>]) -> errors::Result<Vec<(usize, Vec<char>)>>
+    where C: Carrier + ?Sized {
+    let symbols = carrier.read_symbols(input)?;
+    Ok(codecs.iter()
+        .enumerate()
+        .map(|(index, codec)| {
+            let encoded: Vec<AB> = symbols.iter()
+                .map(|is_a| if *is_a { codec.a() } else { codec.b() })
+                .collect();
+            (index, codec.decode(&encoded))
+        })
+        .collect())
+}
+
+// A codec whose encoding of any secret is the `symbols`, and which records the elements that it decodes.
+struct Symbols<'a> {
+    symbols: &'a [bool],
+    read: RefCell<Vec<bool>>,
+}
+
+impl<'a> BaconCodec for Symbols<'a> {
+    type ABTYPE = bool;
+    type CONTENT = char;
+
+    fn encode(&self, _input: &[char]) -> Vec<bool> {
+        self.symbols.to_vec()
+    }
+
+    fn encode_elem(&self, _elem: &char) -> Vec<bool> {
+        Vec::new()
+    }
+
+    fn decode(&self, input: &[bool]) -> Vec<char> {
+        self.read.borrow_mut().extend_from_slice(input);
+        Vec::new()
+    }
+
+    fn decode_elems(&self, _elems: &[bool]) -> char {
+        ' '
+    }
+
+    fn a(&self) -> bool {
+        true
+    }
+
+    fn b(&self) -> bool {
+        false
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        1
+    }
+
+    fn is_a(&self, elem: &bool) -> bool {
+        *elem
+    }
+
+    fn is_b(&self, elem: &bool) -> bool {
+        !*elem
+    }
+}
+
+// A codec that counts the elements that it decodes.
+struct Counter<T> {
+    count: Cell<usize>,
+    content: std::marker::PhantomData<T>,
+}
+
+impl<T: Default> BaconCodec for Counter<T> {
+    type ABTYPE = bool;
+    type CONTENT = T;
+
+    fn encode_elem(&self, _elem: &T) -> Vec<bool> {
+        Vec::new()
+    }
+
+    fn decode(&self, input: &[bool]) -> Vec<T> {
+        self.count.set(self.count.get() + input.len());
+        Vec::new()
+    }
+
+    fn decode_elems(&self, _elems: &[bool]) -> T {
+        T::default()
+    }
+
+    fn a(&self) -> bool {
+        true
+    }
+
+    fn b(&self) -> bool {
+        false
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        1
+    }
+
+    fn is_a(&self, elem: &bool) -> bool {
+        *elem
+    }
+
+    fn is_b(&self, elem: &bool) -> bool {
+        !*elem
+    }
+}
+
+// Counts the elements that the steganographer finds in the input.
+pub(crate) fn count_elements<S>(steganographer: &S, input: &[S::T]) -> errors::Result<usize>
+    where S: Steganographer + ?Sized, S::T: Default {
+    let counter = Counter { count: Cell::new(0), content: std::marker::PhantomData };
+    steganographer.reveal(input, &counter)?;
+    Ok(counter.count.get())
+}
+
+/// The kind of a [Segment](struct.Segment.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SegmentKind {
+    /// The alphabetic characters of the segment carry the Bacon's element A.
+    A,
+    /// The alphabetic characters of the segment carry the Bacon's element B.
+    B,
+    /// The segment does not carry any Bacon's element.
+    Other,
+}
+
+/// A part of a carrier input, classified by a steganographer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    kind: SegmentKind,
+    text: String,
+    range: Option<Range<usize>>,
+}
+
+impl Segment {
+    pub fn new(kind: SegmentKind, text: &str, range: Option<Range<usize>>) -> Segment {
+        Segment {
+            kind,
+            text: text.to_string(),
+            range,
+        }
+    }
+
+    pub fn kind(&self) -> SegmentKind {
+        self.kind
+    }
+
+    /// The text of the segment, without any markup.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The byte range of the text in the input, if the steganographer can track it.
+    pub fn range(&self) -> Option<Range<usize>> {
+        self.range.clone()
+    }
+
+    /// The number of Bacon's elements that the segment carries.
+    pub fn elements(&self) -> usize {
+        match self.kind {
+            SegmentKind::Other => 0,
+            _ => alphabetic_units(&self.text),
+        }
+    }
+}
+
+/// Decodes the Bacon's elements that the segments carry.
+pub fn decode<AB>(segments: &[Segment], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> Vec<char> {
+    decode_texts(segments.iter().map(|segment| (segment.kind, segment.text())), codec)
+}
+
+// Decodes the Bacon's elements of classified texts, which may be borrowed from the input by the steganographers that
+// do not create segments.
+pub(crate) fn decode_texts<'a, I, AB>(texts: I, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> Vec<char>
+    where I: Iterator<Item=(SegmentKind, &'a str)> + Clone {
+    debug_event!(
+        segments = texts.clone().count(),
+        ignored = texts.clone().filter(|(kind, _)| *kind == SegmentKind::Other).count(),
+        "decoding segments");
+    let mut groups = Groups::new(codec.encoded_group_size());
+    for (kind, text) in texts.filter(|(kind, _)| *kind != SegmentKind::Other) {
+        groups.extend(std::iter::repeat_n(kind == SegmentKind::A, alphabetic_units(text)));
+    }
+    codec.decode_groups(&groups)
+}
+
+#[cfg(test)]
+mod carrier_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::markdown::{MarkdownSteganographer, Marker};
+    use crate::stega::punctuation::PunctuationSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn segment_elements() {
+        assert_eq!(Segment::new(SegmentKind::A, "It's", Some(0..4)).elements(), 3);
+        assert_eq!(Segment::new(SegmentKind::B, "42", None).elements(), 0);
+        assert_eq!(Segment::new(SegmentKind::Other, "Other", None).elements(), 0);
+    }
+
+    #[test]
+    fn decode_segments() {
+        let codec = CharCodec::new('a', 'b');
+        // H = AABBB
+        let segments = vec![
+            Segment::new(SegmentKind::A, "He", Some(0..2)),
+            Segment::new(SegmentKind::Other, "ignored", Some(2..9)),
+            Segment::new(SegmentKind::B, "l-lo", Some(9..13)),
+        ];
+        assert_eq!(decode(&segments, &codec), vec!['H']);
+    }
+
+    #[test]
+    fn steganographers_are_carriers() {
+        let codec = CharCodec::new('a', 'b');
+        let public: Vec<char> = "Hello, world".chars().collect();
+        let s = PunctuationSteganographer::new(&[(',', ';')]).unwrap();
+        assert_eq!(Carrier::capacity(&s, &public).unwrap(), 1);
+        assert_eq!(String::from_iter(s.write_symbols(&[false], &public).unwrap().iter()), "Hello; world");
+        assert!(matches!(embed(&s, &['H'], &public, &codec), Err(BaconError::InsufficientCapacity { required: 5, available: 1, .. })));
+
+        let s = MarkdownSteganographer::new(Marker::new(Some("_"), Some("_")), Marker::new(Some("*"), Some("*"))).unwrap();
+        assert_eq!(Carrier::capacity(&s, &public).unwrap(), 10);
+        let disguised = embed(&s, &['H'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "_He_*llo*, world");
+        assert_eq!(s.read_symbols(&disguised).unwrap(), vec![true, true, false, false, false]);
+        assert_eq!(extract(&s, &disguised, &codec).unwrap(), vec!['H']);
+    }
+
+    #[test]
+    fn reveal_with_any_codec() {
+        let v1 = CharCodec::new('a', 'b');
+        let v2 = CharCodecV2::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let disguised = s.disguise_str("W", "Hello world", &v2).unwrap();
+        let disguised: Vec<char> = disguised.chars().collect();
+        let revealed = reveal_with_any(&s, &disguised, &[&v1, &v2]).unwrap();
+        assert_eq!(revealed.len(), 2);
+        assert_eq!(revealed[0], (0, s.reveal(&disguised, &v1).unwrap()));
+        assert_ne!(revealed[0].1[0], 'W');
+        assert_eq!(revealed[1].0, 1);
+        assert_eq!(revealed[1].1[0], 'W');
+        assert!(reveal_with_any::<_, char>(&s, &disguised, &[]).unwrap().is_empty());
+    }
+}
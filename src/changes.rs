@@ -0,0 +1,296 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reports of the changes that a disguise makes to the public input.
+//!
+//! A [ChangeReport](struct.ChangeReport.html) compares the public input with the disguised output, so that the changes
+//! can be checked against a policy, like "never modify quoted strings", and can be reverted precisely. Letters that
+//! differ only in their case are reported as case flips, instead of as replacements.
+//!
+//! ```rust
+//! use bacon_cipher::changes::ChangeKind;
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::markdown::{MarkdownSteganographer, Marker};
+//! use bacon_cipher::Steganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = MarkdownSteganographer::new(Marker::empty(), Marker::new(Some("*"), Some("*"))).unwrap();
+//! let public: Vec<char> = "Hello, \"world\"".chars().collect();
+//! // H = AABBB
+//! let (disguised, report) = s.disguise_with_changes(&['H'], &public, &codec).unwrap();
+//! assert_eq!(disguised.iter().collect::<String>(), "He*llo*, \"world\"");
+//! assert_eq!(report.changes().len(), 2);
+//! assert_eq!(report.changes()[0].kind(), ChangeKind::Insertion);
+//! assert!(!report.touches(&(7..14)));
+//! assert_eq!(report.revert(&disguised), public);
+//! ```
+use std::ops::Range;
+
+use crate::{BaconCodec, errors, Steganographer};
+
+/// The kind of a [Change](struct.Change.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// A letter changed its case.
+    CaseFlip,
+    /// Characters were inserted, like markers or spaces.
+    Insertion,
+    /// Characters were removed.
+    Removal,
+    /// Characters were replaced by others, like a punctuation mark by its counterpart.
+    Replacement,
+}
+
+/// A change of the public input, with the ranges of the changed characters in the public input and in the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    kind: ChangeKind,
+    public: Range<usize>,
+    disguised: Range<usize>,
+    original: String,
+    replacement: String,
+}
+
+impl Change {
+    pub fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+
+    /// The range of the changed characters in the public input, which is empty for insertions.
+    pub fn public(&self) -> &Range<usize> {
+        &self.public
+    }
+
+    /// The range of the changed characters in the output, which is empty for removals.
+    pub fn disguised(&self) -> &Range<usize> {
+        &self.disguised
+    }
+
+    /// The characters of the public input.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The characters of the output.
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+}
+
+/// The changes that turn a public input into a disguised output, in the order of their positions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChangeReport {
+    changes: Vec<Change>,
+}
+
+impl ChangeReport {
+    /// Compares a public input with its disguised output.
+    ///
+    /// The comparison finds the fewest insertions and removals of characters, with the Myers' algorithm, so it is fast
+    /// when the output is similar to the input, like the outputs of the steganographers.
+    pub fn new(public: &[char], disguised: &[char]) -> ChangeReport {
+        let mut changes = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        for (matched_i, matched_j) in common_subsequence(public, disguised).into_iter().chain(Some((public.len(), disguised.len()))) {
+            if i < matched_i || j < matched_j {
+                let kind = match (i < matched_i, j < matched_j) {
+                    (true, true) => ChangeKind::Replacement,
+                    (true, false) => ChangeKind::Removal,
+                    _ => ChangeKind::Insertion,
+                };
+                changes.push(Change::of(kind, i..matched_i, j..matched_j, public, disguised));
+            }
+            if matched_i < public.len() && public[matched_i] != disguised[matched_j] {
+                changes.push(Change::of(ChangeKind::CaseFlip, matched_i..matched_i + 1, matched_j..matched_j + 1, public, disguised));
+            }
+            i = matched_i + 1;
+            j = matched_j + 1;
+        }
+        ChangeReport { changes }
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The number of the changes of a kind.
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|change| change.kind == kind).count()
+    }
+
+    /// Returns true if any change is in the range of positions of the public input. An insertion touches a range if it
+    /// is strictly inside it.
+    pub fn touches(&self, range: &Range<usize>) -> bool {
+        self.changes.iter().any(|change| {
+            if change.public.is_empty() {
+                range.start < change.public.start && change.public.start < range.end
+            } else {
+                change.public.start < range.end && range.start < change.public.end
+            }
+        })
+    }
+
+    /// Restores the public input from the disguised output that the report describes.
+    pub fn revert(&self, disguised: &[char]) -> Vec<char> {
+        let mut public = Vec::with_capacity(disguised.len());
+        let mut position = 0;
+        for change in &self.changes {
+            public.extend_from_slice(&disguised[position..change.disguised.start]);
+            public.extend(change.original.chars());
+            position = change.disguised.end;
+        }
+        public.extend_from_slice(&disguised[position..]);
+        public
+    }
+}
+
+impl Change {
+    fn of(kind: ChangeKind, public: Range<usize>, disguised: Range<usize>, public_input: &[char], output: &[char]) -> Change {
+        Change {
+            kind,
+            original: public_input[public.clone()].iter().collect(),
+            replacement: output[disguised.clone()].iter().collect(),
+            public,
+            disguised,
+        }
+    }
+}
+
+/// Disguises the _secret_ and reports the changes of the _public_ input, like
+/// [Steganographer::disguise_with_changes](../trait.Steganographer.html#method.disguise_with_changes).
+pub fn disguise_with_changes<S, AB>(steganographer: &S, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<(Vec<char>, ChangeReport)>
+    where S: Steganographer<T=char> + ?Sized {
+    let disguised = steganographer.disguise(secret, public, codec)?;
+    let report = ChangeReport::new(public, &disguised);
+    Ok((disguised, report))
+}
+
+// Characters are matched if they are equal, ignoring their case.
+fn same_letter(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+// The positions of the matched characters of a longest common subsequence, found with the Myers' algorithm. The
+// furthest reaching paths of every number of edits are kept, in order to backtrack from the end.
+fn common_subsequence(a: &[char], b: &[char]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    // The furthest x of every diagonal k = x - y, for the paths of d edits, at the index k + d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'edits: for d in 0..=(n + m) {
+        let previous = trace.last();
+        let mut furthest = vec![0; 2 * d as usize + 1];
+        for k in (-d..=d).step_by(2) {
+            let mut x = match previous {
+                None => 0,
+                Some(previous) => {
+                    let at = |k: isize| previous[(k + d - 1) as usize];
+                    if k == -d || (k != d && at(k - 1) < at(k + 1)) { at(k + 1) } else { at(k - 1) + 1 }
+                }
+            };
+            let mut y = x - k;
+            while x < n && y < m && same_letter(a[x as usize], b[y as usize]) {
+                x += 1;
+                y += 1;
+            }
+            furthest[(k + d) as usize] = x;
+            if x >= n && y >= m {
+                trace.push(furthest);
+                break 'edits;
+            }
+        }
+        trace.push(furthest);
+    }
+
+    let mut matched = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        // The start of the diagonal of matched characters, which follows the last edit, and the end of the path before it
+        let (snake, previous) = if d == 0 {
+            ((0, 0), (0, 0))
+        } else {
+            let k = x - y;
+            let furthest = &trace[d as usize - 1];
+            let at = |k: isize| furthest[(k + d - 1) as usize];
+            let down = k == -d || (k != d && at(k - 1) < at(k + 1));
+            let previous_k = if down { k + 1 } else { k - 1 };
+            let previous = (at(previous_k), at(previous_k) - previous_k);
+            let snake = if down { (previous.0, previous.1 + 1) } else { (previous.0 + 1, previous.1) };
+            (snake, previous)
+        };
+        while x > snake.0 && y > snake.1 {
+            x -= 1;
+            y -= 1;
+            matched.push((x as usize, y as usize));
+        }
+        x = previous.0;
+        y = previous.1;
+    }
+    matched.reverse();
+    matched
+}
+
+#[cfg(test)]
+mod changes_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::whitespace::WhitespaceSteganographer;
+
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        Vec::from_iter(s.chars())
+    }
+
+    #[test]
+    fn compare() {
+        let report = ChangeReport::new(&chars("a cat, sat"), &chars("A *cat*; sat!"));
+        let kinds: Vec<ChangeKind> = report.changes().iter().map(|change| change.kind()).collect();
+        // The adjacent `*` and `;` replace the `,`
+        assert_eq!(kinds, vec![ChangeKind::CaseFlip, ChangeKind::Insertion, ChangeKind::Replacement, ChangeKind::Insertion]);
+        assert_eq!(report.changes()[2].public(), &(5..6));
+        assert_eq!(report.changes()[2].disguised(), &(6..8));
+        assert_eq!((report.changes()[2].original(), report.changes()[2].replacement()), (",", "*;"));
+        assert_eq!(report.revert(&chars("A *cat*; sat!")), chars("a cat, sat"));
+        assert!(ChangeReport::new(&chars("same"), &chars("same")).is_empty());
+        let removed = ChangeReport::new(&chars("a  b"), &chars("a b"));
+        assert_eq!(removed.count(ChangeKind::Removal), 1);
+        assert_eq!(removed.revert(&chars("a b")), chars("a  b"));
+        assert_eq!(ChangeReport::new(&[], &chars("ab")).count(ChangeKind::Insertion), 1);
+    }
+
+    #[test]
+    fn report_the_changes_of_a_disguise() {
+        let codec = CharCodec::new('a', 'b');
+        let public = chars("This is \"a public\" message that contains a secret one");
+        let s = LetterCaseSteganographer::new();
+        let (disguised, report) = s.disguise_with_changes(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(report.count(ChangeKind::CaseFlip), report.changes().len());
+        assert!(report.touches(&(8..18)));
+        assert!(!report.touches(&(19..26)));
+        assert_eq!(report.revert(&disguised), public);
+
+        let s = WhitespaceSteganographer::new();
+        let public = chars("one two three four five six seven");
+        let (disguised, report) = s.disguise_with_changes(&['H'], &public, &codec).unwrap();
+        assert_eq!(report.count(ChangeKind::Insertion), 3);
+        assert_eq!(report.revert(&disguised), public);
+    }
+}
@@ -14,8 +14,77 @@
 use std::marker::PhantomData;
 
 use crate::BaconCodec;
+use crate::codecs::groups::Groups;
+
+const GROUP_SIZE: usize = 5;
+
+// The codes of the letters `A` to `Z` in the first version of the cipher, where `I` and `J` share a code, as well as `U`
+// and `V`. The bits of a code are the elements of the group from the first one, with 1 for the element B.
+const V1_CODES: &[u8; 26] = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 23];
+// The letters of the codes of the first version
+const V1_LETTERS: &[u8] = b"ABCDEFGHIKLMNOPQRSTUWXYZ";
+
+// In the second version, every letter has its own code.
+const V2_CODES: &[u8; 26] = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25];
+const V2_LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// The code of an ASCII letter. Other characters are not encoded.
+const fn code_of(codes: &[u8; 26], elem: &char) -> Option<u8> {
+    if elem.is_ascii_alphabetic() {
+        Some(codes[(elem.to_ascii_uppercase() as u8 - b'A') as usize])
+    } else {
+        None
+    }
+}
+
+// Appends the elements of the code of an ASCII letter to `output`.
+fn push_code<T: Clone>(codes: &[u8; 26], elem: &char, elem_a: &T, elem_b: &T, output: &mut Vec<T>) {
+    if let Some(code) = code_of(codes, elem) {
+        for bit in (0..GROUP_SIZE).rev() {
+            output.push(if (code >> bit) & 1 == 0 { elem_a.clone() } else { elem_b.clone() });
+        }
+    }
+}
+
+// The letter of a group of elements, or a space if the group is not complete, contains elements other than A and B,
+// or its code does not belong to any letter. An element that is both A and B is taken as A.
+fn letter_of<T>(letters: &[u8], elems: &[T], is_a: impl Fn(&T) -> bool, is_b: impl Fn(&T) -> bool) -> char {
+    if elems.len() != GROUP_SIZE {
+        return ' ';
+    }
+    let mut code = 0;
+    for elem in elems {
+        code <<= 1;
+        if is_b(elem) && !is_a(elem) {
+            code |= 1;
+        } else if !is_a(elem) {
+            return ' ';
+        }
+    }
+    letter_of_code(letters, code)
+}
+
+const fn letter_of_code(letters: &[u8], code: usize) -> char {
+    if code < letters.len() { letters[code] as char } else { ' ' }
+}
+
+fn encode_groups(codes: &[u8; 26], input: &[char]) -> Groups {
+    let groups = Groups::from_patterns(input.iter().filter_map(|elem| code_of(codes, elem)).collect(), GROUP_SIZE);
+    debug_event!(input = input.len(), elements = groups.len(), "encoded");
+    groups
+}
+
+// An incomplete group at the end is decoded to a space, like the incomplete groups of `decode`.
+fn decode_groups(letters: &[u8], groups: &Groups) -> Vec<char> {
+    debug_event!(elements = groups.len(), groups = groups.complete().len(), incomplete = groups.incomplete_len(), "decoding");
+    let mut decoded: Vec<char> = groups.complete().iter().map(|code| letter_of_code(letters, *code as usize)).collect();
+    if groups.incomplete().is_some() {
+        decoded.push(' ');
+    }
+    decoded
+}
 
-#[derive(PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 /// A codec that encodes data of type `char`.
 ///
 /// The encoding is done by substituting with two given elements (`elem_a` and `elem_b`) of type `T`.
@@ -25,12 +94,43 @@ pub struct CharCodec<T> {
     pd: PhantomData<char>,
     elem_a: T,
     elem_b: T,
+    a_aliases: Vec<T>,
+    b_aliases: Vec<T>,
 }
 
 impl<T> CharCodec<T> {
     /// Create a new `CharCodec` using elements `elem_a` and `elem_b` for substitution.
-    pub fn new(elem_a: T, elem_b: T) -> CharCodec<T> {
-        CharCodec { pd: PhantomData, elem_a, elem_b }
+    ///
+    /// The codec can be created in constant contexts, like a `static`:
+    ///
+    /// ```rust
+    /// use bacon_cipher::codecs::char_codec::CharCodec;
+    /// use bacon_cipher::BaconCodec;
+    ///
+    /// static CODEC: CharCodec<char> = CharCodec::new('A', 'B');
+    ///
+    /// assert_eq!(CODEC.encode(&['H']), vec!['A', 'A', 'B', 'B', 'B']);
+    /// ```
+    pub const fn new(elem_a: T, elem_b: T) -> CharCodec<T> {
+        CharCodec { pd: PhantomData, elem_a, elem_b, a_aliases: Vec::new(), b_aliases: Vec::new() }
+    }
+
+    /// Accepts other symbols as the A and B elements during decoding, like `'.'` along with `'0'`. The encoding uses
+    /// only `elem_a` and `elem_b`. A symbol that is an A and a B element is taken as A.
+    pub fn with_aliases(mut self, a_aliases: &[T], b_aliases: &[T]) -> Self where T: Clone {
+        self.a_aliases.extend_from_slice(a_aliases);
+        self.b_aliases.extend_from_slice(b_aliases);
+        self
+    }
+}
+
+impl CharCodec<char> {
+    /// Accepts the other case of the A and B elements during decoding, e.g. `'a'` along with `'A'`.
+    pub fn case_insensitive(self) -> Self {
+        let other_case = |c: char| if c.is_lowercase() { c.to_uppercase().next() } else { c.to_lowercase().next() };
+        let a_aliases: Vec<char> = other_case(self.elem_a).into_iter().collect();
+        let b_aliases: Vec<char> = other_case(self.elem_b).into_iter().collect();
+        self.with_aliases(&a_aliases, &b_aliases)
     }
 }
 
@@ -51,88 +151,59 @@ impl<T: PartialEq + Clone> BaconCodec for CharCodec<T> {
     type ABTYPE = T;
     type CONTENT = char;
 
+    fn encode(&self, input: &[char]) -> Vec<T> {
+        let mut encoded = Vec::with_capacity(input.len() * GROUP_SIZE);
+        for elem in input {
+            push_code(V1_CODES, elem, &self.elem_a, &self.elem_b, &mut encoded);
+        }
+        debug_event!(input = input.len(), elements = encoded.len(), "encoded");
+        encoded
+    }
+
     fn encode_elem(&self, elem: &char) -> Vec<T> {
-        match elem {
-            'a' | 'A' => vec![self.a(), self.a(), self.a(), self.a(), self.a()],
-            'b' | 'B' => vec![self.a(), self.a(), self.a(), self.a(), self.b()],
-            'c' | 'C' => vec![self.a(), self.a(), self.a(), self.b(), self.a()],
-            'd' | 'D' => vec![self.a(), self.a(), self.a(), self.b(), self.b()],
-            'e' | 'E' => vec![self.a(), self.a(), self.b(), self.a(), self.a()],
-            'f' | 'F' => vec![self.a(), self.a(), self.b(), self.a(), self.b()],
-            'g' | 'G' => vec![self.a(), self.a(), self.b(), self.b(), self.a()],
-            'h' | 'H' => vec![self.a(), self.a(), self.b(), self.b(), self.b()],
-            'i' | 'I' => vec![self.a(), self.b(), self.a(), self.a(), self.a()],
-            'j' | 'J' => vec![self.a(), self.b(), self.a(), self.a(), self.a()],
-            'k' | 'K' => vec![self.a(), self.b(), self.a(), self.a(), self.b()],
-            'l' | 'L' => vec![self.a(), self.b(), self.a(), self.b(), self.a()],
-            'm' | 'M' => vec![self.a(), self.b(), self.a(), self.b(), self.b()],
-            'n' | 'N' => vec![self.a(), self.b(), self.b(), self.a(), self.a()],
-            'o' | 'O' => vec![self.a(), self.b(), self.b(), self.a(), self.b()],
-            'p' | 'P' => vec![self.a(), self.b(), self.b(), self.b(), self.a()],
-            'q' | 'Q' => vec![self.a(), self.b(), self.b(), self.b(), self.b()],
-            'r' | 'R' => vec![self.b(), self.a(), self.a(), self.a(), self.a()],
-            's' | 'S' => vec![self.b(), self.a(), self.a(), self.a(), self.b()],
-            't' | 'T' => vec![self.b(), self.a(), self.a(), self.b(), self.a()],
-            'u' | 'U' => vec![self.b(), self.a(), self.a(), self.b(), self.b()],
-            'v' | 'V' => vec![self.b(), self.a(), self.a(), self.b(), self.b()],
-            'w' | 'W' => vec![self.b(), self.a(), self.b(), self.a(), self.a()],
-            'x' | 'X' => vec![self.b(), self.a(), self.b(), self.a(), self.b()],
-            'y' | 'Y' => vec![self.b(), self.a(), self.b(), self.b(), self.a()],
-            'z' | 'Z' => vec![self.b(), self.a(), self.b(), self.b(), self.b()],
-            _ => vec![]
+        let mut encoded = Vec::with_capacity(GROUP_SIZE);
+        push_code(V1_CODES, elem, &self.elem_a, &self.elem_b, &mut encoded);
+        encoded
+    }
+
+    fn encode_groups(&self, input: &[char]) -> Groups {
+        encode_groups(V1_CODES, input)
+    }
+
+    fn decode_groups(&self, groups: &Groups) -> Vec<char> {
+        if groups.group_size() == GROUP_SIZE {
+            decode_groups(V1_LETTERS, groups)
+        } else {
+            self.decode(&groups.to_encoded(self))
         }
     }
 
+    fn decode_pattern(&self, pattern: u8) -> char {
+        letter_of_code(V1_LETTERS, pattern as usize)
+    }
+
     fn decode_elems(&self, elems: &[T]) -> char {
-        match elems {
-            m if m == vec![self.a(), self.a(), self.a(), self.a(), self.a()].as_slice() => 'A',
-            m if m == vec![self.a(), self.a(), self.a(), self.a(), self.b()].as_slice() => 'B',
-            m if m == vec![self.a(), self.a(), self.a(), self.b(), self.a()].as_slice() => 'C',
-            m if m == vec![self.a(), self.a(), self.a(), self.b(), self.b()].as_slice() => 'D',
-            m if m == vec![self.a(), self.a(), self.b(), self.a(), self.a()].as_slice() => 'E',
-            m if m == vec![self.a(), self.a(), self.b(), self.a(), self.b()].as_slice() => 'F',
-            m if m == vec![self.a(), self.a(), self.b(), self.b(), self.a()].as_slice() => 'G',
-            m if m == vec![self.a(), self.a(), self.b(), self.b(), self.b()].as_slice() => 'H',
-            m if m == vec![self.a(), self.b(), self.a(), self.a(), self.a()].as_slice() => 'I',
-            m if m == vec![self.a(), self.b(), self.a(), self.a(), self.a()].as_slice() => 'J',
-            m if m == vec![self.a(), self.b(), self.a(), self.a(), self.b()].as_slice() => 'K',
-            m if m == vec![self.a(), self.b(), self.a(), self.b(), self.a()].as_slice() => 'L',
-            m if m == vec![self.a(), self.b(), self.a(), self.b(), self.b()].as_slice() => 'M',
-            m if m == vec![self.a(), self.b(), self.b(), self.a(), self.a()].as_slice() => 'N',
-            m if m == vec![self.a(), self.b(), self.b(), self.a(), self.b()].as_slice() => 'O',
-            m if m == vec![self.a(), self.b(), self.b(), self.b(), self.a()].as_slice() => 'P',
-            m if m == vec![self.a(), self.b(), self.b(), self.b(), self.b()].as_slice() => 'Q',
-            m if m == vec![self.b(), self.a(), self.a(), self.a(), self.a()].as_slice() => 'R',
-            m if m == vec![self.b(), self.a(), self.a(), self.a(), self.b()].as_slice() => 'S',
-            m if m == vec![self.b(), self.a(), self.a(), self.b(), self.a()].as_slice() => 'T',
-            m if m == vec![self.b(), self.a(), self.a(), self.b(), self.b()].as_slice() => 'U',
-            m if m == vec![self.b(), self.a(), self.a(), self.b(), self.b()].as_slice() => 'V',
-            m if m == vec![self.b(), self.a(), self.b(), self.a(), self.a()].as_slice() => 'W',
-            m if m == vec![self.b(), self.a(), self.b(), self.a(), self.b()].as_slice() => 'X',
-            m if m == vec![self.b(), self.a(), self.b(), self.b(), self.a()].as_slice() => 'Y',
-            m if m == vec![self.b(), self.a(), self.b(), self.b(), self.b()].as_slice() => 'Z',
-            _ => ' '
-        }
+        letter_of(V1_LETTERS, elems, |elem| self.is_a(elem), |elem| self.is_b(elem))
     }
 
     fn a(&self) -> T { self.elem_a.clone() }
 
     fn b(&self) -> T { self.elem_b.clone() }
 
-    fn encoded_group_size(&self) -> usize { 5 }
+    fn encoded_group_size(&self) -> usize { GROUP_SIZE }
 
     fn is_a(&self, elem: &T) -> bool {
-        elem == &self.a()
+        elem == &self.elem_a || self.a_aliases.contains(elem)
     }
 
     fn is_b(&self, elem: &T) -> bool {
-        elem == &self.b()
+        elem == &self.elem_b || self.b_aliases.contains(elem)
     }
 }
 
 // ---------------------------------------------- V2 ---------------------------------------------//
 
-#[derive(PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 /// A codec that encodes data of type `char`.
 ///
 /// The encoding is done by substituting with two given elements (`elem_a` and `elem_b`) of type `T`.
@@ -142,12 +213,33 @@ pub struct CharCodecV2<T> {
     pd: PhantomData<char>,
     elem_a: T,
     elem_b: T,
+    a_aliases: Vec<T>,
+    b_aliases: Vec<T>,
 }
 
 impl<T> CharCodecV2<T> {
-    /// Create a new `CharCodec` using elements `elem_a` and `elem_b` for substitution.
-    pub fn new(elem_a: T, elem_b: T) -> CharCodecV2<T> {
-        CharCodecV2 { pd: PhantomData, elem_a, elem_b }
+    /// Create a new `CharCodec` using elements `elem_a` and `elem_b` for substitution. Like
+    /// [CharCodec::new](struct.CharCodec.html#method.new), it can be called in constant contexts.
+    pub const fn new(elem_a: T, elem_b: T) -> CharCodecV2<T> {
+        CharCodecV2 { pd: PhantomData, elem_a, elem_b, a_aliases: Vec::new(), b_aliases: Vec::new() }
+    }
+
+    /// Accepts other symbols as the A and B elements during decoding, like `'.'` along with `'0'`. The encoding uses
+    /// only `elem_a` and `elem_b`. A symbol that is an A and a B element is taken as A.
+    pub fn with_aliases(mut self, a_aliases: &[T], b_aliases: &[T]) -> Self where T: Clone {
+        self.a_aliases.extend_from_slice(a_aliases);
+        self.b_aliases.extend_from_slice(b_aliases);
+        self
+    }
+}
+
+impl CharCodecV2<char> {
+    /// Accepts the other case of the A and B elements during decoding, e.g. `'a'` along with `'A'`.
+    pub fn case_insensitive(self) -> Self {
+        let other_case = |c: char| if c.is_lowercase() { c.to_uppercase().next() } else { c.to_lowercase().next() };
+        let a_aliases: Vec<char> = other_case(self.elem_a).into_iter().collect();
+        let b_aliases: Vec<char> = other_case(self.elem_b).into_iter().collect();
+        self.with_aliases(&a_aliases, &b_aliases)
     }
 }
 
@@ -168,82 +260,53 @@ impl<T: PartialEq + Clone> BaconCodec for CharCodecV2<T> {
     type ABTYPE = T;
     type CONTENT = char;
 
+    fn encode(&self, input: &[char]) -> Vec<T> {
+        let mut encoded = Vec::with_capacity(input.len() * GROUP_SIZE);
+        for elem in input {
+            push_code(V2_CODES, elem, &self.elem_a, &self.elem_b, &mut encoded);
+        }
+        debug_event!(input = input.len(), elements = encoded.len(), "encoded");
+        encoded
+    }
+
     fn encode_elem(&self, elem: &char) -> Vec<T> {
-        match elem {
-            'a' | 'A' => vec![self.a(), self.a(), self.a(), self.a(), self.a()],
-            'b' | 'B' => vec![self.a(), self.a(), self.a(), self.a(), self.b()],
-            'c' | 'C' => vec![self.a(), self.a(), self.a(), self.b(), self.a()],
-            'd' | 'D' => vec![self.a(), self.a(), self.a(), self.b(), self.b()],
-            'e' | 'E' => vec![self.a(), self.a(), self.b(), self.a(), self.a()],
-            'f' | 'F' => vec![self.a(), self.a(), self.b(), self.a(), self.b()],
-            'g' | 'G' => vec![self.a(), self.a(), self.b(), self.b(), self.a()],
-            'h' | 'H' => vec![self.a(), self.a(), self.b(), self.b(), self.b()],
-            'i' | 'I' => vec![self.a(), self.b(), self.a(), self.a(), self.a()],
-            'j' | 'J' => vec![self.a(), self.b(), self.a(), self.a(), self.b()],
-            'k' | 'K' => vec![self.a(), self.b(), self.a(), self.b(), self.a()],
-            'l' | 'L' => vec![self.a(), self.b(), self.a(), self.b(), self.b()],
-            'm' | 'M' => vec![self.a(), self.b(), self.b(), self.a(), self.a()],
-            'n' | 'N' => vec![self.a(), self.b(), self.b(), self.a(), self.b()],
-            'o' | 'O' => vec![self.a(), self.b(), self.b(), self.b(), self.a()],
-            'p' | 'P' => vec![self.a(), self.b(), self.b(), self.b(), self.b()],
-            'q' | 'Q' => vec![self.b(), self.a(), self.a(), self.a(), self.a()],
-            'r' | 'R' => vec![self.b(), self.a(), self.a(), self.a(), self.b()],
-            's' | 'S' => vec![self.b(), self.a(), self.a(), self.b(), self.a()],
-            't' | 'T' => vec![self.b(), self.a(), self.a(), self.b(), self.b()],
-            'u' | 'U' => vec![self.b(), self.a(), self.b(), self.a(), self.a()],
-            'v' | 'V' => vec![self.b(), self.a(), self.b(), self.a(), self.b()],
-            'w' | 'W' => vec![self.b(), self.a(), self.b(), self.b(), self.a()],
-            'x' | 'X' => vec![self.b(), self.a(), self.b(), self.b(), self.b()],
-            'y' | 'Y' => vec![self.b(), self.b(), self.a(), self.a(), self.a()],
-            'z' | 'Z' => vec![self.b(), self.b(), self.a(), self.a(), self.b()],
-            _ => vec![]
+        let mut encoded = Vec::with_capacity(GROUP_SIZE);
+        push_code(V2_CODES, elem, &self.elem_a, &self.elem_b, &mut encoded);
+        encoded
+    }
+
+    fn encode_groups(&self, input: &[char]) -> Groups {
+        encode_groups(V2_CODES, input)
+    }
+
+    fn decode_groups(&self, groups: &Groups) -> Vec<char> {
+        if groups.group_size() == GROUP_SIZE {
+            decode_groups(V2_LETTERS, groups)
+        } else {
+            self.decode(&groups.to_encoded(self))
         }
     }
 
+    fn decode_pattern(&self, pattern: u8) -> char {
+        letter_of_code(V2_LETTERS, pattern as usize)
+    }
+
     fn decode_elems(&self, elems: &[T]) -> char {
-        match elems {
-            m if m == vec![self.a(), self.a(), self.a(), self.a(), self.a()].as_slice() => 'A',
-            m if m == vec![self.a(), self.a(), self.a(), self.a(), self.b()].as_slice() => 'B',
-            m if m == vec![self.a(), self.a(), self.a(), self.b(), self.a()].as_slice() => 'C',
-            m if m == vec![self.a(), self.a(), self.a(), self.b(), self.b()].as_slice() => 'D',
-            m if m == vec![self.a(), self.a(), self.b(), self.a(), self.a()].as_slice() => 'E',
-            m if m == vec![self.a(), self.a(), self.b(), self.a(), self.b()].as_slice() => 'F',
-            m if m == vec![self.a(), self.a(), self.b(), self.b(), self.a()].as_slice() => 'G',
-            m if m == vec![self.a(), self.a(), self.b(), self.b(), self.b()].as_slice() => 'H',
-            m if m == vec![self.a(), self.b(), self.a(), self.a(), self.a()].as_slice() => 'I',
-            m if m == vec![self.a(), self.b(), self.a(), self.a(), self.b()].as_slice() => 'J',
-            m if m == vec![self.a(), self.b(), self.a(), self.b(), self.a()].as_slice() => 'K',
-            m if m == vec![self.a(), self.b(), self.a(), self.b(), self.b()].as_slice() => 'L',
-            m if m == vec![self.a(), self.b(), self.b(), self.a(), self.a()].as_slice() => 'M',
-            m if m == vec![self.a(), self.b(), self.b(), self.a(), self.b()].as_slice() => 'N',
-            m if m == vec![self.a(), self.b(), self.b(), self.b(), self.a()].as_slice() => 'O',
-            m if m == vec![self.a(), self.b(), self.b(), self.b(), self.b()].as_slice() => 'P',
-            m if m == vec![self.b(), self.a(), self.a(), self.a(), self.a()].as_slice() => 'Q',
-            m if m == vec![self.b(), self.a(), self.a(), self.a(), self.b()].as_slice() => 'R',
-            m if m == vec![self.b(), self.a(), self.a(), self.b(), self.a()].as_slice() => 'S',
-            m if m == vec![self.b(), self.a(), self.a(), self.b(), self.b()].as_slice() => 'T',
-            m if m == vec![self.b(), self.a(), self.b(), self.a(), self.a()].as_slice() => 'U',
-            m if m == vec![self.b(), self.a(), self.b(), self.a(), self.b()].as_slice() => 'V',
-            m if m == vec![self.b(), self.a(), self.b(), self.b(), self.a()].as_slice() => 'W',
-            m if m == vec![self.b(), self.a(), self.b(), self.b(), self.b()].as_slice() => 'X',
-            m if m == vec![self.b(), self.b(), self.a(), self.a(), self.a()].as_slice() => 'Y',
-            m if m == vec![self.b(), self.b(), self.a(), self.a(), self.b()].as_slice() => 'Z',
-            _ => ' '
-        }
+        letter_of(V2_LETTERS, elems, |elem| self.is_a(elem), |elem| self.is_b(elem))
     }
 
     fn a(&self) -> T { self.elem_a.clone() }
 
     fn b(&self) -> T { self.elem_b.clone() }
 
-    fn encoded_group_size(&self) -> usize { 5 }
+    fn encoded_group_size(&self) -> usize { GROUP_SIZE }
 
     fn is_a(&self, elem: &T) -> bool {
-        elem == &self.a()
+        elem == &self.elem_a || self.a_aliases.contains(elem)
     }
 
     fn is_b(&self, elem: &T) -> bool {
-        elem == &self.b()
+        elem == &self.elem_b || self.b_aliases.contains(elem)
     }
 }
 
@@ -328,4 +391,45 @@ mod char_codec_tests {
         let string = String::from_iter(decoded.iter());
         assert_eq!("MYSECRET", string);
     }
+
+    #[test]
+    fn decode_invalid_groups_to_spaces() {
+        let codec = CharCodec::new('a', 'b');
+        assert_eq!(codec.decode(&['b', 'b', 'b', 'b', 'b', 'a', 'x', 'a', 'a', 'a', 'a', 'b']), vec![' ', ' ', ' ']);
+        assert_eq!(CharCodecV2::new('a', 'b').decode(&['b', 'b', 'a', 'a', 'b', 'b', 'b', 'b', 'a', 'a']), vec!['Z', ' ']);
+        assert_eq!(codec.encode_elem(&'é'), Vec::<char>::new());
+        assert_eq!(codec.encode_elem(&'v'), codec.encode_elem(&'U'));
+    }
+
+    #[test]
+    fn decode_leniently() {
+        let codec = CharCodec::new('a', 'b');
+        // H = AABBB, I = ABAAA
+        let input: Vec<char> = "aab-bb ab\naaa.".chars().collect();
+        assert_eq!(codec.decode_lenient(&input), vec!['H', 'I']);
+        assert_eq!(codec.decode(&input), vec![' ', ' ', ' ']);
+        let codec = CharCodecV2::new(false, true);
+        assert_eq!(codec.decode_lenient(&[false, false, true, true, true]), vec!['H']);
+    }
+
+    #[test]
+    fn decode_with_aliases() {
+        // H = AABBB
+        let codec = CharCodec::new('0', '1').with_aliases(&['.', 'o'], &['-']);
+        assert_eq!(codec.decode(&['0', '.', '-', '1', '-']), vec!['H']);
+        assert_eq!(codec.encode(&['H']), vec!['0', '0', '1', '1', '1']);
+        assert!(codec.is_a(&'o') && !codec.is_b(&'o'));
+        let codec = CharCodecV2::new('a', 'b').case_insensitive();
+        assert_eq!(codec.decode(&['a', 'A', 'b', 'B', 'b']), vec!['H']);
+        assert_eq!(codec.decode_lenient(&['A', 'a', 'x', 'B', 'B', 'B']), vec!['H']);
+        assert_eq!(CharCodec::new('a', 'b').decode(&['a', 'A', 'b', 'B', 'b']), vec![' ']);
+    }
+
+    #[test]
+    fn decode_patterns() {
+        // H = AABBB
+        assert_eq!(CharCodec::new('a', 'b').decode_pattern(0b00111), 'H');
+        assert_eq!(CharCodecV2::new('a', 'b').decode_pattern(25), 'Z');
+        assert_eq!(CharCodecV2::new('a', 'b').decode_pattern(26), ' ');
+    }
 }
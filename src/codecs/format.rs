@@ -0,0 +1,127 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The textual layout of encoded text, as Baconian ciphertexts are conventionally written, like `AABBB ABAAA`.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::codecs::format::GroupFormat;
+//!
+//! let codec = CharCodec::new('A', 'B');
+//! let format = GroupFormat::new().with_separator('/').with_groups_per_line(2);
+//! let encoded = format.encode(&['H', 'I', 'H'], &codec);
+//! assert_eq!(encoded, "AABBB/ABAAA\nAABBB");
+//! assert_eq!(format.decode(" AABBB / ABAAA\r\n\tAAB BB ", &codec), vec!['H', 'I', 'H']);
+//! ```
+use crate::BaconCodec;
+
+/// How the groups of the encoded text are separated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GroupFormat {
+    separator: Option<char>,
+    groups_per_line: Option<usize>,
+}
+
+impl GroupFormat {
+    /// A format without separators, in which the groups are written one after the other.
+    pub fn new() -> GroupFormat {
+        GroupFormat::default()
+    }
+
+    /// Separates the groups with a character, like a space or `/`.
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.set_separator(Some(separator));
+        self
+    }
+
+    pub fn set_separator(&mut self, separator: Option<char>) {
+        self.separator = separator;
+    }
+
+    /// Starts a new line after every `groups` groups. A value of 0 keeps all the groups in a single line.
+    pub fn with_groups_per_line(mut self, groups: usize) -> Self {
+        self.set_groups_per_line(Some(groups).filter(|groups| *groups > 0));
+        self
+    }
+
+    pub fn set_groups_per_line(&mut self, groups: Option<usize>) {
+        self.groups_per_line = groups;
+    }
+
+    pub fn separator(&self) -> Option<char> {
+        self.separator
+    }
+
+    pub fn groups_per_line(&self) -> Option<usize> {
+        self.groups_per_line
+    }
+
+    /// Encodes the input and writes its groups in this format.
+    pub fn encode(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=char, CONTENT=char>) -> String {
+        let encoded = codec.encode(input);
+        let mut output = String::with_capacity(encoded.len() * 2);
+        for (index, group) in encoded.chunks(codec.encoded_group_size()).enumerate() {
+            if index > 0 {
+                match (self.groups_per_line, self.separator) {
+                    (Some(groups), _) if index % groups == 0 => output.push('\n'),
+                    (_, Some(separator)) => output.push(separator),
+                    _ => (),
+                }
+            }
+            output.extend(group.iter());
+        }
+        output
+    }
+
+    /// Decodes text that is written in this format, or any other layout of the groups. The separator and any
+    /// whitespace are ignored, while any other character that is not an element of the codec is decoded like
+    /// [BaconCodec::decode](../../trait.BaconCodec.html#method.decode) does.
+    pub fn decode(&self, text: &str, codec: &dyn BaconCodec<ABTYPE=char, CONTENT=char>) -> Vec<char> {
+        let encoded: Vec<char> = text.chars()
+            .filter(|c| !c.is_whitespace() && Some(*c) != self.separator)
+            .collect();
+        codec.decode(&encoded)
+    }
+}
+
+#[cfg(test)]
+mod format_tests {
+    use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+
+    use super::*;
+
+    #[test]
+    fn encode_with_separators() {
+        let codec = CharCodec::new('a', 'b');
+        // H = AABBB, I = ABAAA
+        let secret = ['H', 'I', 'H', 'I'];
+        assert_eq!(GroupFormat::new().encode(&secret, &codec), "aabbbabaaaaabbbabaaa");
+        assert_eq!(GroupFormat::new().with_separator(' ').encode(&secret, &codec), "aabbb abaaa aabbb abaaa");
+        assert_eq!(GroupFormat::new().with_groups_per_line(3).encode(&secret, &codec), "aabbbabaaaaabbb\nabaaa");
+        assert_eq!(GroupFormat::new().with_separator(' ').with_groups_per_line(0).groups_per_line(), None);
+        assert_eq!(GroupFormat::new().with_separator('/').encode(&[], &codec), "");
+    }
+
+    #[test]
+    fn decode_with_separators() {
+        let codec = CharCodecV2::new('0', '1');
+        let format = GroupFormat::new().with_separator('/');
+        let encoded = format.encode(&['J', 'A', 'Z'], &codec);
+        assert_eq!(encoded, "01001/00000/11001");
+        assert_eq!(format.decode(&encoded, &codec), vec!['J', 'A', 'Z']);
+        assert_eq!(format.decode("0 1 0 0\n1 / 000\t00", &codec), vec!['J', 'A']);
+        // Other characters are not ignored
+        assert_eq!(GroupFormat::new().decode("01001|00000", &codec), vec!['J', ' ', ' ']);
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoded groups as bit patterns.
+//!
+//! A [Groups](struct.Groups.html) stores every group of Bacon's elements as a `u8`, instead of a `Vec` of substitution
+//! elements. The first element of a group is its most significant bit and the Bacon's element B is the bit 1, so the
+//! groups of 5 elements are the numbers 0 to 31, like `AABBB` = 7. The substitution elements of a codec are used only
+//! when the groups are converted from or to them.
+//!
+//! The groups can also be written as binary or hex text, which is how most online Baconian tools and CyberChef
+//! exchange them: the binary text has a `0` for every A and a `1` for every B, and the hex text has two digits for
+//! every group, like CyberChef's `From Binary` with a byte length of the group size, followed by `To Hex`.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::codecs::groups::Groups;
+//! use bacon_cipher::BaconCodec;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let groups = Groups::from_encoded(&codec.encode(&['H', 'I']), &codec);
+//! assert_eq!(groups.to_binary_string(), "0011101000");
+//! assert_eq!(groups.to_hex(), "0708");
+//! assert_eq!(Groups::from_hex("07 08", 5).unwrap(), groups);
+//! assert_eq!(codec.decode_groups(&Groups::from_binary_string("00111 01000", 5).unwrap()), vec!['H', 'I']);
+//! ```
+use crate::{BaconCodec, errors};
+use crate::errors::BaconError;
+
+/// The largest group size that fits in the bit patterns.
+pub const MAX_GROUP_SIZE: usize = 8;
+
+/// A sequence of Bacon's elements, packed in groups of bit patterns. The last group may be incomplete.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Groups {
+    patterns: Vec<u8>,
+    group_size: usize,
+    len: usize,
+}
+
+impl Groups {
+    /// Creates empty `Groups` of the given size.
+    ///
+    /// Panics if the group size is 0 or larger than [MAX_GROUP_SIZE](constant.MAX_GROUP_SIZE.html).
+    pub fn new(group_size: usize) -> Groups {
+        assert!(group_size > 0 && group_size <= MAX_GROUP_SIZE, "Unsupported group size {}", group_size);
+        Groups { patterns: Vec::new(), group_size, len: 0 }
+    }
+
+    /// Creates `Groups` from complete bit patterns.
+    pub fn from_patterns(patterns: Vec<u8>, group_size: usize) -> Groups {
+        let mut groups = Groups::new(group_size);
+        groups.len = patterns.len() * group_size;
+        groups.patterns = patterns;
+        groups
+    }
+
+    /// Packs the elements of an iterator, which yields true for the Bacon's element A.
+    pub fn from_elements<I: IntoIterator<Item=bool>>(elements: I, group_size: usize) -> Groups {
+        let mut groups = Groups::new(group_size);
+        groups.extend(elements);
+        groups
+    }
+
+    /// Packs the substitution elements of a codec. Elements that are not the A element are taken as B.
+    pub fn from_encoded<AB, C: BaconCodec<ABTYPE=AB> + ?Sized>(encoded: &[AB], codec: &C) -> Groups {
+        Groups::from_elements(encoded.iter().map(|elem| codec.is_a(elem)), codec.encoded_group_size())
+    }
+
+    /// Appends an element, which is true for the Bacon's element A.
+    pub fn push(&mut self, is_a: bool) {
+        let offset = self.len % self.group_size;
+        if offset == 0 {
+            self.patterns.push(0);
+        }
+        if !is_a {
+            let last = self.patterns.len() - 1;
+            self.patterns[last] |= 1 << (self.group_size - 1 - offset);
+        }
+        self.len += 1;
+    }
+
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// The number of the elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The bit patterns of the complete groups.
+    pub fn complete(&self) -> &[u8] {
+        &self.patterns[..self.len / self.group_size]
+    }
+
+    /// The number of the elements of the incomplete group at the end, which is 0 if all the groups are complete.
+    pub fn incomplete_len(&self) -> usize {
+        self.len % self.group_size
+    }
+
+    /// The bit pattern of the incomplete group at the end, with its elements in the most significant bits of the group.
+    pub fn incomplete(&self) -> Option<u8> {
+        if self.incomplete_len() == 0 {
+            None
+        } else {
+            self.patterns.last().cloned()
+        }
+    }
+
+    /// Returns true if the element at the index is the Bacon's element A.
+    pub fn is_a(&self, index: usize) -> Option<bool> {
+        if index < self.len {
+            let pattern = self.patterns[index / self.group_size];
+            Some((pattern >> (self.group_size - 1 - index % self.group_size)) & 1 == 0)
+        } else {
+            None
+        }
+    }
+
+    /// The elements, which are true for the Bacon's element A.
+    pub fn elements(&self) -> impl Iterator<Item=bool> + '_ {
+        (0..self.len).map(move |index| self.is_a(index).unwrap_or(false))
+    }
+
+    /// Converts the elements of a group to the substitution elements of a codec.
+    pub fn to_encoded<AB, C: BaconCodec<ABTYPE=AB> + ?Sized>(&self, codec: &C) -> Vec<AB> {
+        self.elements().map(|is_a| if is_a { codec.a() } else { codec.b() }).collect()
+    }
+
+    /// Parses a binary text, with a `0` for the Bacon's element A and a `1` for B. Whitespace is ignored.
+    pub fn from_binary_string(text: &str, group_size: usize) -> errors::Result<Groups> {
+        let mut groups = Groups::new(group_size);
+        for (position, c) in text.char_indices().filter(|(_, c)| !c.is_whitespace()) {
+            match c {
+                '0' => groups.push(true),
+                '1' => groups.push(false),
+                _ => return Err(invalid_digit(c, position)),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Writes the elements as binary text, with a `0` for the Bacon's element A and a `1` for B.
+    pub fn to_binary_string(&self) -> String {
+        self.elements().map(|is_a| if is_a { '0' } else { '1' }).collect()
+    }
+
+    /// Parses a hex text, with two digits for every group. Whitespace is ignored and the digits may be upper or lower case.
+    pub fn from_hex(text: &str, group_size: usize) -> errors::Result<Groups> {
+        let mut patterns = Vec::new();
+        let mut high: Option<(usize, u32)> = None;
+        for (position, c) in text.char_indices().filter(|(_, c)| !c.is_whitespace()) {
+            let digit = c.to_digit(16).ok_or_else(|| invalid_digit(c, position))?;
+            match high.take() {
+                None => high = Some((position, digit)),
+                Some((start, high)) => {
+                    let pattern = high << 4 | digit;
+                    if pattern >> group_size != 0 {
+                        return Err(BaconError::InvalidInput {
+                            message: format!("The group {:02x} does not fit in {} elements", pattern, group_size),
+                            position: Some(start),
+                            source: None,
+                        });
+                    }
+                    patterns.push(pattern as u8);
+                }
+            }
+        }
+        match high {
+            None => Ok(Groups::from_patterns(patterns, group_size)),
+            Some((start, _)) => Err(BaconError::InvalidInput {
+                message: "A group needs two hex digits".to_string(),
+                position: Some(start),
+                source: None,
+            }),
+        }
+    }
+
+    /// Writes the complete groups as lowercase hex text, with two digits for every group. An incomplete group at the end
+    /// is left out, because it could not be told apart from a complete one.
+    pub fn to_hex(&self) -> String {
+        self.complete().iter().map(|pattern| format!("{:02x}", pattern)).collect()
+    }
+}
+
+fn invalid_digit(c: char, position: usize) -> BaconError {
+    BaconError::InvalidInput { message: format!("Unexpected character '{}'", c), position: Some(position), source: None }
+}
+
+impl Extend<bool> for Groups {
+    fn extend<I: IntoIterator<Item=bool>>(&mut self, iter: I) {
+        for is_a in iter {
+            self.push(is_a);
+        }
+    }
+}
+
+#[cfg(test)]
+mod groups_tests {
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn pack_elements() {
+        // H = AABBB, I = ABAAA
+        let groups = Groups::from_elements(vec![true, true, false, false, false, true, false, true], 5);
+        assert_eq!(groups.len(), 8);
+        assert_eq!(groups.complete(), &[7]);
+        assert_eq!(groups.incomplete_len(), 3);
+        assert_eq!(groups.incomplete(), Some(0b01000));
+        assert_eq!(groups.is_a(6), Some(false));
+        assert_eq!(groups.is_a(8), None);
+        assert_eq!(groups.elements().filter(|is_a| *is_a).count(), 4);
+        assert_eq!(Groups::from_patterns(vec![7, 8], 5).elements().collect::<Vec<bool>>(),
+                   vec![true, true, false, false, false, true, false, true, true, true]);
+    }
+
+    #[test]
+    fn convert_substitution_elements() {
+        let codec = CharCodec::new('x', 'y');
+        let groups = Groups::from_encoded(&codec.encode(&['H', 'I']), &codec);
+        assert_eq!(groups.complete(), &[7, 8]);
+        assert_eq!(groups.to_encoded(&codec), codec.encode(&['H', 'I']));
+        assert_eq!(codec.encode_groups(&['H', 'é', 'I']), groups);
+        assert_eq!(codec.decode_groups(&groups), vec!['H', 'I']);
+    }
+
+    #[test]
+    fn binary_and_hex_text() {
+        // H = AABBB, I = ABAAA
+        let groups = Groups::from_binary_string("00111 0100\n0 01", 5).unwrap();
+        assert_eq!(groups.complete(), &[7, 8]);
+        assert_eq!(groups.to_binary_string(), "001110100001");
+        assert_eq!(groups.to_hex(), "0708");
+        assert_eq!(Groups::from_hex("0708", 5).unwrap().complete(), groups.complete());
+        assert_eq!(Groups::from_hex(" 1F\t1e ", 5).unwrap().complete(), &[31, 30]);
+        assert!(Groups::from_hex("", 5).unwrap().is_empty());
+        match Groups::from_binary_string("0012", 5) {
+            Err(BaconError::InvalidInput { position, .. }) => assert_eq!(position, Some(3)),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        match Groups::from_hex("07 20", 5) {
+            Err(BaconError::InvalidInput { position, .. }) => assert_eq!(position, Some(3)),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(Groups::from_hex("070", 5).is_err());
+        assert!(Groups::from_hex("0g", 5).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn unsupported_group_size() {
+        Groups::new(9);
+    }
+}
@@ -11,4 +11,6 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-pub mod char_codec;
\ No newline at end of file
+pub mod char_codec;
+pub mod format;
+pub mod groups;
\ No newline at end of file
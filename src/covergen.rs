@@ -0,0 +1,213 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generation of cover texts, for users that do not have a suitable public text to hide a secret in.
+
+use std::collections::HashMap;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::BaconError;
+use crate::random::Random;
+use crate::wordlist::WordList;
+
+const MIN_SENTENCE_WORDS: usize = 5;
+const MAX_SENTENCE_WORDS: usize = 12;
+// How many times the generated cover is doubled in size while searching for a cover with enough capacity.
+const MAX_ATTEMPTS: usize = 8;
+
+enum Model {
+    Words(Vec<String>),
+    // Every word maps to the words that follow it in the corpus.
+    Markov {
+        starts: Vec<String>,
+        successors: HashMap<String, Vec<String>>,
+        ends: Vec<String>,
+    },
+}
+
+/// Generates plausible cover sentences, either from a [WordList](../wordlist/struct.WordList.html), or from a
+/// Markov chain that is trained with a corpus text.
+pub struct CoverGenerator {
+    model: Model,
+    seed: u64,
+}
+
+impl CoverGenerator {
+    /// Creates a `CoverGenerator` that produces sentences of random words of a word list.
+    pub fn from_wordlist(wordlist: &WordList) -> errors::Result<CoverGenerator> {
+        if wordlist.is_empty() {
+            Err(BaconError::GeneralError("Cannot generate a cover text from an empty word list".to_string()))
+        } else {
+            Ok(CoverGenerator {
+                model: Model::Words(wordlist.words().iter().map(|word| word.to_lowercase()).collect()),
+                seed: 0,
+            })
+        }
+    }
+
+    /// Creates a `CoverGenerator` that produces sentences using a Markov chain of the words of a corpus.
+    pub fn from_corpus(corpus: &str) -> errors::Result<CoverGenerator> {
+        let mut starts = Vec::new();
+        let mut ends = Vec::new();
+        let mut successors: HashMap<String, Vec<String>> = HashMap::new();
+        let mut previous: Option<String> = None;
+
+        for token in corpus.split_whitespace() {
+            let word: String = token.chars().filter(|c| c.is_alphabetic()).flat_map(|c| c.to_lowercase()).collect();
+            if word.is_empty() {
+                continue;
+            }
+            match previous.take() {
+                Some(previous) => successors.entry(previous).or_default().push(word.clone()),
+                None => starts.push(word.clone()),
+            }
+            if token.ends_with(['.', '!', '?']) {
+                ends.push(word);
+            } else {
+                previous = Some(word);
+            }
+        }
+
+        if starts.is_empty() {
+            Err(BaconError::GeneralError("Cannot generate a cover text from a corpus without words".to_string()))
+        } else {
+            Ok(CoverGenerator {
+                model: Model::Markov { starts, successors, ends },
+                seed: 0,
+            })
+        }
+    }
+
+    /// Sets the seed of the generation. The same seed always produces the same cover text.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Generates sentences that contain at least the given number of alphabetic characters.
+    pub fn generate(&self, letters: usize) -> String {
+        let mut random = Random::new(self.seed);
+        let mut sentences: Vec<String> = Vec::new();
+        let mut count = 0;
+        while count < letters || sentences.is_empty() {
+            let words = self.sentence(&mut random);
+            count += words.iter().map(|word| word.chars().count()).sum::<usize>();
+            let sentence = words.join(" ");
+            let mut chars = sentence.chars();
+            if let Some(first) = chars.next() {
+                sentences.push(first.to_uppercase().chain(chars).chain(".".chars()).collect());
+            }
+        }
+        sentences.join(" ")
+    }
+
+    fn sentence(&self, random: &mut Random) -> Vec<String> {
+        let length = MIN_SENTENCE_WORDS + random.below(MAX_SENTENCE_WORDS - MIN_SENTENCE_WORDS + 1);
+        match &self.model {
+            Model::Words(words) => {
+                (0..length).map(|_| words[random.below(words.len())].clone()).collect()
+            }
+            Model::Markov { starts, successors, ends } => {
+                let mut words = vec![starts[random.below(starts.len())].clone()];
+                while words.len() < MAX_SENTENCE_WORDS {
+                    let last = &words[words.len() - 1];
+                    if words.len() >= length && ends.contains(last) {
+                        break;
+                    }
+                    match successors.get(last) {
+                        Some(next) => {
+                            let word = next[random.below(next.len())].clone();
+                            words.push(word);
+                        }
+                        None => break,
+                    }
+                }
+                words
+            }
+        }
+    }
+
+    /// Generates a cover text with enough capacity for the secret and disguises the secret in it, using the given steganographer.
+    pub fn disguise<S, AB>(&self, steganographer: &S, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>>
+        where S: Steganographer<T=char> {
+        let mut letters = secret.iter().filter(|c| c.is_alphabetic()).count() * codec.encoded_group_size();
+        let mut result = Err(BaconError::GeneralError("Could not generate a cover text".to_string()));
+        for _ in 0..MAX_ATTEMPTS {
+            let public: Vec<char> = self.generate(letters).chars().collect();
+            result = steganographer.disguise(secret, &public, codec);
+            if result.is_ok() {
+                break;
+            }
+            letters = letters.max(1) * 2;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod covergen_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::whitespace::WhitespaceSteganographer;
+
+    use super::*;
+
+    const CORPUS: &str = "The cat sat on the mat. The dog sat on the log. A cat and a dog met on the road! \
+    The road was long and the day was warm. Was the dog happy? The cat was happy on the mat.";
+
+    #[test]
+    fn generate_from_a_word_list() {
+        let g = CoverGenerator::from_wordlist(&WordList::parse("one two three four")).unwrap().with_seed(3);
+        let text = g.generate(100);
+        assert!(text.chars().filter(|c| c.is_alphabetic()).count() >= 100);
+        assert!(text.ends_with('.'));
+        assert_eq!(text, g.generate(100));
+        assert!(CoverGenerator::from_wordlist(&WordList::default()).is_err());
+    }
+
+    #[test]
+    fn generate_from_a_corpus() {
+        let g = CoverGenerator::from_corpus(CORPUS).unwrap();
+        let text = g.generate(200);
+        assert!(text.chars().filter(|c| c.is_alphabetic()).count() >= 200);
+        assert!(text.split_whitespace().all(|word| {
+            let word: String = word.chars().filter(|c| c.is_alphabetic()).collect();
+            CORPUS.to_lowercase().contains(&word.to_lowercase())
+        }));
+        assert!(CoverGenerator::from_corpus("...").is_err());
+    }
+
+    #[test]
+    fn disguise_in_a_generated_cover() {
+        let codec = CharCodec::new('a', 'b');
+        let secret = ['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'];
+        let g = CoverGenerator::from_corpus(CORPUS).unwrap();
+
+        let s = LetterCaseSteganographer::new();
+        let disguised = g.disguise(&s, &secret, &codec).unwrap();
+        let string = String::from_iter(s.reveal(&disguised, &codec).unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+
+        let s = WhitespaceSteganographer::new();
+        let disguised = g.disguise(&s, &secret, &codec).unwrap();
+        let string = String::from_iter(s.reveal(&disguised, &codec).unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+}
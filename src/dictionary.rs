@@ -0,0 +1,260 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dictionary-assisted decoding, that segments a decoded stream of letters into words and finds where the meaningful
+//! text ends.
+//!
+//! A revealed secret does not contain spaces and is usually followed by the noise that the rest of the cover text
+//! decodes to.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::dictionary::{self, FrequencyDictionary};
+//! use bacon_cipher::BaconCodec;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let encoded = codec.encode(&"MYSECRETHLWHB".chars().collect::<Vec<char>>());
+//! let decoded = dictionary::decode_scored(&codec, &encoded, &FrequencyDictionary::english());
+//! assert_eq!(decoded.text(), "MY SECRET");
+//! assert_eq!(decoded.noise(), "HLWHB");
+//! ```
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::BaconCodec;
+use crate::errors::{self, BaconError};
+
+const ENGLISH: &str = include_str!("../data/english.txt");
+
+// The cost of a character that does not belong to any word, in addition to the cost of the rarest possible word
+const UNKNOWN_PENALTY: f64 = 10.0;
+
+/// Words with their frequencies, used to segment text that does not contain spaces.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrequencyDictionary {
+    counts: HashMap<String, u64>,
+    total: u64,
+    max_word_len: usize,
+}
+
+impl FrequencyDictionary {
+    /// The bundled dictionary of common English words.
+    pub fn english() -> FrequencyDictionary {
+        Self::parse(ENGLISH).expect("The bundled dictionary is valid")
+    }
+
+    /// Parses a `FrequencyDictionary` from text.
+    ///
+    /// Each line contains a word, optionally followed by its count, which defaults to 1. Empty lines and lines
+    /// starting with `#` are ignored. The words are case insensitive.
+    pub fn parse(text: &str) -> errors::Result<FrequencyDictionary> {
+        let mut dictionary = FrequencyDictionary::default();
+        let mut offset = 0;
+        for line in text.split('\n') {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                let mut fields = trimmed.split_whitespace();
+                let word = fields.next().unwrap_or_default();
+                let count = match fields.next() {
+                    Some(count) => count.parse::<u64>().map_err(|error| BaconError::InvalidInput {
+                        message: format!("Invalid count of the word {}: {}", word, error),
+                        position: Some(offset),
+                        source: None,
+                    })?,
+                    None => 1,
+                };
+                dictionary.insert(word, count);
+            }
+            offset += line.len() + 1;
+        }
+        Ok(dictionary)
+    }
+
+    /// Reads and parses a `FrequencyDictionary`. See [parse](struct.FrequencyDictionary.html#method.parse) for the format.
+    pub fn from_reader<R: Read>(mut reader: R) -> errors::Result<FrequencyDictionary> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Self::parse(&text)
+    }
+
+    /// Reads and parses the `FrequencyDictionary` of a file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> errors::Result<FrequencyDictionary> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Adds the `count` to the frequency of the `word`.
+    pub fn insert(&mut self, word: &str, count: u64) {
+        if word.is_empty() || count == 0 {
+            return;
+        }
+        let word = word.to_uppercase();
+        self.max_word_len = self.max_word_len.max(word.chars().count());
+        *self.counts.entry(word).or_insert(0) += count;
+        self.total += count;
+    }
+
+    /// The count of the word, or 0 if the word is not in the dictionary.
+    pub fn count(&self, word: &str) -> u64 {
+        self.counts.get(&word.to_uppercase()).cloned().unwrap_or(0)
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Segments the letters into the most likely sequence of words.
+    ///
+    /// The meaningful text is the longest sequence of words at the start of the letters. It ends at the first
+    /// character that does not belong to any word.
+    pub fn segment(&self, letters: &str) -> Segmentation {
+        self.segment_normalized(letters, &self.counts)
+    }
+
+    // Segments the letters with the counts of the words, as they are keyed in the `counts`.
+    fn segment_normalized(&self, letters: &str, counts: &HashMap<String, u64>) -> Segmentation {
+        let chars: Vec<char> = letters.to_uppercase().chars().collect();
+        let total = self.total.max(1) as f64;
+        let unknown_cost = total.ln() + UNKNOWN_PENALTY;
+        // The lowest cost of segmenting the first i characters and the start of the last token
+        let mut best: Vec<(f64, usize, bool)> = vec![(0.0, 0, false); chars.len() + 1];
+        for end in 1..=chars.len() {
+            best[end] = (best[end - 1].0 + unknown_cost, end - 1, false);
+            for start in end.saturating_sub(self.max_word_len)..end {
+                let candidate: String = chars[start..end].iter().collect();
+                if let Some(count) = counts.get(&candidate) {
+                    let cost = best[start].0 + total.ln() - (*count as f64).ln();
+                    if cost < best[end].0 {
+                        best[end] = (cost, start, true);
+                    }
+                }
+            }
+        }
+        let mut tokens = Vec::new();
+        let mut end = chars.len();
+        while end > 0 {
+            let (_, start, is_word) = best[end];
+            tokens.push((start, is_word));
+            end = start;
+        }
+        tokens.reverse();
+        let meaningful_end = tokens.iter()
+            .take_while(|(_, is_word)| *is_word)
+            .count();
+        let words_end = tokens.get(meaningful_end).map(|(start, _)| *start).unwrap_or(chars.len());
+        let mut words = Vec::new();
+        for (index, (start, _)) in tokens.iter().take(meaningful_end).enumerate() {
+            let end = tokens.get(index + 1).map(|(start, _)| *start).unwrap_or(chars.len());
+            words.push(chars[*start..end].iter().collect());
+        }
+        Segmentation {
+            words,
+            meaningful_end: words_end,
+            noise: chars[words_end..].iter().collect(),
+        }
+    }
+}
+
+/// The segmentation of a stream of letters into words, followed by a noise tail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segmentation {
+    words: Vec<String>,
+    meaningful_end: usize,
+    noise: String,
+}
+
+impl Segmentation {
+    /// The words of the meaningful text.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// The meaningful text, with its words separated by spaces.
+    pub fn text(&self) -> String {
+        self.words.join(" ")
+    }
+
+    /// The index of the character of the letters where the meaningful text ends.
+    pub fn meaningful_end(&self) -> usize {
+        self.meaningful_end
+    }
+
+    /// The characters after the meaningful text.
+    pub fn noise(&self) -> &str {
+        &self.noise
+    }
+}
+
+/// Decodes the encoded elements and segments the decoded letters with the dictionary.
+///
+/// The words of the dictionary are normalized with the codec, so that for example `LOVE` matches the decoded
+/// `LOUE` of the classic alphabet, where U and V share their code.
+pub fn decode_scored<AB>(
+    codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>,
+    encoded: &[AB],
+    dictionary: &FrequencyDictionary) -> Segmentation {
+    let decoded: String = codec.decode(encoded).into_iter().collect();
+    let mut counts = HashMap::with_capacity(dictionary.counts.len());
+    for (word, count) in dictionary.counts.iter() {
+        let chars: Vec<char> = word.chars().collect();
+        let normalized: String = codec.decode(&codec.encode(&chars)).into_iter().collect();
+        *counts.entry(normalized.to_uppercase()).or_insert(0) += *count;
+    }
+    dictionary.segment_normalized(&decoded, &counts)
+}
+
+#[cfg(test)]
+mod dictionary_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn segment_letters() {
+        let dictionary = FrequencyDictionary::english();
+        let segmentation = dictionary.segment("meetmeatdawnXQZ");
+        assert_eq!(segmentation.text(), "MEET ME AT DAWN");
+        assert_eq!(segmentation.meaningful_end(), 12);
+        assert_eq!(segmentation.noise(), "XQZ");
+        assert_eq!(dictionary.segment("QZX").words().len(), 0);
+    }
+
+    #[test]
+    fn parse_a_dictionary() {
+        let dictionary = FrequencyDictionary::parse("# Comment\nsecret 10\n\nmessage\nsecret 5\n").unwrap();
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.count("SECRET"), 15);
+        assert_eq!(dictionary.count("message"), 1);
+        assert!(matches!(FrequencyDictionary::parse("one 1\ntwo x\n"), Err(BaconError::InvalidInput { position: Some(6), .. })));
+        assert!(FrequencyDictionary::from_path("/non/existing/path").is_err());
+    }
+
+    #[test]
+    fn decode_with_the_classic_alphabet() {
+        let codec = CharCodec::new('a', 'b');
+        // V and U share their code, so LOVE is decoded as LOUE
+        let encoded = codec.encode(&Vec::from_iter("IloveMYSECRETHLWHB".chars()));
+        let decoded = decode_scored(&codec, &encoded, &FrequencyDictionary::english());
+        assert_eq!(decoded.text(), "I LOUE MY SECRET");
+        assert_eq!(decoded.noise(), "HLWHB");
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Steganographers that are selected at runtime.
+//!
+//! The methods of [Steganographer](../trait.Steganographer.html) are generic over the substitution elements of the codec,
+//! so a `dyn Steganographer` cannot be created. [DynSteganographer](trait.DynSteganographer.html) is its object safe
+//! counterpart for `char` steganographers, which uses codecs with `char` substitution elements. It is implemented for
+//! every `Steganographer<T=char>` and a `Box<dyn DynSteganographer>` is a `Steganographer` again, which accepts any codec.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodecV2;
+//! use bacon_cipher::dynamic::DynSteganographer;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//! use bacon_cipher::stega::whitespace::WhitespaceSteganographer;
+//! use bacon_cipher::Steganographer;
+//!
+//! let choice = "letter-case";
+//! let s: Box<dyn DynSteganographer> = match choice {
+//!     "letter-case" => Box::new(LetterCaseSteganographer::new()),
+//!     _ => Box::new(WhitespaceSteganographer::new()),
+//! };
+//!
+//! let codec = CharCodecV2::new(false, true);
+//! let disguised = s.disguise_str("My secret", "This is a public message that contains a secret one", &codec).unwrap();
+//! assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+//! ```
+use crate::{BaconCodec, errors, Steganographer};
+use crate::mapping::Mapping;
+
+/// A codec for `char` secrets that uses `char` substitution elements, as the [DynSteganographer](trait.DynSteganographer.html)s do.
+pub type CharElementsCodec<'a> = dyn BaconCodec<ABTYPE=char, CONTENT=char> + 'a;
+
+/// An object safe [Steganographer](../trait.Steganographer.html) of `char`s, which uses codecs with `char` substitution elements.
+///
+/// The methods are prefixed with `dyn_`, so that they are not ambiguous with the methods of the `Steganographer`s.
+/// It is `Send` and `Sync`, like all the steganographers of the crate, so that a boxed steganographer can be shared
+/// across threads.
+pub trait DynSteganographer: Send + Sync {
+    /// See [Steganographer::disguise](../trait.Steganographer.html#tymethod.disguise).
+    fn dyn_disguise(&self, secret: &[char], public: &[char], codec: &CharElementsCodec) -> errors::Result<Vec<char>>;
+
+    /// See [Steganographer::reveal](../trait.Steganographer.html#tymethod.reveal).
+    fn dyn_reveal(&self, input: &[char], codec: &CharElementsCodec) -> errors::Result<Vec<char>>;
+
+    /// See [Steganographer::capacity](../trait.Steganographer.html#method.capacity).
+    fn dyn_capacity(&self, public: &[char]) -> errors::Result<usize>;
+
+    /// See [Steganographer::mapping](../trait.Steganographer.html#method.mapping).
+    fn dyn_mapping(&self, input: &[char]) -> errors::Result<Mapping>;
+
+    /// See [Steganographer::strip](../trait.Steganographer.html#method.strip).
+    fn dyn_strip(&self, input: &[char]) -> errors::Result<Vec<char>>;
+}
+
+impl<S: Steganographer<T=char> + Send + Sync> DynSteganographer for S {
+    fn dyn_disguise(&self, secret: &[char], public: &[char], codec: &CharElementsCodec) -> errors::Result<Vec<char>> {
+        self.disguise(secret, public, codec)
+    }
+
+    fn dyn_reveal(&self, input: &[char], codec: &CharElementsCodec) -> errors::Result<Vec<char>> {
+        self.reveal(input, codec)
+    }
+
+    fn dyn_capacity(&self, public: &[char]) -> errors::Result<usize> {
+        self.capacity(public)
+    }
+
+    fn dyn_mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        self.mapping(input)
+    }
+
+    fn dyn_strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        self.strip(input)
+    }
+}
+
+impl Steganographer for Box<dyn DynSteganographer> {
+    type T = char;
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        self.as_ref().dyn_disguise(secret, public, &CharElements { codec })
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        self.as_ref().dyn_reveal(input, &CharElements { codec })
+    }
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        self.as_ref().dyn_capacity(public)
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        self.as_ref().dyn_mapping(input)
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        self.as_ref().dyn_strip(input)
+    }
+}
+
+// Substitutes the elements of a codec with the characters 'a' and 'b'.
+struct CharElements<'a, AB> {
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>,
+}
+
+impl<'a, AB> CharElements<'a, AB> {
+    fn to_char(&self, elem: &AB) -> char {
+        if self.codec.is_a(elem) { 'a' } else { 'b' }
+    }
+
+    fn to_elem(&self, c: &char) -> AB {
+        if *c == 'a' { self.codec.a() } else { self.codec.b() }
+    }
+}
+
+impl<'a, AB> BaconCodec for CharElements<'a, AB> {
+    type ABTYPE = char;
+    type CONTENT = char;
+
+    fn encode(&self, input: &[char]) -> Vec<char> {
+        self.codec.encode(input).iter().map(|elem| self.to_char(elem)).collect()
+    }
+
+    fn encode_elem(&self, elem: &char) -> Vec<char> {
+        self.codec.encode_elem(elem).iter().map(|elem| self.to_char(elem)).collect()
+    }
+
+    fn decode(&self, input: &[char]) -> Vec<char> {
+        let elems: Vec<AB> = input.iter().map(|c| self.to_elem(c)).collect();
+        self.codec.decode(&elems)
+    }
+
+    fn decode_elems(&self, elems: &[char]) -> char {
+        let elems: Vec<AB> = elems.iter().map(|c| self.to_elem(c)).collect();
+        self.codec.decode_elems(&elems)
+    }
+
+    fn a(&self) -> char {
+        'a'
+    }
+
+    fn b(&self) -> char {
+        'b'
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        self.codec.encoded_group_size()
+    }
+
+    fn is_a(&self, elem: &char) -> bool {
+        *elem == 'a'
+    }
+
+    fn is_b(&self, elem: &char) -> bool {
+        *elem == 'b'
+    }
+}
+
+#[cfg(test)]
+mod dynamic_tests {
+    use std::iter::FromIterator;
+
+    use crate::carrier::SegmentKind;
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::morse::{MorseSteganographer, to_morse};
+    use crate::stega::protected::{ProtectedSteganographer, Region};
+
+    use super::*;
+
+    #[test]
+    fn select_at_runtime() {
+        let codec = CharCodec::new('X', 'Y');
+        let steganographers: Vec<Box<dyn DynSteganographer>> = vec![
+            Box::new(LetterCaseSteganographer::new()),
+            Box::new(MorseSteganographer::new()),
+        ];
+        let publics = vec!["Hello world".to_string(), to_morse("CQ CQ de W1AW K")];
+        for (s, public) in steganographers.iter().zip(publics) {
+            let public = Vec::from_iter(public.chars());
+            assert_eq!(s.dyn_capacity(&public).unwrap(), Steganographer::capacity(s, &public).unwrap());
+            let disguised = s.disguise(&['H'], &public, &codec).unwrap();
+            assert_eq!(s.reveal(&disguised, &codec).unwrap()[0], 'H');
+            // H = AABBB
+            let kinds: Vec<bool> = s.mapping(&disguised).unwrap().slots().iter().take(5).map(|slot| slot.kind() == SegmentKind::A).collect();
+            assert_eq!(kinds, vec![true, true, false, false, false]);
+        }
+    }
+
+    #[test]
+    fn wrap_a_boxed_steganographer() {
+        let codec = CharCodec::new('a', 'b');
+        let boxed: Box<dyn DynSteganographer> = Box::new(LetterCaseSteganographer::new());
+        let s = ProtectedSteganographer::new(boxed).with_region(Region::Range(0..6));
+        let disguised = s.disguise_str("H", "Hello, world", &codec).unwrap();
+        assert_eq!(disguised, "Hello, woRLD");
+        assert_eq!(s.strip(&Vec::from_iter(disguised.chars())).unwrap(), Vec::from_iter("Hello, world".chars()));
+    }
+}
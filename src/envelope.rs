@@ -0,0 +1,306 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A versioned envelope of hidden messages, whose header tells a revealer how the message was processed.
+//!
+//! The header precedes the encoded secret, as Bacon's elements with the bits of its fields, from the most significant,
+//! and `B` for the bit 1:
+//!
+//! | Field     | Bits | Content                                                                   |
+//! |-----------|------|---------------------------------------------------------------------------|
+//! | magic     | 10   | `BABBAABBBA`, which marks the start of an envelope                        |
+//! | version   | 3    | The version of the format, which is 1                                     |
+//! | flags     | 5    | Compressed, encrypted, error correction, timestamped and a reserved bit   |
+//! | length    | 16   | The number of the groups of the encoded secret                            |
+//! | timestamp | 40   | The seconds since the UNIX epoch, only if the timestamped flag is set     |
+//!
+//! The compressed, encrypted and error correction flags describe the processing of the secret by other layers, before
+//! the disguise, so that new layers remain compatible with the envelopes of the older ones.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::envelope::{self, Envelope};
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = LetterCaseSteganographer::new();
+//! let public: Vec<char> = "It is a truth universally acknowledged, that a single man in possession of a good fortune, \
+//!     must be in want of a wife.".chars().collect();
+//! let disguised = envelope::disguise(&s, &Envelope::new().with_timestamp(1_234_567_890), &['H', 'I'], &public, &codec).unwrap();
+//! let (envelope, secret) = envelope::reveal(&s, &disguised, &codec).unwrap();
+//! assert_eq!(envelope.timestamp(), Some(1_234_567_890));
+//! assert_eq!(secret, vec!['H', 'I']);
+//! ```
+use crate::{BaconCodec, errors};
+use crate::carrier::Carrier;
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+
+/// The version of the envelopes that are written.
+pub const VERSION: u8 = 1;
+
+const MAGIC: u64 = 0b10_1100_1110;
+const MAGIC_BITS: usize = 10;
+const VERSION_BITS: usize = 3;
+const FLAGS_BITS: usize = 5;
+const LENGTH_BITS: usize = 16;
+const TIMESTAMP_BITS: usize = 40;
+
+const COMPRESSED: u64 = 0b10000;
+const ENCRYPTED: u64 = 0b01000;
+const ERROR_CORRECTION: u64 = 0b00100;
+const TIMESTAMPED: u64 = 0b00010;
+
+/// The header of a hidden message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope {
+    version: u8,
+    compressed: bool,
+    encrypted: bool,
+    error_correction: bool,
+    timestamp: Option<u64>,
+}
+
+impl Envelope {
+    /// Creates an `Envelope` of the current [VERSION](constant.VERSION.html), without flags.
+    pub fn new() -> Envelope {
+        Envelope { version: VERSION, compressed: false, encrypted: false, error_correction: false, timestamp: None }
+    }
+
+    /// Marks the secret as compressed.
+    pub fn with_compressed(mut self, compressed: bool) -> Self {
+        self.set_compressed(compressed);
+        self
+    }
+
+    pub fn set_compressed(&mut self, compressed: bool) {
+        self.compressed = compressed;
+    }
+
+    /// Marks the secret as encrypted.
+    pub fn with_encrypted(mut self, encrypted: bool) -> Self {
+        self.set_encrypted(encrypted);
+        self
+    }
+
+    pub fn set_encrypted(&mut self, encrypted: bool) {
+        self.encrypted = encrypted;
+    }
+
+    /// Marks the secret as protected by an error correcting code.
+    pub fn with_error_correction(mut self, error_correction: bool) -> Self {
+        self.set_error_correction(error_correction);
+        self
+    }
+
+    pub fn set_error_correction(&mut self, error_correction: bool) {
+        self.error_correction = error_correction;
+    }
+
+    /// Stamps the envelope with the seconds since the UNIX epoch, like the ones of
+    /// `SystemTime::now().duration_since(UNIX_EPOCH)`. Only the lowest 40 bits are kept.
+    pub fn with_timestamp(mut self, seconds: u64) -> Self {
+        self.set_timestamp(Some(seconds));
+        self
+    }
+
+    pub fn set_timestamp(&mut self, seconds: Option<u64>) {
+        self.timestamp = seconds.map(|seconds| seconds & ((1 << TIMESTAMP_BITS) - 1));
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn compressed(&self) -> bool {
+        self.compressed
+    }
+
+    pub fn encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    pub fn error_correction(&self) -> bool {
+        self.error_correction
+    }
+
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// Encodes the secret and prepends the header of the envelope.
+    pub fn seal<AB>(&self, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Groups> {
+        BaconError::check_secret(secret)?;
+        let payload = codec.encode_groups(secret);
+        let length = payload.complete().len();
+        if length >> LENGTH_BITS != 0 {
+            return Err(BaconError::GeneralError(format!("Cannot seal a secret of more than {} groups", (1usize << LENGTH_BITS) - 1)));
+        }
+        let flags = [(self.compressed, COMPRESSED), (self.encrypted, ENCRYPTED), (self.error_correction, ERROR_CORRECTION), (self.timestamp.is_some(), TIMESTAMPED)]
+            .iter()
+            .filter(|(set, _)| *set)
+            .fold(0, |flags, (_, flag)| flags | flag);
+        let mut sealed = Groups::new(codec.encoded_group_size());
+        push_bits(&mut sealed, MAGIC, MAGIC_BITS);
+        push_bits(&mut sealed, self.version as u64, VERSION_BITS);
+        push_bits(&mut sealed, flags, FLAGS_BITS);
+        push_bits(&mut sealed, length as u64, LENGTH_BITS);
+        if let Some(timestamp) = self.timestamp {
+            push_bits(&mut sealed, timestamp, TIMESTAMP_BITS);
+        }
+        sealed.extend(payload.elements());
+        Ok(sealed)
+    }
+
+    /// Parses the header of a sealed secret and decodes the secret. The elements that follow the secret are ignored.
+    ///
+    /// Fails if the elements do not start with an envelope, or if its version is newer than the current one.
+    pub fn open<AB>(sealed: &Groups, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<(Envelope, Vec<char>)> {
+        let mut bits = Bits { elements: sealed.elements(), read: 0 };
+        if bits.read(MAGIC_BITS)? != MAGIC {
+            return Err(invalid_envelope("The input does not start with an envelope", 0));
+        }
+        let version = bits.read(VERSION_BITS)? as u8;
+        if version > VERSION {
+            return Err(invalid_envelope(&format!("Unsupported envelope version {}", version), MAGIC_BITS));
+        }
+        let flags = bits.read(FLAGS_BITS)?;
+        let length = bits.read(LENGTH_BITS)? as usize;
+        let timestamp = if flags & TIMESTAMPED != 0 { Some(bits.read(TIMESTAMP_BITS)?) } else { None };
+        let envelope = Envelope {
+            version,
+            compressed: flags & COMPRESSED != 0,
+            encrypted: flags & ENCRYPTED != 0,
+            error_correction: flags & ERROR_CORRECTION != 0,
+            timestamp,
+        };
+        let payload_len = length * codec.encoded_group_size();
+        let payload = Groups::from_elements(bits.elements.by_ref().take(payload_len), codec.encoded_group_size());
+        if payload.len() < payload_len {
+            return Err(invalid_envelope("The secret of the envelope is truncated", bits.read + payload.len()));
+        }
+        Ok((envelope, codec.decode_groups(&payload)))
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Envelope {
+        Envelope::new()
+    }
+}
+
+/// Seals the secret in the envelope and writes it to the public input of the carrier.
+pub fn disguise<C, AB>(carrier: &C, envelope: &Envelope, secret: &[char], public: &[C::Unit], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<C::Unit>>
+    where C: Carrier + ?Sized {
+    let symbols: Vec<bool> = envelope.seal(secret, codec)?.elements().collect();
+    let available = carrier.capacity(public)?;
+    if available < symbols.len() {
+        return Err(BaconError::InsufficientCapacity { required: symbols.len(), available, unit: CapacityUnit::Carriers });
+    }
+    carrier.write_symbols(&symbols, public)
+}
+
+/// Reads the elements of the input of the carrier and [opens](struct.Envelope.html#method.open) their envelope.
+pub fn reveal<C, AB>(carrier: &C, input: &[C::Unit], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<(Envelope, Vec<char>)>
+    where C: Carrier + ?Sized {
+    let sealed = Groups::from_elements(carrier.read_symbols(input)?, codec.encoded_group_size());
+    Envelope::open(&sealed, codec)
+}
+
+// Appends the lowest `count` bits of the value, from the most significant.
+fn push_bits(groups: &mut Groups, value: u64, count: usize) {
+    groups.extend((0..count).rev().map(|bit| value >> bit & 1 == 0));
+}
+
+// Reads the bits of the header from the elements.
+struct Bits<I> {
+    elements: I,
+    read: usize,
+}
+
+impl<I: Iterator<Item=bool>> Bits<I> {
+    fn read(&mut self, count: usize) -> errors::Result<u64> {
+        let mut value = 0;
+        for _ in 0..count {
+            match self.elements.next() {
+                Some(is_a) => value = value << 1 | !is_a as u64,
+                None => return Err(invalid_envelope("The header of the envelope is truncated", self.read)),
+            }
+            self.read += 1;
+        }
+        Ok(value)
+    }
+}
+
+// The position is the index of the element at which the envelope is not valid.
+fn invalid_envelope(message: &str, position: usize) -> BaconError {
+    BaconError::InvalidInput { message: message.to_string(), position: Some(position), source: None }
+}
+
+#[cfg(test)]
+mod envelope_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn seal_and_open() {
+        let codec = CharCodec::new('a', 'b');
+        let envelope = Envelope::new().with_compressed(true).with_error_correction(true);
+        let sealed = envelope.seal(&['H', 'I'], &codec).unwrap();
+        assert_eq!(sealed.len(), 34 + 10);
+        // H = AABBB, I = ABAAA
+        assert_eq!(&sealed.to_encoded(&codec)[..18], Vec::from_iter("babbaabbbaaabbabaa".chars()).as_slice());
+        assert_eq!(Envelope::open(&sealed, &codec).unwrap(), (envelope, vec!['H', 'I']));
+
+        let stamped = Envelope::new().with_encrypted(true).with_timestamp(u64::MAX);
+        assert_eq!(stamped.timestamp(), Some((1 << 40) - 1));
+        let mut sealed = stamped.seal(&['H'], &codec).unwrap();
+        sealed.extend(vec![true; 7]);
+        let (opened, secret) = Envelope::open(&sealed, &codec).unwrap();
+        assert_eq!(opened, stamped);
+        assert!(opened.encrypted() && !opened.compressed());
+        assert_eq!(secret, vec!['H']);
+    }
+
+    #[test]
+    fn open_invalid_envelopes() {
+        let codec = CharCodec::new('a', 'b');
+        assert!(matches!(Envelope::open(&codec.encode_groups(&['H', 'E', 'L', 'L', 'O']), &codec),
+            Err(BaconError::InvalidInput { position: Some(0), .. })));
+        let sealed = Envelope::new().seal(&['H', 'I'], &codec).unwrap();
+        let truncated = Groups::from_elements(sealed.elements().take(40), 5);
+        assert!(matches!(Envelope::open(&truncated, &codec), Err(BaconError::InvalidInput { position: Some(40), .. })));
+        let header = Groups::from_elements(sealed.elements().take(20), 5);
+        assert!(matches!(Envelope::open(&header, &codec), Err(BaconError::InvalidInput { position: Some(20), .. })));
+        let mut newer = Groups::new(5);
+        push_bits(&mut newer, MAGIC, MAGIC_BITS);
+        push_bits(&mut newer, 2, VERSION_BITS);
+        assert!(matches!(Envelope::open(&newer, &codec), Err(BaconError::InvalidInput { position: Some(10), .. })));
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = Vec::from_iter("It is a truth universally acknowledged, that a single man in possession of a good fortune".chars());
+        let disguised = disguise(&s, &Envelope::new(), &['H', 'I'], &public, &codec).unwrap();
+        assert_eq!(reveal(&s, &disguised, &codec).unwrap(), (Envelope::new(), vec!['H', 'I']));
+        assert!(matches!(disguise(&s, &Envelope::new().with_timestamp(0), &['H', 'I'], &public, &codec),
+            Err(BaconError::InsufficientCapacity { required: 84, available: 73, .. })));
+    }
+}
@@ -11,24 +11,135 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use std::{fmt, result};
+use std::{fmt, io, result, str};
 use std::error::Error;
+use std::sync::Arc;
 
 pub type Result<T> = result::Result<T, BaconError>;
 
+/// The errors of the crate.
+///
+/// The `GeneralError`, `CodecError` and `SteganographerError` describe invalid configurations. The rest of the variants
+/// carry the data of the cause, so that programs can handle them without parsing the messages.
+///
+/// The errors of the underlying readers and parsers are available through [source](#method.source).
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
 pub enum BaconError {
     GeneralError(String),
     CodecError(String),
     SteganographerError(String),
+    /// The secret contains a `character` that cannot be encoded, at the char `position`.
+    InvalidSecret { character: char, position: usize },
+    /// The public input can carry `available` elements of the encoded secret, out of the `required` ones.
+    InsufficientCapacity { required: usize, available: usize, unit: CapacityUnit },
+    /// The public input contains a `character` of a marker, at the char `position`.
+    MarkerInInput { character: char, position: usize },
+    /// A `found` marker, like the start tag of an element, is nested in an `expected` marker of the other Bacon's element.
+    UnexpectedMarker { expected: String, found: String },
+    /// The input cannot be parsed. The `position` is the byte offset of the error, if it is known.
+    InvalidInput { message: String, position: Option<usize>, source: Option<SharedError> },
+    /// The secret that is revealed from a disguised output differs from the disguised one, starting from the symbol at
+    /// the `position` of the secret and the element at the `element` index of the encoded secret. The `found` symbol is
+    /// `None` if the revealed secret is shorter.
+    RoundTripMismatch { position: usize, element: usize, expected: String, found: Option<String> },
+    /// An I/O error occured.
+    Io(SharedError),
+    /// The input is not valid UTF-8.
+    Utf8(str::Utf8Error),
+}
+
+impl BaconError {
+    /// Returns an `InvalidSecret` error for the first character of the secret that is neither alphabetic nor a space.
+    pub fn check_secret(secret: &[char]) -> Result<()> {
+        match secret.iter().position(|s| !s.is_alphabetic() && s != &' ') {
+            Some(position) => Err(BaconError::InvalidSecret { character: secret[position], position }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The parts of the public input that carry the elements of the encoded secret.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum CapacityUnit {
+    Letters,
+    Words,
+    Spaces,
+    PunctuationCharacters,
+    /// The characters or markup that a steganographer finds to carry Bacon's elements, of any kind.
+    Carriers,
+}
+
+/// An error of an underlying reader or parser, shared so that the `BaconError` can be cloned.
+///
+/// Two `SharedError`s are equal if they are the same error, or if their messages are equal.
+#[derive(Debug, Clone)]
+pub struct SharedError(Arc<dyn Error + Send + Sync>);
+
+impl SharedError {
+    pub fn new<E: Error + Send + Sync + 'static>(error: E) -> SharedError {
+        SharedError(Arc::new(error))
+    }
+
+    /// The wrapped error. Use `downcast_ref` to retrieve its concrete type.
+    pub fn get(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl PartialEq for SharedError {
+    fn eq(&self, other: &SharedError) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl Eq for SharedError {}
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for CapacityUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CapacityUnit::Letters => write!(f, "letters"),
+            CapacityUnit::Words => write!(f, "words of the dictionary"),
+            CapacityUnit::Spaces => write!(f, "spaces between words"),
+            CapacityUnit::PunctuationCharacters => write!(f, "punctuation characters"),
+            CapacityUnit::Carriers => write!(f, "carriers of Bacon's elements"),
+        }
+    }
 }
 
 impl fmt::Display for BaconError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &BaconError::GeneralError(ref message) => write!(f, "{}", message),
-            &BaconError::CodecError(ref message) => write!(f, "{}", message),
-            &BaconError::SteganographerError(ref message) => write!(f, "{}", message),
+            BaconError::GeneralError(message) => write!(f, "{}", message),
+            BaconError::CodecError(message) => write!(f, "{}", message),
+            BaconError::SteganographerError(message) => write!(f, "{}", message),
+            BaconError::InvalidSecret { character, position } => {
+                write!(f, "The secret can contain only alphabetic characters. This is an invalid secret: found {:?} at {}", character, position)
+            }
+            BaconError::InsufficientCapacity { required, available, unit } => {
+                write!(f, "The public input should have at least {} {}. It was found to have {}", required, unit, available)
+            }
+            BaconError::MarkerInInput { character, position } => {
+                write!(f, "The public input contains the marker character {:?} at {}", character, position)
+            }
+            BaconError::UnexpectedMarker { expected, found } => write!(f, "Found a {} element nested in a {} element", found, expected),
+            BaconError::InvalidInput { message, position: Some(position), .. } => write!(f, "Invalid input at {}: {}", position, message),
+            BaconError::InvalidInput { message, position: None, .. } => write!(f, "Invalid input: {}", message),
+            BaconError::RoundTripMismatch { position, element, expected, found: Some(found) } => {
+                write!(f, "The revealed secret diverges at {} (element {}): expected {} but found {}", position, element, expected, found)
+            }
+            BaconError::RoundTripMismatch { position, element, expected, found: None } => {
+                write!(f, "The revealed secret ends at {} (element {}): expected {}", position, element, expected)
+            }
+            BaconError::Io(error) => write!(f, "An I/O error occured: {}", error),
+            BaconError::Utf8(error) => write!(f, "Invalid UTF-8: {}", error),
         }
     }
 }
@@ -39,6 +150,70 @@ impl Error for BaconError {
             BaconError::GeneralError(_) => "A general error occured",
             BaconError::CodecError(_) => "An error coming from a codec occured",
             BaconError::SteganographerError(_) => "An error coming from a steganographer occured",
+            BaconError::InvalidSecret { .. } => "The secret contains a character that cannot be encoded",
+            BaconError::InsufficientCapacity { .. } => "The public input is too small for the secret",
+            BaconError::MarkerInInput { .. } => "The public input contains a marker character",
+            BaconError::UnexpectedMarker { .. } => "An element is nested in an element of the other type",
+            BaconError::InvalidInput { .. } => "The input cannot be parsed",
+            BaconError::RoundTripMismatch { .. } => "The secret cannot be revealed from the disguised output",
+            BaconError::Io(_) => "An I/O error occured",
+            BaconError::Utf8(_) => "The input is not valid UTF-8",
         }
     }
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BaconError::InvalidInput { source: Some(source), .. } => Some(source.get()),
+            BaconError::Io(error) => Some(error.get()),
+            BaconError::Utf8(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BaconError {
+    fn from(error: io::Error) -> BaconError {
+        BaconError::Io(SharedError::new(error))
+    }
+}
+
+impl From<str::Utf8Error> for BaconError {
+    fn from(error: str::Utf8Error) -> BaconError {
+        BaconError::Utf8(error)
+    }
+}
+
+#[cfg(test)]
+mod errors_tests {
+    use super::*;
+
+    #[test]
+    fn check_secret() {
+        assert!(BaconError::check_secret(&['M', 'y', ' ', 's']).is_ok());
+        assert_eq!(BaconError::check_secret(&['M', 'y', '!', '?']),
+                   Err(BaconError::InvalidSecret { character: '!', position: 2 }));
+    }
+
+    #[test]
+    fn display() {
+        let error = BaconError::InsufficientCapacity { required: 40, available: 9, unit: CapacityUnit::Letters };
+        assert_eq!(error.to_string(), "The public input should have at least 40 letters. It was found to have 9");
+        let error = BaconError::InvalidInput { message: "unexpected end".to_string(), position: Some(3), source: None };
+        assert_eq!(error.to_string(), "Invalid input at 3: unexpected end");
+    }
+
+    #[test]
+    fn source() {
+        let error = BaconError::from(io::Error::new(io::ErrorKind::UnexpectedEof, "the input ended"));
+        assert_eq!(error.to_string(), "An I/O error occured: the input ended");
+        let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(error.clone(), error);
+
+        let bytes = vec![0x66, 0xff];
+        let utf8_error = str::from_utf8(&bytes).unwrap_err();
+        let error = BaconError::from(utf8_error);
+        assert_eq!(error.source().unwrap().downcast_ref::<str::Utf8Error>(), Some(&utf8_error));
+        assert!(BaconError::GeneralError("general".to_string()).source().is_none());
+    }
 }
@@ -35,11 +35,11 @@ The crate offers codecs that _encode / decode_ and  steganographers that _hide /
 
 **Available steganographers:**
 
-* LetterCaseSteganographer: Applies steganography based on the case of the characters.
+* LetterCaseSteganographer: Applies steganography based on the case of the characters. The letters that follow the secret may be padded with the secret repeated, random letters or unused groups.
 
     E.g. Lowercase for Bacon's element A, capital for Bacon's element B.
 
-* MarkdownSteganographer: Applies steganography based on Markdown tags that surround elements.
+* MarkdownSteganographer: Applies steganography based on Markdown tags that surround elements. The tags may be symmetric, like `*`, or asymmetric, like `<sub>` and `</sub>`.
 
     E.g. Sourround an element with `**` for Bacon's element A and the rest of the elements are considered as Bacon's element B.
 
@@ -47,6 +47,141 @@ The crate offers codecs that _encode / decode_ and  steganographers that _hide /
 
     E.g. Sourround an element with `<b>` and `</b>` for Bacon's element A and with `<i>` and `</i>` for Bacon's element B.
 
+* WhitespaceSteganographer: Applies steganography based on the number of spaces between words.
+
+    E.g. A single space for Bacon's element A and a double space for Bacon's element B. The visible characters remain untouched.
+
+* MathAlphanumericSteganographer: Applies steganography based on the style of the letters, using the Unicode _Mathematical Alphanumeric Symbols_.
+
+    E.g. Plain letters for Bacon's element A and bold letters (`𝐚`, `𝐛`, ...) for Bacon's element B.
+
+* PunctuationSteganographer: Applies steganography based on the form of typographically equivalent punctuation characters.
+
+    E.g. A straight apostrophe (`'`) for Bacon's element A and a curly one (`’`) for Bacon's element B.
+
+* LeetSteganographer: Applies steganography based on leet speak, substituting letters with their leet forms.
+
+    E.g. A plain `e` for Bacon's element A and a `3` for Bacon's element B.
+
+* SentencePunctuationSteganographer: Applies steganography based on stylistic choices of formal documents, like the spaces after the sentences and the Oxford comma of the lists.
+
+    E.g. A single space after a sentence or `a, b and c` for Bacon's element A and two spaces or `a, b, and c` for Bacon's element B.
+
+* DirectionMarkSteganographer: Applies steganography based on the invisible text direction marks, placed after the spaces between words.
+
+    E.g. A LEFT-TO-RIGHT MARK (U+200E) for Bacon's element A and a RIGHT-TO-LEFT MARK (U+200F) for Bacon's element B.
+
+* ZeroWidthSteganographer: Applies steganography based on invisible zero-width characters, placed after the letters.
+
+    E.g. A ZERO WIDTH NON-JOINER (U+200C) for Bacon's element A and a ZERO WIDTH JOINER (U+200D) for Bacon's element B.
+
+* WordParitySteganographer: Applies steganography based on the parity of the length of the words, substituting words with synonyms when needed.
+
+    E.g. Words with even length for Bacon's element A and words with odd length for Bacon's element B.
+
+* AcrosticSteganographer: Applies steganography based on the first letter of each word, inserting words from a word list where needed.
+
+    E.g. Words starting with `a`-`m` for Bacon's element A and words starting with `n`-`z` for Bacon's element B.
+
+* NullCipherSteganographer: Produces one word per Bacon's element, chosen from two disjoint word lists.
+
+    E.g. Words like `sun` and `moon` for Bacon's element A and words like `river` and `sea` for Bacon's element B.
+
+* HtmlEntitySteganographer: Applies steganography based on HTML numeric character references, which render identically to the literal letters.
+
+    E.g. A literal `e` for Bacon's element A and `&#101;` for Bacon's element B.
+
+* CssClassSteganographer: Applies steganography based on the class attribute of the HTML elements that surround elements. (needs the feature `extended-steganography`)
+
+    E.g. Surround an element with `<span class="x">` and `</span>` for Bacon's element A and with `<span class="y">` and `</span>` for Bacon's element B.
+
+* XmlSteganographer: Applies steganography based on namespace-qualified XML elements that surround elements. (needs the feature `xml-steganography`)
+
+    E.g. Surround an element with `<n1:x>` and `</n1:x>` for Bacon's element A and with `<n2:x>` and `</n2:x>` for Bacon's element B, where `n1` and `n2` are bound to different namespaces.
+
+* RegexSteganographer: Reveals the elements of spans that are matched by regular expressions. (needs the feature `regex-steganography`)
+
+    E.g. Spans matched by `__(.+?)__` for Bacon's element A and spans matched by `_(.+?)_` for Bacon's element B.
+
+* DomChannelSteganographer: Reveals the elements of the text of HTML or XML elements, as they are classified by a user-defined callback, like by their id, their style attribute or their depth. (needs the feature `extended-steganography`)
+
+    E.g. Elements with `style="color:#000"` for Bacon's element A and elements with `style="color:#001"` for Bacon's element B.
+
+* PercentEncodingSteganographer: Applies steganography based on the case of the hexadecimal digits of the percent-encoded bytes of URLs, and optionally on the encoding of their unreserved characters.
+
+    E.g. `%2F` for Bacon's element A and `%2f` for Bacon's element B.
+
+* HexCaseSteganographer: Applies steganography based on the case of the hexadecimal digits of hex dumps and case-insensitive identifiers, like UUIDs and git hashes.
+
+    E.g. A lowercase `e` for Bacon's element A and an uppercase `E` for Bacon's element B, in `550e8400-e29b-41d4-a716-446655440000`.
+
+* HybridSteganographer: Applies steganography based on both the letter case and the number of spaces between words, interleaving the elements of the secret across the two channels.
+
+    E.g. `tHIS is  a public message`, in which the letters and the spaces carry the elements in turn.
+
+* MorseSteganographer: Applies steganography based on the spacing of the letters of a Morse transcript.
+
+    E.g. A single space between two letters for Bacon's element A and a double space for Bacon's element B, like `-.-.  --.-`.
+
+* ScatteredSteganographer: Wraps another steganographer and spreads the encoded secret over its carriers, in the pseudo-random order of a seed or passphrase.
+
+    E.g. With the LetterCaseSteganographer, the capital letters are scattered throughout the text, instead of occupying its start.
+
+* SourceCodeSteganographer: Applies steganography based on stylistic choices of a code file, with pluggable rules for each language.
+
+    E.g. `"text"` for Bacon's element A and `'text'` for Bacon's element B, or a trailing comma in a list that spans lines for Bacon's element A and no trailing comma for Bacon's element B.
+
+* ProtectedSteganographer: Wraps another steganographer and keeps regions of the public text verbatim, like ranges of positions, URLs, code or the matches of regular expressions.
+
+    E.g. The URLs of the text are not altered by the LetterCaseSteganographer and are skipped by the reveal.
+
+* ValidatedSteganographer: Wraps another steganographer and uses only the carriers that keep the text valid in its host format, according to a validator, like a JSON parser or an HTML checker.
+
+    E.g. With the LetterCaseSteganographer and a JSON validator, the letters of the strings carry the elements, but the letters of `true` and `null` do not.
+
+* Inverted: Wraps another steganographer and swaps the meaning of the Bacon's elements A and B, for receivers who got the convention backwards.
+
+    E.g. With the LetterCaseSteganographer, a capital letter for Bacon's element A and a lowercase letter for Bacon's element B.
+
+* PngTextSteganographer: Stores the output of another steganographer in a text chunk of a PNG image, without touching the image data. (needs the feature `png-steganography`)
+
+    E.g. A `tEXt` chunk with the keyword `Comment` and the text `tHiS IS a PUbLic mEssAge`.
+
+* JpegSteganographer: Writes the output of another steganographer to the comment or the EXIF `ImageDescription` of a JPEG image, without touching the image data.
+
+    E.g. An `ImageDescription` with the text `a pHOToGraph of the sea`.
+
+* LsbSteganographer: Applies steganography based on the least significant bit of every byte of a binary medium, like the samples of an image or a WAV file. It hides secrets of `char`s in bytes, as a `ByteSteganographer`.
+
+    E.g. An even byte for Bacon's element A and an odd byte for Bacon's element B, after the header of the file.
+
+**Optional features:**
+
+* `extended-steganography`: Enables the steganographers that are based on HTML or XML parsing.
+
+* `xml-steganography`: Enables the steganographer that is based on namespace-aware XML parsing.
+
+* `regex-steganography`: Enables the steganographer that is based on regular expressions.
+
+* `fast-marker-search`: The MarkdownSteganographer searches the markers using the Aho-Corasick algorithm, which is faster for large inputs.
+
+* `async`: Enables the asynchronous streams, that are based on tokio.
+
+* `cli`: Builds the `bacon` command line tool, with the subcommands `encode`, `decode`, `disguise`, `reveal` and `schemes`.
+
+* `profiles`: Enables the steganography profiles, that define a codec and a steganographer in a TOML or JSON file.
+
+* `python`: Enables the `bacon_cipher` Python module, which is built with [maturin](https://www.maturin.rs).
+
+* `grapheme-clusters`: The steganographers process extended grapheme clusters instead of single `char`s, so that combining marks, emoji and ZWJ sequences of the public text are never split.
+
+* `wordlist`: Enables the dictionary-assisted decoding, with a bundled English frequency dictionary, that segments the decoded letters into words and separates the meaningful text from the noise that follows it.
+
+* `png-steganography`: Enables the steganographer that hides text in the `tEXt`, `zTXt` and `iTXt` chunks of PNG images.
+
+* `test-support`: Enables the `test_support` module, with generators of random secrets, cover texts and marker pairs and a round-trip property for the schemes, to property-test the crates that build on bacon-cipher.
+* `tracing`: Emits [tracing](https://docs.rs/tracing) spans and events while encoding, parsing, disguising and revealing, like the number of the decoded groups and the markers that are not terminated, which help to find out why a reveal produces garbage.
+
 ## Encoding - Decoding
 
 ### Encode a message to Bacon codes
@@ -152,7 +287,6 @@ assert!(hidden_message.starts_with("MYSECRET"));
 use bacon_cipher::codecs::char_codec::CharCodec;
 use bacon_cipher::stega::markdown::{MarkdownSteganographer, Marker};
 use bacon_cipher::{BaconCodec, Steganographer};
-use std::iter::FromIterator;
 
 // Define a Bacon Codec that encodes using the characters 'A' and 'B'
 let codec = CharCodec::new('a', 'b');
@@ -168,13 +302,10 @@ let s = MarkdownSteganographer::new(
 let public = "This is a public message that contains a secret one";
 
 // This is the message that we want to hide.
-let secret_chars: Vec<char> = "My secret".chars().collect();
+let secret = "My secret";
 
-let output = s.disguise(
-    &secret_chars,
-    &Vec::from_iter(public.chars()),
-    &codec);
-let string = String::from_iter(output.unwrap().iter());
+// Strings can be disguised without collecting their characters
+let string = s.disguise_str(secret, public, &codec).unwrap();
 assert!(string == "T*h*i*s* *is* a *pu*b*l*ic m*e*ss*a*ge tha*t* c*o*ntains *a* se*c*re*t* one");
 ```
 
@@ -184,7 +315,6 @@ assert!(string == "T*h*i*s* *is* a *pu*b*l*ic m*e*ss*a*ge tha*t* c*o*ntains *a*
 use bacon_cipher::codecs::char_codec::CharCodec;
 use bacon_cipher::stega::markdown::{MarkdownSteganographer, Marker};
 use bacon_cipher::{BaconCodec, Steganographer};
-use std::iter::FromIterator;
 
 // Define a Bacon Codec that encodes using the characters 'A' and 'B'
 let codec = CharCodec::new('a', 'b');
@@ -200,14 +330,64 @@ let s = MarkdownSteganographer::new(
 let public = "T*h*i*s* *is* a *pu*b*l*ic m*e*ss*a*ge tha*t* c*o*ntains *a* se*c*re*t* one";
 
 // This is the hidden message
-let output = s.reveal(
-    &Vec::from_iter(public.chars()),
-    &codec);
+let output = s.reveal_str(public, &codec);
 assert!(output.is_ok());
-let string = String::from_iter(output.unwrap().iter());
+let string = output.unwrap();
 assert!(string.starts_with("MYSECRET"));
 ```
 
+## Cover text generation
+
+If there is no suitable public text, a cover text can be generated along with the disguise.
+
+```
+use bacon_cipher::codecs::char_codec::CharCodec;
+use bacon_cipher::covergen::CoverGenerator;
+use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+use bacon_cipher::Steganographer;
+use std::iter::FromIterator;
+
+let codec = CharCodec::new('a', 'b');
+let s = LetterCaseSteganographer::new();
+
+// Train a generator with a corpus
+let generator = CoverGenerator::from_corpus("The cat sat on the mat. The dog sat on the log.").unwrap();
+
+// Generate a cover text and hide the secret in it
+let secret_chars: Vec<char> = "My secret".chars().collect();
+let disguised = generator.disguise(&s, &secret_chars, &codec).unwrap();
+
+let hidden_message = String::from_iter(s.reveal(&disguised, &codec).unwrap().iter());
+assert!(hidden_message.starts_with("MYSECRET"));
+```
+
+## Sharing across threads
+
+The codecs, the steganographers and the schemes of a [Registry](registry/struct.Registry.html) are `Send` and `Sync`.
+A server can configure them once and share them across its worker threads in an `Arc`, whose clones are cheap,
+instead of creating or cloning them for every request.
+
+```
+use std::sync::Arc;
+use std::thread;
+use bacon_cipher::registry::Registry;
+
+let scheme = Arc::new(Registry::default().scheme("letter-case").unwrap());
+
+let workers: Vec<_> = (0..4).map(|_| {
+    let scheme = Arc::clone(&scheme);
+    thread::spawn(move || {
+        let public: Vec<char> = "This is a public message that contains a secret one".chars().collect();
+        let disguised = scheme.disguise(&['H', 'I'], &public).unwrap();
+        scheme.reveal(&disguised).unwrap()
+    })
+}).collect();
+
+for worker in workers {
+    assert!(worker.join().unwrap().starts_with(&['H', 'I']));
+}
+```
+
 ## Licence
 
 At your option, under:
@@ -217,9 +397,43 @@ At your option, under:
 
 */
 
+#[macro_use]
+mod trace;
+
+pub mod analysis;
+#[cfg(feature = "async")]
+pub mod async_stream;
+pub mod binary;
+pub mod carrier;
+pub mod changes;
 pub mod codecs;
+pub mod covergen;
+#[cfg(feature = "wordlist")]
+pub mod dictionary;
+pub mod dynamic;
+pub mod envelope;
 pub mod stega;
+pub mod stream;
+pub mod visualize;
+pub mod watermark;
 pub mod errors;
+pub mod mapping;
+pub mod markers;
+pub mod posts;
+pub mod shares;
+#[cfg(feature = "profiles")]
+pub mod profile;
+#[cfg(feature = "python")]
+mod python;
+pub mod registry;
+pub mod report;
+pub mod session;
+pub mod stats;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod thread_safety;
+pub mod wordlist;
+mod random;
 
 /// A codec that enables encoding and decoding based on the [Bacon's cipher](https://en.wikipedia.org/wiki/Bacon%27s_cipher)
 pub trait BaconCodec {
@@ -234,10 +448,11 @@ pub trait BaconCodec {
     ///
     /// E.g. For `CONTENT=char`, `ABTYPE=char`, `a='A'` and `b='B'`, the encoding of `['M','y',' ','s','e','c','r','e','t']` is _ABABBBABBABAAABAABAAAAABABAAAAAABAABAABA_
     fn encode(&self, input: &[Self::CONTENT]) -> Vec<Self::ABTYPE> {
-        input.iter()
-            .map(|elem| self.encode_elem(elem))
-            .flat_map(|elem| elem)
-            .collect()
+        let encoded: Vec<Self::ABTYPE> = input.iter()
+            .flat_map(|elem| self.encode_elem(elem))
+            .collect();
+        debug_event!(input = input.len(), elements = encoded.len(), "encoded");
+        encoded
     }
 
     /// Encodes a single emenent of `Self::CONTENT` to a Vec of `Self::ABTYPE`.
@@ -247,11 +462,47 @@ pub trait BaconCodec {
     ///
     /// E.g. For `CONTENT=char`, `ABTYPE=char`, `a='A'` and `b='B'`, the decoding of _ABABBBABBABAAABAABAAAAABABAAAAAABAABAABA_ is `['M','Y','S','E','C','R','E','T']`
     fn decode(&self, input: &[Self::ABTYPE]) -> Vec<Self::CONTENT> {
+        debug_event!(
+            elements = input.len(),
+            groups = input.len() / self.encoded_group_size(),
+            incomplete = input.len() % self.encoded_group_size(),
+            "decoding");
         input.chunks(self.encoded_group_size())
-            .map(|elem| self.decode_elems(&elem))
+            .map(|elem| self.decode_elems(elem))
             .collect()
     }
 
+    /// Decodes like [decode](#method.decode), ignoring the elements that are neither the `A` nor the `B` substitution
+    /// element, like the spaces, newlines or punctuation of a copied ciphertext.
+    ///
+    /// E.g. For `a='A'` and `b='B'`, the decoding of _AABBB ABAAA_ is `['H', 'I']`
+    fn decode_lenient(&self, input: &[Self::ABTYPE]) -> Vec<Self::CONTENT> where Self::ABTYPE: Clone {
+        let filtered: Vec<Self::ABTYPE> = input.iter()
+            .filter(|elem| self.is_a(elem) || self.is_b(elem))
+            .cloned()
+            .collect();
+        self.decode(&filtered)
+    }
+
+    /// Encodes like [encode](#method.encode), to [Groups](codecs/groups/struct.Groups.html) of bit patterns that do not
+    /// depend on the substitution elements.
+    fn encode_groups(&self, input: &[Self::CONTENT]) -> codecs::groups::Groups {
+        codecs::groups::Groups::from_encoded(&self.encode(input), self)
+    }
+
+    /// Decodes [Groups](codecs/groups/struct.Groups.html) of bit patterns. An incomplete group at the end is decoded
+    /// like [decode](#method.decode) does.
+    fn decode_groups(&self, groups: &codecs::groups::Groups) -> Vec<Self::CONTENT> {
+        self.decode(&groups.to_encoded(self))
+    }
+
+    /// Decodes the bit pattern of a complete group, like the patterns of [Groups](codecs/groups/struct.Groups.html),
+    /// to one element of `Self::CONTENT`.
+    fn decode_pattern(&self, pattern: u8) -> Self::CONTENT {
+        let group = codecs::groups::Groups::from_patterns(vec![pattern], self.encoded_group_size());
+        self.decode_elems(&group.to_encoded(self))
+    }
+
     /// Decode an array of elements to produce one element of `Self::CΟΝΤΕΝΤ`
     fn decode_elems(&self, elems: &[Self::ABTYPE]) -> Self::CONTENT;
 
@@ -286,4 +537,112 @@ pub trait Steganographer {
 
     /// Reveals the _secret_ that is hidden in an array of type `T`, using a [BaconCodec](trait.BaconCodec.html).
     fn reveal<AB>(&self, input: &[Self::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<Self::T>>;
+
+    /// Disguises the _secret_ like [disguise](#tymethod.disguise), taking the _secret_ and the _public_ input from any
+    /// iterator, like the `chars()` of a string, instead of slices.
+    fn disguise_iter<S, P, AB>(&self, secret: S, public: P, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<Self::T>>
+        where S: IntoIterator<Item=Self::T>, P: IntoIterator<Item=Self::T> {
+        let secret: Vec<Self::T> = secret.into_iter().collect();
+        let public: Vec<Self::T> = public.into_iter().collect();
+        self.disguise(&secret, &public, codec)
+    }
+
+    /// Reveals the _secret_ like [reveal](#tymethod.reveal), taking the input from any iterator.
+    fn reveal_iter<I, AB>(&self, input: I, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<Self::T>>
+        where I: IntoIterator<Item=Self::T> {
+        let input: Vec<Self::T> = input.into_iter().collect();
+        self.reveal(&input, codec)
+    }
+
+    /// Disguises the _secret_ like [disguise](#tymethod.disguise) and appends the output to a sink, like a `String`,
+    /// a `Vec` or a [CharWriter](stream/struct.CharWriter.html). Nothing is appended if the disguise fails.
+    ///
+    /// The default implementation extends the sink with the output of [disguise](#tymethod.disguise). Steganographers
+    /// that produce their output sequentially write it to the sink directly.
+    fn disguise_to<E, AB>(&self, secret: &[Self::T], public: &[Self::T], sink: &mut E, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<()>
+        where E: Extend<Self::T> {
+        sink.extend(self.disguise(secret, public, codec)?);
+        Ok(())
+    }
+
+    /// Disguises the _secret_ like [disguise_to](#method.disguise_to) into a new collection of the output, like a `String`.
+    fn disguise_as<C, AB>(&self, secret: &[Self::T], public: &[Self::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<C>
+        where C: Default + Extend<Self::T> {
+        let mut disguised = C::default();
+        self.disguise_to(secret, public, &mut disguised, codec)?;
+        Ok(disguised)
+    }
+
+    /// Disguises the _secret_ string into the _public_ string and returns the disguised string.
+    fn disguise_str<AB>(&self, secret: &str, public: &str, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<String>
+        where Self: Steganographer<T=char> {
+        let secret: Vec<char> = secret.chars().collect();
+        let public: Vec<char> = public.chars().collect();
+        self.disguise_as(&secret, &public, codec)
+    }
+
+    /// Reveals the _secret_ that is hidden in a string.
+    fn reveal_str<AB>(&self, input: &str, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<String>
+        where Self: Steganographer<T=char> {
+        self.reveal_iter(input.chars(), codec)
+            .map(|revealed| revealed.into_iter().collect())
+    }
+
+    /// The number of Bacon's elements that the _public_ input can carry.
+    ///
+    /// The default implementation counts the elements that the steganographer finds in the _public_ input. Steganographers
+    /// that generate their output, without being limited by the _public_ input, return `usize::MAX`.
+    fn capacity(&self, public: &[Self::T]) -> errors::Result<usize> where Self::T: Default {
+        carrier::count_elements(self, public)
+    }
+
+    /// Reveals the _secret_ like [reveal](#tymethod.reveal), along with diagnostics about the extraction, like the number
+    /// of the elements that were found and the groups of them that are invalid.
+    fn reveal_report<AB>(&self, input: &[Self::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<report::RevealReport<Self::T>> {
+        report::reveal_report(self, input, codec)
+    }
+
+    /// Disguises the _secret_ and reveals it from the output, to check that the _secret_ is recoverable before publishing
+    /// the output.
+    ///
+    /// The revealed secret is compared with the _secret_ as the codec decodes it, so that letters that share their code
+    /// are equal. Revealed content that follows the _secret_ is ignored. A
+    /// [RoundTripMismatch](errors/enum.BaconError.html#variant.RoundTripMismatch) error describes where the recovery diverges.
+    fn verify<AB>(&self, secret: &[Self::T], public: &[Self::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<()>
+        where Self::T: PartialEq + std::fmt::Debug {
+        report::verify(self, secret, public, codec)
+    }
+
+    /// Disguises the _secret_ like [disguise](#tymethod.disguise) and reports the changes of the _public_ input, like the
+    /// letters whose case is flipped and the inserted markers, in a [ChangeReport](changes/struct.ChangeReport.html).
+    fn disguise_with_changes<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<(Vec<char>, changes::ChangeReport)>
+        where Self: Steganographer<T=char> {
+        changes::disguise_with_changes(self, secret, public, codec)
+    }
+
+    /// Returns the [Mapping](mapping/struct.Mapping.html) of the characters of the input that carry Bacon's elements.
+    ///
+    /// The default implementation returns an error, for the steganographers that do not support mappings.
+    fn mapping(&self, _input: &[Self::T]) -> errors::Result<mapping::Mapping> {
+        Err(errors::BaconError::SteganographerError("The steganographer does not support mappings".to_string()))
+    }
+
+    /// Removes the channel of the steganographer from a disguised input, like the markers or the unnatural letter case,
+    /// so that the input is an innocent text again after its secret is consumed.
+    ///
+    /// The default implementation returns an error, for the steganographers that do not support stripping.
+    fn strip(&self, _input: &[Self::T]) -> errors::Result<Vec<Self::T>> {
+        Err(errors::BaconError::SteganographerError("The steganographer does not support stripping".to_string()))
+    }
+
+    /// Disguises the _secret_ like [disguise](#tymethod.disguise) and returns the [Mapping](mapping/struct.Mapping.html)
+    /// of the characters of the output that carry the elements of the encoded _secret_.
+    fn disguise_with_mapping<AB>(&self, secret: &[Self::T], public: &[Self::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<(Vec<Self::T>, mapping::Mapping)> {
+        mapping::disguise_with_mapping(self, secret, public, codec)
+    }
+
+    /// Reveals the _secret_ that is carried by the characters of the [Mapping](mapping/struct.Mapping.html), ignoring the rest of the input.
+    fn reveal_with_mapping<AB>(&self, input: &[Self::T], mapping: &mapping::Mapping, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<Self::T>> {
+        mapping::reveal_with_mapping(self, input, mapping, codec)
+    }
 }
@@ -0,0 +1,226 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The mapping between the Bacon's elements of an encoded secret and the characters of the cover text that carry them.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//! use bacon_cipher::carrier::SegmentKind;
+//! use bacon_cipher::Steganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = LetterCaseSteganographer::new();
+//! let public: Vec<char> = "Hello world".chars().collect();
+//!
+//! // H = AABBB
+//! let (disguised, mapping) = s.disguise_with_mapping(&['H'], &public, &codec).unwrap();
+//! assert_eq!(disguised.iter().collect::<String>(), "heLLO world");
+//! assert_eq!(mapping.len(), 5);
+//! assert_eq!(mapping.slots()[2].position(), 2);
+//! assert_eq!(mapping.slots()[2].kind(), SegmentKind::B);
+//! ```
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::{Segment, SegmentKind};
+use crate::errors::BaconError;
+use crate::stega::units;
+
+/// A character of a cover text that carries a Bacon's element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot {
+    position: usize,
+    kind: SegmentKind,
+}
+
+impl Slot {
+    /// Creates a `Slot` for the character at the `position`, that carries the element A or B.
+    pub fn new(position: usize, kind: SegmentKind) -> Slot {
+        Slot { position, kind }
+    }
+
+    /// The index of the character in the cover text.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Either [SegmentKind::A](../carrier/enum.SegmentKind.html) or [SegmentKind::B](../carrier/enum.SegmentKind.html).
+    pub fn kind(&self) -> SegmentKind {
+        self.kind
+    }
+}
+
+/// The slots of a cover text, in the order of the elements that they carry.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Mapping {
+    slots: Vec<Slot>,
+}
+
+impl Mapping {
+    pub fn new(slots: Vec<Slot>) -> Mapping {
+        Mapping { slots }
+    }
+
+    /// Creates the `Mapping` of classified segments, whose ranges are byte ranges in the UTF-8 encoded input.
+    ///
+    /// The slots are the first characters of the units of the segments that start with an alphabetic character.
+    pub fn from_segments(input: &[char], segments: &[Segment]) -> Mapping {
+        let mut slots = Vec::new();
+        let mut byte_offset = 0;
+        let mut position = 0;
+        for segment in segments.iter().filter(|segment| segment.kind() != SegmentKind::Other) {
+            let range = match segment.range() {
+                Some(range) => range,
+                None => continue,
+            };
+            while byte_offset < range.start && position < input.len() {
+                byte_offset += input[position].len_utf8();
+                position += 1;
+            }
+            let end = (position..input.len())
+                .scan(byte_offset, |offset, index| {
+                    *offset += input[index].len_utf8();
+                    Some((index, *offset))
+                })
+                .find(|(_, offset)| *offset >= range.end)
+                .map(|(index, _)| index + 1)
+                .unwrap_or(input.len());
+            let mut start = position;
+            for unit in units(&input[position..end]) {
+                if unit[0].is_alphabetic() {
+                    slots.push(Slot::new(start, segment.kind()));
+                }
+                start += unit.len();
+            }
+        }
+        Mapping { slots }
+    }
+
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// The number of slots.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// The slots that carry the group of elements of the symbol at the `index` of the secret.
+    pub fn group(&self, index: usize, group_size: usize) -> &[Slot] {
+        let start = (index * group_size).min(self.slots.len());
+        let end = (start + group_size).min(self.slots.len());
+        &self.slots[start..end]
+    }
+
+    /// The slot of the character at the `position` of the cover text, if the character carries an element.
+    pub fn slot_at(&self, position: usize) -> Option<&Slot> {
+        self.slots.binary_search_by_key(&position, |slot| slot.position)
+            .ok()
+            .map(|index| &self.slots[index])
+    }
+}
+
+pub(crate) fn disguise_with_mapping<S, AB>(
+    steganographer: &S,
+    secret: &[S::T],
+    public: &[S::T],
+    codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=S::T>) -> errors::Result<(Vec<S::T>, Mapping)>
+    where S: Steganographer + ?Sized {
+    let disguised = steganographer.disguise(secret, public, codec)?;
+    let mut mapping = steganographer.mapping(&disguised)?;
+    // The rest of the slots do not carry any element of the secret
+    mapping.slots.truncate(codec.encode(secret).len());
+    Ok((disguised, mapping))
+}
+
+pub(crate) fn reveal_with_mapping<S, AB>(
+    steganographer: &S,
+    input: &[S::T],
+    mapping: &Mapping,
+    codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=S::T>) -> errors::Result<Vec<S::T>>
+    where S: Steganographer + ?Sized {
+    let input_mapping = steganographer.mapping(input)?;
+    let encoded = mapping.slots.iter()
+        .map(|slot| {
+            match input_mapping.slot_at(slot.position) {
+                Some(input_slot) if input_slot.kind == SegmentKind::A => Ok(codec.a()),
+                Some(_) => Ok(codec.b()),
+                None => Err(BaconError::InvalidInput {
+                    message: format!("The character at {} does not carry any Bacon's element", slot.position),
+                    position: None,
+                    source: None,
+                }),
+            }
+        })
+        .collect::<errors::Result<Vec<AB>>>()?;
+    Ok(codec.decode(&encoded))
+}
+
+#[cfg(test)]
+mod mapping_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::markdown::{MarkdownSteganographer, Marker};
+    use crate::stega::whitespace::WhitespaceSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn mapping_of_segments() {
+        let input = Vec::from_iter("*é*ü**ö😀** *αβ".chars());
+        let segments = vec![
+            Segment::new(SegmentKind::A, "é", Some(1..3)),
+            Segment::new(SegmentKind::Other, "ü", Some(4..6)),
+            Segment::new(SegmentKind::B, "ö😀", Some(8..14)),
+            Segment::new(SegmentKind::A, "αβ", Some(18..22)),
+        ];
+        assert_eq!(Mapping::from_segments(&input, &segments).slots(), &[
+            Slot::new(1, SegmentKind::A),
+            Slot::new(6, SegmentKind::B),
+            Slot::new(12, SegmentKind::A),
+            Slot::new(13, SegmentKind::A),
+        ]);
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_mapping() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(Marker::empty(), Marker::new(Some("*"), Some("*"))).unwrap();
+        // H = AABBB
+        let (disguised, mapping) = s.disguise_with_mapping(&['H'], &Vec::from_iter("Hello world".chars()), &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "He*llo* world");
+        assert_eq!(mapping.slots().iter().map(|slot| slot.position()).collect::<Vec<usize>>(), vec![0, 1, 3, 4, 5]);
+        assert_eq!(mapping.group(0, 5), mapping.slots());
+        assert!(mapping.group(1, 5).is_empty());
+        assert_eq!(s.reveal_with_mapping(&disguised, &mapping, &codec).unwrap(), vec!['H']);
+    }
+
+    #[test]
+    fn reveal_with_mapping_ignores_the_rest_of_the_input() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let mapping = Mapping::new((6..11).map(|position| Slot::new(position, SegmentKind::A)).collect());
+        // The carriers of the mapping are the letters of "woRLD"
+        let input = Vec::from_iter("HELLO woRLD".chars());
+        assert_eq!(s.reveal_with_mapping(&input, &mapping, &codec).unwrap(), vec!['H']);
+        let mapping = Mapping::new(vec![Slot::new(5, SegmentKind::A)]);
+        assert!(s.reveal_with_mapping(&input, &mapping, &codec).is_err());
+        assert!(WhitespaceSteganographer::new().mapping(&input).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The markers that surround the carrier characters, for the [MarkdownSteganographer](../stega/markdown/struct.MarkdownSteganographer.html)
+//! and the tag steganographers, along with [presets](presets/index.html) of markers that are known to work together.
+pub use crate::stega::markdown::Marker;
+#[cfg(feature = "extended-steganography")]
+pub use crate::stega::tags::Tag;
+
+pub mod presets;
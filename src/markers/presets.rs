@@ -0,0 +1,134 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ready-made pairs of A and B markers.
+//!
+//! Every preset is accepted by the constructor of its steganographer and renders naturally in the flavor that it is
+//! named after. The first marker of a pair is the marker of the Bacon's element A and an [empty](../struct.Marker.html#method.empty)
+//! marker leaves the characters unmarked.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::markers::presets;
+//! use bacon_cipher::stega::markdown::MarkdownSteganographer;
+//! use bacon_cipher::Steganographer;
+//!
+//! let (a_marker, b_marker) = presets::markdown_strikethrough();
+//! let s = MarkdownSteganographer::new(a_marker, b_marker).unwrap();
+//! let codec = CharCodec::new('a', 'b');
+//! let disguised = s.disguise_str("H", "Hello world", &codec).unwrap();
+//! assert_eq!(disguised, "He~~llo~~ world");
+//! ```
+use crate::markers::Marker;
+#[cfg(feature = "extended-steganography")]
+use crate::markers::Tag;
+
+/// Unmarked characters and `**bold**` characters.
+pub fn markdown_bold() -> (Marker, Marker) {
+    (Marker::empty(), Marker::new(Some("**"), Some("**")))
+}
+
+/// Unmarked characters and `*italic*` characters.
+pub fn markdown_italic() -> (Marker, Marker) {
+    (Marker::empty(), Marker::new(Some("*"), Some("*")))
+}
+
+/// `*italic*` and `**bold**` characters.
+pub fn markdown_italic_bold() -> (Marker, Marker) {
+    (Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**")))
+}
+
+/// Unmarked characters and `~~strikethrough~~` characters, as in GitHub Flavored Markdown.
+pub fn markdown_strikethrough() -> (Marker, Marker) {
+    (Marker::empty(), Marker::new(Some("~~"), Some("~~")))
+}
+
+/// Unmarked characters and `` `code` `` characters.
+pub fn markdown_code() -> (Marker, Marker) {
+    (Marker::empty(), Marker::code_span())
+}
+
+/// `<i>italic</i>` and `<b>bold</b>` characters, written as inline HTML in Markdown.
+pub fn html_italic_bold() -> (Marker, Marker) {
+    (Marker::new(Some("<i>"), Some("</i>")), Marker::new(Some("<b>"), Some("</b>")))
+}
+
+/// Unmarked characters and `^(superscript)` characters, as on Reddit.
+pub fn reddit_superscript() -> (Marker, Marker) {
+    (Marker::empty(), Marker::new(Some("^("), Some(")")))
+}
+
+/// Unmarked characters and `>!spoiler!<` characters, as on Reddit.
+pub fn reddit_spoiler() -> (Marker, Marker) {
+    (Marker::empty(), Marker::new(Some(">!"), Some("!<")))
+}
+
+/// `__underlined__` and `**bold**` characters, as on Discord.
+pub fn discord_underline_bold() -> (Marker, Marker) {
+    (Marker::new(Some("__"), Some("__")), Marker::new(Some("**"), Some("**")))
+}
+
+/// Unmarked characters and `||spoiler||` characters, as on Discord.
+pub fn discord_spoiler() -> (Marker, Marker) {
+    (Marker::empty(), Marker::new(Some("||"), Some("||")))
+}
+
+/// `<i>` and `<b>` elements, for the [SimpleTagSteganographer](../../stega/tags/struct.SimpleTagSteganographer.html).
+#[cfg(feature = "extended-steganography")]
+pub fn html_italic_bold_tags() -> (Tag, Tag) {
+    (Tag::new(Some("<i>"), Some("</i>")), Tag::new(Some("<b>"), Some("</b>")))
+}
+
+/// `<span>` elements with the given classes, for the [SimpleTagSteganographer](../../stega/tags/struct.SimpleTagSteganographer.html).
+#[cfg(feature = "extended-steganography")]
+pub fn span_class_tags(a_class: &str, b_class: &str) -> (Tag, Tag) {
+    let span = |class: &str| Tag::new(Some(&format!("<span class=\"{}\">", class)), Some("</span>"));
+    (span(a_class), span(b_class))
+}
+
+#[cfg(test)]
+mod presets_tests {
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::markdown::MarkdownSteganographer;
+    use crate::Steganographer;
+
+    use super::*;
+
+    #[test]
+    fn markdown_presets() {
+        let codec = CharCodec::new('a', 'b');
+        let presets = [
+            markdown_bold(), markdown_italic(), markdown_italic_bold(), markdown_strikethrough(), markdown_code(),
+            html_italic_bold(), reddit_superscript(), reddit_spoiler(), discord_underline_bold(), discord_spoiler(),
+        ];
+        for (a_marker, b_marker) in presets {
+            let s = MarkdownSteganographer::new(a_marker, b_marker).unwrap();
+            let disguised = s.disguise_str("My secret", "This is a public message, (with) <some> ^markup^ || that hides it", &codec).unwrap();
+            assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+        }
+    }
+
+    #[cfg(feature = "extended-steganography")]
+    #[test]
+    fn tag_presets() {
+        use crate::stega::tags::SimpleTagSteganographer;
+
+        let codec = CharCodec::new('a', 'b');
+        for (a_tag, b_tag) in [html_italic_bold_tags(), span_class_tags("a", "b")] {
+            let s = SimpleTagSteganographer::builder().with_a_tag(a_tag).with_b_tag(b_tag).build().unwrap();
+            let disguised = s.disguise_str("My secret", "This is a public message that contains a secret one", &codec).unwrap();
+            assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+        }
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Spreads a secret across short messages, like the posts of a thread, that are each too short to carry it alone.
+//!
+//! Every post starts with a group that holds the number of the groups of the secret that follow it in the same post.
+//! This way, every post carries as many groups as fit both in its capacity and in the [MAX_LENGTH](constant.MAX_LENGTH.html)
+//! of a post, for any [Carrier](../carrier/trait.Carrier.html), even the ones that lengthen their input. The posts must
+//! be revealed in the order of the disguise.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::posts;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = LetterCaseSteganographer::new();
+//! let covers: Vec<Vec<char>> = vec![
+//!     "Morning walk by the river, the fog is lifting slowly".chars().collect(),
+//!     "Coffee first, then the long list of errands".chars().collect(),
+//!     "See you all at the market later".chars().collect(),
+//! ];
+//! let covers: Vec<&[char]> = covers.iter().map(|cover| &cover[..]).collect();
+//! let disguised = posts::disguise(&s, &['S', 'E', 'C', 'R', 'E', 'T'], &covers, &codec).unwrap();
+//! let disguised: Vec<&[char]> = disguised.iter().map(|post| &post[..]).collect();
+//! assert_eq!(posts::reveal(&s, &disguised, &codec).unwrap(), vec!['S', 'E', 'C', 'R', 'E', 'T']);
+//! ```
+use crate::{BaconCodec, errors};
+use crate::carrier::Carrier;
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+
+/// The maximum number of the characters of a post, like the ones of most short message platforms.
+pub const MAX_LENGTH: usize = 280;
+
+/// Encodes the secret with the codec and spreads its groups across the covers, in their order, so that no disguised post
+/// is longer than [MAX_LENGTH](constant.MAX_LENGTH.html) characters.
+///
+/// Returns the disguised posts, one for every cover. The covers that follow the end of the secret are disguised as
+/// well, carrying no groups.
+pub fn disguise<C, AB>(carrier: &C, secret: &[char], covers: &[&[char]], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<Vec<char>>>
+    where C: Carrier<Unit=char> + ?Sized {
+    disguise_with_length(carrier, secret, covers, MAX_LENGTH, codec)
+}
+
+/// Like [disguise](fn.disguise.html), for posts of at most `max_length` characters.
+///
+/// Fails if a cover is longer than `max_length`, if a cover cannot be disguised within `max_length`, even without
+/// carrying any groups, or if the covers cannot carry the whole secret.
+pub fn disguise_with_length<C, AB>(carrier: &C, secret: &[char], covers: &[&[char]], max_length: usize, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<Vec<char>>>
+    where C: Carrier<Unit=char> + ?Sized {
+    BaconError::check_secret(secret)?;
+    let group_size = codec.encoded_group_size();
+    // The most groups that the header of a post can count
+    let most = (1usize << group_size) - 1;
+    let encoded = codec.encode_groups(secret);
+    let mut remaining = encoded.complete();
+    let mut carried = 0;
+    let mut disguised = Vec::with_capacity(covers.len());
+    for (index, cover) in covers.iter().enumerate() {
+        if cover.len() > max_length {
+            return Err(BaconError::InvalidInput {
+                message: format!("The post {} is longer than {} characters", index + 1, max_length),
+                position: None,
+                source: None,
+            });
+        }
+        let capacity = carrier.capacity(cover)?;
+        if capacity < group_size {
+            disguised.push(carrier.write_symbols(&[], cover)?);
+            continue;
+        }
+        let mut count = remaining.len().min(capacity / group_size - 1).min(most);
+        loop {
+            let mut patterns = vec![count as u8];
+            patterns.extend_from_slice(&remaining[..count]);
+            let symbols: Vec<bool> = Groups::from_patterns(patterns, group_size).elements().collect();
+            let post = carrier.write_symbols(&symbols, cover)?;
+            if post.len() <= max_length {
+                disguised.push(post);
+                break;
+            } else if count == 0 {
+                return Err(BaconError::GeneralError(format!("The post {} cannot be disguised in {} characters", index + 1, max_length)));
+            }
+            count -= 1;
+        }
+        carried += count;
+        remaining = &remaining[count..];
+    }
+    if !remaining.is_empty() {
+        return Err(BaconError::InsufficientCapacity {
+            required: encoded.complete().len() * group_size,
+            available: carried * group_size,
+            unit: CapacityUnit::Carriers,
+        });
+    }
+    Ok(disguised)
+}
+
+/// Reads the groups of the disguised posts, in the order of the disguise, and decodes the secret.
+pub fn reveal<C, AB>(carrier: &C, posts: &[&[char]], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>>
+    where C: Carrier<Unit=char> + ?Sized {
+    let group_size = codec.encoded_group_size();
+    let mut patterns = Vec::new();
+    for (index, post) in posts.iter().enumerate() {
+        let read = Groups::from_elements(carrier.read_symbols(post)?, group_size);
+        // The posts that carry less than a group carry nothing
+        if let Some((count, groups)) = read.complete().split_first() {
+            let count = *count as usize;
+            if groups.len() < count {
+                return Err(BaconError::InvalidInput {
+                    message: format!("The post {} carries {} groups instead of {}", index + 1, groups.len(), count),
+                    position: None,
+                    source: None,
+                });
+            }
+            patterns.extend_from_slice(&groups[..count]);
+        }
+    }
+    Ok(codec.decode_groups(&Groups::from_patterns(patterns, group_size)))
+}
+
+#[cfg(test)]
+mod posts_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::direction_mark::DirectionMarkSteganographer;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    fn covers(texts: &[&str]) -> Vec<Vec<char>> {
+        texts.iter().map(|text| Vec::from_iter(text.chars())).collect()
+    }
+
+    #[test]
+    fn disguise_and_reveal_across_posts() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let covers = covers(&[
+            "Morning walk by the river",
+            "Coffee first, then errands",
+            "Short",
+            "See you all at the market later today",
+            "And the last post with the rest",
+        ]);
+        let covers: Vec<&[char]> = covers.iter().map(|cover| &cover[..]).collect();
+        let secret = ['A', 'T', 'T', 'A', 'C', 'K', 'A', 'T', 'D', 'A', 'W', 'N'];
+        let disguised = disguise(&s, &secret, &covers, &codec).unwrap();
+        assert_eq!(disguised.len(), 5);
+        let disguised: Vec<&[char]> = disguised.iter().map(|post| &post[..]).collect();
+        // The letters of every post, but the short one, carry a header and the secret groups that fit after it
+        let carried: Vec<Vec<char>> = disguised.iter()
+            .map(|post| reveal(&s, &[post], &codec).unwrap())
+            .collect();
+        assert_eq!(carried, vec![
+            vec!['A', 'T', 'T'],
+            vec!['A', 'C', 'K'],
+            vec![],
+            vec!['A', 'T', 'D', 'A', 'W'],
+            vec!['N'],
+        ]);
+        assert_eq!(reveal(&s, &disguised, &codec).unwrap(), Vec::from_iter(secret.iter().cloned()));
+    }
+
+    #[test]
+    fn posts_do_not_exceed_the_max_length() {
+        let codec = CharCodec::new('a', 'b');
+        // Every mark lengthens the post by a character
+        let s = DirectionMarkSteganographer::new();
+        let covers = covers(&[&"word ".repeat(40)[..198], &"word ".repeat(40)[..199]]);
+        let covers: Vec<&[char]> = covers.iter().map(|cover| &cover[..]).collect();
+        let secret = ['H', 'E', 'L', 'L', 'O'];
+        assert!(disguise_with_length(&s, &secret, &covers, 200, &codec).is_err());
+        let disguised = disguise_with_length(&s, &secret, &covers, 220, &codec).unwrap();
+        assert!(disguised.iter().all(|post| post.len() <= 220));
+        let disguised: Vec<&[char]> = disguised.iter().map(|post| &post[..]).collect();
+        assert_eq!(reveal(&s, &disguised[..1], &codec).unwrap(), vec!['H', 'E', 'L']);
+        assert_eq!(reveal(&s, &disguised, &codec).unwrap(), vec!['H', 'E', 'L', 'L', 'O']);
+    }
+
+    #[test]
+    fn disguise_fails() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let long = Vec::from_iter("a".repeat(MAX_LENGTH + 1).chars());
+        assert!(matches!(disguise(&s, &['H'], &[&long[..]], &codec), Err(BaconError::InvalidInput { .. })));
+        let covers = covers(&["Two short", "posts only"]);
+        let covers: Vec<&[char]> = covers.iter().map(|cover| &cover[..]).collect();
+        assert_eq!(disguise(&s, &['H', 'E', 'L', 'L', 'O'], &covers, &codec),
+                   Err(BaconError::InsufficientCapacity { required: 25, available: 0, unit: CapacityUnit::Carriers }));
+    }
+}
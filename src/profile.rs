@@ -0,0 +1,446 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Steganography profiles that are defined in TOML or JSON files, so that the parties that exchange secrets can share
+//! a profile file instead of synchronizing constructor calls. Needs the feature `profiles`.
+//!
+//! A profile defines the codec and the steganographer of a [Scheme](../registry/struct.Scheme.html). E.g.
+//!
+//! ```toml
+//! [codec]
+//! version = "v2"
+//! a = "0"
+//! b = "1"
+//!
+//! [steganographer]
+//! type = "markdown"
+//! a-marker = { start = "[", end = "](#a)" }
+//! b-marker = "**"
+//! escape-policy = "strip"
+//! ```
+//!
+//! The `codec` table is optional and defaults to the first version of the cipher. The steganographer `type` is one of
+//! `letter-case`, `markdown`, `html-entity`, `math-alphanumeric`, `punctuation`, `whitespace`, `tag`
+//! (needs the feature `extended-steganography`), or `scheme`, which accepts any configuration string of the
+//! default [Registry](../registry/struct.Registry.html).
+//!
+//! The optional `framing` is `none`, the default, or `envelope`, which seals the secret in an
+//! [Envelope](../envelope/struct.Envelope.html). The `letter-case` steganographer accepts a `padding`, which is `none`,
+//! `repeat`, `nulls` or `{ random-letters = <seed> }`. E.g.
+//!
+//! ```toml
+//! framing = "envelope"
+//!
+//! [steganographer]
+//! type = "letter-case"
+//! padding = { random-letters = 7 }
+//! ```
+
+use serde::Deserialize;
+
+use crate::errors;
+use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+use crate::envelope::Envelope;
+use crate::errors::BaconError;
+use crate::registry::{Codec, Registry, Scheme};
+use crate::stega::html_entity::HtmlEntitySteganographer;
+use crate::stega::letter_case::LetterCaseSteganographer;
+use crate::stega::markdown::{EscapePolicy, MarkdownSteganographer, Marker, PLACEHOLDER};
+use crate::stega::math_alphanumeric::{LetterStyle, MathAlphanumericSteganographer};
+use crate::stega::punctuation::PunctuationSteganographer;
+#[cfg(feature = "extended-steganography")]
+use crate::stega::tags::{SimpleTagSteganographer, Tag};
+use crate::stega::whitespace::WhitespaceSteganographer;
+use crate::stega::{Padding, Wrapping};
+
+/// A full scheme description.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    codec: CodecProfile,
+    #[serde(default)]
+    framing: Framing,
+    steganographer: SteganographerProfile,
+}
+
+/// How the secret is framed in the carriers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Framing {
+    /// The encoded secret is written as it is.
+    #[default]
+    None,
+    /// The secret is sealed in an [Envelope](../envelope/struct.Envelope.html), whose header precedes it.
+    Envelope,
+}
+
+/// The version of the Bacon's cipher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodecVersion {
+    /// The [CharCodec](../codecs/char_codec/struct.CharCodec.html)
+    #[default]
+    V1,
+    /// The [CharCodecV2](../codecs/char_codec/struct.CharCodecV2.html)
+    V2,
+}
+
+/// The codec of a [Profile](struct.Profile.html).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CodecProfile {
+    #[serde(default)]
+    version: CodecVersion,
+    #[serde(default = "default_a")]
+    a: char,
+    #[serde(default = "default_b")]
+    b: char,
+}
+
+fn default_a() -> char {
+    'a'
+}
+
+fn default_b() -> char {
+    'b'
+}
+
+impl Default for CodecProfile {
+    fn default() -> CodecProfile {
+        CodecProfile {
+            version: CodecVersion::default(),
+            a: default_a(),
+            b: default_b(),
+        }
+    }
+}
+
+impl CodecProfile {
+    pub fn version(&self) -> CodecVersion {
+        self.version
+    }
+
+    pub fn a(&self) -> char {
+        self.a
+    }
+
+    pub fn b(&self) -> char {
+        self.b
+    }
+
+    /// Creates the codec.
+    pub fn codec(&self) -> errors::Result<Codec> {
+        if self.a == self.b {
+            return Err(BaconError::CodecError("The A and B elements should be different".to_string()));
+        }
+        Ok(match self.version {
+            CodecVersion::V1 => Box::new(CharCodec::new(self.a, self.b)),
+            CodecVersion::V2 => Box::new(CharCodecV2::new(self.a, self.b)),
+        })
+    }
+}
+
+/// A Markdown marker. Either a string that is used as both the start and the end marker, or a template that contains
+/// the placeholder `{}`, or a table with the `start` and the `end` markers.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum MarkerProfile {
+    Symmetric(String),
+    Asymmetric { start: String, end: String },
+}
+
+impl MarkerProfile {
+    fn marker(&self) -> errors::Result<Marker> {
+        match self {
+            MarkerProfile::Symmetric(marker) if marker.contains(PLACEHOLDER) => Marker::template(marker),
+            MarkerProfile::Symmetric(marker) => Ok(Marker::new(Some(marker), Some(marker))),
+            MarkerProfile::Asymmetric { start, end } => Ok(Marker::new(Some(start), Some(end))),
+        }
+    }
+}
+
+/// The steganographer of a [Profile](struct.Profile.html).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum SteganographerProfile {
+    #[serde(rename_all = "kebab-case")]
+    LetterCase {
+        #[serde(default)]
+        ascii_only: bool,
+        #[serde(default)]
+        padding: Padding,
+    },
+    /// A missing marker leaves the characters unmarked.
+    #[serde(rename_all = "kebab-case")]
+    Markdown {
+        a_marker: Option<MarkerProfile>,
+        b_marker: Option<MarkerProfile>,
+        #[serde(default)]
+        escape_policy: EscapePolicy,
+        #[serde(default)]
+        wrapping: Wrapping,
+    },
+    #[serde(rename_all = "kebab-case")]
+    HtmlEntity {
+        #[serde(default)]
+        hexadecimal: bool,
+    },
+    #[serde(rename_all = "kebab-case")]
+    MathAlphanumeric {
+        a_style: LetterStyle,
+        b_style: LetterStyle,
+    },
+    /// The substitution table is a list of `[A form, B form]` pairs. The default table is used if it is missing.
+    Punctuation {
+        substitutions: Option<Vec<(char, char)>>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    Whitespace {
+        a_spaces: usize,
+        b_spaces: usize,
+    },
+    /// The start nodes of the tags, like `<b>`. The end nodes are optional and default to the closing tags of the start nodes.
+    #[cfg(feature = "extended-steganography")]
+    #[serde(rename_all = "kebab-case")]
+    Tag {
+        a_tag: String,
+        a_end_tag: Option<String>,
+        b_tag: String,
+        b_end_tag: Option<String>,
+        #[serde(default)]
+        wrapping: Wrapping,
+    },
+    /// A configuration string of the default [Registry](../registry/struct.Registry.html).
+    Scheme {
+        config: String,
+    },
+}
+
+impl Profile {
+    /// Parses a `Profile` from TOML.
+    pub fn from_toml(toml: &str) -> errors::Result<Profile> {
+        toml::from_str(toml).map_err(|error| BaconError::GeneralError(format!("Invalid profile: {}", error)))
+    }
+
+    /// Parses a `Profile` from JSON.
+    pub fn from_json(json: &str) -> errors::Result<Profile> {
+        serde_json::from_str(json).map_err(|error| BaconError::GeneralError(format!("Invalid profile: {}", error)))
+    }
+
+    pub fn codec(&self) -> &CodecProfile {
+        &self.codec
+    }
+
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    pub fn steganographer(&self) -> &SteganographerProfile {
+        &self.steganographer
+    }
+
+    /// Creates the steganographer and the codec of the profile, along with its framing.
+    pub fn scheme(&self) -> errors::Result<Scheme> {
+        let scheme = self.unframed_scheme(self.codec.codec()?)?;
+        Ok(match self.framing {
+            Framing::None => scheme,
+            Framing::Envelope => scheme.with_envelope(Envelope::new()),
+        })
+    }
+
+    fn unframed_scheme(&self, codec: Codec) -> errors::Result<Scheme> {
+        match &self.steganographer {
+            SteganographerProfile::LetterCase { ascii_only, padding } => {
+                let mut steganographer = LetterCaseSteganographer::new();
+                steganographer.set_ascii_only(*ascii_only);
+                steganographer.set_padding(*padding);
+                Ok(Scheme::new("letter-case", steganographer, codec))
+            }
+            SteganographerProfile::Markdown { a_marker, b_marker, escape_policy, wrapping } => {
+                let marker = |marker: &Option<MarkerProfile>| marker.as_ref().map(|m| m.marker()).unwrap_or_else(|| Ok(Marker::empty()));
+                let steganographer = MarkdownSteganographer::new(marker(a_marker)?, marker(b_marker)?)?
+                    .with_escape_policy(*escape_policy)
+                    .with_wrapping(*wrapping);
+                Ok(Scheme::new("markdown", steganographer, codec))
+            }
+            SteganographerProfile::HtmlEntity { hexadecimal } => {
+                let mut steganographer = HtmlEntitySteganographer::new();
+                steganographer.set_hexadecimal(*hexadecimal);
+                Ok(Scheme::new("html-entity", steganographer, codec))
+            }
+            SteganographerProfile::MathAlphanumeric { a_style, b_style } => {
+                Ok(Scheme::new("math-alphanumeric", MathAlphanumericSteganographer::new(*a_style, *b_style)?, codec))
+            }
+            SteganographerProfile::Punctuation { substitutions } => {
+                let steganographer = match substitutions {
+                    Some(substitutions) => PunctuationSteganographer::new(substitutions)?,
+                    None => PunctuationSteganographer::default(),
+                };
+                Ok(Scheme::new("punctuation", steganographer, codec))
+            }
+            SteganographerProfile::Whitespace { a_spaces, b_spaces } => {
+                Ok(Scheme::new("whitespace", WhitespaceSteganographer::with_spaces(*a_spaces, *b_spaces)?, codec))
+            }
+            #[cfg(feature = "extended-steganography")]
+            SteganographerProfile::Tag { a_tag, a_end_tag, b_tag, b_end_tag, wrapping } => {
+                let tag = |start_node: &str, end_node: &Option<String>| {
+                    let tag = Tag::new(Some(start_node), None);
+                    let end_node = match (end_node, tag.selector()) {
+                        (Some(end_node), _) => end_node.clone(),
+                        (None, Some(selector)) => format!("</{}>", selector.name()),
+                        (None, None) => return Err(BaconError::GeneralError(format!("Invalid start tag {:?}", start_node))),
+                    };
+                    Ok(Tag::new(Some(start_node), Some(&end_node)))
+                };
+                let steganographer = SimpleTagSteganographer::new(tag(a_tag, a_end_tag)?, tag(b_tag, b_end_tag)?)
+                    .with_wrapping(*wrapping);
+                Ok(Scheme::new("tag", steganographer, codec))
+            }
+            SteganographerProfile::Scheme { config } => Registry::default().scheme_with_codec(config, codec),
+        }
+    }
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    fn disguise_and_reveal(profile: &Profile) -> String {
+        let scheme = profile.scheme().unwrap();
+        let public = "This is a public message that contains a secret one";
+        let disguised = scheme.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars())).unwrap();
+        String::from_iter(scheme.reveal(&disguised).unwrap().iter())
+    }
+
+    #[test]
+    fn profile_from_toml() {
+        let profile = Profile::from_toml(r#"
+            [codec]
+            version = "v2"
+            a = "0"
+            b = "1"
+
+            [steganographer]
+            type = "markdown"
+            a-marker = { start = "[", end = "](#a)" }
+            b-marker = "**"
+            escape-policy = "strip"
+        "#).unwrap();
+        assert_eq!(profile.codec().version(), CodecVersion::V2);
+        assert_eq!(profile.codec().a(), '0');
+        let scheme = profile.scheme().unwrap();
+        // H = AABBB
+        let output = scheme.disguise(&['H'], &Vec::from_iter("He*llo".chars())).unwrap();
+        assert_eq!(String::from_iter(output.iter()), "[He](#a)**llo**");
+        assert!(disguise_and_reveal(&profile).starts_with("MYSECRET"));
+
+        let profile = Profile::from_toml("[steganographer]\ntype = \"letter-case\"").unwrap();
+        assert_eq!(profile.codec(), &CodecProfile::default());
+        assert!(disguise_and_reveal(&profile).starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn profile_from_json() {
+        let profiles = [
+            r#"{"steganographer": {"type": "math-alphanumeric", "a-style": "plain", "b-style": "bold"}}"#,
+            r#"{"steganographer": {"type": "html-entity", "hexadecimal": true}}"#,
+            r#"{"codec": {"a": "x", "b": "y"}, "steganographer": {"type": "scheme", "config": "markdown:*/**"}}"#,
+            r#"{"steganographer": {"type": "markdown", "b-marker": "_{}_"}}"#,
+            r#"{"steganographer": {"type": "scheme", "config": "zero-width"}}"#,
+        ];
+        for json in profiles.iter() {
+            let profile = Profile::from_json(json).unwrap();
+            assert!(disguise_and_reveal(&profile).starts_with("MYSECRET"), "{}", json);
+        }
+        let profile = Profile::from_json(r#"{"steganographer": {"type": "markdown", "a-marker": "*", "b-marker": "**", "wrapping": "words"}}"#).unwrap();
+        // H = AABBB
+        let output = profile.scheme().unwrap().disguise(&['H'], &Vec::from_iter("Here is my plan: I go".chars())).unwrap();
+        assert_eq!(String::from_iter(output.iter()), "Here *is* **my** plan: **I** go");
+        let profile = Profile::from_json(r#"{"steganographer": {"type": "punctuation", "substitutions": [["'", "’"]]}}"#).unwrap();
+        assert!(profile.scheme().is_ok());
+        let profile = Profile::from_json(r#"{"steganographer": {"type": "whitespace", "a-spaces": 1, "b-spaces": 3}}"#).unwrap();
+        assert!(profile.scheme().is_ok());
+    }
+
+    #[cfg(feature = "extended-steganography")]
+    #[test]
+    fn tag_profile() {
+        let profile = Profile::from_toml(r#"
+            [steganographer]
+            type = "tag"
+            a-tag = "<b>"
+            b-tag = "<span class=\"x\">"
+        "#).unwrap();
+        let scheme = profile.scheme().unwrap();
+        let output = scheme.disguise(&['H'], &Vec::from_iter("Hello".chars())).unwrap();
+        assert_eq!(String::from_iter(output.iter()), "<b>He</b><span class=\"x\">llo</span>");
+    }
+
+    #[test]
+    fn framing_and_padding() {
+        let profile = Profile::from_toml(r#"
+            framing = "envelope"
+
+            [steganographer]
+            type = "letter-case"
+            padding = { random-letters = 7 }
+        "#).unwrap();
+        assert_eq!(profile.framing(), Framing::Envelope);
+        assert_eq!(profile.steganographer(), &SteganographerProfile::LetterCase { ascii_only: false, padding: Padding::RandomLetters(7) });
+        let scheme = profile.scheme().unwrap();
+        assert!(scheme.envelope().is_some());
+        // The header of the envelope needs 34 elements
+        assert_eq!(scheme.required_capacity(&['H', 'I']), 44);
+        let public = Vec::from_iter("It is a truth universally acknowledged, that a single man in possession of a good fortune".chars());
+        let disguised = scheme.disguise(&['H', 'I'], &public).unwrap();
+        assert_eq!(scheme.reveal(&disguised).unwrap(), vec!['H', 'I']);
+        assert!(scheme.verify(&['H', 'I'], &public).is_ok());
+        // Every carrier is padded
+        assert_ne!(disguised[60..], public[60..]);
+
+        let profiles = [
+            r#"{"framing": "envelope", "steganographer": {"type": "scheme", "config": "markdown:*/**"}}"#,
+            r#"{"framing": "none", "steganographer": {"type": "letter-case", "padding": "repeat"}}"#,
+            r#"{"steganographer": {"type": "letter-case", "padding": "nulls"}}"#,
+            r#"{"framing": "envelope", "steganographer": {"type": "letter-case", "padding": {"random-letters": 7}}}"#,
+        ];
+        let public = public.repeat(2);
+        for json in profiles.iter() {
+            let scheme = Profile::from_json(json).unwrap().scheme().unwrap();
+            let disguised = scheme.disguise(&['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'], &public).unwrap();
+            assert_eq!(String::from_iter(scheme.reveal(&disguised).unwrap().iter()), "MYSECRET", "{}", json);
+        }
+        assert!(Profile::from_json(r#"{"framing": "unknown", "steganographer": {"type": "letter-case"}}"#).is_err());
+        assert!(Profile::from_json(r#"{"steganographer": {"type": "letter-case", "padding": "unknown"}}"#).is_err());
+    }
+
+    #[test]
+    fn profile_failures() {
+        assert!(Profile::from_toml("").is_err());
+        assert!(Profile::from_toml("[steganographer]\ntype = \"unknown\"").is_err());
+        assert!(Profile::from_toml("[steganographer]\ntype = \"letter-case\"\nunknown = 1").is_err());
+        assert!(Profile::from_json("{\"steganographer\": {\"type\": \"letter-case\"}, \"codec\": {\"a\": \"ab\"}}").is_err());
+        let profile = Profile::from_json("{\"steganographer\": {\"type\": \"letter-case\"}, \"codec\": {\"a\": \"b\"}}").unwrap();
+        assert!(profile.scheme().is_err());
+        let profile = Profile::from_json("{\"steganographer\": {\"type\": \"markdown\"}}").unwrap();
+        assert!(profile.scheme().is_err());
+        let profile = Profile::from_json("{\"steganographer\": {\"type\": \"scheme\", \"config\": \"zero-width:x\"}}").unwrap();
+        assert!(profile.scheme().is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `bacon_cipher` Python module. Needs the feature `python`.
+//!
+//! The module is built with [maturin](https://www.maturin.rs), using the `pyproject.toml` of the crate, which builds
+//! the crate as a `cdylib` only for the extension module. The default codec of the module uses `a` and `b`:
+//!
+//! ```python
+//! import bacon_cipher
+//!
+//! codec = bacon_cipher.CharCodec()
+//! steganographer = bacon_cipher.Steganographer("letter-case", codec)
+//! disguised = steganographer.disguise("My secret", "This is a public message that contains a secret one")
+//! assert steganographer.reveal(disguised).startswith("MYSECRET")
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+use crate::errors::BaconError;
+use crate::registry::{Codec, Registry, Scheme};
+
+fn value_error(error: BaconError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+fn single_char(name: &str, s: &str) -> PyResult<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(PyValueError::new_err(format!("The element {} should be a single character. It was found to be {:?}", name, s))),
+    }
+}
+
+/// A codec that encodes and decodes `str`s, substituting with the characters `a` and `b`.
+#[pyclass(name = "CharCodec", module = "bacon_cipher", from_py_object)]
+#[derive(Clone)]
+pub struct PyCharCodec {
+    a: char,
+    b: char,
+    version: u8,
+}
+
+impl PyCharCodec {
+    fn codec(&self) -> Codec {
+        if self.version == 1 {
+            Box::new(CharCodec::new(self.a, self.b))
+        } else {
+            Box::new(CharCodecV2::new(self.a, self.b))
+        }
+    }
+}
+
+#[pymethods]
+impl PyCharCodec {
+    /// Creates a codec for the first or the second version of the Bacon's cipher.
+    #[new]
+    #[pyo3(signature = (a = "a", b = "b", version = 1))]
+    fn new(a: &str, b: &str, version: u8) -> PyResult<PyCharCodec> {
+        let (a, b) = (single_char("a", a)?, single_char("b", b)?);
+        if a == b {
+            Err(PyValueError::new_err("The A and B elements should be different"))
+        } else if version != 1 && version != 2 {
+            Err(PyValueError::new_err(format!("The version should be 1 or 2. It was found to be {}", version)))
+        } else {
+            Ok(PyCharCodec { a, b, version })
+        }
+    }
+
+    /// Encodes the secret to a `str` of the `a` and `b` elements.
+    fn encode(&self, secret: &str) -> String {
+        let secret: Vec<char> = secret.chars().collect();
+        self.codec().encode(&secret).into_iter().collect()
+    }
+
+    /// Decodes the `a` and `b` elements of the input. Any other characters are ignored.
+    fn decode(&self, encoded: &str) -> String {
+        let codec = self.codec();
+        let encoded: Vec<char> = encoded.chars()
+            .filter(|c| codec.is_a(c) || codec.is_b(c))
+            .collect();
+        codec.decode(&encoded).into_iter().collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CharCodec('{}', '{}', version={})", self.a, self.b, self.version)
+    }
+}
+
+/// A steganographer, created from a scheme of the registry, like `letter-case` or `markdown:*/**`.
+#[pyclass(name = "Steganographer", module = "bacon_cipher", unsendable)]
+pub struct PySteganographer {
+    scheme: Scheme,
+}
+
+#[pymethods]
+impl PySteganographer {
+    #[new]
+    #[pyo3(signature = (scheme = "letter-case", codec = None))]
+    fn new(scheme: &str, codec: Option<PyCharCodec>) -> PyResult<PySteganographer> {
+        let codec = codec.unwrap_or(PyCharCodec { a: 'a', b: 'b', version: 1 });
+        Ok(PySteganographer {
+            scheme: Registry::default().scheme_with_codec(scheme, codec.codec()).map_err(value_error)?,
+        })
+    }
+
+    /// Disguises the secret into the public text.
+    fn disguise(&self, secret: &str, public: &str) -> PyResult<String> {
+        let secret: Vec<char> = secret.chars().collect();
+        let public: Vec<char> = public.chars().collect();
+        Ok(self.scheme.disguise(&secret, &public).map_err(value_error)?.into_iter().collect())
+    }
+
+    /// Reveals the secret that is hidden in the input.
+    fn reveal(&self, input: &str) -> PyResult<String> {
+        let input: Vec<char> = input.chars().collect();
+        Ok(self.scheme.reveal(&input).map_err(value_error)?.into_iter().collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Steganographer('{}')", self.scheme.name())
+    }
+}
+
+/// Returns the names of the schemes that can create a `Steganographer`.
+#[pyfunction]
+fn schemes() -> Vec<String> {
+    Registry::default().names().into_iter().map(|name| name.to_string()).collect()
+}
+
+#[pymodule]
+fn bacon_cipher(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCharCodec>()?;
+    m.add_class::<PySteganographer>()?;
+    m.add_function(wrap_pyfunction!(schemes, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod python_tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode() {
+        let codec = PyCharCodec::new("A", "B", 1).unwrap();
+        assert_eq!(codec.encode("My secret"), "ABABBBABBABAAABAABAAAAABABAAAAAABAABAABA");
+        assert_eq!(codec.decode("ABABB BABBA"), "MY");
+        let codec = PyCharCodec::new("0", "1", 2).unwrap();
+        assert_eq!(codec.encode("j"), "01001");
+        assert!(PyCharCodec::new("A", "A", 1).is_err());
+        assert!(PyCharCodec::new("AB", "C", 1).is_err());
+        assert!(PyCharCodec::new("A", "B", 3).is_err());
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let s = PySteganographer::new("markdown:*/**", None).unwrap();
+        let disguised = s.disguise("My secret", "This is a public message that contains a secret one").unwrap();
+        assert!(s.reveal(&disguised).unwrap().starts_with("MYSECRET"));
+        let s = PySteganographer::new("letter-case", Some(PyCharCodec::new("A", "B", 2).unwrap())).unwrap();
+        assert!(s.disguise("My secret", "Too short").is_err());
+        assert!(PySteganographer::new("unknown", None).is_err());
+        assert!(schemes().contains(&"letter-case".to_string()));
+    }
+}
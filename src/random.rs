@@ -0,0 +1,74 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A small, deterministic pseudo-random generator (SplitMix64).
+//
+// It is not cryptographically secure. It is used where the crate needs reproducible choices based on a seed,
+// so that the same seed produces the same output on every platform and version.
+#[derive(Debug, Clone)]
+pub(crate) struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub(crate) fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Returns a number in `0..bound`. The `bound` must be greater than zero.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    // Returns a permutation of `0..len`, shuffled with the Fisher-Yates algorithm.
+    pub(crate) fn permutation(&mut self, len: usize) -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..len).collect();
+        for i in (1..len).rev() {
+            permutation.swap(i, self.below(i + 1));
+        }
+        permutation
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut r1 = Random::new(42);
+        let mut r2 = Random::new(42);
+        let v1: Vec<usize> = (0..10).map(|_| r1.below(100)).collect();
+        let v2: Vec<usize> = (0..10).map(|_| r2.below(100)).collect();
+        assert_eq!(v1, v2);
+        assert!(v1.iter().all(|n| *n < 100));
+    }
+
+    #[test]
+    fn permutation() {
+        let mut permutation = Random::new(42).permutation(20);
+        assert_eq!(permutation, Random::new(42).permutation(20));
+        assert_ne!(permutation, (0..20).collect::<Vec<usize>>());
+        permutation.sort();
+        assert_eq!(permutation, (0..20).collect::<Vec<usize>>());
+    }
+}
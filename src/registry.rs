@@ -0,0 +1,616 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Creates steganographers from configuration strings.
+//!
+//! A configuration string is the name of a scheme, optionally followed by a colon and an argument, like `letter-case`,
+//! `markdown:*/**` or `tag:<b>/<i>`. The [Registry](struct.Registry.html) maps the names to factories that construct
+//! [Scheme](struct.Scheme.html)s, which are steganographers along with the codecs that they use.
+//!
+//! The default registry contains the following schemes:
+//!
+//! * `letter-case`
+//! * `markdown`, `markdown:<B marker>` or `markdown:<A marker>/<B marker>`, where an empty marker leaves the characters unmarked
+//!   and a marker that contains the placeholder `{}` is a [template](../stega/markdown/struct.Marker.html#method.template).
+//!   The default is `markdown:/**`.
+//! * `html-entity`
+//! * `math-alphanumeric`
+//! * `punctuation`
+//! * `whitespace`
+//! * `zero-width`
+//! * `tag:<A start tag>/<B start tag>`, like `tag:<b>/<i>` (needs the feature `extended-steganography`)
+//!
+//! More schemes can be added with [register](struct.Registry.html#method.register).
+//!
+//! Other crates can contribute schemes as [Plugin](trait.Plugin.html)s. A plugin that is registered with
+//! [register_global](fn.register_global.html) is contained in every default registry that is created afterwards, so that
+//! it is available to the profiles and the `bacon` command line tool, which lists the schemes with `bacon schemes`.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::codecs::char_codec::CharCodec;
+use crate::dynamic::DynSteganographer;
+use crate::envelope::{self, Envelope};
+use crate::errors::{BaconError, CapacityUnit};
+use crate::report::RevealReport;
+use crate::stega::html_entity::HtmlEntitySteganographer;
+use crate::stega::letter_case::LetterCaseSteganographer;
+use crate::stega::markdown::{MarkdownSteganographer, Marker, PLACEHOLDER};
+use crate::stega::math_alphanumeric::MathAlphanumericSteganographer;
+use crate::stega::punctuation::PunctuationSteganographer;
+#[cfg(feature = "extended-steganography")]
+use crate::stega::tags::{SimpleTagSteganographer, Tag, TagSelector};
+use crate::stega::whitespace::WhitespaceSteganographer;
+use crate::stega::zero_width::ZeroWidthSteganographer;
+
+/// A codec for `char` secrets that uses `char` substitution elements.
+pub type Codec = Box<dyn BaconCodec<ABTYPE=char, CONTENT=char> + Send + Sync>;
+
+// A steganographer along with its codec, with the types erased.
+trait ErasedScheme: Send + Sync {
+    fn disguise(&self, secret: &[char], public: &[char]) -> errors::Result<Vec<char>>;
+
+    fn reveal(&self, input: &[char]) -> errors::Result<Vec<char>>;
+
+    fn reveal_report(&self, input: &[char]) -> errors::Result<RevealReport<char>>;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize>;
+
+    fn required_capacity(&self, secret: &[char]) -> usize;
+
+    fn verify(&self, secret: &[char], public: &[char]) -> errors::Result<()>;
+
+    fn envelope(&self) -> Option<&Envelope>;
+
+    fn set_envelope(&mut self, envelope: Option<Envelope>);
+}
+
+// A steganographer that seals the secret in an envelope, if one is set.
+struct Framed<S> {
+    steganographer: S,
+    envelope: Option<Envelope>,
+}
+
+impl<S: Steganographer<T=char>> Steganographer for Framed<S> {
+    type T = char;
+
+    // The sealed secret is written with the codec, so that the steganographer pads it like an encoded secret
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let envelope = match &self.envelope {
+            Some(envelope) => envelope,
+            None => return self.steganographer.disguise(secret, public, codec),
+        };
+        let sealed: Vec<bool> = envelope.seal(secret, codec)?.elements().collect();
+        let available = Steganographer::capacity(&self.steganographer, public)?;
+        if available < sealed.len() {
+            return Err(BaconError::InsufficientCapacity { required: sealed.len(), available, unit: CapacityUnit::Carriers });
+        }
+        self.steganographer.disguise(secret, public, &Sealed { codec, sealed })
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        match &self.envelope {
+            Some(_) => envelope::reveal(&self.steganographer, input, codec).map(|(_, secret)| secret),
+            None => self.steganographer.reveal(input, codec),
+        }
+    }
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Steganographer::capacity(&self.steganographer, public)
+    }
+}
+
+// A codec that encodes any secret to the elements of its envelope.
+struct Sealed<'a, AB> {
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>,
+    sealed: Vec<bool>,
+}
+
+impl<'a, AB> BaconCodec for Sealed<'a, AB> {
+    type ABTYPE = AB;
+    type CONTENT = char;
+
+    fn encode(&self, _input: &[char]) -> Vec<AB> {
+        self.sealed.iter()
+            .map(|is_a| if *is_a { self.codec.a() } else { self.codec.b() })
+            .collect()
+    }
+
+    fn encode_elem(&self, elem: &char) -> Vec<AB> {
+        self.codec.encode_elem(elem)
+    }
+
+    fn decode_elems(&self, elems: &[AB]) -> char {
+        self.codec.decode_elems(elems)
+    }
+
+    fn a(&self) -> AB {
+        self.codec.a()
+    }
+
+    fn b(&self) -> AB {
+        self.codec.b()
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        self.codec.encoded_group_size()
+    }
+
+    fn is_a(&self, elem: &AB) -> bool {
+        self.codec.is_a(elem)
+    }
+
+    fn is_b(&self, elem: &AB) -> bool {
+        self.codec.is_b(elem)
+    }
+}
+
+struct Pair<S, AB> {
+    steganographer: Framed<S>,
+    codec: Box<dyn BaconCodec<ABTYPE=AB, CONTENT=char> + Send + Sync>,
+}
+
+impl<S, AB> Pair<S, AB> {
+    fn new(steganographer: S, codec: Box<dyn BaconCodec<ABTYPE=AB, CONTENT=char> + Send + Sync>) -> Pair<S, AB> {
+        Pair {
+            steganographer: Framed { steganographer, envelope: None },
+            codec,
+        }
+    }
+}
+
+impl<S: Steganographer<T=char> + Send + Sync, AB> ErasedScheme for Pair<S, AB> {
+    fn disguise(&self, secret: &[char], public: &[char]) -> errors::Result<Vec<char>> {
+        self.steganographer.disguise(secret, public, self.codec.as_ref())
+    }
+
+    fn reveal(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        self.steganographer.reveal(input, self.codec.as_ref())
+    }
+
+    fn reveal_report(&self, input: &[char]) -> errors::Result<RevealReport<char>> {
+        self.steganographer.reveal_report(input, self.codec.as_ref())
+    }
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        self.steganographer.capacity(public)
+    }
+
+    fn required_capacity(&self, secret: &[char]) -> usize {
+        self.steganographer.envelope
+            .and_then(|envelope| envelope.seal(secret, self.codec.as_ref()).ok())
+            .map(|sealed| sealed.len())
+            .unwrap_or_else(|| self.codec.encode(secret).len())
+    }
+
+    fn verify(&self, secret: &[char], public: &[char]) -> errors::Result<()> {
+        self.steganographer.verify(secret, public, self.codec.as_ref())
+    }
+
+    fn envelope(&self) -> Option<&Envelope> {
+        self.steganographer.envelope.as_ref()
+    }
+
+    fn set_envelope(&mut self, envelope: Option<Envelope>) {
+        self.steganographer.envelope = envelope;
+    }
+}
+
+/// A steganographer along with the codec that it uses.
+pub struct Scheme {
+    name: String,
+    inner: Box<dyn ErasedScheme>,
+}
+
+impl Scheme {
+    pub fn new<S, AB>(name: &str, steganographer: S, codec: Box<dyn BaconCodec<ABTYPE=AB, CONTENT=char> + Send + Sync>) -> Scheme
+        where S: Steganographer<T=char> + Send + Sync + 'static,
+              AB: 'static {
+        Scheme {
+            name: name.to_string(),
+            inner: Box::new(Pair::new(steganographer, codec)),
+        }
+    }
+
+    /// Seals the secrets in the envelope, whose header precedes them in the carriers, like
+    /// [envelope::disguise](../envelope/fn.disguise.html). The reveal opens the envelope and returns the secret only.
+    pub fn with_envelope(mut self, envelope: Envelope) -> Self {
+        self.set_envelope(Some(envelope));
+        self
+    }
+
+    pub fn set_envelope(&mut self, envelope: Option<Envelope>) {
+        self.inner.set_envelope(envelope);
+    }
+
+    pub fn envelope(&self) -> Option<&Envelope> {
+        self.inner.envelope()
+    }
+
+    /// The configuration string that created the scheme.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn disguise(&self, secret: &[char], public: &[char]) -> errors::Result<Vec<char>> {
+        enter_span!("disguise", scheme = %self.name);
+        self.inner.disguise(secret, public)
+    }
+
+    pub fn reveal(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        enter_span!("reveal", scheme = %self.name);
+        self.inner.reveal(input)
+    }
+
+    /// Reveals the secret along with diagnostics. See [Steganographer::reveal_report](../trait.Steganographer.html#method.reveal_report).
+    pub fn reveal_report(&self, input: &[char]) -> errors::Result<RevealReport<char>> {
+        self.inner.reveal_report(input)
+    }
+
+    /// The number of Bacon's elements that the _public_ text can carry. See [Steganographer::capacity](../trait.Steganographer.html#method.capacity).
+    pub fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        self.inner.capacity(public)
+    }
+
+    /// The number of Bacon's elements that the codec of the scheme encodes the _secret_ to.
+    pub fn required_capacity(&self, secret: &[char]) -> usize {
+        self.inner.required_capacity(secret)
+    }
+
+    /// Disguises the _secret_ and reveals it from the output. See [Steganographer::verify](../trait.Steganographer.html#method.verify).
+    pub fn verify(&self, secret: &[char], public: &[char]) -> errors::Result<()> {
+        self.inner.verify(secret, public)
+    }
+}
+
+type Factory = Box<dyn Fn(&str, Codec) -> errors::Result<Box<dyn ErasedScheme>> + Send + Sync>;
+
+/// Describes how the steganographer of a scheme transforms the public text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    mapping: bool,
+    markup: bool,
+    argument: bool,
+}
+
+impl Capabilities {
+    /// Creates `Capabilities` without any capability.
+    pub fn new() -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// The steganographer supports [mappings](../mapping/struct.Mapping.html).
+    pub fn with_mapping(mut self) -> Self {
+        self.mapping = true;
+        self
+    }
+
+    /// The steganographer adds markup, like Markdown markers, HTML tags or entities, to the public text.
+    pub fn with_markup(mut self) -> Self {
+        self.markup = true;
+        self
+    }
+
+    /// The scheme accepts an argument in its configuration string.
+    pub fn with_argument(mut self) -> Self {
+        self.argument = true;
+        self
+    }
+
+    pub fn supports_mapping(&self) -> bool {
+        self.mapping
+    }
+
+    pub fn adds_markup(&self) -> bool {
+        self.markup
+    }
+
+    pub fn accepts_argument(&self) -> bool {
+        self.argument
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = [(self.mapping, "mapping"), (self.markup, "markup"), (self.argument, "argument")].iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, name)| *name)
+            .collect();
+        write!(f, "{}", names.join(", "))
+    }
+}
+
+/// A scheme that is contributed by another crate.
+pub trait Plugin: Send + Sync {
+    /// The name of the scheme, which starts its configuration strings.
+    fn name(&self) -> &str;
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+    }
+
+    /// Creates the steganographer of the scheme from the argument of a configuration string, which is empty if there is none.
+    fn create(&self, argument: &str) -> errors::Result<Box<dyn DynSteganographer>>;
+}
+
+static PLUGINS: Mutex<Vec<Arc<dyn Plugin>>> = Mutex::new(Vec::new());
+
+/// Registers a plugin in every default [Registry](struct.Registry.html) that is created afterwards.
+///
+/// A plugin replaces any built-in scheme or plugin with the same name.
+pub fn register_global<P: Plugin + 'static>(plugin: P) {
+    PLUGINS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(Arc::new(plugin));
+}
+
+struct Entry {
+    name: String,
+    capabilities: Capabilities,
+    factory: Factory,
+}
+
+/// Maps the names of schemes to factories that construct them.
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// Creates an empty `Registry`. Use [default](struct.Registry.html#method.default) for a `Registry` that contains the built-in schemes.
+    pub fn new() -> Registry {
+        Registry {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Registers a factory for the given name, replacing any factory that is already registered for it.
+    ///
+    /// The factory is called with the argument of the configuration string, which is empty if there is none.
+    pub fn register<S, F>(&mut self, name: &str, factory: F)
+        where S: Steganographer<T=char> + Send + Sync + 'static,
+              F: Fn(&str) -> errors::Result<S> + Send + Sync + 'static {
+        self.register_with_capabilities(name, Capabilities::new(), factory);
+    }
+
+    /// Registers a factory like [register](struct.Registry.html#method.register), along with the capabilities of its scheme.
+    pub fn register_with_capabilities<S, F>(&mut self, name: &str, capabilities: Capabilities, factory: F)
+        where S: Steganographer<T=char> + Send + Sync + 'static,
+              F: Fn(&str) -> errors::Result<S> + Send + Sync + 'static {
+        let erase = move |argument: &str, codec: Codec| -> errors::Result<Box<dyn ErasedScheme>> {
+            Ok(Box::new(Pair::new(factory(argument)?, codec)))
+        };
+        self.entries.retain(|entry| entry.name != name);
+        self.entries.push(Entry { name: name.to_string(), capabilities, factory: Box::new(erase) });
+    }
+
+    /// Registers a plugin in this registry only. See [register_global](fn.register_global.html).
+    pub fn register_plugin<P: Plugin + 'static>(&mut self, plugin: P) {
+        self.add_plugin(Arc::new(plugin));
+    }
+
+    fn add_plugin(&mut self, plugin: Arc<dyn Plugin>) {
+        let name = plugin.name().to_string();
+        let capabilities = plugin.capabilities();
+        self.register_with_capabilities(&name, capabilities, move |argument| plugin.create(argument));
+    }
+
+    /// The names of the registered schemes.
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    /// The capabilities of a registered scheme.
+    pub fn capabilities(&self, name: &str) -> Option<Capabilities> {
+        self.entries.iter().find(|entry| entry.name == name).map(|entry| entry.capabilities)
+    }
+
+    /// Creates the scheme of the configuration string, using a [CharCodec](../codecs/char_codec/struct.CharCodec.html).
+    pub fn scheme(&self, config: &str) -> errors::Result<Scheme> {
+        self.scheme_with_codec(config, Box::new(CharCodec::new('a', 'b')))
+    }
+
+    /// Creates the scheme of the configuration string, using the given codec.
+    pub fn scheme_with_codec(&self, config: &str, codec: Codec) -> errors::Result<Scheme> {
+        let (name, argument) = match config.find(':') {
+            Some(index) => (&config[..index], &config[index + 1..]),
+            None => (config, ""),
+        };
+        let factory = self.entries.iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| &entry.factory)
+            .ok_or_else(|| BaconError::GeneralError(format!("Unknown scheme {:?}", name)))?;
+        Ok(Scheme {
+            name: config.to_string(),
+            inner: factory(argument, codec)?,
+        })
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        let mut registry = Registry::new();
+        let mapping = Capabilities::new().with_mapping();
+        registry.register_with_capabilities("letter-case", mapping, without_argument("letter-case", LetterCaseSteganographer::new));
+        registry.register_with_capabilities("markdown", mapping.with_markup().with_argument(), markdown);
+        registry.register_with_capabilities("html-entity", Capabilities::new().with_markup(),
+                                            without_argument("html-entity", HtmlEntitySteganographer::new));
+        registry.register_with_capabilities("math-alphanumeric", mapping,
+                                            without_argument("math-alphanumeric", MathAlphanumericSteganographer::default));
+        registry.register_with_capabilities("punctuation", mapping, without_argument("punctuation", PunctuationSteganographer::default));
+        registry.register("whitespace", without_argument("whitespace", WhitespaceSteganographer::new));
+        registry.register_with_capabilities("zero-width", mapping, without_argument("zero-width", ZeroWidthSteganographer::new));
+        #[cfg(feature = "extended-steganography")]
+        registry.register_with_capabilities("tag", Capabilities::new().with_markup().with_argument(), tag);
+        let plugins: Vec<Arc<dyn Plugin>> = PLUGINS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        for plugin in plugins {
+            registry.add_plugin(plugin);
+        }
+        registry
+    }
+}
+
+fn without_argument<S, F: Fn() -> S>(name: &'static str, constructor: F) -> impl Fn(&str) -> errors::Result<S> {
+    move |argument| {
+        if argument.is_empty() {
+            Ok(constructor())
+        } else {
+            Err(BaconError::GeneralError(format!("The scheme {:?} does not accept any arguments", name)))
+        }
+    }
+}
+
+fn markdown(argument: &str) -> errors::Result<MarkdownSteganographer> {
+    let marker = |marker: &str| {
+        if marker.is_empty() {
+            Ok(Marker::empty())
+        } else if marker.contains(PLACEHOLDER) {
+            Marker::template(marker)
+        } else {
+            Ok(Marker::new(Some(marker), Some(marker)))
+        }
+    };
+    let (a_marker, b_marker) = match argument.find('/') {
+        Some(index) => (&argument[..index], &argument[index + 1..]),
+        None if argument.is_empty() => ("", "**"),
+        None => ("", argument),
+    };
+    MarkdownSteganographer::new(marker(a_marker)?, marker(b_marker)?)
+}
+
+#[cfg(feature = "extended-steganography")]
+fn tag(argument: &str) -> errors::Result<SimpleTagSteganographer> {
+    let tag = |start_node: &str| {
+        TagSelector::parse(start_node)
+            .map(|selector| Tag::new(Some(start_node), Some(&format!("</{}>", selector.name()))))
+            .ok_or_else(|| BaconError::GeneralError(format!("Invalid start tag {:?}", start_node)))
+    };
+    match argument.find(">/<") {
+        Some(index) => Ok(SimpleTagSteganographer::new(tag(&argument[..index + 1])?, tag(&argument[index + 2..])?)),
+        None => Err(BaconError::GeneralError(
+            format!("The tag scheme should be defined like tag:<b>/<i>. It was found to be tag:{}", argument))),
+    }
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodecV2;
+    use crate::stega::morse::{MorseSteganographer, to_morse};
+
+    use super::*;
+
+    fn disguise_and_reveal(scheme: &Scheme) -> String {
+        let public = "This is a public message that contains a secret one";
+        let disguised = scheme.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars())).unwrap();
+        String::from_iter(scheme.reveal(&disguised).unwrap().iter())
+    }
+
+    #[test]
+    fn built_in_schemes() {
+        let registry = Registry::default();
+        for name in registry.names() {
+            assert!(registry.scheme(if name == "tag" { "tag:<b>/<i>" } else { name }).is_ok(), "{}", name);
+        }
+        for config in &["letter-case", "markdown", "html-entity", "math-alphanumeric", "zero-width"] {
+            assert!(disguise_and_reveal(&registry.scheme(config).unwrap()).starts_with("MYSECRET"), "{}", config);
+        }
+        #[cfg(feature = "extended-steganography")]
+        assert!(disguise_and_reveal(&registry.scheme("tag:<b>/<i>").unwrap()).starts_with("MYSECRET"));
+        for config in &["markdown:*", "markdown:*/**", "markdown:[{}](#{})/**", "letter-case"] {
+            let scheme = registry.scheme_with_codec(config, Box::new(CharCodecV2::new('0', '1'))).unwrap();
+            assert_eq!(scheme.name(), *config);
+            assert!(disguise_and_reveal(&scheme).starts_with("MYSECRET"), "{}", config);
+        }
+    }
+
+    #[test]
+    fn scheme_arguments() {
+        let registry = Registry::default();
+        let scheme = registry.scheme("markdown:*/**").unwrap();
+        // H = AABBB
+        let output = scheme.disguise(&['H'], &Vec::from_iter("Hello".chars())).unwrap();
+        assert_eq!(String::from_iter(output.iter()), "*He***llo**");
+        assert_eq!(scheme.capacity(&Vec::from_iter("Hello".chars())).unwrap(), 5);
+        assert_eq!(scheme.required_capacity(&['H', ' ', 'i']), 10);
+        #[cfg(feature = "extended-steganography")]
+        {
+            let scheme = registry.scheme("tag:<b class=\"x\">/<i>").unwrap();
+            let output = scheme.disguise(&['H'], &Vec::from_iter("Hello".chars())).unwrap();
+            assert_eq!(String::from_iter(output.iter()), "<b class=\"x\">He</b><i>llo</i>");
+        }
+    }
+
+    #[test]
+    fn register_a_scheme() {
+        let mut registry = Registry::new();
+        assert!(registry.scheme("letter-case").is_err());
+        registry.register("ascii-case", |_: &str| Ok(LetterCaseSteganographer::new().ascii_only()));
+        assert_eq!(registry.names(), vec!["ascii-case"]);
+        assert!(disguise_and_reveal(&registry.scheme("ascii-case").unwrap()).starts_with("MYSECRET"));
+    }
+
+    struct Morse;
+
+    impl Plugin for Morse {
+        fn name(&self) -> &str {
+            "morse-spacing"
+        }
+
+        fn create(&self, argument: &str) -> errors::Result<Box<dyn DynSteganographer>> {
+            without_argument("morse-spacing", MorseSteganographer::new)(argument)
+                .map(|s| Box::new(s) as Box<dyn DynSteganographer>)
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            Capabilities::new().with_mapping()
+        }
+    }
+
+    #[test]
+    fn register_plugins() {
+        let mut registry = Registry::new();
+        registry.register_plugin(Morse);
+        assert_eq!(registry.names(), vec!["morse-spacing"]);
+        assert_eq!(registry.capabilities("morse-spacing").unwrap().to_string(), "mapping");
+        let scheme = registry.scheme("morse-spacing").unwrap();
+        let public = Vec::from_iter(to_morse("CQ CQ de W1AW K").chars());
+        assert_eq!(scheme.reveal(&scheme.disguise(&['H'], &public).unwrap()).unwrap()[0], 'H');
+        assert!(registry.scheme("morse-spacing:x").is_err());
+
+        register_global(Morse);
+        let registry = Registry::default();
+        assert!(registry.names().contains(&"morse-spacing"));
+        assert!(registry.scheme("morse-spacing").is_ok());
+        assert_eq!(registry.capabilities("markdown").unwrap().to_string(), "mapping, markup, argument");
+        assert_eq!(registry.capabilities("whitespace"), Some(Capabilities::new()));
+        assert_eq!(registry.capabilities("zero-width"), Some(Capabilities::new().with_mapping()));
+    }
+
+    #[test]
+    fn scheme_failures() {
+        let registry = Registry::default();
+        assert!(registry.scheme("zero-width:x").is_err());
+        assert!(registry.scheme("letter-case:x").is_err());
+        assert!(registry.scheme("markdown:**/**").is_err());
+        assert!(registry.scheme("markdown:{}x").is_err());
+        #[cfg(feature = "extended-steganography")]
+        {
+            assert!(registry.scheme("tag:<b>").is_err());
+            assert!(registry.scheme("tag:<>/<i>").is_err());
+        }
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Diagnostics of a reveal, that show how trustworthy the revealed secret is.
+use std::cell::Cell;
+use std::fmt::Debug;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::BaconError;
+
+/// The secret that is revealed by a steganographer, along with diagnostics about the extraction.
+///
+/// It is created by [Steganographer::reveal_report](../trait.Steganographer.html#method.reveal_report).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevealReport<T> {
+    message: Vec<T>,
+    carrier_slots_used: usize,
+    invalid_groups: usize,
+    truncated: bool,
+}
+
+impl<T> RevealReport<T> {
+    /// The revealed secret. Unlike the output of `reveal`, it does not contain the content of an incomplete trailing group.
+    pub fn message(&self) -> &[T] {
+        &self.message
+    }
+
+    /// Consumes the report and returns the revealed secret.
+    pub fn into_message(self) -> Vec<T> {
+        self.message
+    }
+
+    /// The number of Bacon's elements that were found in the input.
+    pub fn carrier_slots_used(&self) -> usize {
+        self.carrier_slots_used
+    }
+
+    /// The number of complete groups of elements that do not correspond to any content of the codec.
+    pub fn invalid_groups(&self) -> usize {
+        self.invalid_groups
+    }
+
+    /// Whether the input ends with an incomplete group of elements.
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// The ratio of the complete groups that are valid, between 0.0 and 1.0. It is 0.0 if there are no complete groups.
+    pub fn confidence(&self) -> f64 {
+        let groups = self.message.len();
+        if groups == 0 {
+            0.0
+        } else {
+            (groups - self.invalid_groups) as f64 / groups as f64
+        }
+    }
+}
+
+// Delegates to a codec, recording the elements that are decoded.
+struct RecordingCodec<'a, AB, T> {
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=T>,
+    slots: Cell<usize>,
+    invalid_groups: Cell<usize>,
+}
+
+impl<'a, AB, T> RecordingCodec<'a, AB, T> {
+    // A group is valid if it is the encoding of the content that it decodes to.
+    fn is_valid(&self, group: &[AB], content: &T) -> bool {
+        let encoded = self.codec.encode_elem(content);
+        encoded.len() == group.len() &&
+            encoded.iter().zip(group.iter()).all(|(e, g)| self.codec.is_a(e) == self.codec.is_a(g))
+    }
+}
+
+impl<'a, AB, T> BaconCodec for RecordingCodec<'a, AB, T> {
+    type ABTYPE = AB;
+    type CONTENT = T;
+
+    fn encode_elem(&self, elem: &T) -> Vec<AB> {
+        self.codec.encode_elem(elem)
+    }
+
+    fn decode(&self, input: &[AB]) -> Vec<T> {
+        self.slots.set(self.slots.get() + input.len());
+        input.chunks(self.encoded_group_size())
+            .map(|group| {
+                let content = self.codec.decode_elems(group);
+                if group.len() == self.encoded_group_size() && !self.is_valid(group, &content) {
+                    self.invalid_groups.set(self.invalid_groups.get() + 1);
+                }
+                content
+            })
+            .collect()
+    }
+
+    fn decode_elems(&self, elems: &[AB]) -> T {
+        self.codec.decode_elems(elems)
+    }
+
+    fn a(&self) -> AB {
+        self.codec.a()
+    }
+
+    fn b(&self) -> AB {
+        self.codec.b()
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        self.codec.encoded_group_size()
+    }
+
+    fn is_a(&self, elem: &AB) -> bool {
+        self.codec.is_a(elem)
+    }
+
+    fn is_b(&self, elem: &AB) -> bool {
+        self.codec.is_b(elem)
+    }
+}
+
+pub(crate) fn reveal_report<S, AB>(steganographer: &S, input: &[S::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=S::T>) -> errors::Result<RevealReport<S::T>>
+    where S: Steganographer + ?Sized {
+    let recording = RecordingCodec {
+        codec,
+        slots: Cell::new(0),
+        invalid_groups: Cell::new(0),
+    };
+    let mut message = steganographer.reveal(input, &recording)?;
+    let slots = recording.slots.get();
+    let truncated = !slots.is_multiple_of(codec.encoded_group_size());
+    message.truncate(slots / codec.encoded_group_size());
+    Ok(RevealReport {
+        message,
+        carrier_slots_used: slots,
+        invalid_groups: recording.invalid_groups.get(),
+        truncated,
+    })
+}
+
+pub(crate) fn verify<S, AB>(steganographer: &S, secret: &[S::T], public: &[S::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=S::T>) -> errors::Result<()>
+    where S: Steganographer + ?Sized, S::T: PartialEq + Debug {
+    let disguised = steganographer.disguise(secret, public, codec)?;
+    let revealed = reveal_report(steganographer, &disguised, codec)?.into_message();
+    let expected = codec.decode(&codec.encode(secret));
+    let position = match expected.iter().zip(revealed.iter()).position(|(e, r)| e != r) {
+        Some(position) => position,
+        None if revealed.len() < expected.len() => revealed.len(),
+        None => return Ok(()),
+    };
+    let group_size = codec.encoded_group_size();
+    let expected_elems = codec.encode_elem(&expected[position]);
+    let offset = match revealed.get(position) {
+        Some(found) => codec.encode_elem(found).iter()
+            .zip(expected_elems.iter())
+            .position(|(f, e)| codec.is_a(f) != codec.is_a(e))
+            .unwrap_or(0),
+        None => 0,
+    };
+    Err(BaconError::RoundTripMismatch {
+        position,
+        element: position * group_size + offset,
+        expected: format!("{:?}", expected[position]),
+        found: revealed.get(position).map(|found| format!("{:?}", found)),
+    })
+}
+
+#[cfg(test)]
+mod report_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn report_of_a_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = Vec::from_iter("tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one".chars());
+        let report = s.reveal_report(&public, &codec).unwrap();
+        assert_eq!(String::from_iter(report.message().iter()), "MYSECRET");
+        assert_eq!(report.carrier_slots_used(), 42);
+        assert_eq!(report.invalid_groups(), 0);
+        assert!(report.truncated());
+        assert_eq!(report.confidence(), 1.0);
+    }
+
+    #[test]
+    fn report_of_invalid_groups() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        // H = AABBB and BBBBB is not a valid group
+        let report = s.reveal_report(&Vec::from_iter("heLLO WORLD".chars()), &codec).unwrap();
+        assert_eq!(String::from_iter(report.message().iter()), "H ");
+        assert_eq!(report.invalid_groups(), 1);
+        assert!(!report.truncated());
+        assert_eq!(report.confidence(), 0.5);
+
+        let report = s.reveal_report(&Vec::from_iter("123".chars()), &codec).unwrap();
+        assert_eq!(report.carrier_slots_used(), 0);
+        assert_eq!(report.confidence(), 0.0);
+    }
+
+    // Capitalizes the second character of the disguised output, like a careless publisher
+    struct CapitalizingPublisher(LetterCaseSteganographer);
+
+    impl Steganographer for CapitalizingPublisher {
+        type T = char;
+
+        fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+            let mut disguised = self.0.disguise(secret, public, codec)?;
+            disguised[1] = disguised[1].to_ascii_uppercase();
+            Ok(disguised)
+        }
+
+        fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+            self.0.reveal(input, codec)
+        }
+    }
+
+    #[test]
+    fn verify_a_round_trip() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        // J is revealed as I, which shares its code
+        assert!(s.verify(&Vec::from_iter("Jam".chars()), &Vec::from_iter("This is a public message".chars()), &codec).is_ok());
+        // H = AABBB, while the publisher capitalizes the second letter, so that ABBBB = Q is revealed
+        let publisher = CapitalizingPublisher(LetterCaseSteganographer::new());
+        assert_eq!(publisher.verify(&['H'], &Vec::from_iter("Hello world".chars()), &codec), Err(BaconError::RoundTripMismatch {
+            position: 0,
+            element: 1,
+            expected: "'H'".to_string(),
+            found: Some("'Q'".to_string()),
+        }));
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sessions that disguise a secret in a public text that arrives in chunks, like the paragraphs of a document or the
+//! words that a user types, and reveal it from a disguised text that arrives in chunks.
+//!
+//! A [DisguiseSession](struct.DisguiseSession.html) encodes the secret up front and writes its elements to every chunk,
+//! as much as the chunk can carry, with any [Carrier](../carrier/trait.Carrier.html). It keeps track of the elements
+//! that are embedded and the ones that still need a carrier.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::session::DisguiseSession;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let mut session = DisguiseSession::new(LetterCaseSteganographer::new(), &['H', 'I'], &codec).unwrap();
+//! assert_eq!(session.disguise_str("Hello, ").unwrap(), "heLLO, ");
+//! assert_eq!(session.remaining(), 5);
+//! assert_eq!(session.disguise_str("I am here. ").unwrap(), "i Am here. ");
+//! assert_eq!(session.disguise_str("Bye.").unwrap(), "Bye.");
+//! assert!(session.is_complete());
+//! ```
+//!
+//! A [RevealSession](struct.RevealSession.html) reads the elements of every chunk and keeps the elements of a group
+//! that is split across chunks, until the group is completed by the next ones.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::session::RevealSession;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let mut session = RevealSession::new(LetterCaseSteganographer::new(), &codec).with_expected_len(2);
+//! assert_eq!(session.reveal_str("heLLO, ").unwrap(), "H");
+//! assert_eq!(session.reveal_str("i Am").unwrap(), "");
+//! assert_eq!(session.pending(), 3);
+//! assert_eq!(session.reveal_str(" here.").unwrap(), "I");
+//! assert_eq!(session.message_so_far(), &['H', 'I']);
+//! assert!(session.is_complete());
+//! ```
+use crate::{BaconCodec, errors};
+use crate::carrier::Carrier;
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+
+/// Disguises a secret in a public input that arrives in chunks.
+///
+/// Every chunk is disguised on its own, so the chunks should be split where the carrier does not carry elements across
+/// them, like between the words for the letter case or between the paragraphs for the number of spaces between words.
+/// The chunks that arrive after the secret is embedded are returned untouched. Since the session disguises every chunk
+/// as it arrives, the padding of the steganographers applies only to the chunk that completes the secret.
+pub struct DisguiseSession<C> {
+    carrier: C,
+    symbols: Vec<bool>,
+    embedded: usize,
+}
+
+impl<C: Carrier> DisguiseSession<C> {
+    /// Creates a session that disguises the _secret_, encoded with the codec, with the carrier.
+    pub fn new<AB>(carrier: C, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<DisguiseSession<C>> {
+        BaconError::check_secret(secret)?;
+        let symbols = codec.encode(secret).iter().map(|elem| codec.is_a(elem)).collect();
+        Ok(DisguiseSession { carrier, symbols, embedded: 0 })
+    }
+
+    pub fn carrier(&self) -> &C {
+        &self.carrier
+    }
+
+    /// Disguises the next chunk of the public input, with as many of the remaining elements as it can carry.
+    pub fn disguise(&mut self, chunk: &[C::Unit]) -> errors::Result<Vec<C::Unit>> where C::Unit: Clone {
+        let count = self.carrier.capacity(chunk)?.min(self.remaining());
+        if count == 0 {
+            return Ok(chunk.to_vec());
+        }
+        let disguised = self.carrier.write_symbols(&self.symbols[self.embedded..self.embedded + count], chunk)?;
+        self.embedded += count;
+        Ok(disguised)
+    }
+
+    /// The number of the elements of the encoded secret.
+    pub fn total(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// The number of the elements that are embedded in the chunks so far.
+    pub fn embedded(&self) -> usize {
+        self.embedded
+    }
+
+    /// The number of the elements that still need a carrier, which is the capacity that the next chunks should have.
+    pub fn remaining(&self) -> usize {
+        self.symbols.len() - self.embedded
+    }
+
+    /// Returns true if the whole secret is embedded.
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Ends the session and returns the carrier.
+    ///
+    /// Fails if the chunks could not carry the whole secret.
+    pub fn finish(self) -> errors::Result<C> {
+        if self.is_complete() {
+            Ok(self.carrier)
+        } else {
+            Err(BaconError::InsufficientCapacity {
+                required: self.total(),
+                available: self.embedded,
+                unit: CapacityUnit::Carriers,
+            })
+        }
+    }
+}
+
+impl<C: Carrier<Unit=char>> DisguiseSession<C> {
+    /// Disguises the next chunk of a public string, like [disguise](#method.disguise).
+    pub fn disguise_str(&mut self, chunk: &str) -> errors::Result<String> {
+        let chunk: Vec<char> = chunk.chars().collect();
+        self.disguise(&chunk).map(|disguised| disguised.into_iter().collect())
+    }
+}
+
+/// Reveals a secret from a disguised input that arrives in chunks.
+///
+/// The elements of every chunk are read by the carrier and decoded as soon as their groups are complete. Like the
+/// [DisguiseSession](struct.DisguiseSession.html), the chunks should be split where the carrier does not carry elements
+/// across them. The padding of the steganographers is not removed, since the end of the input is not known; a known
+/// length of the secret ends the session instead.
+pub struct RevealSession<'a, C, AB> {
+    carrier: C,
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>,
+    group: Groups,
+    message: Vec<char>,
+    expected_len: Option<usize>,
+}
+
+impl<'a, C: Carrier, AB> RevealSession<'a, C, AB> {
+    /// Creates a session that reveals a secret with the carrier and decodes it with the codec.
+    pub fn new(carrier: C, codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> RevealSession<'a, C, AB> {
+        RevealSession {
+            carrier,
+            codec,
+            group: Groups::new(codec.encoded_group_size()),
+            message: Vec::new(),
+            expected_len: None,
+        }
+    }
+
+    /// Sets the number of the characters of the secret, so that the session is complete when they are revealed.
+    pub fn with_expected_len(mut self, expected_len: usize) -> Self {
+        self.set_expected_len(expected_len);
+        self
+    }
+
+    pub fn set_expected_len(&mut self, expected_len: usize) {
+        self.expected_len = Some(expected_len);
+    }
+
+    pub fn expected_len(&self) -> Option<usize> {
+        self.expected_len
+    }
+
+    pub fn carrier(&self) -> &C {
+        &self.carrier
+    }
+
+    /// Reads the next chunk of the disguised input and returns the characters that it completes.
+    ///
+    /// The chunks that arrive after the session is complete are ignored.
+    pub fn reveal(&mut self, chunk: &[C::Unit]) -> errors::Result<&[char]> {
+        let start = self.message.len();
+        for is_a in self.carrier.read_symbols(chunk)? {
+            if self.is_complete() {
+                break;
+            }
+            self.group.push(is_a);
+            if self.group.len() == self.codec.encoded_group_size() {
+                self.message.extend(self.codec.decode_groups(&self.group));
+                self.group = Groups::new(self.codec.encoded_group_size());
+            }
+        }
+        if let Some(expected_len) = self.expected_len {
+            self.message.truncate(expected_len.max(start));
+        }
+        Ok(&self.message[start..])
+    }
+
+    /// The characters that are revealed so far.
+    pub fn message_so_far(&self) -> &[char] {
+        &self.message
+    }
+
+    /// The number of the elements of the group that is not complete yet.
+    pub fn pending(&self) -> usize {
+        self.group.len()
+    }
+
+    /// Returns true if the expected number of characters is revealed. Without an expected length, the session is
+    /// never complete.
+    pub fn is_complete(&self) -> bool {
+        self.expected_len.is_some_and(|expected_len| self.message.len() >= expected_len)
+    }
+
+    /// Ends the session and returns the revealed secret. The elements of an incomplete group are ignored.
+    pub fn finish(self) -> Vec<char> {
+        self.message
+    }
+}
+
+impl<'a, C: Carrier<Unit=char>, AB> RevealSession<'a, C, AB> {
+    /// Reads the next chunk of a disguised string, like [reveal](#method.reveal).
+    pub fn reveal_str(&mut self, chunk: &str) -> errors::Result<String> {
+        let chunk: Vec<char> = chunk.chars().collect();
+        self.reveal(&chunk).map(|revealed| revealed.iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod session_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::lsb::LsbSteganographer;
+    use crate::stega::whitespace::WhitespaceSteganographer;
+    use crate::Steganographer;
+
+    use super::*;
+
+    #[test]
+    fn disguise_the_chunks_as_a_whole() {
+        let codec = CharCodec::new('a', 'b');
+        let secret = Vec::from_iter("My secret".chars());
+        let public = "This is a public message. It contains a secret one, and it arrives in words as it is typed.";
+        let s = LetterCaseSteganographer::new();
+        let mut session = DisguiseSession::new(s.clone(), &secret, &codec).unwrap();
+        assert_eq!(session.total(), 40);
+        let mut disguised = String::new();
+        for word in public.split_inclusive(' ') {
+            disguised.push_str(&session.disguise_str(word).unwrap());
+        }
+        assert_eq!(disguised, s.disguise_str("My secret", public, &codec).unwrap());
+        assert_eq!(session.embedded(), 40);
+        assert!(session.finish().is_ok());
+
+        let mut session = DisguiseSession::new(WhitespaceSteganographer::new(), &['H'], &codec).unwrap();
+        let paragraphs = ["One two three.\n", "Four five six seven."];
+        let disguised: Vec<String> = paragraphs.iter().map(|paragraph| session.disguise_str(paragraph).unwrap()).collect();
+        // H = AABBB
+        assert_eq!(disguised, vec!["One two three.\n", "Four  five  six  seven."]);
+    }
+
+    #[test]
+    fn report_the_remaining_demand() {
+        let codec = CharCodec::new('a', 'b');
+        let mut session = DisguiseSession::new(LsbSteganographer::new(), &['H', 'I'], &codec).unwrap();
+        assert_eq!(session.disguise(&[0, 0, 0, 0]).unwrap(), vec![0, 0, 1, 1]);
+        assert_eq!(session.remaining(), 6);
+        assert!(!session.is_complete());
+        match session.finish() {
+            Err(BaconError::InsufficientCapacity { required, available, .. }) => assert_eq!((required, available), (10, 4)),
+            other => panic!("Unexpected result {:?}", other.map(|_| ())),
+        }
+        assert!(DisguiseSession::new(LsbSteganographer::new(), &['1'], &codec).is_err());
+    }
+
+    #[test]
+    fn reveal_the_chunks_as_a_whole() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let disguised = s.disguise_str("My secret", "This is a public message that contains a secret one", &codec).unwrap();
+        let mut session = RevealSession::new(s.clone(), &codec);
+        let mut revealed = String::new();
+        for chunk in disguised.split_inclusive(' ') {
+            revealed.push_str(&session.reveal_str(chunk).unwrap());
+        }
+        assert_eq!(revealed, "MYSECRET");
+        // The 2 elements of the incomplete group at the end are not decoded
+        assert_eq!(session.pending(), 2);
+        assert!(!session.is_complete());
+        assert_eq!(session.finish(), Vec::from_iter(revealed.chars()));
+
+        let mut session = RevealSession::new(LsbSteganographer::new(), &codec).with_expected_len(1);
+        // H = AABBB, I = ABAAA
+        assert_eq!(session.reveal(&[0, 2, 1, 3]).unwrap(), &[] as &[char]);
+        assert_eq!(session.reveal(&[5, 0, 1]).unwrap(), &['H']);
+        assert!(session.is_complete());
+        assert_eq!(session.reveal(&[0, 1, 0, 0, 0]).unwrap(), &[] as &[char]);
+        assert_eq!(session.message_so_far(), &['H']);
+    }
+}
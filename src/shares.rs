@@ -0,0 +1,294 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Splits a secret to shares, so that any `threshold` of them reveal it, while fewer reveal nothing.
+//!
+//! The groups of the encoded secret are split with the [Shamir's secret sharing](https://en.wikipedia.org/wiki/Shamir%27s_secret_sharing)
+//! over the finite field of the bit patterns of a group, so that every share is a sequence of groups as well and can be
+//! disguised by any [Carrier](../carrier/trait.Carrier.html), in its own public text. Every share starts with a header of
+//! its index, the threshold and the number of the groups of the secret.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::shares;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = LetterCaseSteganographer::new();
+//! let publics: Vec<Vec<char>> = vec![
+//!     "It is a truth universally acknowledged, that a single man in possession of a good fortune".chars().collect(),
+//!     "However little known the feelings or views of such a man may be on his first entering".chars().collect(),
+//!     "This truth is so well fixed in the minds of the surrounding families, that he is considered".chars().collect(),
+//! ];
+//! let publics: Vec<&[char]> = publics.iter().map(|public| &public[..]).collect();
+//! // Use a cryptographically secure generator, like the one of the `getrandom` crate
+//! let mut state = 7u8;
+//! let random = || { state = state.wrapping_mul(31).wrapping_add(11); state };
+//! let disguised = shares::disguise(&s, &['H', 'I'], &publics, 2, random, &codec).unwrap();
+//! assert_eq!(shares::reveal(&s, &[&disguised[2][..], &disguised[0][..]], &codec).unwrap(), vec!['H', 'I']);
+//! ```
+use crate::{BaconCodec, errors};
+use crate::carrier::Carrier;
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+
+/// The bits of the number of the groups of the secret, in the header of a share.
+const LENGTH_BITS: usize = 16;
+
+/// Splits the encoded secret to `count` shares, any `threshold` of which reveal the secret.
+///
+/// The coefficients of the polynomials are taken from `random`, which should be a cryptographically secure generator,
+/// since the shares are as secure as their coefficients. Fails if the `threshold` is 0 or greater than the `count`, or
+/// if the `count` does not fit in a group of the codec.
+pub fn split<AB, R>(secret: &[char], threshold: usize, count: usize, mut random: R, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<Groups>>
+    where R: FnMut() -> u8 {
+    BaconError::check_secret(secret)?;
+    let field = Field::new(codec.encoded_group_size());
+    if threshold == 0 || threshold > count {
+        return Err(BaconError::GeneralError(format!("Cannot split to {} shares with a threshold of {}", count, threshold)));
+    }
+    if count > field.mask as usize {
+        return Err(BaconError::GeneralError(format!("Cannot split to more than {} shares with groups of {} elements", field.mask, field.bits)));
+    }
+    let encoded = codec.encode_groups(secret);
+    let patterns = encoded.complete();
+    if patterns.len() >> LENGTH_BITS != 0 {
+        return Err(BaconError::GeneralError(format!("Cannot split a secret of more than {} groups", (1usize << LENGTH_BITS) - 1)));
+    }
+    let mut shares: Vec<Vec<u8>> = (1..=count)
+        .map(|index| {
+            let mut header = vec![index as u8, threshold as u8];
+            header.extend(field.split_number(patterns.len()));
+            header
+        })
+        .collect();
+    for secret_pattern in patterns {
+        // The polynomial of degree `threshold - 1` whose value at 0 is the pattern of the secret
+        let coefficients: Vec<u8> = (1..threshold).map(|_| random() & field.mask).collect();
+        for (index, share) in shares.iter_mut().enumerate() {
+            let x = index as u8 + 1;
+            let y = coefficients.iter().rev().fold(0, |y, coefficient| field.mul(y, x) ^ coefficient);
+            share.push(field.mul(y, x) ^ secret_pattern);
+        }
+    }
+    Ok(shares.into_iter().map(|share| Groups::from_patterns(share, field.bits)).collect())
+}
+
+/// Combines the shares of [split](fn.split.html) and decodes the secret. Any shares may be given, in any order, as long
+/// as they are at least as many as the threshold.
+pub fn combine<AB>(shares: &[Groups], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+    let field = Field::new(codec.encoded_group_size());
+    let headers: Vec<(u8, usize, usize)> = shares.iter()
+        .map(|share| field.header(share))
+        .collect::<errors::Result<_>>()?;
+    let (_, threshold, length) = match headers.first() {
+        Some(header) => *header,
+        None => return Err(BaconError::GeneralError("No shares were given".to_string())),
+    };
+    if headers.iter().any(|(_, other_threshold, other_length)| *other_threshold != threshold || *other_length != length) {
+        return Err(invalid_share("The shares belong to different secrets"));
+    }
+    if shares.len() < threshold {
+        return Err(BaconError::GeneralError(format!("The secret needs {} shares, but {} were given", threshold, shares.len())));
+    }
+    let xs: Vec<u8> = headers.iter().map(|(index, _, _)| *index).take(threshold).collect();
+    if (1..xs.len()).any(|i| xs[..i].contains(&xs[i])) {
+        return Err(invalid_share("The shares have the same index"));
+    }
+    // The Lagrange basis polynomials at 0. The subtraction of the field is the exclusive or.
+    let basis: Vec<u8> = xs.iter()
+        .map(|xi| xs.iter()
+            .filter(|xj| *xj != xi)
+            .fold(1, |product, xj| field.mul(product, field.mul(*xj, field.inverse(xj ^ xi)))))
+        .collect();
+    let header_len = field.header_len();
+    let patterns: Vec<u8> = (header_len..header_len + length)
+        .map(|position| shares.iter().zip(basis.iter())
+            .fold(0, |pattern, (share, basis)| pattern ^ field.mul(share.complete()[position], *basis)))
+        .collect();
+    Ok(codec.decode_groups(&Groups::from_patterns(patterns, field.bits)))
+}
+
+/// Splits the secret like [split](fn.split.html), to as many shares as the public inputs, and writes every share to its
+/// public input.
+pub fn disguise<C, AB, R>(carrier: &C, secret: &[char], publics: &[&[C::Unit]], threshold: usize, random: R, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<Vec<C::Unit>>>
+    where C: Carrier + ?Sized, R: FnMut() -> u8 {
+    split(secret, threshold, publics.len(), random, codec)?.iter()
+        .zip(publics)
+        .map(|(share, public)| {
+            let symbols: Vec<bool> = share.elements().collect();
+            let available = carrier.capacity(public)?;
+            if available < symbols.len() {
+                return Err(BaconError::InsufficientCapacity { required: symbols.len(), available, unit: CapacityUnit::Carriers });
+            }
+            carrier.write_symbols(&symbols, public)
+        })
+        .collect()
+}
+
+/// Reads the shares of the disguised inputs and combines them like [combine](fn.combine.html).
+pub fn reveal<C, AB>(carrier: &C, inputs: &[&[C::Unit]], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>>
+    where C: Carrier + ?Sized {
+    let field = Field::new(codec.encoded_group_size());
+    let shares: Vec<Groups> = inputs.iter()
+        .map(|input| {
+            let read = Groups::from_elements(carrier.read_symbols(input)?, field.bits);
+            // The carriers that follow the share are not part of it
+            let (_, _, length) = field.header(&read)?;
+            let patterns = &read.complete()[..field.header_len() + length];
+            Ok(Groups::from_patterns(patterns.to_vec(), field.bits))
+        })
+        .collect::<errors::Result<_>>()?;
+    combine(&shares, codec)
+}
+
+fn invalid_share(message: &str) -> BaconError {
+    BaconError::InvalidInput { message: message.to_string(), position: None, source: None }
+}
+
+// The finite field of the bit patterns of a group, GF(2^bits).
+struct Field {
+    bits: usize,
+    mask: u8,
+    // The irreducible polynomial, without its highest term
+    reduction: u8,
+}
+
+impl Field {
+    fn new(bits: usize) -> Field {
+        let reduction = match bits {
+            1 => 0b1,
+            2 => 0b11,
+            3 => 0b011,
+            4 => 0b0011,
+            5 => 0b0_0101,
+            6 => 0b00_0011,
+            7 => 0b000_0011,
+            _ => 0b0001_1011,
+        };
+        Field { bits, mask: (((1u16 << bits) - 1) as u8), reduction }
+    }
+
+    fn mul(&self, mut a: u8, mut b: u8) -> u8 {
+        let mut product = 0;
+        for _ in 0..self.bits {
+            if b & 1 == 1 {
+                product ^= a;
+            }
+            b >>= 1;
+            let carry = a >> (self.bits - 1) & 1 == 1;
+            a = (((a as u16) << 1) as u8) & self.mask;
+            if carry {
+                a ^= self.reduction;
+            }
+        }
+        product
+    }
+
+    // The inverse is a^(2^bits - 2), since a^(2^bits - 1) = 1 for any a but 0.
+    fn inverse(&self, a: u8) -> u8 {
+        (0..self.mask - 1).fold(1, |power, _| self.mul(power, a))
+    }
+
+    // The number of the groups of the header of a share.
+    fn header_len(&self) -> usize {
+        2 + LENGTH_BITS.div_ceil(self.bits)
+    }
+
+    // The groups of a number, from the most significant.
+    fn split_number(&self, number: usize) -> Vec<u8> {
+        let groups = LENGTH_BITS.div_ceil(self.bits);
+        (0..groups).rev().map(|group| (number >> (group * self.bits)) as u8 & self.mask).collect()
+    }
+
+    // The index, the threshold and the length of a share.
+    fn header(&self, share: &Groups) -> errors::Result<(u8, usize, usize)> {
+        let patterns = share.complete();
+        if patterns.len() < self.header_len() {
+            return Err(invalid_share("The share is too short"));
+        }
+        let length = patterns[2..self.header_len()].iter().fold(0, |length, pattern| length << self.bits | *pattern as usize);
+        if patterns[0] == 0 || patterns[1] == 0 || patterns.len() < self.header_len() + length {
+            return Err(invalid_share("The share is not valid"));
+        }
+        Ok((patterns[0], patterns[1] as usize, length))
+    }
+}
+
+#[cfg(test)]
+mod shares_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    fn random() -> impl FnMut() -> u8 {
+        let mut state = 42u64;
+        move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as u8
+        }
+    }
+
+    #[test]
+    fn field_arithmetic() {
+        for bits in 1..=8 {
+            let field = Field::new(bits);
+            for a in 1..=field.mask {
+                assert_eq!(field.mul(a, field.inverse(a)), 1, "{} in GF(2^{})", a, bits);
+                assert_eq!(field.mul(a, 1), a);
+            }
+        }
+        assert_eq!(Field::new(5).split_number(1000), vec![0, 0, 31, 8]);
+    }
+
+    #[test]
+    fn split_and_combine() {
+        let codec = CharCodec::new('a', 'b');
+        let shares = split(&['H', 'E', 'L', 'L', 'O'], 3, 5, random(), &codec).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share.complete().len() == 6 + 5));
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<Groups> = subset.iter().map(|index| shares[*index].clone()).collect();
+            assert_eq!(combine(&subset, &codec).unwrap(), vec!['H', 'E', 'L', 'L', 'O']);
+        }
+        assert_eq!(combine(&shares, &codec).unwrap(), vec!['H', 'E', 'L', 'L', 'O']);
+        assert!(matches!(combine(&shares[..2], &codec), Err(BaconError::GeneralError(_))));
+        assert!(combine(&[shares[0].clone(), shares[0].clone(), shares[1].clone()], &codec).is_err());
+        assert!(split(&['H'], 0, 2, random(), &codec).is_err());
+        assert!(split(&['H'], 3, 2, random(), &codec).is_err());
+        assert!(split(&['H'], 2, 32, random(), &codec).is_err());
+        assert!(split(&['1'], 2, 3, random(), &codec).is_err());
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let publics: Vec<Vec<char>> = [
+            "It is a truth universally acknowledged, that a single man in possession of a good fortune",
+            "However little known the feelings or views of such a man may be on his first entering",
+            "This truth is so well fixed in the minds of the surrounding families, that he is considered",
+        ].iter().map(|public| Vec::from_iter(public.chars())).collect();
+        let publics: Vec<&[char]> = publics.iter().map(|public| &public[..]).collect();
+        let disguised = disguise(&s, &['H', 'I', 'Z'], &publics, 2, random(), &codec).unwrap();
+        assert_eq!(reveal(&s, &[&disguised[1][..], &disguised[2][..]], &codec).unwrap(), vec!['H', 'I', 'Z']);
+        assert!(reveal(&s, &[&disguised[1][..]], &codec).is_err());
+        let short = Vec::from_iter("Too short".chars());
+        assert!(matches!(disguise(&s, &['H'], &[&short[..], publics[0]], 2, random(), &codec),
+            Err(BaconError::InsufficientCapacity { .. })));
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Letter statistics, to analyze decoded output.
+//!
+//! Only the letters `A` to `Z` are counted, regardless of their case.
+//!
+//! ```rust
+//! use bacon_cipher::stats::Histogram;
+//!
+//! let histogram = Histogram::of("Meet me at dawn");
+//! assert_eq!(histogram.total(), 12);
+//! assert_eq!(histogram.count('e'), 3);
+//! assert!(histogram.chi_squared() < Histogram::of("QXZJQXZJVVKK").chi_squared());
+//! ```
+
+/// The frequencies of the letters A to Z in English text.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153, 0.00772, 0.04025, 0.02406,
+    0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056, 0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// The index of coincidence of English text.
+pub const ENGLISH_INDEX_OF_COINCIDENCE: f64 = 0.0667;
+
+/// The index of coincidence of uniformly random letters.
+pub const RANDOM_INDEX_OF_COINCIDENCE: f64 = 1.0 / 26.0;
+
+/// The counts of the letters `A` to `Z` of a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Histogram {
+    counts: [usize; 26],
+}
+
+impl Histogram {
+    /// Counts the letters of the text.
+    pub fn of(text: &str) -> Histogram {
+        let mut counts = [0usize; 26];
+        for c in text.chars().filter(|c| c.is_ascii_alphabetic()) {
+            counts[(c.to_ascii_uppercase() as u8 - b'A') as usize] += 1;
+        }
+        Histogram { counts }
+    }
+
+    /// The counts of the letters, from `A` to `Z`.
+    pub fn counts(&self) -> &[usize; 26] {
+        &self.counts
+    }
+
+    /// The count of a letter. It is 0 for characters that are not letters from `A` to `Z`.
+    pub fn count(&self, letter: char) -> usize {
+        if letter.is_ascii_alphabetic() {
+            self.counts[(letter.to_ascii_uppercase() as u8 - b'A') as usize]
+        } else {
+            0
+        }
+    }
+
+    /// The number of the letters that were counted.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The relative frequencies of the letters, from `A` to `Z`. They are all 0.0 if there are no letters.
+    pub fn frequencies(&self) -> [f64; 26] {
+        let total = self.total();
+        let mut frequencies = [0.0; 26];
+        if total > 0 {
+            for (frequency, count) in frequencies.iter_mut().zip(self.counts.iter()) {
+                *frequency = *count as f64 / total as f64;
+            }
+        }
+        frequencies
+    }
+
+    /// The probability that two letters that are chosen at random are the same. It is 0.0 for fewer than two letters.
+    ///
+    /// It is close to [ENGLISH_INDEX_OF_COINCIDENCE](constant.ENGLISH_INDEX_OF_COINCIDENCE.html) for English text and
+    /// close to [RANDOM_INDEX_OF_COINCIDENCE](constant.RANDOM_INDEX_OF_COINCIDENCE.html) for random letters.
+    pub fn index_of_coincidence(&self) -> f64 {
+        let total = self.total();
+        if total < 2 {
+            return 0.0;
+        }
+        let coincidences: usize = self.counts.iter().map(|count| count * count.saturating_sub(1)).sum();
+        coincidences as f64 / (total * (total - 1)) as f64
+    }
+
+    /// The chi-squared statistic of the counts against the letter frequencies of English text.
+    ///
+    /// Lower values indicate that the text is more likely to be English. It is 0.0 if there are no letters.
+    pub fn chi_squared(&self) -> f64 {
+        let total = self.total() as f64;
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.counts.iter()
+            .zip(ENGLISH_FREQUENCIES.iter())
+            .map(|(count, frequency)| {
+                let expected = total * frequency;
+                (*count as f64 - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    /// The total variation distance between the frequencies of the letters and the ones of English text, between 0.0
+    /// and 1.0. It is 1.0 if there are no letters.
+    pub fn distance_from_english(&self) -> f64 {
+        if self.total() == 0 {
+            return 1.0;
+        }
+        let distance: f64 = self.frequencies().iter()
+            .zip(ENGLISH_FREQUENCIES.iter())
+            .map(|(frequency, expected)| (frequency - expected).abs())
+            .sum();
+        distance / 2.0
+    }
+}
+
+/// The [index of coincidence](struct.Histogram.html#method.index_of_coincidence) of the letters of the text.
+pub fn index_of_coincidence(text: &str) -> f64 {
+    Histogram::of(text).index_of_coincidence()
+}
+
+/// The [chi-squared statistic](struct.Histogram.html#method.chi_squared) of the letters of the text against English.
+pub fn chi_squared(text: &str) -> f64 {
+    Histogram::of(text).chi_squared()
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn histogram() {
+        let histogram = Histogram::of("Hello, World!");
+        assert_eq!(histogram.total(), 10);
+        assert_eq!(histogram.count('L'), 3);
+        assert_eq!(histogram.count('!'), 0);
+        assert_eq!(histogram.frequencies()[11], 0.3);
+        assert_eq!(Histogram::of("123").frequencies(), [0.0; 26]);
+        assert_eq!(Histogram::of("").distance_from_english(), 1.0);
+    }
+
+    #[test]
+    fn index_of_coincidence_of_texts() {
+        assert_eq!(index_of_coincidence("AAAA"), 1.0);
+        assert_eq!(index_of_coincidence("ABCD"), 0.0);
+        assert_eq!(index_of_coincidence("A"), 0.0);
+        let english = "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness";
+        assert!((index_of_coincidence(english) - ENGLISH_INDEX_OF_COINCIDENCE).abs() < 0.02);
+    }
+
+    #[test]
+    fn chi_squared_of_texts() {
+        assert_eq!(chi_squared(""), 0.0);
+        assert!(chi_squared("the quick brown fox jumps over the lazy dog") < chi_squared("qxzjqxzjvvkk"));
+    }
+}
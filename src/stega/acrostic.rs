@@ -0,0 +1,262 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::BaconError;
+use crate::random::Random;
+use crate::stega::split_words;
+use crate::wordlist::WordList;
+
+/// Applies steganography based on the first letter of each word.
+///
+/// Words that start with a letter of the A set carry the Bacon's element A and words that start with a letter of the
+/// B set carry the Bacon's element B. Words that start with a letter of neither set are ignored.
+///
+/// Since the words of a public text cannot be changed, the disguise inserts words from a [WordList](../../wordlist/struct.WordList.html)
+/// wherever the public text does not provide a word of the needed set. A whole cover text can also be produced from the
+/// word list, using [generate](struct.AcrosticSteganographer.html#method.generate).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcrosticSteganographer {
+    a_letters: Vec<char>,
+    b_letters: Vec<char>,
+    wordlist: WordList,
+    seed: u64,
+}
+
+impl AcrosticSteganographer {
+    /// Creates an `AcrosticSteganographer` with the letters of the A and the B sets. The sets should be disjoint.
+    pub fn new(a_letters: &str, b_letters: &str) -> errors::Result<AcrosticSteganographer> {
+        let a_letters: Vec<char> = a_letters.chars().flat_map(|c| c.to_lowercase()).collect();
+        let b_letters: Vec<char> = b_letters.chars().flat_map(|c| c.to_lowercase()).collect();
+        if a_letters.is_empty() || b_letters.is_empty() {
+            Err(BaconError::SteganographerError("The letter sets should not be empty".to_string()))
+        } else if a_letters.iter().chain(b_letters.iter()).any(|c| !c.is_alphabetic()) {
+            Err(BaconError::SteganographerError("The letter sets can contain only alphabetic characters".to_string()))
+        } else if a_letters.iter().any(|c| b_letters.contains(c)) {
+            Err(BaconError::SteganographerError("The letter sets should be disjoint".to_string()))
+        } else {
+            Ok(AcrosticSteganographer {
+                a_letters,
+                b_letters,
+                wordlist: WordList::default(),
+                seed: 0,
+            })
+        }
+    }
+
+    /// Sets the word list that provides the words that are inserted during disguise and generation.
+    pub fn with_wordlist(mut self, wordlist: WordList) -> Self {
+        self.set_wordlist(wordlist);
+        self
+    }
+
+    pub fn set_wordlist(&mut self, wordlist: WordList) {
+        self.wordlist = wordlist;
+    }
+
+    /// Sets the seed for the selection of the words of the word list. The same seed always produces the same output.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    // Returns Some(true) for a word of the A set, Some(false) for a word of the B set and None otherwise.
+    fn classify(&self, word: &str) -> Option<bool> {
+        word.chars().next()
+            .and_then(|first| first.to_lowercase().next())
+            .and_then(|first| {
+                if self.a_letters.contains(&first) {
+                    Some(true)
+                } else if self.b_letters.contains(&first) {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn pick_word(&self, a: bool, random: &mut Random) -> errors::Result<String> {
+        let candidates = self.wordlist.filter(|word| self.classify(word) == Some(a));
+        if candidates.is_empty() {
+            Err(BaconError::SteganographerError(
+                format!("The word list does not contain any word of the {} set", if a { "A" } else { "B" })))
+        } else {
+            Ok(candidates[random.below(candidates.len())].to_string())
+        }
+    }
+
+    fn validate_secret(secret: &[char]) -> errors::Result<()> {
+        BaconError::check_secret(secret)
+    }
+
+    /// Generates a cover text that contains the secret, using only words of the word list.
+    pub fn generate<AB>(&self, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        Self::validate_secret(secret)?;
+        let mut random = Random::new(self.seed);
+        let words = codec.encode(secret).iter()
+            .map(|elem| self.pick_word(codec.is_a(elem), &mut random))
+            .collect::<errors::Result<Vec<String>>>()?;
+        let text = words.join(" ");
+        let mut chars = text.chars();
+        Ok(chars.next()
+            .map(|first| first.to_uppercase().chain(chars).chain(".".chars()).collect())
+            .unwrap_or_default())
+    }
+}
+
+impl Default for AcrosticSteganographer {
+    /// Letters `a` to `m` for A and `n` to `z` for B.
+    fn default() -> AcrosticSteganographer {
+        AcrosticSteganographer::new("abcdefghijklm", "nopqrstuvwxyz").unwrap()
+    }
+}
+
+impl Steganographer for AcrosticSteganographer {
+    type T = char;
+
+    /// Words of the word list are inserted where needed, so the capacity is `usize::MAX`.
+    fn capacity(&self, _public: &[char]) -> errors::Result<usize> {
+        Ok(usize::MAX)
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        Self::validate_secret(secret)?;
+        let encoded = codec.encode(secret);
+        let mut random = Random::new(self.seed);
+        let mut disguised = String::new();
+        let mut i = 0;
+
+        for (is_word, text) in split_words(public) {
+            if is_word {
+                while i < encoded.len() {
+                    let needed = codec.is_a(&encoded[i]);
+                    match self.classify(&text) {
+                        None => break,
+                        Some(class) if class == needed => {
+                            i += 1;
+                            break;
+                        }
+                        Some(_) => {
+                            disguised.push_str(&self.pick_word(needed, &mut random)?);
+                            disguised.push(' ');
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            disguised.push_str(&text);
+        }
+        while i < encoded.len() {
+            disguised.push(' ');
+            disguised.push_str(&self.pick_word(codec.is_a(&encoded[i]), &mut random)?);
+            i += 1;
+        }
+
+        Ok(disguised.chars().collect())
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = split_words(input).iter()
+            .filter(|(is_word, _)| *is_word)
+            .filter_map(|(_, word)| self.classify(word))
+            .map(|a| if a { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+}
+
+#[cfg(test)]
+mod acrostic_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    fn wordlist() -> WordList {
+        WordList::parse("apple banana cherry date melon nut orange pear quince tomato")
+    }
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(AcrosticSteganographer::new("", "b").is_err());
+        assert!(AcrosticSteganographer::new("a1", "b").is_err());
+        assert!(AcrosticSteganographer::new("ab", "bc").is_err());
+        assert!(AcrosticSteganographer::new("aB", "cd").is_ok());
+    }
+
+    #[test]
+    fn disguise_fails_without_a_word_list() {
+        let codec = CharCodec::new('a', 'b');
+        let s = AcrosticSteganographer::default();
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("All animals are equal".chars()),
+            &codec);
+        assert!(output.is_err())
+    }
+
+    #[test]
+    fn disguise_uses_the_public_words_when_possible() {
+        let codec = CharCodec::new('a', 'b');
+        let s = AcrosticSteganographer::default();
+        // H = AABBB
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("All birds sing; now they rest, 42 times!".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "All birds sing; now they rest, 42 times!");
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = AcrosticSteganographer::default().with_wordlist(wordlist()).with_seed(7);
+        let public = "This is a public message";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        // The words of the public text are kept in order
+        let mut words = string.split_whitespace();
+        assert!(public.split_whitespace().all(|public_word| words.any(|word| word == public_word)));
+        assert_eq!(string, String::from_iter(s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap().iter()));
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn generate_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = AcrosticSteganographer::default().with_wordlist(wordlist());
+        let generated = s.generate(&['H', 'i'], &codec).unwrap();
+        let string = String::from_iter(generated.iter());
+        assert_eq!(string.split_whitespace().count(), 10);
+        assert!(string.ends_with('.'));
+
+        let output = s.reveal(&generated, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "HI");
+    }
+}
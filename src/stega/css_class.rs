@@ -0,0 +1,340 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use html5ever::parse_document;
+use html5ever::rcdom::{Handle, NodeData, RcDom};
+use html5ever::tendril::TendrilSink;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::{BaconError, CapacityUnit};
+use crate::stega::{alphabetic_units, units};
+
+/// Applies steganography based on the class attribute of the HTML elements that surround the characters.
+///
+/// E.g. Surround a character with `<span class="x">` and `</span>` for Bacon's element A and with `<span class="y">`
+/// and `</span>` for Bacon's element B.
+///
+/// Using the same element with different classes is much less conspicuous in real web pages than using distinct tags.
+///
+/// The public input is treated as plain text, so the characters `<`, `>` and `&` are escaped.
+/// Builds a [CssClassSteganographer](struct.CssClassSteganographer.html). The classes are validated by
+/// [build](#method.build), like in [CssClassSteganographer::new](struct.CssClassSteganographer.html#method.new).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssClassSteganographerBuilder {
+    element: String,
+    a_class: String,
+    b_class: String,
+    optimize_disguise: bool,
+}
+
+impl Default for CssClassSteganographerBuilder {
+    fn default() -> CssClassSteganographerBuilder {
+        CssClassSteganographerBuilder {
+            element: "span".to_string(),
+            a_class: String::new(),
+            b_class: String::new(),
+            optimize_disguise: true,
+        }
+    }
+}
+
+impl CssClassSteganographerBuilder {
+    /// Sets the class of the Bacon's element A.
+    pub fn with_a_class(mut self, class: &str) -> Self {
+        self.a_class = class.to_string();
+        self
+    }
+
+    /// Sets the class of the Bacon's element B.
+    pub fn with_b_class(mut self, class: &str) -> Self {
+        self.b_class = class.to_string();
+        self
+    }
+
+    /// See [CssClassSteganographer::with_element](struct.CssClassSteganographer.html#method.with_element).
+    pub fn with_element(mut self, element: &str) -> Self {
+        self.element = element.to_string();
+        self
+    }
+
+    /// Whether consecutive characters with the same class are surrounded by a single element. The default is true.
+    pub fn with_optimize_disguise(mut self, optimize_disguise: bool) -> Self {
+        self.optimize_disguise = optimize_disguise;
+        self
+    }
+
+    pub fn build(self) -> errors::Result<CssClassSteganographer> {
+        let mut steganographer = CssClassSteganographer::new(&self.a_class, &self.b_class)?.with_element(&self.element)?;
+        steganographer.set_optimize_disguise(self.optimize_disguise);
+        Ok(steganographer)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssClassSteganographer {
+    element: String,
+    a_class: String,
+    b_class: String,
+    optimize_disguise: bool,
+}
+
+impl CssClassSteganographer {
+    /// Creates a `CssClassSteganographer` that uses `span` elements with the given classes.
+    pub fn new(a_class: &str, b_class: &str) -> errors::Result<CssClassSteganographer> {
+        let is_valid = |class: &str| !class.is_empty() && class.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if !is_valid(a_class) || !is_valid(b_class) {
+            Err(BaconError::SteganographerError(format!("Invalid class names {:?} and {:?}", a_class, b_class)))
+        } else if a_class == b_class {
+            Err(BaconError::SteganographerError(format!("Cannot use the class {:?} for both A and B", a_class)))
+        } else {
+            Ok(CssClassSteganographer {
+                element: "span".to_string(),
+                a_class: a_class.to_string(),
+                b_class: b_class.to_string(),
+                optimize_disguise: true,
+            })
+        }
+    }
+
+    /// Uses the element with the given local name, instead of `span`. The name should be an HTML tag name, of ASCII
+    /// letters, digits and hyphens, that starts with a letter.
+    pub fn with_element(mut self, element: &str) -> errors::Result<Self> {
+        let mut chars = element.chars();
+        let is_valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !is_valid {
+            return Err(BaconError::SteganographerError(format!("Invalid element name {:?}", element)));
+        }
+        self.element = element.to_ascii_lowercase();
+        Ok(self)
+    }
+
+    /// Creates a [CssClassSteganographerBuilder](struct.CssClassSteganographerBuilder.html).
+    pub fn builder() -> CssClassSteganographerBuilder {
+        CssClassSteganographerBuilder::default()
+    }
+
+    #[deprecated(note = "Use CssClassSteganographer::builder().with_optimize_disguise(false) or set_optimize_disguise(false)")]
+    pub fn no_optimize_disguise_output(mut self) -> Self {
+        self.set_optimize_disguise(false);
+        self
+    }
+
+    pub fn set_optimize_disguise(&mut self, b: bool) {
+        self.optimize_disguise = b;
+    }
+
+    fn start_node(&self, class: &str) -> String {
+        format!("<{} class=\"{}\">", self.element, class)
+    }
+
+    fn end_node(&self) -> String {
+        format!("</{}>", self.element)
+    }
+
+    // Collects the text of the elements that are classified as A (true) or B (false).
+    fn parse(&self, handle: &Handle, acc: &mut Vec<(String, bool)>, classification: Option<bool>) {
+        let mut current = classification;
+
+        match handle.data {
+            NodeData::Text { ref contents } => {
+                if let Some(a) = classification {
+                    acc.push((contents.borrow().to_string(), a));
+                }
+            }
+            NodeData::Element { ref name, ref attrs, .. } if name.local.as_ref() == self.element => {
+                let attrs = attrs.borrow();
+                let classes: Vec<&str> = attrs.iter()
+                    .filter(|attr| attr.name.local.as_ref() == "class")
+                    .flat_map(|attr| attr.value.split_whitespace())
+                    .collect();
+                if classes.contains(&self.a_class.as_str()) {
+                    current = Some(true);
+                } else if classes.contains(&self.b_class.as_str()) {
+                    current = Some(false);
+                }
+            }
+            _ => { /* ignore */ }
+        }
+
+        for child in handle.children.borrow().iter() {
+            self.parse(child, acc, current);
+        }
+    }
+}
+
+impl Steganographer for CssClassSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(alphabetic_units(&public.iter().collect::<String>()))
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let encoded = codec.encode(secret);
+        let available = Steganographer::capacity(self, public)?;
+        if available < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available,
+                unit: CapacityUnit::Letters,
+            });
+        }
+
+        let mut disguised = String::new();
+        let mut i = 0;
+        // The class of the element that is currently open. Since A and B share the same end node, adjacent
+        // elements are merged by keeping the element open, instead of replacing strings afterwards.
+        let mut open: Option<&str> = None;
+
+        for unit in units(public) {
+            let pc: String = unit.iter().collect();
+            let class = match encoded.get(i) {
+                Some(elem) if unit[0].is_alphabetic() && codec.is_a(elem) => Some(self.a_class.as_str()),
+                Some(elem) if unit[0].is_alphabetic() && codec.is_b(elem) => Some(self.b_class.as_str()),
+                _ => None,
+            };
+            if class.is_some() {
+                i += 1;
+            }
+            if open.is_some() && (open != class || !self.optimize_disguise) {
+                disguised.push_str(&self.end_node());
+                open = None;
+            }
+            if let (None, Some(class)) = (open, class) {
+                disguised.push_str(&self.start_node(class));
+                open = Some(class);
+            }
+            for c in pc.chars() {
+                match c {
+                    '<' => disguised.push_str("&lt;"),
+                    '>' => disguised.push_str("&gt;"),
+                    '&' => disguised.push_str("&amp;"),
+                    _ => disguised.push(c),
+                }
+            }
+        }
+        if open.is_some() {
+            disguised.push_str(&self.end_node());
+        }
+
+        Ok(disguised.chars().collect())
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let input_iter: Vec<String> = input.iter().map(|ch| ch.to_string()).collect();
+        let dom = parse_document(RcDom::default(), Default::default()).from_iter(input_iter);
+
+        let mut parsed = Vec::new();
+        self.parse(&dom.document, &mut parsed, None);
+        let encoded: Vec<AB> = parsed.iter()
+            .flat_map(|(string, a)| {
+                let v: Vec<AB> = (0..alphabetic_units(string))
+                    .map(|_| if *a { codec.a() } else { codec.b() })
+                    .collect();
+                v
+            })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+}
+
+#[cfg(test)]
+mod css_class_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(CssClassSteganographer::new("x", "x").is_err());
+        assert!(CssClassSteganographer::new("", "x").is_err());
+        assert!(CssClassSteganographer::new("x\"", "y").is_err());
+        assert!(CssClassSteganographer::new("x-1", "y_2").is_ok());
+        assert!(CssClassSteganographer::builder().with_a_class("x").build().is_err());
+        let s = CssClassSteganographer::builder()
+            .with_a_class("x")
+            .with_b_class("y")
+            .with_element("EM")
+            .with_optimize_disguise(false)
+            .build()
+            .unwrap();
+        assert_eq!((s.element.as_str(), s.optimize_disguise), ("em", false));
+        assert!(CssClassSteganographer::new("x", "y").unwrap().with_element("my-element2").is_ok());
+        for element in ["span onclick=x", "a><script", "", "1a", "-a", "é"].iter() {
+            assert!(CssClassSteganographer::new("x", "y").unwrap().with_element(element).is_err(), "{}", element);
+            assert!(CssClassSteganographer::builder().with_a_class("x").with_b_class("y").with_element(element).build().is_err());
+        }
+    }
+
+    #[test]
+    fn disguise_a_secret_to_a_char_array() {
+        let codec = CharCodec::new('a', 'b');
+        let s = CssClassSteganographer::new("x", "y").unwrap();
+        // H = AABBB
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("Hello world".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "<span class=\"x\">He</span><span class=\"y\">llo</span> world");
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = CssClassSteganographer::new("note", "hint").unwrap().with_element("em").unwrap();
+        let public = "This is a public message that contains a secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        assert!(string.starts_with("<em class=\"note\">T</em><em class=\"hint\">h</em>"));
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_escapes_the_public_text() {
+        let codec = CharCodec::new('a', 'b');
+        let s = CssClassSteganographer::new("x", "y").unwrap();
+        // H = AABBB
+        let output = s.disguise(&['H'], &Vec::from_iter("1 < 2 & Hello <i>".chars()), &codec).unwrap();
+        let string = String::from_iter(output.iter());
+        assert_eq!(string, "1 &lt; 2 &amp; <span class=\"x\">He</span><span class=\"y\">llo</span> &lt;i&gt;");
+        assert_eq!(s.reveal(&output, &codec).unwrap(), vec!['H']);
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = CssClassSteganographer::new("x", "y").unwrap();
+        let output = s.disguise(&['H'], &Vec::from_iter("Hi 42".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 5, available: 2, unit: CapacityUnit::Letters }));
+    }
+
+    #[test]
+    fn reveal_classifies_by_class_attribute() {
+        let codec = CharCodec::new('a', 'b');
+        let s = CssClassSteganographer::new("x", "y").unwrap();
+        // H = AABBB
+        let public = "<p><span id=\"1\" class=\"big x\">He</span><span>ignored</span><span class=\"y\">l<b>l</b>o</span> world</p>";
+        let output = s.reveal(&Vec::from_iter(public.chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "H");
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// The LEFT-TO-RIGHT MARK, which carries the Bacon's element A.
+pub const LRM: char = '\u{200E}';
+/// The RIGHT-TO-LEFT MARK, which carries the Bacon's element B.
+pub const RLM: char = '\u{200F}';
+
+/// Applies steganography based on the invisible text direction marks.
+///
+/// A [LRM](constant.LRM.html) carries the Bacon's element A and a [RLM](constant.RLM.html) carries the Bacon's element B.
+/// The disguise places a mark after every run of spaces that separates two words, which is a neutral position for the
+/// bidirectional algorithm. The marks that already exist in the public input are removed, so that they are not revealed
+/// as elements.
+///
+/// A mark may still change how the neighbouring numbers, punctuation or words of the other direction are displayed. For
+/// example, a LRM between two Hebrew words of a left-to-right paragraph splits their right-to-left run and swaps the
+/// words visually. If the marks should never reorder the text visually, use [preserve_order](#method.preserve_order).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirectionMarkSteganographer {
+    preserve_order: bool,
+}
+
+// The direction of a strong character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl DirectionMarkSteganographer {
+    pub fn new() -> DirectionMarkSteganographer {
+        DirectionMarkSteganographer { preserve_order: false }
+    }
+
+    /// Places the marks only between two letters of the base direction of their paragraph, which is the direction of its
+    /// first letter, like the spaces of `hello world` in a left-to-right paragraph or of `שלום עולם` in a right-to-left
+    /// paragraph. There, a mark of either direction does not change the order in which the text is displayed.
+    pub fn preserve_order(mut self) -> Self {
+        self.set_preserve_order(true);
+        self
+    }
+
+    pub fn set_preserve_order(&mut self, b: bool) {
+        self.preserve_order = b;
+    }
+
+    pub fn preserves_order(&self) -> bool {
+        self.preserve_order
+    }
+
+    // Returns the positions at which a mark is placed, which are the ends of the runs of spaces that separate two words.
+    // The input should not contain any marks.
+    fn positions(&self, input: &[char]) -> Vec<usize> {
+        let bases = base_directions(input);
+        let mut positions = Vec::new();
+        let mut i = 0;
+        while i < input.len() {
+            if input[i] == ' ' {
+                let start = i;
+                while i < input.len() && input[i] == ' ' {
+                    i += 1;
+                }
+                let between_words = start > 0 && i < input.len() && !input[start - 1].is_whitespace() && !input[i].is_whitespace();
+                if between_words && (!self.preserve_order || Self::in_direction(&input[start - 1], &input[i], bases[i])) {
+                    positions.push(i);
+                }
+            } else {
+                i += 1;
+            }
+        }
+        positions
+    }
+
+    fn in_direction(before: &char, after: &char, base: Direction) -> bool {
+        direction(before) == Some(base) && direction(after) == Some(base)
+    }
+}
+
+// The base direction of the paragraph of every character, which is the direction of the first letter of the paragraph,
+// or left-to-right if it has no letters.
+fn base_directions(input: &[char]) -> Vec<Direction> {
+    let mut bases = Vec::with_capacity(input.len());
+    for paragraph in input.split_inclusive(|c| *c == '\n' || *c == '\u{2029}') {
+        let base = paragraph.iter().find_map(direction).unwrap_or(Direction::LeftToRight);
+        bases.extend(paragraph.iter().map(|_| base));
+    }
+    bases
+}
+
+fn is_mark(c: &char) -> bool {
+    *c == LRM || *c == RLM
+}
+
+// The direction of the letters. The letters of the right-to-left scripts, like Hebrew and Arabic, are found by their
+// Unicode blocks. The rest of the characters, like digits and punctuation, are not strong.
+fn direction(c: &char) -> Option<Direction> {
+    match *c {
+        '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}' |
+        '\u{10800}'..='\u{10FFF}' | '\u{1E800}'..='\u{1EFFF}' if c.is_alphabetic() => Some(Direction::RightToLeft),
+        _ if c.is_alphabetic() => Some(Direction::LeftToRight),
+        _ => None,
+    }
+}
+
+impl Steganographer for DirectionMarkSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        let public: Vec<char> = public.iter().filter(|c| !is_mark(c)).cloned().collect();
+        Ok(self.positions(&public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let public: Vec<char> = public.iter().filter(|c| !is_mark(c)).cloned().collect();
+        let positions = self.positions(&public);
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if positions.len() < secret_size * codec.encoded_group_size() {
+            return Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: positions.len(),
+                unit: CapacityUnit::Spaces,
+            });
+        }
+
+        let encoded = codec.encode(secret);
+        let mut disguised = Vec::with_capacity(public.len() + encoded.len());
+        let mut cursor = 0;
+        for (position, elem) in positions.into_iter().zip(encoded) {
+            disguised.extend_from_slice(&public[cursor..position]);
+            disguised.push(if codec.is_a(&elem) { LRM } else { RLM });
+            cursor = position;
+        }
+        disguised.extend_from_slice(&public[cursor..]);
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = input.iter()
+            .filter(|c| is_mark(c))
+            .map(|c| if *c == LRM { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        Ok(input.iter().filter(|c| !is_mark(c)).cloned().collect())
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(input.iter().enumerate()
+            .filter(|(_, c)| is_mark(c))
+            .map(|(position, c)| Slot::new(position, if *c == LRM { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod direction_mark_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DirectionMarkSteganographer::new();
+        let public = "This is a public message that contains a secret one too";
+        let disguised = s.disguise(&['H', 'i'], &Vec::from_iter(public.chars()), &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        // H = AABBB, I = ABAAA
+        assert_eq!(string, "This \u{200E}is \u{200E}a \u{200F}public \u{200F}message \u{200F}that \u{200E}contains \u{200F}a \
+        \u{200E}secret \u{200E}one \u{200E}too");
+        assert!(String::from_iter(s.reveal(&disguised, &codec).unwrap().iter()).starts_with("HI"));
+        assert_eq!(String::from_iter(s.strip(&disguised).unwrap().iter()), public);
+        assert_eq!(s.mapping(&disguised).unwrap().slots().len(), 10);
+    }
+
+    #[test]
+    fn existing_marks_are_removed() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DirectionMarkSteganographer::new();
+        let public = Vec::from_iter("\u{200F}one two three four five six\u{200E}".chars());
+        assert_eq!(s.capacity(&public).unwrap(), 5);
+        // A = AAAAA
+        let disguised = s.disguise(&['A'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "one \u{200E}two \u{200E}three \u{200E}four \u{200E}five \u{200E}six");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['A']);
+    }
+
+    #[test]
+    fn preserve_the_order_of_the_text() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DirectionMarkSteganographer::new().preserve_order();
+        assert!(s.preserves_order());
+        // Only the spaces between two letters of the direction of the paragraph are used
+        let public = Vec::from_iter("It costs 5 euros (or more) in שלום עולם טוב and then we go home now".chars());
+        assert_eq!(DirectionMarkSteganographer::new().capacity(&public).unwrap(), 15);
+        assert_eq!(s.capacity(&public).unwrap(), 7);
+        // Z = BABBB
+        let disguised = s.disguise(&['Z'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()),
+                   "It \u{200F}costs 5 euros (or \u{200E}more) in שלום עולם טוב and \u{200F}then \u{200F}we \u{200F}go home now");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['Z']);
+    }
+
+    #[test]
+    fn preserve_the_order_of_right_to_left_paragraphs() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DirectionMarkSteganographer::new().preserve_order();
+        // A LRM would swap the Hebrew words of a left-to-right paragraph
+        assert_eq!(s.capacity(&Vec::from_iter("It is שלום עולם טוב".chars())).unwrap(), 1);
+        // The Hebrew words of a right-to-left paragraph may be separated by a LRM
+        let public = Vec::from_iter("שלום עולם טוב מאוד לכולם\nhello world".chars());
+        assert_eq!(s.capacity(&public).unwrap(), 5);
+        // H = AABBB
+        let disguised = s.disguise(&['H'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()),
+                   "שלום \u{200E}עולם \u{200E}טוב \u{200F}מאוד \u{200F}לכולם\nhello \u{200F}world");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['H']);
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DirectionMarkSteganographer::new().preserve_order();
+        let output = s.disguise(&['H'], &Vec::from_iter("Pay five euros, not 6 euros".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 5, available: 2, unit: CapacityUnit::Spaces }));
+    }
+}
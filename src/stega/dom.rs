@@ -0,0 +1,304 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hooks into the parsed DOM of HTML or XML, for channels that the crate does not provide, like the ids, the style
+//! attributes or the depth of the elements.
+//!
+//! [for_each_text_node](fn.for_each_text_node.html) visits the text nodes of an input along with the elements that
+//! contain them, and the [DomChannelSteganographer](struct.DomChannelSteganographer.html) reveals the elements of a
+//! channel that is defined by an [ElementClassifier](trait.ElementClassifier.html), like a closure.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::Steganographer;
+//! use bacon_cipher::stega::dom::{DomChannelSteganographer, ElementData};
+//! use bacon_cipher::stega::tags::ParseMode;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! // The elements whose id ends with an even digit carry the Bacon's element A and the rest the Bacon's element B
+//! let s = DomChannelSteganographer::new(|element: &ElementData| {
+//!     element.attribute("id")
+//!         .and_then(|id| id.chars().last())
+//!         .and_then(|c| c.to_digit(10))
+//!         .map(|digit| digit.is_multiple_of(2))
+//! }).with_parse_mode(ParseMode::Fragment);
+//! // H = AABBB
+//! let input = r#"<p id="p2">He</p><p id="p7">llo</p> world"#;
+//! assert_eq!(s.reveal_str(input, &codec).unwrap(), "H");
+//! ```
+use html5ever::rcdom::{Handle, NodeData};
+
+use crate::{BaconCodec, carrier, errors, Steganographer};
+use crate::carrier::{Segment, SegmentKind};
+use crate::errors::BaconError;
+use crate::stega::tags::{self, ParseMode, SimpleTagSteganographer};
+
+/// An element of a parsed input, as it is given to the hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementData {
+    name: String,
+    attributes: Vec<(String, String)>,
+    depth: usize,
+}
+
+impl ElementData {
+    /// The local name of the element, like `span`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The local names and the values of the attributes.
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// The value of the attribute with the local name, like `style`.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The number of the elements that contain the element. The outermost elements, like the `<html>` of a document or
+    /// the top-level elements of a fragment, have a depth of 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Parses the input and calls `f` for every text node, in document order, along with the elements that contain it,
+/// from the outermost to the innermost.
+///
+/// The text of the elements that cannot contain markup, like `<script>`, is visited as well. Like
+/// [SimpleTagSteganographer::parse_str](../tags/struct.SimpleTagSteganographer.html#method.parse_str), the inputs whose
+/// elements are nested deeper than [MAX_NESTING_DEPTH](../tags/constant.MAX_NESTING_DEPTH.html) are rejected.
+pub fn for_each_text_node<F>(input: &str, parse_mode: ParseMode, mut f: F) -> errors::Result<()>
+    where F: FnMut(&str, &[ElementData]) {
+    let parsed = tags::parse_input(input, parse_mode)?;
+    let mut ancestors = Vec::new();
+    for child in parsed.root.children.borrow().iter() {
+        visit(child, &mut ancestors, &mut f);
+    }
+    Ok(())
+}
+
+fn visit<F>(handle: &Handle, ancestors: &mut Vec<ElementData>, f: &mut F)
+    where F: FnMut(&str, &[ElementData]) {
+    match handle.data {
+        NodeData::Text { ref contents } => f(&contents.borrow(), ancestors),
+        NodeData::Element { ref name, ref attrs, .. } => {
+            ancestors.push(ElementData {
+                name: name.local.to_string(),
+                attributes: tags::attributes(&attrs.borrow()),
+                depth: ancestors.len(),
+            });
+            for child in handle.children.borrow().iter() {
+                visit(child, ancestors, f);
+            }
+            ancestors.pop();
+        }
+        _ => {
+            for child in handle.children.borrow().iter() {
+                visit(child, ancestors, f);
+            }
+        }
+    }
+}
+
+/// Classifies the elements of a DOM channel.
+///
+/// It is implemented for the closures that take an [ElementData](struct.ElementData.html) and return an `Option<bool>`.
+pub trait ElementClassifier {
+    /// The Bacon's element that the text of the element carries: `Some(true)` for A, `Some(false)` for B, or `None`
+    /// if the element is transparent, in which case its text inherits the Bacon's element of its ancestors.
+    fn classify_element(&self, element: &ElementData) -> Option<bool>;
+}
+
+impl<F> ElementClassifier for F where F: Fn(&ElementData) -> Option<bool> {
+    fn classify_element(&self, element: &ElementData) -> Option<bool> {
+        self(element)
+    }
+}
+
+/// Reveals the elements of a custom DOM channel, which is defined by an [ElementClassifier](trait.ElementClassifier.html).
+///
+/// The text of an element carries the Bacon's element of its innermost classified element, while the text outside of any
+/// classified element, or in elements that cannot contain markup, does not carry any.
+///
+/// The classifier cannot produce any output, so the disguise is done by a
+/// [SimpleTagSteganographer](../tags/struct.SimpleTagSteganographer.html) that should be set with
+/// [with_disguise_steganographer](struct.DomChannelSteganographer.html#method.with_disguise_steganographer).
+#[derive(Debug, Clone)]
+pub struct DomChannelSteganographer<C> {
+    classifier: C,
+    parse_mode: ParseMode,
+    disguise_steganographer: Option<SimpleTagSteganographer>,
+}
+
+impl<C: ElementClassifier> DomChannelSteganographer<C> {
+    /// Creates a `DomChannelSteganographer` that parses HTML documents.
+    pub fn new(classifier: C) -> DomChannelSteganographer<C> {
+        DomChannelSteganographer {
+            classifier,
+            parse_mode: ParseMode::Document,
+            disguise_steganographer: None,
+        }
+    }
+
+    pub fn with_parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.set_parse_mode(parse_mode);
+        self
+    }
+
+    pub fn set_parse_mode(&mut self, parse_mode: ParseMode) {
+        self.parse_mode = parse_mode;
+    }
+
+    /// Sets the steganographer that disguises the secrets. Its tags should be classified as the Bacon's elements that
+    /// they mark.
+    pub fn with_disguise_steganographer(mut self, steganographer: SimpleTagSteganographer) -> Self {
+        self.disguise_steganographer = Some(steganographer);
+        self
+    }
+
+    pub fn set_disguise_steganographer(&mut self, steganographer: SimpleTagSteganographer) {
+        self.disguise_steganographer = Some(steganographer);
+    }
+
+    pub fn classifier(&self) -> &C {
+        &self.classifier
+    }
+
+    /// Classifies the text of the input to segments. The segments do not have ranges, since they are the text nodes
+    /// of the parsed input.
+    pub fn segments(&self, input: &[char]) -> errors::Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+        for_each_text_node(&input.iter().collect::<String>(), self.parse_mode, |text, ancestors| {
+            if ancestors.last().is_some_and(|parent| tags::is_raw_text(self.parse_mode, parent.name())) {
+                return;
+            }
+            let elem = ancestors.iter().rev().find_map(|element| self.classifier.classify_element(element));
+            if let Some(is_a) = elem {
+                segments.push(Segment::new(if is_a { SegmentKind::A } else { SegmentKind::B }, text, None));
+            }
+        })?;
+        Ok(segments)
+    }
+}
+
+impl<C: ElementClassifier> Steganographer for DomChannelSteganographer<C> {
+    type T = char;
+
+    /// The capacity of the disguise steganographer, or 0 without one.
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        match self.disguise_steganographer {
+            Some(ref steganographer) => steganographer.capacity(public),
+            None => Ok(0),
+        }
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        match self.disguise_steganographer {
+            Some(ref steganographer) => steganographer.disguise(secret, public, codec),
+            None => Err(BaconError::SteganographerError(
+                "The DomChannelSteganographer needs a disguise steganographer in order to disguise a secret".to_string())),
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        Ok(carrier::decode(&self.segments(input)?, codec))
+    }
+
+    /// Strips the input with the disguise steganographer.
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        match self.disguise_steganographer {
+            Some(ref steganographer) => steganographer.strip(input),
+            None => Err(BaconError::SteganographerError("The steganographer does not support stripping".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod dom_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::tags::Tag;
+
+    use super::*;
+
+    fn style(element: &ElementData) -> Option<bool> {
+        match element.attribute("style") {
+            Some("color:#000") => Some(true),
+            Some("color:#001") => Some(false),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn visit_the_text_nodes() {
+        let mut visited = Vec::new();
+        for_each_text_node(r#"<p id="x">Hi <b class="y">there</b></p>!"#, ParseMode::Fragment, |text, ancestors| {
+            let path: Vec<String> = ancestors.iter().map(|element| format!("{}@{}", element.name(), element.depth())).collect();
+            visited.push((text.to_string(), path.join("/"), ancestors.last().and_then(|element| element.attribute("class")).map(String::from)));
+        }).unwrap();
+        assert_eq!(visited, vec![
+            ("Hi ".to_string(), "p@0".to_string(), None),
+            ("there".to_string(), "p@0/b@1".to_string(), Some("y".to_string())),
+            ("!".to_string(), "".to_string(), None),
+        ]);
+        let nested = "<div>".repeat(tags::MAX_NESTING_DEPTH + 1);
+        assert!(matches!(for_each_text_node(&nested, ParseMode::Fragment, |_, _| {}), Err(BaconError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn reveal_by_style() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DomChannelSteganographer::new(style).with_parse_mode(ParseMode::Fragment);
+        // H = AABBB, the innermost classified element wins and the unclassified elements are transparent
+        let input = r#"<span style="color:#000">H<em>e</em><span style="color:#001">l</span></span><span style="color:#001">lo</span> world<script>x</script>"#;
+        let segments = s.segments(&Vec::from_iter(input.chars())).unwrap();
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::A, "H", None),
+            Segment::new(SegmentKind::A, "e", None),
+            Segment::new(SegmentKind::B, "l", None),
+            Segment::new(SegmentKind::B, "lo", None),
+        ]);
+        assert_eq!(s.reveal_str(input, &codec).unwrap(), "H");
+    }
+
+    #[test]
+    fn reveal_by_depth_parity() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DomChannelSteganographer::new(|element: &ElementData| Some(element.depth().is_multiple_of(2)))
+            .with_parse_mode(ParseMode::Fragment);
+        // I = ABAAA
+        let input = "<p>a<i>b</i>aaa</p>";
+        assert_eq!(s.reveal_str(input, &codec).unwrap(), "I");
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = DomChannelSteganographer::new(style).with_parse_mode(ParseMode::Fragment);
+        assert!(s.disguise(&['H'], &['a'; 5], &codec).is_err());
+        let s = s.with_disguise_steganographer(SimpleTagSteganographer::new(
+            Tag::new(Some(r#"<span style="color:#000">"#), Some("</span>")),
+            Tag::new(Some(r#"<span style="color:#001">"#), Some("</span>"))));
+        let public = Vec::from_iter("This is a public message".chars());
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        assert!(String::from_iter(s.reveal(&disguised, &codec).unwrap().iter()).starts_with("HI"));
+        assert_eq!(s.strip(&disguised).unwrap(), public);
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::ops::Range;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// The characters that may separate the groups of digits of a hexadecimal run, like in UUIDs, MAC addresses and hex dumps.
+pub const SEPARATORS: [char; 3] = ['-', ':', ' '];
+
+/// The default minimum number of digits of a hexadecimal run.
+pub const DEFAULT_MIN_DIGITS: usize = 8;
+
+/// Applies steganography based on the case of the hexadecimal digits of hex dumps and case-insensitive identifiers,
+/// like UUIDs and git hashes.
+///
+/// A lowercase digit `a`-`f` carries the Bacon's element A and an uppercase digit `A`-`F` carries the Bacon's element B.
+///
+/// The digits are searched in hexadecimal runs of the text: words that consist of hexadecimal digits, optionally
+/// with a `0x` prefix, which may be separated by single [SEPARATORS](constant.SEPARATORS.html). A run should have at
+/// least a minimum number of digits and contain at least one decimal digit, so that English words like `decade` are
+/// not taken for hexadecimal. Base64 is case sensitive and is never used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexCaseSteganographer {
+    min_digits: usize,
+}
+
+impl HexCaseSteganographer {
+    /// Creates a `HexCaseSteganographer` for runs of at least [DEFAULT_MIN_DIGITS](constant.DEFAULT_MIN_DIGITS.html) digits.
+    pub fn new() -> HexCaseSteganographer {
+        HexCaseSteganographer { min_digits: DEFAULT_MIN_DIGITS }
+    }
+
+    /// Sets the minimum number of digits of a hexadecimal run.
+    pub fn with_min_digits(mut self, min_digits: usize) -> Self {
+        self.set_min_digits(min_digits);
+        self
+    }
+
+    pub fn set_min_digits(&mut self, min_digits: usize) {
+        self.min_digits = min_digits;
+    }
+
+    pub fn min_digits(&self) -> usize {
+        self.min_digits
+    }
+
+    /// Locates the hexadecimal runs of the input.
+    pub fn hex_runs(&self, input: &[char]) -> Vec<Range<usize>> {
+        let mut runs = Vec::new();
+        // The current run and the number of its digits
+        let mut current: Option<(Range<usize>, usize)> = None;
+        for word in words(input) {
+            let digits = match hex_digits(&input[word.clone()]) {
+                Some(prefix) => word.start + prefix..word.end,
+                None => {
+                    runs.extend(current.take().and_then(|run| self.accept(input, run)));
+                    continue;
+                }
+            };
+            current = match current.take() {
+                Some((run, count)) if word.start == run.end + 1 && SEPARATORS.contains(&input[run.end]) => {
+                    Some((run.start..digits.end, count + digits.len()))
+                }
+                previous => {
+                    runs.extend(previous.and_then(|run| self.accept(input, run)));
+                    Some((digits.clone(), digits.len()))
+                }
+            };
+        }
+        runs.extend(current.and_then(|run| self.accept(input, run)));
+        runs
+    }
+
+    fn accept(&self, input: &[char], (run, count): (Range<usize>, usize)) -> Option<Range<usize>> {
+        if count >= self.min_digits && input[run.clone()].iter().any(|c| c.is_ascii_digit()) {
+            Some(run)
+        } else {
+            None
+        }
+    }
+
+    // The positions of the digits that are letters.
+    fn carriers(&self, input: &[char]) -> Vec<usize> {
+        self.hex_runs(input).into_iter()
+            .flatten()
+            .filter(|position| input[*position].is_ascii_hexdigit() && input[*position].is_ascii_alphabetic())
+            .collect()
+    }
+}
+
+impl Default for HexCaseSteganographer {
+    fn default() -> HexCaseSteganographer {
+        HexCaseSteganographer::new()
+    }
+}
+
+// The ranges of the maximal runs of alphanumeric characters.
+fn words(input: &[char]) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (position, c) in input.iter().enumerate() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(position),
+            (false, Some(word_start)) => {
+                words.push(word_start..position);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(word_start..input.len());
+    }
+    words
+}
+
+// The length of the `0x` prefix of a word that consists of hexadecimal digits.
+fn hex_digits(word: &[char]) -> Option<usize> {
+    let prefix = if word.len() > 2 && word[0] == '0' && (word[1] == 'x' || word[1] == 'X') { 2 } else { 0 };
+    if word[prefix..].iter().all(|c| c.is_ascii_hexdigit()) {
+        Some(prefix)
+    } else {
+        None
+    }
+}
+
+impl Steganographer for HexCaseSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.carriers(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let carriers = self.carriers(public);
+        let encoded = codec.encode(secret);
+        if carriers.len() < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available: carriers.len(),
+                unit: CapacityUnit::Carriers,
+            });
+        }
+        let mut disguised = public.to_vec();
+        for (position, elem) in carriers.into_iter().zip(encoded.iter()) {
+            disguised[position] = if codec.is_a(elem) {
+                public[position].to_ascii_lowercase()
+            } else {
+                public[position].to_ascii_uppercase()
+            };
+        }
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = self.carriers(input).into_iter()
+            .map(|position| if input[position].is_ascii_lowercase() { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(self.carriers(input).into_iter()
+            .map(|position| Slot::new(position, if input[position].is_ascii_lowercase() { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod hex_case_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn locate_hex_runs() {
+        let s = HexCaseSteganographer::new();
+        let input = Vec::from_iter("Commit 3f2a9c1 of a decade, id 550e8400-e29b-41d4-a716-446655440000, dump 0x1f 2e 3d 4c 5b.".chars());
+        let runs: Vec<String> = s.hex_runs(&input).into_iter().map(|run| String::from_iter(&input[run])).collect();
+        // The hash is too short and the decade does not contain decimal digits
+        assert_eq!(runs, vec!["550e8400-e29b-41d4-a716-446655440000", "1f 2e 3d 4c 5b"]);
+        let s = s.with_min_digits(7);
+        assert_eq!(s.hex_runs(&input).len(), 3);
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = HexCaseSteganographer::new();
+        let public = Vec::from_iter("Request 550e8400-e29b-41d4-a716-446655440000 failed at 0xdeadbeef01".chars());
+        assert_eq!(s.capacity(&public).unwrap(), 13);
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()), "Request 550e8400-e29B-41D4-A716-446655440000 failed at 0xdEadbeef01");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I']);
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = HexCaseSteganographer::new();
+        let public = Vec::from_iter("git checkout 9fceb02d".chars());
+        assert_eq!(s.disguise(&['H', 'I'], &public, &codec), Err(BaconError::InsufficientCapacity {
+            required: 10,
+            available: 5,
+            unit: CapacityUnit::Carriers,
+        }));
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::char;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::{BaconError, CapacityUnit};
+
+/// Applies steganography based on HTML numeric character references.
+///
+/// A literal letter carries the Bacon's element A and a letter written as a numeric character reference
+/// (e.g. `&#101;` for `e`) carries the Bacon's element B. Both render identically in a web page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlEntitySteganographer {
+    hexadecimal: bool,
+}
+
+impl HtmlEntitySteganographer {
+    pub fn new() -> HtmlEntitySteganographer {
+        HtmlEntitySteganographer { hexadecimal: false }
+    }
+
+    /// Writes the references in hexadecimal (e.g. `&#x65;`) instead of decimal.
+    pub fn hexadecimal(mut self) -> Self {
+        self.set_hexadecimal(true);
+        self
+    }
+
+    pub fn set_hexadecimal(&mut self, b: bool) {
+        self.hexadecimal = b;
+    }
+
+    fn reference(&self, c: char) -> String {
+        if self.hexadecimal {
+            format!("&#x{:x};", c as u32)
+        } else {
+            format!("&#{};", c as u32)
+        }
+    }
+}
+
+impl Default for HtmlEntitySteganographer {
+    fn default() -> HtmlEntitySteganographer {
+        HtmlEntitySteganographer::new()
+    }
+}
+
+/// A token of an HTML text.
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// A literal `char`.
+    Literal(char),
+    /// A numeric character reference, along with the `char` that it refers to.
+    Numeric(char),
+    /// A named character reference (e.g. `&amp;`).
+    Named(String),
+}
+
+// Returns the length of the numeric character reference that starts at `input[0]`, along with the referenced char.
+fn numeric_reference(input: &[char]) -> Option<(usize, char)> {
+    if input.len() < 4 || input[0] != '&' || input[1] != '#' {
+        return None;
+    }
+    let (radix, start) = if input[2] == 'x' || input[2] == 'X' { (16, 3) } else { (10, 2) };
+    let end = start + input[start..].iter().take_while(|c| c.is_digit(radix)).count();
+    if end == start || input.get(end) != Some(&';') {
+        return None;
+    }
+    let digits: String = input[start..end].iter().collect();
+    u32::from_str_radix(&digits, radix).ok()
+        .and_then(char::from_u32)
+        .map(|c| (end + 1, c))
+}
+
+// Returns the length of the named character reference that starts at `input[0]`.
+fn named_reference(input: &[char]) -> Option<usize> {
+    if input.first() != Some(&'&') {
+        return None;
+    }
+    let end = 1 + input[1..].iter().take_while(|c| c.is_ascii_alphanumeric()).count();
+    if end > 1 && input.get(end) == Some(&';') {
+        Some(end + 1)
+    } else {
+        None
+    }
+}
+
+fn tokenize(input: &[char]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < input.len() {
+        if let Some((len, c)) = numeric_reference(&input[i..]) {
+            tokens.push(Token::Numeric(c));
+            i += len;
+        } else if let Some(len) = named_reference(&input[i..]) {
+            tokens.push(Token::Named(input[i..i + len].iter().collect()));
+            i += len;
+        } else {
+            tokens.push(Token::Literal(input[i]));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+impl Steganographer for HtmlEntitySteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(tokenize(public).iter()
+            .filter(|token| match token {
+                Token::Literal(c) | Token::Numeric(c) => c.is_alphabetic(),
+                Token::Named(_) => false,
+            })
+            .count())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let tokens = tokenize(public);
+        let available_size = self.capacity(public)?;
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if available_size < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: available_size,
+                unit: CapacityUnit::Letters,
+            })
+        } else {
+            let mut encoded = codec.encode(secret).into_iter();
+            let mut disguised = String::new();
+
+            for (index, token) in tokens.iter().enumerate() {
+                match token {
+                    Token::Literal(c) | Token::Numeric(c) if c.is_alphabetic() => {
+                        match encoded.next() {
+                            Some(ref elem) if codec.is_a(elem) => disguised.push(*c),
+                            Some(ref elem) if codec.is_b(elem) => disguised.push_str(&self.reference(*c)),
+                            _ => {
+                                if let Token::Numeric(_) = token {
+                                    disguised.push_str(&self.reference(*c))
+                                } else {
+                                    disguised.push(*c)
+                                }
+                            }
+                        }
+                    }
+                    Token::Numeric(c) => disguised.push_str(&self.reference(*c)),
+                    Token::Named(name) => disguised.push_str(name),
+                    // A literal `&` that would be parsed as the start of a reference, is escaped
+                    Token::Literal('&') if index + 1 < tokens.len() && tokens[index + 1] == Token::Literal('#') => {
+                        disguised.push_str("&amp;")
+                    }
+                    Token::Literal(c) => disguised.push(*c),
+                }
+            }
+
+            Ok(disguised.chars().collect())
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = tokenize(input).iter()
+            .filter_map(|token| {
+                match token {
+                    Token::Literal(c) if c.is_alphabetic() => Some(codec.a()),
+                    Token::Numeric(c) if c.is_alphabetic() => Some(codec.b()),
+                    _ => None,
+                }
+            })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+}
+
+#[cfg(test)]
+mod html_entity_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn tokenize_references() {
+        let input: Vec<char> = "a&#101;&#x41;&amp;&#;&#x;&b".chars().collect();
+        assert_eq!(tokenize(&input), vec![
+            Token::Literal('a'),
+            Token::Numeric('e'),
+            Token::Numeric('A'),
+            Token::Named("&amp;".to_string()),
+            Token::Literal('&'),
+            Token::Literal('#'),
+            Token::Literal(';'),
+            Token::Literal('&'),
+            Token::Literal('#'),
+            Token::Literal('x'),
+            Token::Literal(';'),
+            Token::Literal('&'),
+            Token::Literal('b'),
+        ]);
+    }
+
+    #[test]
+    fn disguise_a_secret_to_a_char_array() {
+        let codec = CharCodec::new('a', 'b');
+        let s = HtmlEntitySteganographer::new();
+        // H = AABBB
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("Fish &amp; chips".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "Fi&#115;&#104; &amp; &#99;hips");
+
+        let s = HtmlEntitySteganographer::new().hexadecimal();
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("Fish &#35;1 cake".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "Fi&#x73;&#x68; &#x23;1 &#x63;ake");
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = HtmlEntitySteganographer::new();
+        let public = "This is a public &#109;essage & it contains a secret one &#";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        assert!(string.ends_with("&amp;#"));
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+}
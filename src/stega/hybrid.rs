@@ -0,0 +1,158 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Carries the elements of a secret in two channels of the same text: the case of its letters and the spacing of its words.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::hybrid::HybridSteganographer;
+//! use bacon_cipher::Steganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = HybridSteganographer::new();
+//! // H = AABBB, the elements alternate between the letters and the spaces
+//! let disguised = s.disguise_str("H", "Hello big world", &codec).unwrap();
+//! assert_eq!(disguised, "hELlo big  world");
+//! assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with('H'));
+//! ```
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::Carrier;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::stega::letter_case::LetterCaseSteganographer;
+use crate::stega::whitespace::WhitespaceSteganographer;
+
+/// Applies steganography based on both the letter case and the spaces between words, which nearly doubles the capacity
+/// of texts with short words.
+///
+/// The elements of the encoded secret are interleaved: they alternate between the letters and the spaces, starting
+/// from the letters, and when one of the channels is full the rest of the elements go to the other one. The reveal
+/// merges the two channels in the same order.
+#[derive(Debug, Clone, Default)]
+pub struct HybridSteganographer {
+    letter_case: LetterCaseSteganographer,
+    whitespace: WhitespaceSteganographer,
+}
+
+impl HybridSteganographer {
+    /// Creates a `HybridSteganographer` with the default letter case and whitespace steganographers.
+    pub fn new() -> HybridSteganographer {
+        HybridSteganographer::default()
+    }
+
+    /// Creates a `HybridSteganographer` that uses the given steganographers for the two channels.
+    pub fn with_steganographers(letter_case: LetterCaseSteganographer, whitespace: WhitespaceSteganographer) -> HybridSteganographer {
+        HybridSteganographer { letter_case, whitespace }
+    }
+
+    pub fn letter_case(&self) -> &LetterCaseSteganographer {
+        &self.letter_case
+    }
+
+    pub fn whitespace(&self) -> &WhitespaceSteganographer {
+        &self.whitespace
+    }
+}
+
+impl Steganographer for HybridSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(Carrier::capacity(&self.letter_case, public)?.saturating_add(Carrier::capacity(&self.whitespace, public)?))
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let symbols: Vec<bool> = codec.encode(secret).iter().map(|elem| codec.is_a(elem)).collect();
+        let letters = Carrier::capacity(&self.letter_case, public)?;
+        let spaces = Carrier::capacity(&self.whitespace, public)?;
+        let available = letters.saturating_add(spaces);
+        if available < symbols.len() {
+            return Err(BaconError::InsufficientCapacity { required: symbols.len(), available, unit: CapacityUnit::Carriers });
+        }
+        let mut letter_symbols = Vec::with_capacity(letters);
+        let mut space_symbols = Vec::with_capacity(spaces);
+        for (symbol, to_letters) in symbols.iter().zip(interleaving(letters, spaces)) {
+            if to_letters {
+                letter_symbols.push(*symbol);
+            } else {
+                space_symbols.push(*symbol);
+            }
+        }
+        let disguised = self.letter_case.write_symbols(&letter_symbols, public)?;
+        self.whitespace.write_symbols(&space_symbols, &disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let mut letter_symbols = self.letter_case.read_symbols(input)?.into_iter();
+        let mut space_symbols = self.whitespace.read_symbols(input)?.into_iter();
+        let encoded: Vec<AB> = interleaving(letter_symbols.len(), space_symbols.len())
+            .filter_map(|from_letters| if from_letters { letter_symbols.next() } else { space_symbols.next() })
+            .map(|is_a| if is_a { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        self.whitespace.strip(&self.letter_case.strip(input)?)
+    }
+}
+
+// The channel of every element, which is true for the letters: the channels alternate until one of them is full.
+fn interleaving(letters: usize, spaces: usize) -> impl Iterator<Item=bool> {
+    let alternating = letters.min(spaces);
+    (0..alternating).flat_map(|_| vec![true, false])
+        .chain(std::iter::repeat_n(letters > spaces, letters.max(spaces) - alternating))
+}
+
+#[cfg(test)]
+mod hybrid_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn interleave_the_channels() {
+        assert_eq!(interleaving(3, 1).collect::<Vec<bool>>(), vec![true, false, true, true]);
+        assert_eq!(interleaving(1, 3).collect::<Vec<bool>>(), vec![true, false, false, false]);
+        assert_eq!(interleaving(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = HybridSteganographer::new();
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        assert_eq!(Steganographer::capacity(&s, &public).unwrap(), 43 + 8);
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()), "tHIS is  a public message that contains a secret one");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I']);
+        assert_eq!(s.strip(&disguised).unwrap(), public);
+    }
+
+    #[test]
+    fn fill_both_channels() {
+        let codec = CharCodec::new('a', 'b');
+        let s = HybridSteganographer::new();
+        // 10 letters and 3 spaces, for 2 letters, while a single channel cannot carry them
+        let public = Vec::from_iter("ab cd ef ghij".chars());
+        assert_eq!(Steganographer::capacity(&s, &public).unwrap(), 13);
+        let disguised = s.disguise(&['Z', 'Y'], &public, &codec).unwrap();
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['Z', 'Y']);
+        assert!(matches!(s.disguise(&['Z', 'Y', 'X'], &public, &codec),
+            Err(BaconError::InsufficientCapacity { required: 15, available: 13, unit: CapacityUnit::Carriers })));
+    }
+}
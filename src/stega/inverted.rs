@@ -0,0 +1,191 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inverts the meaning of the Bacon's elements A and B for another steganographer.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::inverted::Inverted;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//! use bacon_cipher::Steganographer;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! // H = AABBB, with the capital letters carrying the element A
+//! let s = Inverted::new(LetterCaseSteganographer::new());
+//! assert_eq!(s.disguise_str("H", "Hello world", &codec).unwrap(), "HEllo world");
+//! assert!(s.reveal_str("HEllo world", &codec).unwrap().starts_with('H'));
+//! ```
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::mapping::{Mapping, Slot};
+
+/// Wraps another steganographer and swaps the Bacon's elements A and B of the codecs that it is given.
+///
+/// It helps when the sender and the receiver got the convention of a steganographer backwards, like a capital letter
+/// for the element A instead of B, without constructing codecs with swapped substitution elements.
+#[derive(Debug, Clone, Default)]
+pub struct Inverted<S> {
+    steganographer: S,
+}
+
+impl<S: Steganographer> Inverted<S> {
+    /// Creates an `Inverted` that wraps the `steganographer`.
+    pub fn new(steganographer: S) -> Inverted<S> {
+        Inverted { steganographer }
+    }
+
+    /// The wrapped steganographer.
+    pub fn inner(&self) -> &S {
+        &self.steganographer
+    }
+
+    /// Returns the wrapped steganographer.
+    pub fn into_inner(self) -> S {
+        self.steganographer
+    }
+}
+
+impl<S: Steganographer> Steganographer for Inverted<S> {
+    type T = S::T;
+
+    fn disguise<AB>(&self, secret: &[S::T], public: &[S::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=S::T>) -> errors::Result<Vec<S::T>> {
+        self.steganographer.disguise(secret, public, &Swapped { codec })
+    }
+
+    fn reveal<AB>(&self, input: &[S::T], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=S::T>) -> errors::Result<Vec<S::T>> {
+        self.steganographer.reveal(input, &Swapped { codec })
+    }
+
+    fn capacity(&self, public: &[S::T]) -> errors::Result<usize> where S::T: Default {
+        self.steganographer.capacity(public)
+    }
+
+    fn strip(&self, input: &[S::T]) -> errors::Result<Vec<S::T>> {
+        self.steganographer.strip(input)
+    }
+
+    fn mapping(&self, input: &[S::T]) -> errors::Result<Mapping> {
+        let slots = self.steganographer.mapping(input)?.slots().iter()
+            .map(|slot| {
+                let kind = match slot.kind() {
+                    SegmentKind::A => SegmentKind::B,
+                    SegmentKind::B => SegmentKind::A,
+                    other => other,
+                };
+                Slot::new(slot.position(), kind)
+            })
+            .collect();
+        Ok(Mapping::new(slots))
+    }
+}
+
+// A codec that encodes every group of another codec to its complement, so that the elements A and B are swapped.
+struct Swapped<'a, AB, C> {
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=C>,
+}
+
+impl<'a, AB, C> Swapped<'a, AB, C> {
+    fn swap(&self, elem: &AB) -> AB {
+        if self.codec.is_a(elem) { self.codec.b() } else { self.codec.a() }
+    }
+}
+
+impl<'a, AB, C> BaconCodec for Swapped<'a, AB, C> {
+    type ABTYPE = AB;
+    type CONTENT = C;
+
+    fn encode(&self, input: &[C]) -> Vec<AB> {
+        self.codec.encode(input).iter().map(|elem| self.swap(elem)).collect()
+    }
+
+    fn encode_elem(&self, elem: &C) -> Vec<AB> {
+        self.codec.encode_elem(elem).iter().map(|elem| self.swap(elem)).collect()
+    }
+
+    fn decode(&self, input: &[AB]) -> Vec<C> {
+        let input: Vec<AB> = input.iter().map(|elem| self.swap(elem)).collect();
+        self.codec.decode(&input)
+    }
+
+    fn decode_elems(&self, elems: &[AB]) -> C {
+        let elems: Vec<AB> = elems.iter().map(|elem| self.swap(elem)).collect();
+        self.codec.decode_elems(&elems)
+    }
+
+    fn a(&self) -> AB {
+        self.codec.a()
+    }
+
+    fn b(&self) -> AB {
+        self.codec.b()
+    }
+
+    fn encoded_group_size(&self) -> usize {
+        self.codec.encoded_group_size()
+    }
+
+    fn is_a(&self, elem: &AB) -> bool {
+        self.codec.is_a(elem)
+    }
+
+    fn is_b(&self, elem: &AB) -> bool {
+        self.codec.is_b(elem)
+    }
+}
+
+#[cfg(test)]
+mod inverted_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::protected::{ProtectedSteganographer, Region};
+
+    use super::*;
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = Inverted::new(LetterCaseSteganographer::new());
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(&disguised[..11]), "THis iS a P");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I']);
+        // The inner steganographer reads the complement of every group
+        let flipped = codec.decode(&codec.encode(&['H', 'I']).iter().map(|elem| if *elem == 'a' { 'b' } else { 'a' }).collect::<Vec<char>>());
+        assert_eq!(s.inner().reveal(&disguised, &codec).unwrap()[..2], flipped[..]);
+        assert_eq!(Steganographer::capacity(&s, &public).unwrap(), Steganographer::capacity(s.inner(), &public).unwrap());
+    }
+
+    #[test]
+    fn invert_the_mapping() {
+        let codec = CharCodec::new('a', 'b');
+        let s = Inverted::new(LetterCaseSteganographer::new());
+        let public = Vec::from_iter("Hello world".chars());
+        let (disguised, mapping) = s.disguise_with_mapping(&['H'], &public, &codec).unwrap();
+        let kinds: Vec<SegmentKind> = mapping.slots().iter().map(|slot| slot.kind()).collect();
+        assert_eq!(kinds, vec![SegmentKind::A, SegmentKind::A, SegmentKind::B, SegmentKind::B, SegmentKind::B]);
+        assert_eq!(s.reveal_with_mapping(&disguised, &mapping, &codec).unwrap(), vec!['H']);
+    }
+
+    #[test]
+    fn wrap_another_wrapper() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ProtectedSteganographer::new(Inverted::new(LetterCaseSteganographer::new())).with_region(Region::Range(0..6));
+        let disguised = s.disguise_str("H", "Hello, world", &codec).unwrap();
+        assert_eq!(disguised, "Hello, WOrld");
+        assert_eq!(s.reveal_str(&disguised, &codec).unwrap(), "H");
+    }
+}
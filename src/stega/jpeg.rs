@@ -0,0 +1,468 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::str;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::Carrier;
+use crate::errors::BaconError;
+
+const SOI: u8 = 0xD8;
+const SOS: u8 = 0xDA;
+const APP0: u8 = 0xE0;
+const APP1: u8 = 0xE1;
+const COM: u8 = 0xFE;
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const IMAGE_DESCRIPTION: u16 = 0x010E;
+const ASCII: u16 = 2;
+// The maximum length of the data of a segment, excluding its length field
+const MAX_SEGMENT_DATA: usize = 65533;
+
+/// The metadata field of a JPEG image that carries the disguised text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JpegField {
+    /// The comment segment (`COM`).
+    Comment,
+    /// The `ImageDescription` tag of the EXIF metadata (`APP1`). The text is written as UTF-8.
+    ImageDescription,
+}
+
+/// Transports a secret in the metadata of a JPEG image, without touching the image data.
+///
+/// The secret is disguised in a public text by another steganographer and the disguised text is written to a
+/// [JpegField](enum.JpegField.html), replacing its previous content. The rest of the metadata, like the EXIF tags of
+/// a camera, is kept.
+///
+/// As a [Carrier](../../carrier/trait.Carrier.html) of images, it writes the elements to the text of the field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JpegSteganographer<S> {
+    steganographer: S,
+    field: JpegField,
+}
+
+impl<S: Steganographer<T=char>> JpegSteganographer<S> {
+    /// Creates a `JpegSteganographer` that disguises the secret with the `steganographer` and writes it to the
+    /// comment segment.
+    pub fn new(steganographer: S) -> JpegSteganographer<S> {
+        JpegSteganographer {
+            steganographer,
+            field: JpegField::Comment,
+        }
+    }
+
+    pub fn with_field(mut self, field: JpegField) -> Self {
+        self.set_field(field);
+        self
+    }
+
+    pub fn set_field(&mut self, field: JpegField) {
+        self.field = field;
+    }
+
+    pub fn field(&self) -> JpegField {
+        self.field
+    }
+
+    /// Disguises the _secret_ in the _public_ text and writes the disguised text to the field of the _jpeg_ image.
+    pub fn disguise<AB>(&self, secret: &[char], public: &[char], jpeg: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<u8>> {
+        let disguised = self.steganographer.disguise(secret, public, codec)?;
+        self.write_text(&disguised.iter().collect::<String>(), jpeg)
+    }
+
+    // Writes the text to the field, replacing its previous content.
+    fn write_text(&self, text: &str, jpeg: &[u8]) -> errors::Result<Vec<u8>> {
+        let segments = segments(jpeg)?;
+        let mut output = jpeg[..2].to_vec();
+        match self.field {
+            JpegField::Comment => {
+                if text.len() > MAX_SEGMENT_DATA {
+                    return Err(too_long(text.len()));
+                }
+                let mut written = false;
+                for segment in segments.iter().filter(|segment| segment.marker != COM) {
+                    if !written && segment.marker != APP0 && segment.marker != APP1 {
+                        write_segment(&mut output, COM, text.as_bytes());
+                        written = true;
+                    }
+                    output.extend_from_slice(&jpeg[segment.offset..segment.end]);
+                }
+                if !written {
+                    write_segment(&mut output, COM, text.as_bytes());
+                }
+            }
+            JpegField::ImageDescription => {
+                let exif = segments.iter().find(|segment| is_exif(segment, jpeg));
+                let data = match exif {
+                    Some(segment) => with_description(&jpeg[segment.offset + 4..segment.end], text)?,
+                    None => new_exif(text),
+                };
+                if data.len() > MAX_SEGMENT_DATA {
+                    return Err(too_long(data.len()));
+                }
+                let mut written = false;
+                for segment in &segments {
+                    if exif.map(|exif| exif.offset) == Some(segment.offset) {
+                        write_segment(&mut output, APP1, &data);
+                        written = true;
+                    } else {
+                        if !written && segment.marker != APP0 {
+                            write_segment(&mut output, APP1, &data);
+                            written = true;
+                        }
+                        output.extend_from_slice(&jpeg[segment.offset..segment.end]);
+                    }
+                }
+                if !written {
+                    write_segment(&mut output, APP1, &data);
+                }
+            }
+        }
+        output.extend_from_slice(&jpeg[segments.last().map(|segment| segment.end).unwrap_or(2)..]);
+        Ok(output)
+    }
+
+    /// Reveals the secret that is hidden in the field of the _jpeg_ image.
+    pub fn reveal<AB>(&self, jpeg: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let text = self.text(jpeg)?
+            .ok_or_else(|| BaconError::InvalidInput { message: format!("The JPEG image does not have a {:?} field", self.field), position: None, source: None })?;
+        self.steganographer.reveal(&text.chars().collect::<Vec<char>>(), codec)
+    }
+
+    /// The text of the field of the _jpeg_ image, if the image has the field.
+    pub fn text(&self, jpeg: &[u8]) -> errors::Result<Option<String>> {
+        let segments = segments(jpeg)?;
+        match self.field {
+            JpegField::Comment => {
+                let comments: Vec<&[u8]> = segments.iter()
+                    .filter(|segment| segment.marker == COM)
+                    .map(|segment| &jpeg[segment.offset + 4..segment.end])
+                    .collect();
+                if comments.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(str::from_utf8(&comments.concat())?.to_string()))
+                }
+            }
+            JpegField::ImageDescription => {
+                match segments.iter().find(|segment| is_exif(segment, jpeg)) {
+                    Some(segment) => description(&jpeg[segment.offset + 4..segment.end], segment.offset + 4),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+}
+
+impl<S: Steganographer<T=char>> Carrier for JpegSteganographer<S> {
+    type Unit = u8;
+
+    fn capacity(&self, jpeg: &[u8]) -> errors::Result<usize> {
+        Steganographer::capacity(&self.steganographer, &self.cover(jpeg)?)
+    }
+
+    fn write_symbols(&self, symbols: &[bool], jpeg: &[u8]) -> errors::Result<Vec<u8>> {
+        let written = self.steganographer.write_symbols(symbols, &self.cover(jpeg)?)?;
+        self.write_text(&written.iter().collect::<String>(), jpeg)
+    }
+
+    fn read_symbols(&self, jpeg: &[u8]) -> errors::Result<Vec<bool>> {
+        self.steganographer.read_symbols(&self.cover(jpeg)?)
+    }
+}
+
+impl<S: Steganographer<T=char>> JpegSteganographer<S> {
+    // The text of the field, which is empty if the image does not have the field
+    fn cover(&self, jpeg: &[u8]) -> errors::Result<Vec<char>> {
+        Ok(self.text(jpeg)?.unwrap_or_default().chars().collect())
+    }
+}
+
+// A segment of the metadata of a JPEG image, from the byte `offset` of its marker to the `end`.
+struct Segment {
+    marker: u8,
+    offset: usize,
+    end: usize,
+}
+
+fn invalid(message: &str, position: usize) -> BaconError {
+    BaconError::InvalidInput { message: message.to_string(), position: Some(position), source: None }
+}
+
+fn too_long(length: usize) -> BaconError {
+    BaconError::SteganographerError(format!("The metadata of {} bytes do not fit in a JPEG segment", length))
+}
+
+// Splits the metadata of a JPEG image to segments, up to the start of the scan.
+fn segments(jpeg: &[u8]) -> errors::Result<Vec<Segment>> {
+    if jpeg.len() < 2 || jpeg[0] != 0xFF || jpeg[1] != SOI {
+        return Err(invalid("Not a JPEG image", 0));
+    }
+    let mut segments = Vec::new();
+    let mut offset = 2;
+    loop {
+        if jpeg.len() < offset + 4 || jpeg[offset] != 0xFF {
+            return Err(invalid("Invalid JPEG segment", offset));
+        }
+        let marker = jpeg[offset + 1];
+        let end = offset + 2 + u16::from_be_bytes([jpeg[offset + 2], jpeg[offset + 3]]) as usize;
+        if end > jpeg.len() || end < offset + 4 {
+            return Err(invalid("Truncated JPEG segment", offset));
+        }
+        if marker == SOS {
+            return Ok(segments);
+        }
+        segments.push(Segment { marker, offset, end });
+        offset = end;
+    }
+}
+
+fn write_segment(output: &mut Vec<u8>, marker: u8, data: &[u8]) {
+    output.extend_from_slice(&[0xFF, marker]);
+    output.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+    output.extend_from_slice(data);
+}
+
+fn is_exif(segment: &Segment, jpeg: &[u8]) -> bool {
+    segment.marker == APP1 && jpeg[segment.offset + 4..segment.end].starts_with(EXIF_HEADER)
+}
+
+// Reads the integers of a TIFF structure, in its byte order.
+struct Tiff<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+    // The offset of the TIFF structure in the JPEG image, for the errors
+    position: usize,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(app1: &'a [u8], position: usize) -> errors::Result<Tiff<'a>> {
+        let data = &app1[EXIF_HEADER.len()..];
+        let position = position + EXIF_HEADER.len();
+        let big_endian = match data.get(..2) {
+            Some(b"MM") => true,
+            Some(b"II") => false,
+            _ => return Err(invalid("Invalid byte order of the EXIF data", position)),
+        };
+        Ok(Tiff { data, big_endian, position })
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> errors::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.data.get(offset..offset + N).ok_or_else(|| invalid("Truncated EXIF data", self.position + offset))?);
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u16(&self, offset: usize) -> errors::Result<u16> {
+        Ok(u16::from_be_bytes(self.bytes(offset)?))
+    }
+
+    fn u32(&self, offset: usize) -> errors::Result<u32> {
+        Ok(u32::from_be_bytes(self.bytes(offset)?))
+    }
+
+    fn put_u16(&self, output: &mut Vec<u8>, value: u16) {
+        output.extend_from_slice(&if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() });
+    }
+
+    fn put_u32(&self, output: &mut Vec<u8>, value: u32) {
+        output.extend_from_slice(&if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() });
+    }
+
+    // The offset of the IFD0 and its entries of 12 bytes each
+    fn ifd0(&self) -> errors::Result<(usize, Vec<usize>)> {
+        let ifd0 = self.u32(4)? as usize;
+        let count = self.u16(ifd0)? as usize;
+        Ok((ifd0, (0..count).map(|index| ifd0 + 2 + 12 * index).collect()))
+    }
+}
+
+fn description(app1: &[u8], position: usize) -> errors::Result<Option<String>> {
+    let tiff = Tiff::new(app1, position)?;
+    let (_, entries) = tiff.ifd0()?;
+    for entry in entries {
+        if tiff.u16(entry)? == IMAGE_DESCRIPTION {
+            let count = tiff.u32(entry + 4)? as usize;
+            let start = if count <= 4 { entry + 8 } else { tiff.u32(entry + 8)? as usize };
+            let value = tiff.data.get(start..start + count)
+                .ok_or_else(|| invalid("Truncated EXIF data", tiff.position + start))?;
+            let value = value.split(|b| *b == 0).next().unwrap_or_default();
+            return Ok(Some(str::from_utf8(value)?.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+// The data of an APP1 segment with a new IFD0, that contains only the ImageDescription.
+fn new_exif(text: &str) -> Vec<u8> {
+    let mut data = EXIF_HEADER.to_vec();
+    data.extend_from_slice(b"MM\0\x2A\0\0\0\x08");
+    data.extend_from_slice(&[0, 1]);
+    entry(&mut data, &Tiff { data: &[], big_endian: true, position: 0 }, text, 8 + 2 + 12 + 4);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data.extend_from_slice(text.as_bytes());
+    data.push(0);
+    data
+}
+
+// Writes an ImageDescription entry, whose value is at the `value_offset` unless it is inline.
+fn entry(output: &mut Vec<u8>, tiff: &Tiff, text: &str, value_offset: usize) {
+    let count = text.len() + 1;
+    tiff.put_u16(output, IMAGE_DESCRIPTION);
+    tiff.put_u16(output, ASCII);
+    tiff.put_u32(output, count as u32);
+    if count <= 4 {
+        let mut value = text.as_bytes().to_vec();
+        value.resize(4, 0);
+        output.extend_from_slice(&value);
+    } else {
+        tiff.put_u32(output, value_offset as u32);
+    }
+}
+
+// The data of the APP1 segment with the ImageDescription in a copy of the IFD0, that is appended to the TIFF data.
+// The values of the rest of the entries are not moved, so that their offsets remain valid.
+fn with_description(app1: &[u8], text: &str) -> errors::Result<Vec<u8>> {
+    let tiff = Tiff::new(app1, 0)?;
+    let (ifd0, entries) = tiff.ifd0()?;
+    let next_ifd = tiff.u32(ifd0 + 2 + 12 * entries.len())?;
+    let mut data = tiff.data.to_vec();
+    // The IFDs start at word boundaries
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+    let new_ifd0 = data.len();
+    let kept: Vec<usize> = entries.into_iter()
+        .map(|entry| tiff.u16(entry).map(|tag| (entry, tag)))
+        .collect::<errors::Result<Vec<(usize, u16)>>>()?
+        .into_iter()
+        .filter(|(_, tag)| *tag != IMAGE_DESCRIPTION)
+        .map(|(entry, _)| entry)
+        .collect();
+    let value_offset = new_ifd0 + 2 + 12 * (kept.len() + 1) + 4;
+    tiff.put_u16(&mut data, kept.len() as u16 + 1);
+    let mut written = false;
+    for entry in kept {
+        if !written && tiff.u16(entry)? > IMAGE_DESCRIPTION {
+            self::entry(&mut data, &tiff, text, value_offset);
+            written = true;
+        }
+        data.extend_from_slice(&tiff.data[entry..entry + 12]);
+    }
+    if !written {
+        self::entry(&mut data, &tiff, text, value_offset);
+    }
+    tiff.put_u32(&mut data, next_ifd);
+    data.extend_from_slice(text.as_bytes());
+    data.push(0);
+    // Point the header to the new IFD0
+    let mut header = Vec::new();
+    tiff.put_u32(&mut header, new_ifd0 as u32);
+    data[4..8].copy_from_slice(&header);
+    Ok([EXIF_HEADER, &data].concat())
+}
+
+#[cfg(test)]
+mod jpeg_tests {
+    use std::iter::FromIterator;
+
+    use crate::carrier;
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    // The metadata of a JPEG image, followed by a truncated scan
+    fn jpeg(exif: Option<&[u8]>) -> Vec<u8> {
+        let mut jpeg = vec![0xFF, SOI];
+        write_segment(&mut jpeg, APP0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        if let Some(exif) = exif {
+            write_segment(&mut jpeg, APP1, exif);
+        }
+        write_segment(&mut jpeg, COM, b"Old comment");
+        write_segment(&mut jpeg, 0xDB, &[0; 65]);
+        write_segment(&mut jpeg, SOS, &[1, 1, 0, 0, 63, 0]);
+        jpeg.extend_from_slice(&[0x12, 0x34, 0xFF, 0xD9]);
+        jpeg
+    }
+
+    // Little-endian EXIF data with the Make and an old ImageDescription
+    fn camera_exif() -> Vec<u8> {
+        let mut exif = EXIF_HEADER.to_vec();
+        exif.extend_from_slice(b"II\x2A\0\x08\0\0\0");
+        exif.extend_from_slice(&[2, 0]);
+        exif.extend_from_slice(&[0x0E, 0x01, 2, 0, 4, 0, 0, 0, b'o', b'l', b'd', 0]);
+        exif.extend_from_slice(&[0x0F, 0x01, 2, 0, 6, 0, 0, 0, 38, 0, 0, 0]);
+        exif.extend_from_slice(&[0, 0, 0, 0]);
+        exif.extend_from_slice(b"Canon\0");
+        exif
+    }
+
+    #[test]
+    fn disguise_and_reveal_comment() {
+        let codec = CharCodec::new('a', 'b');
+        let s = JpegSteganographer::new(LetterCaseSteganographer::new());
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let output = s.disguise(&Vec::from_iter("My secret".chars()), &public, &jpeg(None), &codec).unwrap();
+        let markers: Vec<u8> = segments(&output).unwrap().iter().map(|segment| segment.marker).collect();
+        assert_eq!(markers, vec![APP0, COM, 0xDB]);
+        assert_eq!(s.text(&output).unwrap().unwrap(), "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+        assert!(output.ends_with(&[0x12, 0x34, 0xFF, 0xD9]));
+        assert!(String::from_iter(s.reveal(&output, &codec).unwrap().iter()).starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_and_reveal_image_description() {
+        let codec = CharCodec::new('a', 'b');
+        let s = JpegSteganographer::new(LetterCaseSteganographer::new()).with_field(JpegField::ImageDescription);
+        let public = Vec::from_iter("A photograph of the sea".chars());
+        assert_eq!(s.text(&jpeg(None)).unwrap(), None);
+        assert_eq!(s.text(&jpeg(Some(&camera_exif()))).unwrap(), Some("old".to_string()));
+        for exif in [None, Some(camera_exif())] {
+            let output = s.disguise(&['H', 'I'], &public, &jpeg(exif.as_deref()), &codec).unwrap();
+            assert_eq!(s.text(&output).unwrap().unwrap(), "a pHOToGraph of the sea");
+            assert_eq!(s.reveal(&output, &codec).unwrap()[..2], ['H', 'I']);
+            let markers: Vec<u8> = segments(&output).unwrap().iter().map(|segment| segment.marker).collect();
+            assert_eq!(markers, vec![APP0, APP1, COM, 0xDB]);
+        }
+        // The Make of the camera is kept
+        let output = s.disguise(&['H'], &public, &jpeg(Some(&camera_exif())), &codec).unwrap();
+        assert!(output.windows(6).any(|window| window == b"Canon\0"));
+    }
+
+    #[test]
+    fn carrier_of_images() {
+        let codec = CharCodec::new('a', 'b');
+        let s = JpegSteganographer::new(LetterCaseSteganographer::new());
+        assert_eq!(Carrier::capacity(&s, &jpeg(None)).unwrap(), 10);
+        let output = carrier::embed(&s, &['H'], &jpeg(None), &codec).unwrap();
+        // H = AABBB
+        assert_eq!(s.text(&output).unwrap().unwrap(), "olD COmment");
+        assert_eq!(carrier::extract(&s, &output, &codec).unwrap()[0], 'H');
+        let s = s.with_field(JpegField::ImageDescription);
+        assert_eq!(Carrier::capacity(&s, &jpeg(None)).unwrap(), 0);
+        assert!(matches!(carrier::embed(&s, &['H'], &jpeg(None), &codec), Err(BaconError::InsufficientCapacity { .. })));
+    }
+
+    #[test]
+    fn invalid_images() {
+        let codec = CharCodec::new('a', 'b');
+        let s = JpegSteganographer::new(LetterCaseSteganographer::new());
+        assert!(matches!(s.reveal(b"\x89PNG", &codec), Err(BaconError::InvalidInput { position: Some(0), .. })));
+        assert!(matches!(s.reveal(&jpeg(None)[..30], &codec), Err(BaconError::InvalidInput { .. })));
+        let s = s.with_field(JpegField::ImageDescription);
+        assert!(matches!(s.reveal(&jpeg(None), &codec), Err(BaconError::InvalidInput { position: None, .. })));
+    }
+}
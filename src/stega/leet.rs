@@ -0,0 +1,241 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// The default substitution table: `a` → `4`, `e` → `3`, `i` → `1`, `o` → `0`, `s` → `5` and `t` → `7`.
+pub const DEFAULT_SUBSTITUTIONS: [(char, char); 6] = [
+    ('a', '4'),
+    ('e', '3'),
+    ('i', '1'),
+    ('o', '0'),
+    ('s', '5'),
+    ('t', '7'),
+];
+
+/// Applies steganography based on leet speak.
+///
+/// Each entry of the substitution table is a pair of a letter and its leet form. Wherever either of them appears in a
+/// word of the public input, the letter, in any case, carries the Bacon's element A and the leet form carries the
+/// Bacon's element B.
+///
+/// Only the words that contain a letter without a leet form are eligible, like `h3llo`, but not `2024` or `toast`. The
+/// disguise never changes these letters, so the reveal finds the same eligible words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeetSteganographer {
+    substitutions: Vec<(char, char)>,
+}
+
+impl LeetSteganographer {
+    /// Creates a `LeetSteganographer` with a substitution table of `(letter, leet form)` pairs.
+    ///
+    /// The letters should be lowercase. Every `char` may appear only once in the table and the leet forms may not be
+    /// letters or whitespace.
+    pub fn new(substitutions: &[(char, char)]) -> errors::Result<LeetSteganographer> {
+        let mut seen: Vec<char> = Vec::new();
+        for &(letter, leet) in substitutions {
+            if !letter.is_lowercase() {
+                return Err(BaconError::SteganographerError(format!("The letter {:?} should be a lowercase letter", letter)));
+            }
+            if leet.is_alphabetic() || leet.is_whitespace() {
+                return Err(BaconError::SteganographerError(format!("Cannot use {:?} as a leet form", leet)));
+            }
+            for c in [letter, leet].iter() {
+                if seen.contains(c) {
+                    return Err(BaconError::SteganographerError(format!("The character {:?} is defined more than once", c)));
+                }
+                seen.push(*c);
+            }
+        }
+        if substitutions.is_empty() {
+            Err(BaconError::SteganographerError("The substitution table should not be empty".to_string()))
+        } else {
+            Ok(LeetSteganographer { substitutions: substitutions.to_vec() })
+        }
+    }
+
+    pub fn substitutions(&self) -> &[(char, char)] {
+        &self.substitutions
+    }
+
+    // Returns the substitution pair that the `char` belongs to, either as the letter, in any case, or as the leet form.
+    fn pair_of(&self, c: &char) -> Option<&(char, char)> {
+        self.substitutions.iter().find(|(letter, leet)| leet == c || c.to_lowercase().eq(letter.to_lowercase()))
+    }
+
+    // Returns the positions of the characters that carry Bacon's elements, which are the letters of the table and
+    // their leet forms in the eligible words. A word is a run of alphanumeric characters and leet forms, which is
+    // eligible if it contains a letter without a leet form.
+    fn carriers(&self, input: &[char]) -> Vec<usize> {
+        let mut carriers = Vec::new();
+        let mut start = 0;
+        while start < input.len() {
+            let in_word = |c: &char| c.is_alphanumeric() || self.pair_of(c).is_some();
+            if !in_word(&input[start]) {
+                start += 1;
+                continue;
+            }
+            let end = (start..input.len()).find(|position| !in_word(&input[*position])).unwrap_or(input.len());
+            let word = &input[start..end];
+            if word.iter().any(|c| c.is_alphabetic() && self.pair_of(c).is_none()) {
+                carriers.extend((start..end).filter(|position| self.pair_of(&input[*position]).is_some()));
+            }
+            start = end;
+        }
+        carriers
+    }
+
+    // Returns true if the carrier is a letter, which carries the Bacon's element A.
+    fn is_letter(&self, c: &char) -> bool {
+        self.pair_of(c).map(|(_, leet)| leet != c).unwrap_or(false)
+    }
+}
+
+impl Default for LeetSteganographer {
+    /// Uses the [DEFAULT_SUBSTITUTIONS](constant.DEFAULT_SUBSTITUTIONS.html).
+    fn default() -> LeetSteganographer {
+        LeetSteganographer { substitutions: DEFAULT_SUBSTITUTIONS.to_vec() }
+    }
+}
+
+impl Steganographer for LeetSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.carriers(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let carriers = self.carriers(public);
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if carriers.len() < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: carriers.len(),
+                unit: CapacityUnit::Letters,
+            })
+        } else {
+            let mut disguised = public.to_vec();
+            for (position, elem) in carriers.into_iter().zip(codec.encode(secret)) {
+                let pc = public[position];
+                if let Some(&(letter, leet)) = self.pair_of(&pc) {
+                    disguised[position] = if !codec.is_a(&elem) {
+                        leet
+                    } else if self.is_letter(&pc) {
+                        // A letter keeps its case
+                        pc
+                    } else {
+                        letter
+                    };
+                }
+            }
+            Ok(disguised)
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = self.carriers(input).into_iter()
+            .map(|position| if self.is_letter(&input[position]) { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        let mut stripped = input.to_vec();
+        for position in self.carriers(input) {
+            if let Some(&(letter, _)) = self.pair_of(&input[position]) {
+                if !self.is_letter(&input[position]) {
+                    stripped[position] = letter;
+                }
+            }
+        }
+        Ok(stripped)
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(self.carriers(input).into_iter()
+            .map(|position| Slot::new(position, if self.is_letter(&input[position]) { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod leet_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(LeetSteganographer::new(&[]).is_err());
+        assert!(LeetSteganographer::new(&[('E', '3')]).is_err());
+        assert!(LeetSteganographer::new(&[('e', 'x')]).is_err());
+        assert!(LeetSteganographer::new(&[('e', ' ')]).is_err());
+        assert!(LeetSteganographer::new(&[('e', '3'), ('b', '3')]).is_err());
+        assert!(LeetSteganographer::new(&[('e', '3'), ('e', '€')]).is_err());
+        assert!(LeetSteganographer::new(&[('e', '3'), ('a', '@'), ('l', '|')]).is_ok());
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LeetSteganographer::default();
+        let public = "In 2024 we meet at the old harbour, then we go to the east gate together.";
+        let disguised = s.disguise(&['H', 'i'], &Vec::from_iter(public.chars()), &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        // H = AABBB, I = ABAAA
+        assert_eq!(string, "In 2024 we m337 at th3 old harbour, then we go to the east gate together.");
+        let revealed = s.reveal(&disguised, &codec).unwrap();
+        assert!(String::from_iter(revealed.iter()).starts_with("HI"));
+        assert_eq!(s.strip(&disguised).unwrap(), Vec::from_iter(public.chars()));
+    }
+
+    #[test]
+    fn only_eligible_words_carry_elements() {
+        let s = LeetSteganographer::default();
+        // `2024` has no letters, `toast` and `7045` only letters with a leet form
+        let input = Vec::from_iter("2024 toast 7045 h3llo W0rld".chars());
+        let slots: Vec<(usize, SegmentKind)> = s.mapping(&input).unwrap().slots().iter()
+            .map(|slot| (slot.position(), slot.kind()))
+            .collect();
+        assert_eq!(slots, vec![(17, SegmentKind::B), (20, SegmentKind::A), (23, SegmentKind::B)]);
+        assert_eq!(s.capacity(&input).unwrap(), 3);
+    }
+
+    #[test]
+    fn disguise_keeps_the_case_of_the_letters() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LeetSteganographer::new(&[('e', '3'), ('a', '@')]).unwrap();
+        // A = AAAAA
+        let disguised = s.disguise(&['A'], &Vec::from_iter("PEAR h3r3 bear".chars()), &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "PEAR here bear");
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LeetSteganographer::default();
+        let output = s.disguise(&['H', 'i'], &Vec::from_iter("Hello world".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 10, available: 3, unit: CapacityUnit::Letters }));
+    }
+}
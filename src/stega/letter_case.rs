@@ -11,78 +11,497 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::iter;
+use std::str;
+
 use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+use crate::stega::{Padding, unit_starts, units, UnitStarts};
+use crate::wordlist::WordList;
 
-pub struct LetterCaseSteganographer {}
+/// Applies steganography based on the case of the characters.
+///
+/// Lowercase characters carry the Bacon's element A and capital characters carry the Bacon's element B.
+///
+/// Only characters with a symmetric, single character case mapping are used as carriers (e.g. `'é'` is a carrier,
+/// while `'ß'` is not, since its uppercase form is `"SS"`). This keeps disguise and reveal in sync for non-ASCII public texts.
+/// The characters of caseless scripts, like `'你'` or `'م'`, are never carriers. The titlecase characters, like `'ǅ'`,
+/// are treated according to the [TitlecasePolicy](enum.TitlecasePolicy.html).
+///
+/// The carriers that follow the secret are left as they are, unless a [Padding](../enum.Padding.html) is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterCaseSteganographer {
+    ascii_only: bool,
+    padding: Padding,
+    titlecase: TitlecasePolicy,
+}
 
 impl LetterCaseSteganographer {
     pub fn new() -> LetterCaseSteganographer {
-        LetterCaseSteganographer {}
+        LetterCaseSteganographer {
+            ascii_only: false,
+            padding: Padding::None,
+            titlecase: TitlecasePolicy::Skip,
+        }
+    }
+
+    /// Creates a [LetterCaseSteganographerBuilder](struct.LetterCaseSteganographerBuilder.html).
+    pub fn builder() -> LetterCaseSteganographerBuilder {
+        LetterCaseSteganographerBuilder::default()
+    }
+
+    /// Restricts the carrier characters to the ASCII letters.
+    pub fn ascii_only(mut self) -> Self {
+        self.set_ascii_only(true);
+        self
+    }
+
+    pub fn set_ascii_only(&mut self, b: bool) {
+        self.ascii_only = b;
+    }
+
+    /// Fills the carriers that follow the secret with the padding, which the reveal removes.
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.set_padding(padding);
+        self
+    }
+
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.padding = padding;
+    }
+
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Sets how the titlecase characters, like `'ǅ'`, are treated. The default is
+    /// [TitlecasePolicy::Skip](enum.TitlecasePolicy.html#variant.Skip).
+    pub fn with_titlecase_policy(mut self, titlecase: TitlecasePolicy) -> Self {
+        self.set_titlecase_policy(titlecase);
+        self
+    }
+
+    pub fn set_titlecase_policy(&mut self, titlecase: TitlecasePolicy) {
+        self.titlecase = titlecase;
+    }
+
+    pub fn titlecase_policy(&self) -> TitlecasePolicy {
+        self.titlecase
+    }
+
+    /// Reveals the secret of UTF-8 encoded input, like [reveal](#method.reveal), without collecting its characters.
+    ///
+    /// The ASCII bytes are classified one by one and only the runs of non-ASCII characters are classified with the
+    /// Unicode case rules, which makes the reveal of mostly ASCII inputs, like web pages and logs, much faster.
+    pub fn reveal_bytes<AB>(&self, input: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        enter_span!("reveal_bytes", steganographer = "letter-case");
+        let input = str::from_utf8(input)?;
+        let bytes = input.as_bytes();
+        let mut groups = Groups::new(codec.encoded_group_size());
+        let mut position = 0;
+        while position < bytes.len() {
+            let non_ascii = bytes[position..].iter().position(|b| !b.is_ascii()).map_or(bytes.len(), |offset| position + offset);
+            groups.extend(bytes[position..non_ascii].iter()
+                .filter(|b| b.is_ascii_alphabetic())
+                .map(|b| b.is_ascii_lowercase()));
+            // A run of non-ASCII characters ends at an ASCII byte, which is always a character boundary
+            let ascii = bytes[non_ascii..].iter().position(|b| b.is_ascii()).map_or(bytes.len(), |offset| non_ascii + offset);
+            if !self.ascii_only && non_ascii < ascii {
+                let run: Vec<char> = input[non_ascii..ascii].chars().collect();
+                groups.extend(units(&run).iter()
+                    .map(|unit| &unit[0])
+                    .filter(|c| self.is_carrier(c))
+                    .map(|c| self.carries_a(c)));
+            }
+            position = ascii;
+        }
+        Ok(self.padding.unpad(&groups, codec))
+    }
+
+    /// Reveals the secret of a string lazily, like [reveal](#method.reveal), without any intermediate buffer.
+    ///
+    /// Every character is decoded from the bit pattern of its group as soon as the group is read, so that scanners can
+    /// stop early or process the secret as it is found. The padding is not removed, since it is found only at the end.
+    ///
+    /// ```rust
+    /// use bacon_cipher::codecs::char_codec::CharCodec;
+    /// use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+    ///
+    /// let s = LetterCaseSteganographer::new();
+    /// let codec = CharCodec::new('a', 'b');
+    /// let revealed: String = s.reveal_lazy("thIS IsA public message", &codec).take(2).collect();
+    /// assert_eq!(revealed, "HI");
+    /// ```
+    pub fn reveal_lazy<'a, AB>(&'a self, input: &'a str, codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> LazyReveal<'a, AB> {
+        LazyReveal { steganographer: self, units: unit_starts(input), codec, finished: false }
+    }
+
+    /// Reveals the secret of an input whose case may have been changed at some positions after the disguise, like the
+    /// first letters of the sentences that many platforms capitalize.
+    ///
+    /// The carriers at the [Erasures](enum.Erasures.html) are not trusted. A group with erased elements is decoded only
+    /// if all the valid groups that it could be decode to the same character, otherwise it is `None`, so that an error
+    /// correcting layer can recover it.
+    ///
+    /// ```rust
+    /// use bacon_cipher::codecs::char_codec::CharCodec;
+    /// use bacon_cipher::stega::letter_case::{Erasures, LetterCaseSteganographer};
+    ///
+    /// let s = LetterCaseSteganographer::new();
+    /// let codec = CharCodec::new('a', 'b');
+    /// // N = ABBAA, whose first element is lost when the sentence is capitalized
+    /// let revealed = s.reveal_with_erasures(&"HELlo world".chars().collect::<Vec<char>>(), &Erasures::SentenceStarts, &codec).unwrap();
+    /// assert_eq!(revealed[0], Some('N'));
+    /// ```
+    pub fn reveal_with_erasures<AB>(&self, input: &[char], erasures: &Erasures, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<Option<char>>> {
+        let mut elements = Vec::new();
+        let mut position = 0;
+        let mut sentence_start = true;
+        for unit in units(input) {
+            let c = unit[0];
+            if self.is_carrier(&c) {
+                let erased = match erasures {
+                    Erasures::SentenceStarts => sentence_start,
+                    Erasures::Positions(positions) => positions.contains(&position),
+                };
+                elements.push(if erased { None } else { Some(self.carries_a(&c)) });
+            }
+            if c.is_alphabetic() {
+                sentence_start = false;
+            } else if c == '.' || c == '!' || c == '?' {
+                sentence_start = true;
+            }
+            position += unit.len();
+        }
+        let group_size = codec.encoded_group_size();
+        Ok(elements.chunks(group_size)
+            .map(|group| {
+                if group.len() < group_size {
+                    group.iter().cloned().collect::<Option<Vec<bool>>>()
+                        .and_then(|group| codec.decode_groups(&Groups::from_elements(group, group_size)).pop())
+                } else {
+                    decode_with_erasures(group, codec)
+                }
+            })
+            .collect())
+    }
+
+    /// Restores the natural case of a disguised input, like [strip](../../trait.Steganographer.html#method.strip), using
+    /// the case of the words of a list, like proper names and acronyms.
+    ///
+    /// The words of the list are matched ignoring their case. The rest of the words are lowercase, except for the first
+    /// word of every sentence and the pronoun `I`, which are capitalized.
+    ///
+    /// ```rust
+    /// use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+    /// use bacon_cipher::wordlist::WordList;
+    ///
+    /// let s = LetterCaseSteganographer::new();
+    /// let input: Vec<char> = "tHiS iS lOnDON. i LIKE nasa".chars().collect();
+    /// let stripped = s.strip_with_words(&input, &WordList::new(&["London", "NASA"]));
+    /// assert_eq!(stripped.iter().collect::<String>(), "This is London. I like NASA");
+    /// ```
+    pub fn strip_with_words(&self, input: &[char], words: &WordList) -> Vec<char> {
+        let units = units(input);
+        let mut stripped = Vec::with_capacity(input.len());
+        let mut sentence_start = true;
+        let mut start = 0;
+        while start < units.len() {
+            let c = units[start][0];
+            if !c.is_alphabetic() {
+                if c == '.' || c == '!' || c == '?' {
+                    sentence_start = true;
+                }
+                stripped.extend_from_slice(units[start]);
+                start += 1;
+                continue;
+            }
+            let end = (start..units.len()).find(|unit| !units[*unit][0].is_alphabetic()).unwrap_or(units.len());
+            let word: String = units[start..end].iter().map(|unit| unit[0]).collect();
+            let listed: Option<Vec<char>> = words.words().iter()
+                .find(|listed| listed.to_lowercase() == word.to_lowercase())
+                .map(|listed| listed.chars().collect());
+            let pronoun = word == "i" || word == "I";
+            for (offset, unit) in units[start..end].iter().enumerate() {
+                let c = unit[0];
+                let upper = match listed.as_ref().and_then(|listed| listed.get(offset)) {
+                    Some(listed) => listed.is_uppercase(),
+                    None => offset == 0 && (sentence_start || pronoun),
+                };
+                if !self.is_carrier(&c) {
+                    stripped.push(c);
+                } else if upper {
+                    stripped.extend(c.to_uppercase());
+                } else {
+                    stripped.extend(c.to_lowercase());
+                }
+                stripped.extend_from_slice(&unit[1..]);
+            }
+            sentence_start = false;
+            start = end;
+        }
+        stripped
+    }
+
+    /// Returns true if the character can carry a Bacon's element.
+    pub fn is_carrier(&self, c: &char) -> bool {
+        if self.ascii_only {
+            c.is_ascii_alphabetic()
+        } else {
+            has_symmetric_case(c) && (self.titlecase != TitlecasePolicy::Skip || !is_titlecase(c))
+        }
     }
+
+    // Writes the units to the sink, with the case of every carrier set to the element that `next_element` returns for
+    // it. The carriers for which it returns `None` are written as they are.
+    pub(crate) fn write_units<F, E>(&self, units: &[&[char]], mut next_element: F, sink: &mut E)
+        where F: FnMut() -> Option<bool>, E: Extend<char> {
+        for unit in units {
+            // Only the base character of a unit changes case. Any combining marks follow it unchanged.
+            let pc = &unit[0];
+            if self.is_carrier(pc) {
+                match next_element() {
+                    Some(true) => sink.extend(pc.to_lowercase()),
+                    Some(false) => sink.extend(pc.to_uppercase()),
+                    None => sink.extend(Some(*pc)),
+                }
+            } else {
+                sink.extend(Some(*pc))
+            }
+            sink.extend(unit[1..].iter().cloned());
+        }
+    }
+
+    // Returns true if the carrier carries the Bacon's element A. The disguise writes only lowercase and uppercase
+    // carriers, so a titlecase carrier is one that was left as it is in the public input.
+    fn carries_a(&self, c: &char) -> bool {
+        if is_titlecase(c) {
+            self.titlecase != TitlecasePolicy::AsUppercase
+        } else {
+            !c.is_uppercase()
+        }
+    }
+}
+
+/// Defines how the [LetterCaseSteganographer](struct.LetterCaseSteganographer.html) treats the titlecase characters,
+/// like `'ǅ'`, which are neither lowercase nor uppercase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TitlecasePolicy {
+    /// The titlecase characters are not carriers, like the characters of caseless scripts. Disguise leaves them as
+    /// they are and reveal ignores them.
+    #[default]
+    Skip,
+    /// The titlecase characters are carriers, which the disguise turns to lowercase or uppercase. The titlecase
+    /// characters that are left as they are, like the ones that follow the secret, are revealed as lowercase.
+    AsLowercase,
+    /// The titlecase characters are carriers, which the disguise turns to lowercase or uppercase. The titlecase
+    /// characters that are left as they are, like the ones that follow the secret, are revealed as uppercase.
+    AsUppercase,
+}
+
+/// The carriers of a disguised input whose case is not trusted by
+/// [reveal_with_erasures](struct.LetterCaseSteganographer.html#method.reveal_with_erasures).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Erasures {
+    /// The first letter of the input and the first letter after every `.`, `!` or `?`.
+    SentenceStarts,
+    /// The characters at the positions of the input.
+    Positions(Vec<usize>),
+}
+
+// Decodes a complete group whose missing elements are None, if all the valid groups that it could be decode to the
+// same character. A group is valid if the codec encodes its decoding back to it.
+fn decode_with_erasures<AB>(group: &[Option<bool>], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> Option<char> {
+    let erased: Vec<usize> = (0..group.len()).filter(|index| group[*index].is_none()).collect();
+    let known = group.iter().fold(0u8, |pattern, elem| pattern << 1 | (*elem == Some(false)) as u8);
+    let mut decoded = None;
+    for fill in 0..1u8 << erased.len() {
+        let pattern = erased.iter().enumerate()
+            .filter(|(bit, _)| fill >> bit & 1 == 1)
+            .fold(known, |pattern, (_, index)| pattern | 1 << (group.len() - 1 - index));
+        let c = codec.decode_pattern(pattern);
+        let encoded = codec.encode_elem(&c);
+        let valid = Groups::from_encoded(&encoded, codec).complete() == [pattern];
+        match decoded {
+            _ if !valid => {}
+            None => decoded = Some(c),
+            Some(previous) if previous != c => return None,
+            _ => {}
+        }
+    }
+    decoded
+}
+
+impl Default for LetterCaseSteganographer {
+    fn default() -> LetterCaseSteganographer {
+        LetterCaseSteganographer::new()
+    }
+}
+
+/// Builds a [LetterCaseSteganographer](struct.LetterCaseSteganographer.html).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LetterCaseSteganographerBuilder {
+    ascii_only: bool,
+    padding: Padding,
+    titlecase: TitlecasePolicy,
+}
+
+impl LetterCaseSteganographerBuilder {
+    /// Restricts the carrier characters to the ASCII letters.
+    pub fn with_ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// Fills the carriers that follow the secret with the padding.
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets how the titlecase characters are treated.
+    pub fn with_titlecase_policy(mut self, titlecase: TitlecasePolicy) -> Self {
+        self.titlecase = titlecase;
+        self
+    }
+
+    pub fn build(self) -> errors::Result<LetterCaseSteganographer> {
+        let mut steganographer = LetterCaseSteganographer::new();
+        steganographer.set_ascii_only(self.ascii_only);
+        steganographer.set_padding(self.padding);
+        steganographer.set_titlecase_policy(self.titlecase);
+        Ok(steganographer)
+    }
+}
+
+// A character has a symmetric case when both its lowercase and uppercase mappings are single characters
+// that differ from each other. Transforming such a character does not change the length of the text
+// and the transformed character is classified back correctly during reveal.
+fn has_symmetric_case(c: &char) -> bool {
+    let mut lower = c.to_lowercase();
+    let mut upper = c.to_uppercase();
+    match (lower.next(), lower.next(), upper.next(), upper.next()) {
+        (Some(l), None, Some(u), None) => l != u && l.is_lowercase() && u.is_uppercase(),
+        _ => false,
+    }
+}
+
+// A titlecase character, like 'ǅ', has a case, but it is neither lowercase nor uppercase.
+fn is_titlecase(c: &char) -> bool {
+    !c.is_lowercase() && !c.is_uppercase() &&
+        c.to_lowercase().ne(iter::once(*c)) && c.to_uppercase().ne(iter::once(*c))
 }
 
 impl Steganographer for LetterCaseSteganographer {
     type T = char;
 
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(units(public).iter()
+            .filter(|unit| self.is_carrier(&unit[0]))
+            .count())
+    }
+
     fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
-        let available_size = public.iter()
-            .filter(|pc| pc.is_alphabetic())
-            .count();
+        let mut disguised = Vec::with_capacity(public.len());
+        self.disguise_to(secret, public, &mut disguised, codec)?;
+        Ok(disguised)
+    }
+
+    fn disguise_to<E, AB>(&self, secret: &[char], public: &[char], sink: &mut E, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<()>
+        where E: Extend<char> {
+        enter_span!("disguise", steganographer = "letter-case");
+        let public_units = units(public);
+        let available_size = self.capacity(public)?;
         let secret_size = secret.iter()
             .filter(|pc| pc.is_alphabetic())
             .count();
 
-        if secret.iter()
-            .filter(|s| !s.is_alphabetic() && s != &&' ')
-            .count() > 0 {
-            Err(errors::BaconError::SteganographerError(
-                format!("The secret can contain only alphabetic characters. This is an invalid secret")))
-        } else if available_size < secret_size * codec.encoded_group_size() {
-            Err(errors::BaconError::SteganographerError(
-                format!("The public input should have at least size {}. It was found to have {}",
-                        secret_size * codec.encoded_group_size(),
-                        available_size)))
+        BaconError::check_secret(secret)?;
+        debug_event!(required = secret_size * codec.encoded_group_size(), available = available_size, "letter carriers");
+        if available_size < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: available_size,
+                unit: CapacityUnit::Letters,
+            })
         } else {
-            let encoded = codec.encode(secret);
+            let mut encoded = codec.encode_groups(secret);
+            self.padding.pad(&mut encoded, available_size, codec);
 
-            let mut disguised: Vec<char> = Vec::new();
             let mut i = 0;
+            self.write_units(&public_units, || {
+                i += 1;
+                encoded.is_a(i - 1)
+            }, sink);
 
-            for pc in public {
-                if pc.is_alphabetic() {
-                    let opt = encoded.get(i);
-                    if opt.is_some() && codec.is_a(opt.unwrap()) {
-                        let mut tmp: Vec<char> = pc.clone().to_lowercase().collect();
-                        disguised.append(&mut tmp);
-                        i = i + 1;
-                    } else if opt.is_some() && codec.is_b(opt.unwrap()) {
-                        let mut tmp: Vec<char> = pc.clone().to_uppercase().collect();
-                        disguised.append(&mut tmp);
-                        i = i + 1;
-                    } else {
-                        disguised.push(pc.clone())
-                    }
-                } else {
-                    disguised.push(pc.clone())
-                }
-            }
-
-            Ok(disguised)
+            Ok(())
         }
     }
 
     fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
-        let encoded: Vec<AB> = input.iter()
-            .filter(|elem| elem.is_alphabetic())
-            .map(|elem| {
-                if elem.is_uppercase() {
-                    codec.b()
-                } else {
-                    codec.a()
-                }
-            })
-            .collect();
-        Ok(codec.decode(&encoded))
+        enter_span!("reveal", steganographer = "letter-case");
+        let encoded = Groups::from_elements(units(input).iter()
+            .map(|unit| &unit[0])
+            .filter(|elem| self.is_carrier(elem))
+            .map(|elem| self.carries_a(elem)), codec.encoded_group_size());
+        Ok(self.padding.unpad(&encoded, codec))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        Ok(self.strip_with_words(input, &WordList::default()))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        let mut slots = Vec::new();
+        let mut position = 0;
+        for unit in units(input) {
+            if self.is_carrier(&unit[0]) {
+                slots.push(Slot::new(position, if self.carries_a(&unit[0]) { SegmentKind::A } else { SegmentKind::B }));
+            }
+            position += unit.len();
+        }
+        Ok(Mapping::new(slots))
+    }
+}
+
+/// The iterator of [LetterCaseSteganographer::reveal_lazy](struct.LetterCaseSteganographer.html#method.reveal_lazy).
+///
+/// An incomplete group at the end of the input is decoded like [reveal](trait.Steganographer.html#tymethod.reveal) does.
+pub struct LazyReveal<'a, AB> {
+    steganographer: &'a LetterCaseSteganographer,
+    units: UnitStarts<'a>,
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>,
+    finished: bool,
+}
+
+impl<'a, AB> Iterator for LazyReveal<'a, AB> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.finished {
+            return None;
+        }
+        let group_size = self.codec.encoded_group_size();
+        let mut pattern = 0u8;
+        let mut len = 0;
+        let steganographer = self.steganographer;
+        for c in self.units.by_ref().filter(|c| steganographer.is_carrier(c)) {
+            pattern = pattern << 1 | !steganographer.carries_a(&c) as u8;
+            len += 1;
+            if len == group_size {
+                return Some(self.codec.decode_pattern(pattern));
+            }
+        }
+        self.finished = true;
+        if len == 0 {
+            None
+        } else {
+            let group = Groups::from_elements((0..len).rev().map(|bit| pattern >> bit & 1 == 0), group_size);
+            self.codec.decode_groups(&group).pop()
+        }
     }
 }
 
@@ -90,10 +509,72 @@ impl Steganographer for LetterCaseSteganographer {
 mod letter_case_tests {
     use std::iter::FromIterator;
 
+    use crate::carrier::Carrier;
     use crate::codecs::char_codec::CharCodec;
 
     use super::*;
 
+    #[test]
+    fn build_a_steganographer() {
+        let s = LetterCaseSteganographer::builder().with_ascii_only(true).build().unwrap();
+        assert!(!s.is_carrier(&'é'));
+        assert!(LetterCaseSteganographer::builder().build().unwrap().is_carrier(&'é'));
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_padding() {
+        let codec = CharCodec::new('a', 'b');
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let s = LetterCaseSteganographer::new().with_padding(Padding::Repeat);
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA, followed by the end of the secret, BBBBB
+        assert_eq!(String::from_iter(&disguised[..20]), "thIS Is A pubLIC MEs");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['H', 'I']);
+        let disguised: String = disguised.into_iter().collect();
+        assert_eq!(s.reveal_bytes(disguised.as_bytes(), &codec).unwrap(), vec!['H', 'I']);
+        for padding in [Padding::RandomLetters(7), Padding::Nulls] {
+            let s = LetterCaseSteganographer::builder().with_padding(padding).build().unwrap();
+            let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+            assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['H', 'I'], "{:?}", padding);
+            assert_ne!(disguised, LetterCaseSteganographer::new().disguise(&['H', 'I'], &public, &codec).unwrap());
+        }
+        let s = LetterCaseSteganographer::new().with_padding(Padding::Repeat);
+        assert_eq!(s.read_symbols(&Vec::from_iter("hEllo".chars())).unwrap(), vec![true, false, true, true, true]);
+    }
+
+    #[test]
+    fn strip_the_letter_case() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = Vec::from_iter("This is a public message. Is it? I think it is!".chars());
+        let disguised = s.disguise(&['H', 'I', 'Z'], &public, &codec).unwrap();
+        assert_ne!(disguised, public);
+        assert_eq!(s.strip(&disguised).unwrap(), public);
+        let words = WordList::new(&["McDonald"]);
+        assert_eq!(String::from_iter(s.strip_with_words(&Vec::from_iter("at mcdONALD's".chars()), &words)), "At McDonald's");
+    }
+
+    #[test]
+    fn reveal_with_erasures() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = Vec::from_iter("hello world. lovely days here".chars());
+        // N = ABBAA, O = ABBAB, R = BAAAA, with the first element of N and R at the start of a sentence
+        let disguised = s.disguise(&['N', 'O', 'R'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(&disguised), "hELlo wORlD. Lovely days here");
+        let capitalized = Vec::from_iter("HELlo wORlD. Lovely days here".chars());
+        assert_ne!(s.reveal(&capitalized, &codec).unwrap()[0], 'N');
+        // R could be A as well
+        let revealed = s.reveal_with_erasures(&capitalized, &Erasures::SentenceStarts, &codec).unwrap();
+        assert_eq!(revealed[..3], [Some('N'), Some('O'), None]);
+        let revealed = s.reveal_with_erasures(&capitalized, &Erasures::Positions(vec![0, 7]), &codec).unwrap();
+        // O could be F as well, while R is not erased
+        assert_eq!(revealed[..3], [Some('N'), None, Some('R')]);
+        // The incomplete group at the end
+        assert_eq!(revealed.last(), Some(&Some(' ')));
+        assert_eq!(s.reveal_with_erasures(&capitalized, &Erasures::Positions(vec![26]), &codec).unwrap().last(), Some(&None));
+    }
+
     #[test]
     fn disguise_fails_because_of_public_message_length() {
         let codec = CharCodec::new('a', 'b');
@@ -102,7 +583,7 @@ mod letter_case_tests {
             &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
             &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
             &codec);
-        assert!(output.is_err())
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 40, available: 8, unit: CapacityUnit::Letters }));
     }
 
     #[test]
@@ -114,7 +595,7 @@ mod letter_case_tests {
             &['M', 'y', '1', 's', 'e', 'c', 'r', 'e', 't'],
             &Vec::from_iter(public.chars()),
             &codec);
-        assert!(output.is_err())
+        assert_eq!(output, Err(BaconError::InvalidSecret { character: '1', position: 2 }));
     }
 
     #[test]
@@ -142,4 +623,146 @@ mod letter_case_tests {
         let string = String::from_iter(output.unwrap().iter());
         assert!(string.starts_with("MYSECRET"));
     }
+
+    #[test]
+    fn disguise_and_reveal_strings() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = "This is a public message that contains a secret one";
+        let disguised = s.disguise_str("My secret", public, &codec).unwrap();
+        assert_eq!(disguised, "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+        assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+        let disguised = s.disguise_iter("My secret".chars().filter(|c| *c != ' '), public.chars(), &codec).unwrap();
+        assert_eq!(s.reveal_iter(disguised.into_iter(), &codec).unwrap()[..8], ['M', 'Y', 'S', 'E', 'C', 'R', 'E', 'T']);
+        assert!(s.disguise_str("My secret", "Too short", &codec).is_err());
+    }
+
+    #[test]
+    fn disguise_to_a_sink() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let mut disguised = String::from("> ");
+        s.disguise_to(&['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'], &public, &mut disguised, &codec).unwrap();
+        assert_eq!(disguised, "> tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+        assert!(s.disguise_to(&['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'], &public[..9], &mut disguised, &codec).is_err());
+        assert_eq!(disguised.len(), 53);
+        let disguised: String = s.disguise_as(&['H', 'I'], &public, &codec).unwrap();
+        assert!(disguised.starts_with("thIS Is A "));
+    }
+
+    #[test]
+    fn reveal_bytes() {
+        let codec = CharCodec::new('a', 'b');
+        let public = "Ça été une journée très élégante à Zürich, où ßtraße n'est pas une rue mais un séjour prévu";
+        for s in [LetterCaseSteganographer::new(), LetterCaseSteganographer::new().ascii_only()] {
+            let disguised = s.disguise_str("My secret", public, &codec).unwrap();
+            assert_eq!(s.reveal_bytes(disguised.as_bytes(), &codec).unwrap(), s.reveal_str(&disguised, &codec).unwrap().chars().collect::<Vec<char>>());
+        }
+        assert!(LetterCaseSteganographer::new().reveal_bytes(&[b'H', 0xff, b'i'], &codec).is_err());
+    }
+
+    #[test]
+    fn reveal_lazily() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let disguised = s.disguise_str("My secret", "Ça été une journée très élégante à Zürich, où ßtraße n'est pas une rue", &codec).unwrap();
+        assert_eq!(s.reveal_lazy(&disguised, &codec).collect::<String>(), s.reveal_str(&disguised, &codec).unwrap());
+        assert_eq!(s.reveal_lazy("", &codec).next(), None);
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_accented_public_text() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = "Ça été une journée très élégante à Zürich, où ßtraße n'est pas une rue mais un séjour prévu";
+        let output = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let disguised = output.unwrap();
+        assert_eq!(disguised.len(), public.chars().count());
+        assert!(disguised.contains(&'ß'));
+        let revealed = s.reveal(&disguised, &codec);
+        let string = String::from_iter(revealed.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn ascii_only_carriers() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new().ascii_only();
+        assert!(s.is_carrier(&'a'));
+        assert!(!s.is_carrier(&'é'));
+        let public = "éééé This is a public message that contains a secret one";
+        let output = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string == "éééé tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+    }
+
+    #[test]
+    fn characters_without_symmetric_case_are_not_carriers() {
+        let s = LetterCaseSteganographer::new();
+        assert!(s.is_carrier(&'é'));
+        assert!(s.is_carrier(&'Ω'));
+        assert!(!s.is_carrier(&'ß'));
+        assert!(!s.is_carrier(&'İ'));
+        assert!(!s.is_carrier(&'中'));
+        assert!(!s.is_carrier(&'1'));
+    }
+
+    #[test]
+    fn titlecase_policies() {
+        let codec = CharCodec::new('a', 'b');
+        // H = AABBB, Z = BABBB
+        let s = LetterCaseSteganographer::new();
+        assert_eq!(s.titlecase_policy(), TitlecasePolicy::Skip);
+        assert!(!s.is_carrier(&'ǅ'));
+        assert!(s.is_carrier(&'ǆ'));
+        assert!(s.is_carrier(&'Ǆ'));
+        assert_eq!(s.reveal_str("ǅaaBBB", &codec).unwrap(), "H");
+
+        let s = LetterCaseSteganographer::builder().with_titlecase_policy(TitlecasePolicy::AsLowercase).build().unwrap();
+        assert!(s.is_carrier(&'ǅ'));
+        assert_eq!(s.reveal_str("ǅaBBB", &codec).unwrap(), "H");
+        assert_eq!(s.reveal_bytes("ǅaBBB".as_bytes(), &codec).unwrap(), vec!['H']);
+        assert_eq!(s.mapping(&Vec::from_iter("ǅaBBB".chars())).unwrap().slots()[0].kind(), SegmentKind::A);
+
+        let s = s.with_titlecase_policy(TitlecasePolicy::AsUppercase);
+        assert_eq!(s.reveal_str("ǅaBBB", &codec).unwrap(), "Z");
+        assert_eq!(s.reveal_lazy("ǅaBBB", &codec).collect::<String>(), "Z");
+        assert_eq!(s.mapping(&Vec::from_iter("ǅaBBB".chars())).unwrap().slots()[0].kind(), SegmentKind::B);
+    }
+
+    #[test]
+    fn disguise_and_reveal_mixed_script_public_text() {
+        let codec = CharCodec::new('a', 'b');
+        let public = "Ǆemal i ǅuro pišu 你好 and Ljudmila says Привет мир, γειά σου κόσμε, مرحبا بالعالم, שלום עולם, こんにちは世界 ǈubav";
+        let public_chars = Vec::from_iter(public.chars());
+        let skip = LetterCaseSteganographer::new();
+        for policy in [TitlecasePolicy::Skip, TitlecasePolicy::AsLowercase, TitlecasePolicy::AsUppercase].iter() {
+            let s = LetterCaseSteganographer::new().with_titlecase_policy(*policy);
+            let disguised = s.disguise(&Vec::from_iter("My secret".chars()), &public_chars, &codec).unwrap();
+            assert_eq!(disguised.len(), public_chars.len());
+            // The caseless characters are never changed
+            for (p, d) in public_chars.iter().zip(disguised.iter()) {
+                if !s.is_carrier(p) {
+                    assert_eq!(p, d);
+                }
+            }
+            let revealed = s.reveal(&disguised, &codec).unwrap();
+            assert!(String::from_iter(revealed.iter()).starts_with("MYSECRET"), "{:?}", policy);
+            let disguised_string = String::from_iter(disguised.iter());
+            assert_eq!(s.reveal_bytes(disguised_string.as_bytes(), &codec).unwrap(), revealed);
+            assert_eq!(s.reveal_lazy(&disguised_string, &codec).collect::<Vec<char>>(), revealed);
+            assert_eq!(s.mapping(&disguised).unwrap().slots().len(), Steganographer::capacity(&s, &disguised).unwrap());
+            // The titlecase characters of the secret are turned to lowercase or uppercase
+            assert_eq!(disguised.contains(&'ǅ'), *policy == TitlecasePolicy::Skip);
+        }
+        let titlecase = LetterCaseSteganographer::new().with_titlecase_policy(TitlecasePolicy::AsLowercase);
+        assert_eq!(Steganographer::capacity(&titlecase, &public_chars).unwrap(), Steganographer::capacity(&skip, &public_chars).unwrap() + 2);
+    }
 }
@@ -0,0 +1,87 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::carrier::Carrier;
+use crate::errors;
+
+/// Applies steganography based on the least significant bit of every byte of a binary medium, like the samples of an
+/// uncompressed image or audio file.
+///
+/// A byte with an even value carries the Bacon's element A and a byte with an odd value carries the Bacon's element B,
+/// so that every byte changes by at most one. The bytes before the offset, like the header of the file, are not altered.
+///
+/// It is a [Carrier](../../carrier/trait.Carrier.html) of bytes, which disguises and reveals secrets as a
+/// [ByteSteganographer](../../binary/trait.ByteSteganographer.html).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsbSteganographer {
+    offset: usize,
+}
+
+impl LsbSteganographer {
+    /// Creates a `LsbSteganographer` that uses all the bytes of the medium.
+    pub fn new() -> LsbSteganographer {
+        LsbSteganographer { offset: 0 }
+    }
+
+    /// Skips the first `offset` bytes of the medium, like the header of a file.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.set_offset(offset);
+        self
+    }
+
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Carrier for LsbSteganographer {
+    type Unit = u8;
+
+    fn capacity(&self, public: &[u8]) -> errors::Result<usize> {
+        Ok(public.len().saturating_sub(self.offset))
+    }
+
+    fn write_symbols(&self, symbols: &[bool], public: &[u8]) -> errors::Result<Vec<u8>> {
+        let mut written = public.to_vec();
+        for (byte, is_a) in written.iter_mut().skip(self.offset).zip(symbols) {
+            *byte = if *is_a { *byte & !1 } else { *byte | 1 };
+        }
+        Ok(written)
+    }
+
+    fn read_symbols(&self, input: &[u8]) -> errors::Result<Vec<bool>> {
+        Ok(input.iter().skip(self.offset).map(|byte| byte & 1 == 0).collect())
+    }
+}
+
+#[cfg(test)]
+mod lsb_tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_symbols() {
+        let s = LsbSteganographer::new().with_offset(2);
+        let public = vec![1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(s.capacity(&public).unwrap(), 5);
+        assert_eq!(s.capacity(&public[..1]).unwrap(), 0);
+        // H = AABBB
+        let written = s.write_symbols(&[true, true, false, false, false], &public).unwrap();
+        assert_eq!(written, vec![1, 2, 2, 4, 5, 7, 7]);
+        assert_eq!(s.read_symbols(&written).unwrap(), vec![true, true, false, false, false]);
+        assert_eq!(s.write_symbols(&[false], &public).unwrap(), vec![1, 2, 3, 4, 5, 6, 7]);
+    }
+}
@@ -13,14 +13,30 @@
 // limitations under the License.
 
 use std::iter::FromIterator;
+use std::ops::Range;
 
-use crate::{BaconCodec, errors, Steganographer};
-use crate::errors::BaconError;
+#[cfg(feature = "fast-marker-search")]
+use aho_corasick::AhoCorasick;
 
+use crate::{BaconCodec, carrier, errors, Steganographer};
+use crate::carrier::{Segment, SegmentKind};
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::Mapping;
+use crate::stega::{percent_encoding, units, Wrapping};
+
+/// The placeholder for the marked characters in a [Marker template](struct.Marker.html#method.template).
+pub const PLACEHOLDER: &str = "{}";
+
+/// The start and end markers that surround the marked characters.
+///
+/// The markers may be symmetric, like `*` and `*`, or asymmetric, like `<sub>` and `</sub>`, `{` and `}` or `((` and `))`.
+/// Pairs of an asymmetric marker may be nested in the input, like `{a{b}c}`, in which case the outer pair defines the segment.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Marker {
     start_marker: Option<String>,
     end_marker: Option<String>,
+    // The end marker contains placeholders
+    template: bool,
 }
 
 impl Marker {
@@ -28,13 +44,54 @@ impl Marker {
         Marker {
             start_marker: start_marker.map(|marker| marker.to_string()),
             end_marker: end_marker.map(|marker| marker.to_string()),
+            template: false,
         }
     }
 
+    /// Creates a `Marker` from a template that contains the [PLACEHOLDER](constant.PLACEHOLDER.html) `{}` for the
+    /// marked characters. E.g. `[{}](https://example.com)`.
+    ///
+    /// The part of the template before the first placeholder is the start marker and should not be empty.
+    /// The rest of the template is the end marker, in which any other placeholders are substituted with the marked characters
+    /// as well. E.g. the template `[{}](#{})` marks the characters `ab` as `[ab](#ab)`. The placeholders in the destination
+    /// of a link, like `](#{})`, are substituted with the percent-encoded characters, e.g. `a b` as `[a b](#a%20b)`.
+    pub fn template(template: &str) -> errors::Result<Marker> {
+        match template.find(PLACEHOLDER) {
+            Some(0) => Err(BaconError::SteganographerError(
+                format!("The template {:?} should not start with the placeholder", template))),
+            Some(index) => {
+                let end_marker = &template[index + PLACEHOLDER.len()..];
+                Ok(Marker {
+                    start_marker: Some(template[..index].to_string()),
+                    end_marker: Some(end_marker.to_string()),
+                    template: end_marker.contains(PLACEHOLDER),
+                })
+            }
+            None => Err(BaconError::SteganographerError(
+                format!("The template {:?} does not contain the placeholder {:?}", template, PLACEHOLDER))),
+        }
+    }
+
+    /// A Markdown link to the given URL, like `[ab](https://example.com)`.
+    pub fn link(url: &str) -> Marker {
+        Marker::new(Some("["), Some(&format!("]({})", url)))
+    }
+
+    /// A Markdown image with the given URL, in which the marked characters are the alternative text, like `![ab](image.png)`.
+    pub fn image(url: &str) -> Marker {
+        Marker::new(Some("!["), Some(&format!("]({})", url)))
+    }
+
+    /// A Markdown code span, like `` `ab` ``.
+    pub fn code_span() -> Marker {
+        Marker::new(Some("`"), Some("`"))
+    }
+
     pub fn empty() -> Marker {
         Marker {
             start_marker: None,
             end_marker: None,
+            template: false,
         }
     }
 
@@ -42,6 +99,29 @@ impl Marker {
         self == &Self::empty()
     }
 
+    pub fn is_template(&self) -> bool {
+        self.template
+    }
+
+    /// Returns true if the start and the end markers are the same.
+    pub fn is_symmetric(&self) -> bool {
+        self.start_marker == self.end_marker
+    }
+
+    // Pairs of asymmetric markers that are not templates can be nested.
+    fn is_nestable(&self) -> bool {
+        !self.template && !self.is_symmetric()
+    }
+
+    // The start and end markers of a nestable marker cannot be told apart if one is a prefix of the other, like `(`
+    // and `((`.
+    fn is_ambiguous(&self) -> bool {
+        match (&self.start_marker, &self.end_marker) {
+            (Some(start), Some(end)) => self.is_nestable() && (start.starts_with(end.as_str()) || end.starts_with(start.as_str())),
+            _ => false,
+        }
+    }
+
     pub fn start_marker(&self) -> &Option<String> {
         &self.start_marker
     }
@@ -57,232 +137,577 @@ impl Marker {
     pub fn end_marker_string(&self) -> String {
         self.end_marker().clone().unwrap_or("".to_string())
     }
+
+    // The part of the end marker that does not depend on the marked characters.
+    fn end_marker_prefix(&self) -> &str {
+        let end_marker = self.end_marker().as_ref().map(|marker| marker.as_str()).unwrap_or("");
+        if self.template {
+            &end_marker[..end_marker.find(PLACEHOLDER).unwrap_or(end_marker.len())]
+        } else {
+            end_marker
+        }
+    }
+
+    // The destination of a link or an image, if the end marker is like `](https://example.com)`.
+    fn link_destination(&self) -> Option<&str> {
+        self.end_marker().as_ref()
+            .and_then(|marker| marker.strip_prefix("]("))
+            .and_then(|marker| marker.strip_suffix(')'))
+    }
+
+    // The parts of the markers that are fixed Markdown syntax. The destination of a link is not part of them.
+    fn delimiters(&self) -> Vec<&str> {
+        let mut delimiters: Vec<&str> = self.start_marker().iter().map(|marker| marker.as_str()).collect();
+        match (self.link_destination(), self.end_marker()) {
+            (Some(_), _) => delimiters.extend(["](", ")"].iter()),
+            (None, Some(end_marker)) => delimiters.push(end_marker.as_str()),
+            (None, None) => {}
+        }
+        delimiters
+    }
+
+    /// Returns the end marker for the given marked characters, substituting the placeholders of a template.
+    /// The marked characters are percent-encoded in the destination of a link.
+    pub fn end_marker_for(&self, marked: &str) -> String {
+        if !self.template {
+            self.end_marker_string()
+        } else if self.link_destination().is_some() {
+            self.end_marker_string().replace(PLACEHOLDER, &percent_encode(marked))
+        } else {
+            self.end_marker_string().replace(PLACEHOLDER, marked)
+        }
+    }
+}
+
+// Percent-encodes the characters that are not unreserved in a URL.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if percent_encoding::is_unreserved(byte as char) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
 }
 
+/// Defines how the characters of the markers that already exist in the public text are treated during disguise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "profiles", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum EscapePolicy {
+    /// The characters are escaped with a backslash, like in Markdown. Backslashes are escaped as well.
+    /// The reveal ignores the escaped markers.
+    #[default]
+    Escape,
+    /// The characters are removed from the public text.
+    Strip,
+    /// The disguise fails.
+    Error,
+}
+
+// A classified part of the input, which refers to its text by its byte range, excluding the markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Span {
+    kind: SegmentKind,
+    range: Range<usize>,
+}
+
+// The markers that may open a segment, along with the kind of the segment.
+type Candidates<'a> = Vec<(SegmentKind, &'a Marker)>;
+
+/// Builds a [MarkdownSteganographer](struct.MarkdownSteganographer.html). The markers that are not set are
+/// [empty](struct.Marker.html#method.empty) and are validated by [build](#method.build), like in
+/// [MarkdownSteganographer::new](struct.MarkdownSteganographer.html#method.new).
+///
+/// ```rust
+/// use bacon_cipher::stega::markdown::{EscapePolicy, Marker, MarkdownSteganographer};
+///
+/// let s = MarkdownSteganographer::builder()
+///     .with_b_marker(Marker::new(Some("*"), Some("*")))
+///     .with_escape_policy(EscapePolicy::Strip)
+///     .build();
+/// assert!(s.is_ok());
+/// assert!(MarkdownSteganographer::builder().build().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkdownSteganographerBuilder {
+    a_marker: Option<Marker>,
+    b_marker: Option<Marker>,
+    escape_policy: EscapePolicy,
+    wrapping: Wrapping,
+}
+
+impl MarkdownSteganographerBuilder {
+    /// Sets the marker of the Bacon's element A.
+    pub fn with_a_marker(mut self, marker: Marker) -> Self {
+        self.a_marker = Some(marker);
+        self
+    }
+
+    /// Sets the marker of the Bacon's element B.
+    pub fn with_b_marker(mut self, marker: Marker) -> Self {
+        self.b_marker = Some(marker);
+        self
+    }
+
+    /// See [MarkdownSteganographer::with_escape_policy](struct.MarkdownSteganographer.html#method.with_escape_policy).
+    pub fn with_escape_policy(mut self, escape_policy: EscapePolicy) -> Self {
+        self.escape_policy = escape_policy;
+        self
+    }
+
+    /// See [MarkdownSteganographer::with_wrapping](struct.MarkdownSteganographer.html#method.with_wrapping).
+    pub fn with_wrapping(mut self, wrapping: Wrapping) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    pub fn build(self) -> errors::Result<MarkdownSteganographer> {
+        let a_marker = self.a_marker.unwrap_or_else(Marker::empty);
+        let b_marker = self.b_marker.unwrap_or_else(Marker::empty);
+        Ok(MarkdownSteganographer::new(a_marker, b_marker)?
+            .with_escape_policy(self.escape_policy)
+            .with_wrapping(self.wrapping))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct MarkdownSteganographer {
     a_marker: Marker,
     b_marker: Marker,
+    escape_policy: EscapePolicy,
+    wrapping: Wrapping,
 }
 
 impl MarkdownSteganographer {
     pub fn new(a_marker: Marker, b_marker: Marker) -> errors::Result<MarkdownSteganographer> {
+        let markers = [&a_marker.start_marker, &a_marker.end_marker, &b_marker.start_marker, &b_marker.end_marker];
         match (&a_marker.start_marker, &a_marker.end_marker, &b_marker.start_marker, &b_marker.end_marker) {
-            (Some(asm), Some(aem), Some(bsm), Some(bem))
-            if asm.contains(bsm) || asm.contains(bem) ||
-                aem.contains(bsm) || aem.contains(bem) ||
-                bsm.contains(asm) || bsm.contains(aem) ||
-                bem.contains(asm) || bem.contains(aem) => {
-                Err(BaconError::SteganographerError(format!("Cannot create a marker with {:?} and {:?}", a_marker, b_marker)))
-            }
             (None, None, None, None) => {
-                Err(BaconError::SteganographerError(format!("Cannot create a marker with both A and B undefined")))
+                Err(BaconError::SteganographerError("Cannot create a marker with both A and B undefined".to_string()))
             }
             (Some(_), None, _, _) |
             (None, Some(_), _, _) |
             (_, _, Some(_), None) |
             (_, _, None, Some(_)) => {
-                Err(BaconError::SteganographerError(format!("A marker should define both start and end")))
+                Err(BaconError::SteganographerError("A marker should define both start and end".to_string()))
+            }
+            _ if markers.iter().any(|marker| marker.as_ref().map(|m| m.is_empty()).unwrap_or(false)) => {
+                Err(BaconError::SteganographerError("A marker cannot be an empty string".to_string()))
+            }
+            _ if a_marker.is_ambiguous() || b_marker.is_ambiguous() => {
+                Err(BaconError::SteganographerError(format!(
+                    "The start and end of an asymmetric marker cannot be prefixes of each other in {:?} and {:?}", a_marker, b_marker)))
+            }
+            // The tokenizer uses the longest start marker that matches and, for identical start markers, the end marker
+            // that is found first. So, only identical markers are ambiguous.
+            (Some(asm), Some(aem), Some(bsm), Some(bem)) if asm == bsm && aem == bem => {
+                Err(BaconError::SteganographerError(format!("Cannot create a marker with {:?} and {:?}", a_marker, b_marker)))
             }
             _ => {
                 Ok(MarkdownSteganographer {
                     a_marker,
                     b_marker,
+                    escape_policy: EscapePolicy::Escape,
+                    wrapping: Wrapping::Characters,
                 })
             }
         }
     }
 
-    fn find_first_occurence_of(&self, input_type: ParsedInputType, input: &str) -> Option<usize> {
-        match input_type {
-            ParsedInputType::A => {
-                self.a_marker.start_marker.as_ref()
-                    .and_then(|start| input.find(start.as_str()))
+    /// Creates a [MarkdownSteganographerBuilder](struct.MarkdownSteganographerBuilder.html).
+    pub fn builder() -> MarkdownSteganographerBuilder {
+        MarkdownSteganographerBuilder::default()
+    }
+
+    /// Sets how the characters of the markers that already exist in the public text are treated.
+    /// The default is [EscapePolicy::Escape](enum.EscapePolicy.html#variant.Escape).
+    pub fn with_escape_policy(mut self, escape_policy: EscapePolicy) -> Self {
+        self.set_escape_policy(escape_policy);
+        self
+    }
+
+    pub fn set_escape_policy(&mut self, escape_policy: EscapePolicy) {
+        self.escape_policy = escape_policy;
+    }
+
+    /// Sets how the markers are aligned to the words of the public text.
+    /// The default is [Wrapping::Characters](../enum.Wrapping.html#variant.Characters).
+    ///
+    /// ```rust
+    /// use bacon_cipher::codecs::char_codec::CharCodec;
+    /// use bacon_cipher::stega::markdown::{Marker, MarkdownSteganographer};
+    /// use bacon_cipher::stega::Wrapping;
+    /// use bacon_cipher::Steganographer;
+    ///
+    /// let s = MarkdownSteganographer::new(Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**")))
+    ///     .unwrap()
+    ///     .with_wrapping(Wrapping::Words);
+    /// let codec = CharCodec::new('a', 'b');
+    /// // H = AABBB
+    /// let disguised = s.disguise_str("H", "Here is my plan: I go", &codec).unwrap();
+    /// assert_eq!(disguised, "Here *is* **my** plan: **I** go");
+    /// ```
+    pub fn with_wrapping(mut self, wrapping: Wrapping) -> Self {
+        self.set_wrapping(wrapping);
+        self
+    }
+
+    pub fn set_wrapping(&mut self, wrapping: Wrapping) {
+        self.wrapping = wrapping;
+    }
+
+    // The punctuation characters of the delimiters of the markers. Letters and whitespace are not considered, as they
+    // cannot be escaped in Markdown. The destinations of links are not considered either, so that e.g. the `.` and the
+    // `/` of a URL are kept intact in the public text.
+    fn is_marker_char(&self, c: char) -> bool {
+        !c.is_alphanumeric() && !c.is_whitespace() &&
+            [&self.a_marker, &self.b_marker].iter()
+                .flat_map(|marker| marker.delimiters())
+                .any(|delimiter| delimiter.contains(c))
+    }
+
+    // Applies the escape policy to the public input.
+    fn escape(&self, public: &[char]) -> errors::Result<Vec<char>> {
+        match self.escape_policy {
+            EscapePolicy::Escape => {
+                let mut escaped = Vec::with_capacity(public.len());
+                for c in public {
+                    if *c == '\\' || self.is_marker_char(*c) {
+                        escaped.push('\\');
+                    }
+                    escaped.push(*c);
+                }
+                Ok(escaped)
             }
-            ParsedInputType::B => {
-                self.b_marker.start_marker.as_ref()
-                    .and_then(|start| input.find(start.as_str()))
+            EscapePolicy::Strip => Ok(public.iter().filter(|c| !self.is_marker_char(**c)).cloned().collect()),
+            EscapePolicy::Error => {
+                match public.iter().position(|c| self.is_marker_char(*c)) {
+                    Some(position) => Err(BaconError::MarkerInInput { character: public[position], position }),
+                    None => Ok(public.to_vec()),
+                }
             }
-            _ => None
         }
     }
 
-    fn parse(&self, input: &str) -> Vec<ParsedInputElement> {
-        let mut input = input;
-        let mut input_elements: Vec<ParsedInputElement> = Vec::new();
-
-        // Search for either a or b start marker
-        loop {
-            // Find the first occurrence in the input
-            let a_start_index = self.find_first_occurence_of(ParsedInputType::A, input).unwrap_or(input.len());
-            let b_start_index = self.find_first_occurence_of(ParsedInputType::B, input).unwrap_or(input.len());
-
-            let (start_index, parsed_input_type) = if a_start_index < b_start_index {
-                (a_start_index, ParsedInputType::A)
-            } else if b_start_index < a_start_index {
-                (b_start_index, ParsedInputType::B)
-            } else {
-                (input.len(), ParsedInputType::Other)
-            };
-
-            let start_size = match parsed_input_type {
-                ParsedInputType::A => self.a_marker.start_marker.as_ref().unwrap().len(),
-                ParsedInputType::B => self.b_marker.start_marker.as_ref().unwrap().len(),
-                _ => 0,
-            };
-            // Remove the first occurence. From now on, work with tmp
-            let tmp: &str = &input[(start_index + start_size)..input.len()];
-            let (end_opt, end_size) = match parsed_input_type {
-                ParsedInputType::A => (self.a_marker.end_marker.as_ref(), self.a_marker.end_marker_string().len()),
-                ParsedInputType::B => (self.b_marker.end_marker.as_ref(), self.b_marker.end_marker_string().len()),
-                _ => (None, 0),
-            };
-            let end_index = (end_opt
-                .and_then(|end| tmp.find(end.as_str()))
-                // In the case the end marker is not found, return the end of the tmp, minus the end_size
-                // (in order not to have out of bounds error since we add the end_size after unwrap_or)
-                .unwrap_or(tmp.len() - end_size)) + end_size;
-            if end_index > 0 {
-                let input_element: &str = &tmp[0..(end_index - end_size)];
-                input_elements.push(ParsedInputElement::new(input_element.to_string(), parsed_input_type.clone()));
-            } else {
+    // Tokenizes the input and returns the marked spans. The ranges of the spans exclude the markers.
+    // Outside of a segment, the longest start marker that matches opens a segment, so that markers like `*` and `**`
+    // can coexist. Inside a segment, only the end marker of the segment closes it. If both start markers match, both
+    // markers are candidates and the end marker that is found first decides the kind of the segment. The nested pairs
+    // of asymmetric markers are counted, so that only the end marker of the outer pair closes the segment.
+    fn parse(&self, input: &str) -> Vec<Span> {
+        let mut spans: Vec<Span> = Vec::new();
+        // The candidate markers, the start of the content of the segment that is currently open and the depth of the
+        // nested pairs in it
+        let mut open: Option<(Candidates, usize, usize)> = None;
+        let mut position = 0;
+        let finder = MarkerFinder::new(self);
+
+        while position < input.len() {
+            position = finder.next_candidate(input, position, open.is_some());
+            if position >= input.len() {
                 break;
             }
-            if tmp.len() <= end_index {
-                input = "";
-            } else {
-                input = &tmp[end_index..tmp.len()];
+            let remaining = &input[position..];
+            if self.escape_policy == EscapePolicy::Escape && remaining.starts_with('\\') {
+                // Skip the backslash along with the escaped char
+                position += 1 + remaining.chars().nth(1).map(|c| c.len_utf8()).unwrap_or(0);
+                continue;
             }
+            match open {
+                Some((ref candidates, content_start, ref mut depth)) => {
+                    let content = input.get(content_start..position).unwrap_or("");
+                    let closed = candidates.iter()
+                        .filter(|(_, marker)| remaining.starts_with(marker.end_marker_prefix()))
+                        .map(|(kind, marker)| (*kind, marker.end_marker_for(content)))
+                        .find(|(_, end_marker)| remaining.starts_with(end_marker.as_str()));
+                    if let Some((kind, end_marker)) = closed {
+                        if *depth > 0 {
+                            *depth -= 1;
+                        } else {
+                            trace_event!(?kind, start = content_start, end = position, "closed marker");
+                            spans.push(Span { kind, range: content_start..position });
+                            open = None;
+                        }
+                        position += end_marker.len();
+                        continue;
+                    }
+                    let nested = candidates.iter()
+                        .filter(|(_, marker)| marker.is_nestable())
+                        .filter_map(|(_, marker)| marker.start_marker().as_ref())
+                        .find(|start| remaining.starts_with(start.as_str()));
+                    if let Some(start) = nested {
+                        *depth += 1;
+                        position += start.len();
+                        continue;
+                    }
+                }
+                None => {
+                    let matched: Candidates = [(SegmentKind::A, &self.a_marker), (SegmentKind::B, &self.b_marker)].iter()
+                        .filter(|(_, marker)| marker.start_marker().as_ref().map(|start| remaining.starts_with(start.as_str())).unwrap_or(false))
+                        .cloned()
+                        .collect();
+                    let longest = matched.iter().map(|(_, marker)| marker.start_marker_string().len()).max();
+                    if let Some(longest) = longest {
+                        let candidates = matched.into_iter()
+                            .filter(|(_, marker)| marker.start_marker_string().len() == longest)
+                            .collect();
+                        position += longest;
+                        open = Some((candidates, position, 0));
+                        continue;
+                    }
+                }
+            }
+            position += remaining.chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+        }
+        // If the end marker is not found, the segment extends to the end of the input
+        if let Some((candidates, content_start, _)) = open {
+            debug_event!(kind = ?candidates[0].0, start = content_start, "unterminated marker");
+            spans.push(Span { kind: candidates[0].0, range: content_start..input.len() });
         }
-        input_elements
+        debug_event!(segments = spans.len(), "parsed markdown");
+        spans
     }
 
-
     // If b_marker is empty, then all the characters that are not marked with a_marker, should be considered as
     // they are marked with b_marker.
     // Similarly, if a_marker is empty, then all the characters that are not marked with b_marker, should be considered as
     // they are marked with a_marker.
-    // This function does exactly this: it takes the parts of `input_string`
+    // This function does exactly this: it takes the parts of `input`
     // that have not be characterized as a_marker (if b_marker is None) or b_marker (if a_marker is None)
-    // and adds them to the Vec of `ParsedInputElement`s as ParsedInputType::B, or ParsedInputType::B respectively.
-    fn replace_unmarked_characters_with(input_string: String, parsed_input_elements: Vec<ParsedInputElement>, start_marker_of_parsed_input_element: &str, end_marker_of_parsed_input_element: &str, parsed_input_type: ParsedInputType) -> Vec<ParsedInputElement> {
-        let mut input_string = input_string;
-        let mut new_parsed_input_elements: Vec<ParsedInputElement> = Vec::new();
-        for pie in parsed_input_elements.into_iter() {
-            // This is the string of the ParsedInputElement that is already found
-            let parsed_input_element_string = format!("{}{}{}",
-                                                      start_marker_of_parsed_input_element,
-                                                      pie.string,
-                                                      end_marker_of_parsed_input_element);
-            // Find this string in the input_string and get its start index
-            let index = input_string.find(&parsed_input_element_string).unwrap_or(input_string.len());
-            // Get the substring of the input_string until the above index
-            let substring: &str = &input_string[0..index];
-            // For each character of the substring, create a new ParsedInputElement and push it to the Vec
-            for c in substring.chars() {
-                new_parsed_input_elements.push(ParsedInputElement::new(c.to_string(), parsed_input_type.clone()));
+    // and adds them to the Vec of `Span`s as SegmentKind::B, or SegmentKind::A respectively.
+    // The parts are found in a single pass, using the ranges of the spans.
+    fn replace_unmarked_characters_with(input: &str, spans: Vec<Span>, marker_of_span: &Marker, kind: SegmentKind) -> Vec<Span> {
+        let mut new_spans: Vec<Span> = Vec::new();
+        let mut offset = 0;
+        let push_unmarked = |new_spans: &mut Vec<Span>, start: usize, end: usize| {
+            for (i, c) in input.get(start..end).unwrap_or("").char_indices() {
+                new_spans.push(Span { kind, range: start + i..start + i + c.len_utf8() });
+            }
+        };
+        for span in spans.into_iter() {
+            let start = span.range.start.saturating_sub(marker_of_span.start_marker_string().len());
+            push_unmarked(&mut new_spans, offset, start.max(offset));
+            offset = (span.range.end + marker_of_span.end_marker_for(input.get(span.range.clone()).unwrap_or("")).len()).min(input.len());
+            new_spans.push(span);
+        }
+        push_unmarked(&mut new_spans, offset, input.len());
+        new_spans
+    }
+
+    // Adds the text between the marked spans as spans of the kind `Other`.
+    fn add_unmarked_spans(&self, input: &str, spans: Vec<Span>) -> Vec<Span> {
+        let mut new_spans: Vec<Span> = Vec::new();
+        let mut offset = 0;
+        for span in spans.into_iter() {
+            let marker = if span.kind == SegmentKind::A { &self.a_marker } else { &self.b_marker };
+            let start = span.range.start.saturating_sub(marker.start_marker_string().len());
+            if start > offset {
+                new_spans.push(Span { kind: SegmentKind::Other, range: offset..start });
             }
-            // Push the known ParsedInputElement as well to the Vec
-            new_parsed_input_elements.push(pie);
-            // Change the input string, removing part that was processed in this iteration
-            input_string = input_string.replace(&format!("{}{}", substring, parsed_input_element_string), "");
+            offset = (span.range.end + marker.end_marker_for(input.get(span.range.clone()).unwrap_or("")).len()).min(input.len());
+            new_spans.push(span);
+        }
+        if offset < input.len() {
+            new_spans.push(Span { kind: SegmentKind::Other, range: offset..input.len() });
+        }
+        new_spans
+    }
+
+    // Classifies the input to spans, which borrow their text from the input only when it is needed.
+    fn spans(&self, input: &str) -> Vec<Span> {
+        let spans = self.parse(input);
+        if self.b_marker.is_empty() {
+            Self::replace_unmarked_characters_with(input, spans, &self.a_marker, SegmentKind::B)
+        } else if self.a_marker.is_empty() {
+            Self::replace_unmarked_characters_with(input, spans, &self.b_marker, SegmentKind::A)
+        } else {
+            self.add_unmarked_spans(input, spans)
+        }
+    }
+
+    /// Classifies the input to segments. The ranges of the segments are byte ranges in the UTF-8 encoded input,
+    /// excluding the markers.
+    pub fn segments(&self, input: &[char]) -> Vec<Segment> {
+        // The spans of the tokenizer are always within the input
+        self.parse_str(&String::from_iter(input.iter())).unwrap_or_default()
+    }
+
+    /// Classifies the input to segments, like [segments](#method.segments), without panicking for any input.
+    ///
+    /// This is the entry point for untrusted input: a segment that cannot be taken from the input is returned as
+    /// [BaconError::InvalidInput](../../errors/enum.BaconError.html#variant.InvalidInput).
+    pub fn parse_str(&self, input: &str) -> errors::Result<Vec<Segment>> {
+        self.spans(input).into_iter()
+            .map(|span| Ok(Segment::new(span.kind, Self::text_of(input, &span)?, Some(span.range))))
+            .collect()
+    }
+
+    // The text of a span, if its range is within the input and on character boundaries.
+    fn text_of<'a>(input: &'a str, span: &Span) -> errors::Result<&'a str> {
+        input.get(span.range.clone()).ok_or_else(|| BaconError::InvalidInput {
+            message: format!("The segment {:?} is not within the input", span.range),
+            position: Some(span.range.start),
+            source: None,
+        })
+    }
+}
+
+// Finds the next position of the input at which a marker or an escape may start, so that the tokenizer does not need
+// to try every position of long inputs.
+#[cfg(feature = "fast-marker-search")]
+struct MarkerFinder {
+    starts: AhoCorasick,
+    // None if an end marker starts with a placeholder, so that it can start at any position
+    ends: Option<AhoCorasick>,
+}
+
+#[cfg(feature = "fast-marker-search")]
+impl MarkerFinder {
+    fn new(steganographer: &MarkdownSteganographer) -> MarkerFinder {
+        let markers = [&steganographer.a_marker, &steganographer.b_marker];
+        let escape: &[&str] = if steganographer.escape_policy == EscapePolicy::Escape { &["\\"] } else { &[] };
+        let starts: Vec<&str> = markers.iter()
+            .filter_map(|marker| marker.start_marker().as_ref().map(|start| start.as_str()))
+            .chain(escape.iter().cloned())
+            .collect();
+        // Inside a segment, the start markers of nested pairs are searched along with the end markers
+        let ends: Vec<&str> = markers.iter()
+            .filter(|marker| !marker.is_empty())
+            .map(|marker| marker.end_marker_prefix())
+            .chain(markers.iter()
+                .filter(|marker| marker.is_nestable())
+                .filter_map(|marker| marker.start_marker().as_ref().map(|start| start.as_str())))
+            .chain(escape.iter().cloned())
+            .collect();
+        MarkerFinder {
+            starts: Self::automaton(&starts),
+            ends: if ends.iter().any(|end| end.is_empty()) { None } else { Some(Self::automaton(&ends)) },
         }
-        // Add any remaining ParsedInputElements
-        for c in input_string.chars().into_iter() {
-            new_parsed_input_elements.push(ParsedInputElement::new(c.to_string(), parsed_input_type.clone()));
+    }
+
+    fn automaton(patterns: &[&str]) -> AhoCorasick {
+        AhoCorasick::new(patterns).expect("The markers should always build an automaton")
+    }
+
+    fn next_candidate(&self, input: &str, position: usize, in_segment: bool) -> usize {
+        let automaton = if in_segment { self.ends.as_ref() } else { Some(&self.starts) };
+        match automaton {
+            Some(automaton) => automaton.find(&input[position..])
+                .map(|found| position + found.start())
+                .unwrap_or(input.len()),
+            None => position,
         }
+    }
+}
 
-        new_parsed_input_elements
+// Without the feature `fast-marker-search`, every position is a candidate.
+#[cfg(not(feature = "fast-marker-search"))]
+struct MarkerFinder;
+
+#[cfg(not(feature = "fast-marker-search"))]
+impl MarkerFinder {
+    fn new(_: &MarkdownSteganographer) -> MarkerFinder {
+        MarkerFinder
+    }
+
+    fn next_candidate(&self, _: &str, position: usize, _: bool) -> usize {
+        position
+    }
+}
+
+impl Default for MarkdownSteganographer {
+    /// Leaves the characters of the Bacon's element A unmarked and marks the characters of the Bacon's element B as bold (`**`).
+    fn default() -> MarkdownSteganographer {
+        MarkdownSteganographer::new(Marker::empty(), Marker::new(Some("**"), Some("**"))).expect("The default markers are valid")
     }
 }
 
 impl Steganographer for MarkdownSteganographer {
     type T = char;
 
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(units(&self.escape(public)?).iter()
+            .filter(|unit| unit[0].is_alphabetic())
+            .count())
+    }
+
     fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
-        let encoded = codec.encode(secret);
+        enter_span!("disguise", steganographer = "markdown");
+        let encoded = codec.encode_groups(secret);
+        let available = Steganographer::capacity(self, public)?;
+        if available < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available,
+                unit: CapacityUnit::Letters,
+            });
+        }
+        let public = self.escape(public)?;
 
-        let mut disguised = String::new();
+        let mut disguised: Vec<char> = Vec::with_capacity(public.len());
         let mut i = 0;
+        let units = units(&public);
+        let elements: Vec<bool> = encoded.elements().collect();
+        let unmarked_carry = self.a_marker.is_empty() || self.b_marker.is_empty();
+        let assigned = self.wrapping.assign(&units, &elements, &mut i, unmarked_carry);
+        // The marker that is currently open, along with the characters that it marks, which are kept only for the
+        // end markers of templates. Adjacent characters with the same marker are merged by keeping it open.
+        let mut open: Option<(&Marker, String)> = None;
 
-        for pc in public {
-            if pc.is_alphabetic() {
-                let opt = encoded.get(i);
-                if opt.is_some() && codec.is_a(opt.unwrap()) {
-                    disguised.push_str(&format!("{}{}{}",
-                                                self.a_marker.start_marker_string(),
-                                                pc.clone(),
-                                                self.a_marker.end_marker_string()));
-                    i = i + 1;
-                } else if opt.is_some() && codec.is_b(opt.unwrap()) {
-                    disguised.push_str(&format!("{}{}{}",
-                                                self.b_marker.start_marker_string(),
-                                                pc.clone(),
-                                                self.b_marker.end_marker_string()));
-                    i = i + 1;
+        for (unit, elem) in units.into_iter().zip(assigned) {
+            let marker = elem.map(|is_a| if is_a { &self.a_marker } else { &self.b_marker });
+            if let Some((open_marker, marked)) = open.take() {
+                if marker == Some(open_marker) {
+                    open = Some((open_marker, marked));
                 } else {
-                    disguised.push(pc.clone())
+                    disguised.extend(open_marker.end_marker_for(&marked).chars());
+                }
+            }
+            if let (None, Some(marker)) = (&open, marker) {
+                if let Some(start_marker) = marker.start_marker() {
+                    disguised.extend(start_marker.chars());
+                }
+                open = Some((marker, String::new()));
+            }
+            if let Some((open_marker, marked)) = open.as_mut() {
+                if open_marker.is_template() {
+                    marked.extend(unit.iter());
                 }
-            } else {
-                disguised.push(pc.clone())
             }
+            disguised.extend_from_slice(unit);
         }
+        if let Some((open_marker, marked)) = open {
+            disguised.extend(open_marker.end_marker_for(&marked).chars());
+        }
+        debug_event!(elements = encoded.len(), carried = i, "markdown carriers");
+        self.wrapping.check_carried(i, encoded.len())?;
 
-        Ok(disguised
-            .replace(&format!("{}{}", self.a_marker.end_marker_string(), self.a_marker.start_marker_string()), "")
-            .replace(&format!("{}{}", self.b_marker.end_marker_string(), self.b_marker.start_marker_string()), "")
-            .chars().collect())
+        Ok(disguised)
     }
 
     fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        enter_span!("reveal", steganographer = "markdown");
         let input_string: String = String::from_iter(input.iter());
-        let parsed_input_elements = self.parse(&input_string);
-        let new_parsed_input_elements: Vec<ParsedInputElement>;
-        if self.b_marker.is_empty() {
-            new_parsed_input_elements = Self::replace_unmarked_characters_with(input_string, parsed_input_elements, self.a_marker.start_marker.as_ref().unwrap_or(&"".to_string()), self.a_marker.end_marker.as_ref().unwrap_or(&"".to_string()), ParsedInputType::B);
-        } else if self.a_marker.is_empty() {
-            new_parsed_input_elements = Self::replace_unmarked_characters_with(input_string, parsed_input_elements, self.b_marker.start_marker.as_ref().unwrap_or(&"".to_string()), self.b_marker.end_marker.as_ref().unwrap_or(&"".to_string()), ParsedInputType::A);
-        } else {
-            new_parsed_input_elements = parsed_input_elements;
-        }
-        let encoded: Vec<AB> = new_parsed_input_elements.iter()
-            .map(|elem| {
-                if elem.tp == ParsedInputType::A {
-                    let v: Vec<AB> = elem.string.chars()
-                        .filter(|sc| sc.is_alphabetic())
-                        .map(|_| codec.a())
-                        .collect();
-                    v
-                } else {
-                    let v: Vec<AB> = elem.string.chars()
-                        .filter(|sc| sc.is_alphabetic())
-                        .map(|_| codec.b())
-                        .collect();
-                    v
-                }
-            })
-            .flat_map(|m| m)
-            .collect();
-        Ok(codec.decode(&encoded))
+        let texts = self.spans(&input_string).into_iter()
+            .map(|span| Ok((span.kind, Self::text_of(&input_string, &span)?)))
+            .collect::<errors::Result<Vec<_>>>()?;
+        Ok(carrier::decode_texts(texts.into_iter(), codec))
     }
-}
-
-#[derive(Debug, PartialEq)]
-struct ParsedInputElement {
-    string: String,
-    tp: ParsedInputType,
-}
 
-impl ParsedInputElement {
-    fn new(string: String, tp: ParsedInputType) -> ParsedInputElement {
-        ParsedInputElement { string, tp }
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        let input_string: String = String::from_iter(input.iter());
+        let mut stripped = Vec::with_capacity(input.len());
+        for span in self.spans(&input_string) {
+            stripped.extend(Self::text_of(&input_string, &span)?.chars());
+        }
+        Ok(stripped)
     }
-}
 
-#[derive(Clone, Debug, PartialEq)]
-enum ParsedInputType {
-    A,
-    B,
-    Other,
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::from_segments(input, &self.parse_str(&String::from_iter(input.iter()))?))
+    }
 }
 
 #[cfg(test)]
@@ -294,6 +719,433 @@ mod markdown_tests {
     use super::*;
 
     #[test]
+    fn common_traits() {
+        let s = MarkdownSteganographer::default();
+        assert_eq!(s, MarkdownSteganographer::builder().with_b_marker(Marker::new(Some("**"), Some("**"))).build().unwrap());
+        assert_ne!(s.clone().with_escape_policy(EscapePolicy::Strip), s);
+        assert!(format!("{:?}", s).contains("\"**\""));
+    }
+
+    #[test]
+    fn marker_templates() {
+        let marker = Marker::template("[{}](#{})").unwrap();
+        assert!(marker.is_template());
+        assert_eq!(marker.start_marker_string(), "[");
+        assert_eq!(marker.end_marker_for("ab"), "](#ab)");
+        assert_eq!(marker.end_marker_for("a b/é"), "](#a%20b%2F%C3%A9)");
+        assert_eq!(Marker::template("é{}€{}").unwrap().end_marker_for("a b"), "€a b");
+        let marker = Marker::template("<{}>").unwrap();
+        assert!(!marker.is_template());
+        assert_eq!(marker, Marker::new(Some("<"), Some(">")));
+        assert!(Marker::template("{}").is_err());
+        assert!(Marker::template("*").is_err());
+        assert_eq!(Marker::link("https://example.com").end_marker_for("ab"), "](https://example.com)");
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_links() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::link("https://a.example.com"),
+            Marker::template("[{}](https://b.example.com/{})").unwrap()).unwrap();
+        // H = AABBB
+        let output = s.disguise(&['H'], &Vec::from_iter("Hello [world]".chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "[He](https://a.example.com)[llo](https://b.example.com/llo) \\[world\\]");
+        let output = s.reveal(&Vec::from_iter(string.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+        // The escapes are kept, so that the stripped text is still Markdown
+        let stripped = s.strip(&Vec::from_iter(string.chars())).unwrap();
+        assert_eq!(String::from_iter(stripped.iter()), "Hello \\[world\\]");
+
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::image("pixel.png")).unwrap();
+        let public = "This is a public message that contains a secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+
+        let s = MarkdownSteganographer::new(
+            Marker::code_span(),
+            Marker::new(Some("**"), Some("**"))).unwrap();
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_overlapping_markers() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("*"), Some("*")),
+            Marker::new(Some("**"), Some("**"))).unwrap();
+        // H = AABBB, A = AAAAA
+        let output = s.disguise(&['H', 'A'], &Vec::from_iter("Hello big world".chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "*He***llo** *big* *wo*rld");
+        let output = s.reveal(&Vec::from_iter(string.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "HA");
+
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("__"), Some("__")),
+            Marker::new(Some("_"), Some("_"))).unwrap();
+        let public = "This is a public message that contains a secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_escapes_the_marker_characters() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(Some("*"), Some("*"))).unwrap();
+        // H = AABBB
+        let public = "He*llo \\*world*";
+        let output = s.disguise(&['H'], &Vec::from_iter(public.chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "He\\**llo* \\\\\\*world\\*");
+        // The unmarked "world" is revealed as AAAAA
+        let output = s.reveal(&Vec::from_iter(string.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "HA");
+
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("*"), Some("*")),
+            Marker::new(Some("__"), Some("__"))).unwrap();
+        let public = "This *is* a public_message that __contains__ a secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_strips_the_marker_characters() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(Some("*"), Some("*"))).unwrap()
+            .with_escape_policy(EscapePolicy::Strip);
+        // H = AABBB
+        let output = s.disguise(&['H'], &Vec::from_iter("He*llo *world*".chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "He*llo* world");
+    }
+
+    #[test]
+    fn disguise_fails_for_marker_characters() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(Some("*"), Some("*"))).unwrap()
+            .with_escape_policy(EscapePolicy::Error);
+        assert_eq!(s.disguise(&['H'], &Vec::from_iter("He*llo world".chars()), &codec),
+                   Err(BaconError::MarkerInInput { character: '*', position: 2 }));
+        assert!(s.disguise(&['H'], &Vec::from_iter("Hello \\world".chars()), &codec).is_ok());
+    }
+
+    #[test]
+    fn parse_non_ascii_input() {
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("*"), Some("*")),
+            Marker::new(Some("__"), Some("__"))).unwrap();
+        let segments = s.segments(&Vec::from_iter("*é*ü__ö😀__ *καφές".chars()));
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::A, "é", Some(1..3)),
+            Segment::new(SegmentKind::Other, "ü", Some(4..6)),
+            Segment::new(SegmentKind::B, "ö😀", Some(8..14)),
+            Segment::new(SegmentKind::Other, " ", Some(16..17)),
+            Segment::new(SegmentKind::A, "καφές", Some(18..28)),
+        ]);
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_non_ascii_public_text() {
+        let codec = CharCodec::new('a', 'b');
+        let public = "Ένα δημόσιο μήνυμα 😀 που περιέχει ένα κρυφό, café naïve";
+        for (a_marker, b_marker) in [
+            (Marker::new(Some("*"), Some("*")), Marker::new(Some("__"), Some("__"))),
+            (Marker::empty(), Marker::new(Some("*"), Some("*"))),
+            (Marker::new(Some("«"), Some("»")), Marker::empty()),
+        ] {
+            let s = MarkdownSteganographer::new(a_marker, b_marker).unwrap();
+            let disguised = s.disguise(
+                &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+                &Vec::from_iter(public.chars()),
+                &codec).unwrap();
+            let output = s.reveal(&disguised, &codec);
+            let string = String::from_iter(output.unwrap().iter());
+            assert!(string.starts_with("MYSECRET"));
+        }
+    }
+
+    #[test]
+    fn reveal_unterminated_markers() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("*"), Some("*")),
+            Marker::new(Some("__"), Some("__"))).unwrap();
+        // H = AABBB
+        let output = s.reveal(&Vec::from_iter("*He*__llò".chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+        let output = s.reveal(&Vec::from_iter("café*".chars()), &codec);
+        assert!(output.unwrap().is_empty());
+    }
+
+    #[test]
+    fn segments_of_the_input() {
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("*"), Some("*")),
+            Marker::new(Some("__"), Some("__"))).unwrap();
+        let segments = s.segments(&Vec::from_iter("*He*l__lo__ world".chars()));
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::A, "He", Some(1..3)),
+            Segment::new(SegmentKind::Other, "l", Some(4..5)),
+            Segment::new(SegmentKind::B, "lo", Some(7..9)),
+            Segment::new(SegmentKind::Other, " world", Some(11..17)),
+        ]);
+
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(Some("*"), Some("*"))).unwrap();
+        let segments = s.segments(&Vec::from_iter("He*ll*o".chars()));
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::A, "H", Some(0..1)),
+            Segment::new(SegmentKind::A, "e", Some(1..2)),
+            Segment::new(SegmentKind::B, "ll", Some(3..5)),
+            Segment::new(SegmentKind::A, "o", Some(6..7)),
+        ]);
+    }
+
+    #[test]
+    fn segments_of_repeated_substrings() {
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(Some("*"), Some("*"))).unwrap();
+        // The second marked "b" appears earlier unmarked
+        let segments = s.segments(&Vec::from_iter("*b*b *b*".chars()));
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::B, "b", Some(1..2)),
+            Segment::new(SegmentKind::A, "b", Some(3..4)),
+            Segment::new(SegmentKind::A, " ", Some(4..5)),
+            Segment::new(SegmentKind::B, "b", Some(6..7)),
+        ]);
+
+        let codec = CharCodec::new('a', 'b');
+        // H = AABBB
+        let output = s.reveal(&Vec::from_iter("He*llo* He*llo*".chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "HH");
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_asymmetric_markers() {
+        let codec = CharCodec::new('a', 'b');
+        for (a_marker, b_marker) in [
+            (Marker::new(Some("<sub>"), Some("</sub>")), Marker::new(Some("<sup>"), Some("</sup>"))),
+            (Marker::new(Some("{"), Some("}")), Marker::new(Some("(("), Some("))"))),
+            (Marker::new(Some("("), Some(")")), Marker::new(Some("(("), Some("))"))),
+            (Marker::empty(), Marker::new(Some("<sub>"), Some("</sub>"))),
+        ] {
+            assert!(!a_marker.is_symmetric() || a_marker.is_empty());
+            let s = MarkdownSteganographer::new(a_marker, b_marker).unwrap();
+            let disguised = s.disguise_str("My secret", "This is a public message (with braces) {and} <tags> that hides it", &codec).unwrap();
+            assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+        }
+    }
+
+    #[test]
+    fn disguise_and_reveal_whole_words() {
+        let codec = CharCodec::new('a', 'b');
+        let public = "It is a truth universally acknowledged, that a single man in possession of a good fortune, must be in \
+            want of a wife. I do not know if he is in or out, so we go up to see. ".repeat(20);
+        let s = MarkdownSteganographer::new(Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))).unwrap()
+            .with_wrapping(Wrapping::Words);
+        let disguised = s.disguise_str("My secret", &public, &codec).unwrap();
+        assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+        // Every marked segment is a whole word
+        let disguised: Vec<char> = disguised.chars().collect();
+        let segments = s.segments(&disguised);
+        let input: String = disguised.iter().collect();
+        for segment in segments.iter().filter(|segment| segment.kind() != SegmentKind::Other) {
+            assert!(segment.text().chars().all(char::is_alphabetic));
+            let range = segment.range().unwrap();
+            let marker_len = if segment.kind() == SegmentKind::A { 1 } else { 2 };
+            assert!(input[..range.start - marker_len].chars().last().map(|c| !c.is_alphabetic()).unwrap_or(true));
+            assert!(input[range.end + marker_len..].chars().next().map(|c| !c.is_alphabetic()).unwrap_or(true));
+        }
+
+        // The unmarked characters carry elements, so the words that do not fit are marked per character
+        let s = MarkdownSteganographer::builder()
+            .with_b_marker(Marker::new(Some("*"), Some("*")))
+            .with_wrapping(Wrapping::Words)
+            .build().unwrap();
+        // H = AABBB
+        assert_eq!(s.disguise_str("H", "He is a pal", &codec).unwrap(), "He *is* *a* pal");
+        assert_eq!(s.disguise_str("H", "Hello", &codec).unwrap(), "He*llo*");
+        assert!(s.reveal_str(&s.disguise_str("My secret", &public, &codec).unwrap(), &codec).unwrap().starts_with("MYSECRET"));
+
+        let s = MarkdownSteganographer::default().with_wrapping(Wrapping::Words);
+        assert!(s.disguise_str("H", "Hello", &codec).is_ok());
+        let s = MarkdownSteganographer::new(Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))).unwrap()
+            .with_wrapping(Wrapping::Words);
+        assert!(matches!(s.disguise_str("H", "Hello", &codec), Err(BaconError::InsufficientCapacity { required: 5, available: 0, .. })));
+    }
+
+    #[test]
+    fn disguise_with_minimum_markup() {
+        let codec = CharCodec::new('a', 'b');
+        let public = "It is a truth universally acknowledged, that a single man in possession of a good fortune, must be in \
+            want of a wife. ".repeat(10);
+        let marked = |wrapping: Wrapping| {
+            let s = MarkdownSteganographer::new(Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))).unwrap()
+                .with_wrapping(wrapping);
+            let disguised = s.disguise_str("My secret", &public, &codec).unwrap();
+            assert!(s.reveal_str(&disguised, &codec).unwrap().starts_with("MYSECRET"));
+            s.segments(&Vec::from_iter(disguised.chars())).iter().filter(|segment| segment.kind() != SegmentKind::Other).count()
+        };
+        // Every run of the same element is marked by a single pair of markers
+        let encoded = codec.encode(&Vec::from_iter("MYSECRET".chars()));
+        let runs = 1 + encoded.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        assert_eq!(marked(Wrapping::MinimumMarkup), runs);
+        assert!(marked(Wrapping::Characters) > runs);
+
+        // H = AABBB
+        let s = MarkdownSteganographer::new(Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))).unwrap()
+            .with_wrapping(Wrapping::MinimumMarkup);
+        assert_eq!(s.disguise_str("H", "He is here", &codec).unwrap(), "*He* is **her**e");
+        assert_eq!(s.disguise_str("H", "A cat is here", &codec).unwrap(), "A *ca*t is **her**e");
+    }
+
+    #[test]
+    fn minimum_markup_does_not_skip_the_words_that_are_needed() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))).unwrap()
+            .with_wrapping(Wrapping::MinimumMarkup);
+        // A = AAAAA, C = AAABA
+        let secret = Vec::from_iter("AC".chars());
+        let public = Vec::from_iter("I am at my desk in a big old house".chars());
+        assert!(Steganographer::verify(&s, &secret, &public, &codec).is_ok());
+        let disguised = s.disguise(&secret, &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "*I* *am* *at* my *des***k** *i*n a big old house");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), secret);
+
+        assert!(matches!(s.disguise_str("AC", "I am at my", &codec),
+            Err(BaconError::InsufficientCapacity { required: 10, available: 7, .. })));
+    }
+
+    #[test]
+    fn parse_nested_asymmetric_markers() {
+        let s = MarkdownSteganographer::new(
+            Marker::new(Some("{"), Some("}")),
+            Marker::new(Some("(("), Some("))"))).unwrap();
+        let segments = s.segments(&Vec::from_iter("{a{b}c} ((d)) {e{f".chars()));
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::A, "a{b}c", Some(1..6)),
+            Segment::new(SegmentKind::Other, " ", Some(7..8)),
+            Segment::new(SegmentKind::B, "d", Some(10..11)),
+            Segment::new(SegmentKind::Other, " ", Some(13..14)),
+            Segment::new(SegmentKind::A, "e{f", Some(15..18)),
+        ]);
+    }
+
+    #[test]
+    fn links_keep_the_punctuation_of_the_public_text() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::link("https://a.example.com/x"),
+            Marker::template("[{}](#{})").unwrap()).unwrap();
+        // The `.`, `:` and `/` of the URLs are not escaped in the public text, but `[` is
+        // H = AABBB
+        let output = s.disguise(&['H'], &Vec::from_iter("He: lo see a.b/c [d]".chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "[He](https://a.example.com/x): [lo](#lo) [s](#s)ee a.b/c \\[d\\]");
+        let output = s.reveal(&Vec::from_iter(string.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+
+        let s = s.with_escape_policy(EscapePolicy::Strip);
+        let output = s.disguise(&['H'], &Vec::from_iter("He: lo see a.b/c [d]".chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "[He](https://a.example.com/x): [lo](#lo) [s](#s)ee a.b/c d");
+
+        // The marked characters are percent-encoded in the destination of the link
+        let segments = s.parse_str("[a b](#a%20b)").unwrap();
+        assert_eq!(segments, vec![Segment::new(SegmentKind::B, "a b", Some(1..4))]);
+    }
+
+    #[test]
+    fn parse_adversarial_input() {
+        let markers = vec![
+            (Marker::new(Some("*"), Some("*")), Marker::new(Some("**"), Some("**"))),
+            (Marker::template("é{}€{}").unwrap(), Marker::empty()),
+            (Marker::empty(), Marker::new(Some("{{"), Some("}"))),
+            (Marker::link("é"), Marker::image("€")),
+        ];
+        let inputs = ["\\", "*\\", "é€é", "éé€€{{}}}", "![é](€)[€](é", "{{{{é}}€", "**\\**é*"];
+        for (a_marker, b_marker) in markers {
+            let s = MarkdownSteganographer::new(a_marker, b_marker).unwrap();
+            for input in inputs.iter() {
+                let segments = s.parse_str(input).unwrap();
+                assert_eq!(segments, s.segments(&Vec::from_iter(input.chars())));
+                assert!(segments.iter().all(|segment| segment.range().map(|range| &input[range] == segment.text()).unwrap_or(false)));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_a_large_input() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::template("[{}](#{})").unwrap(),
+            Marker::new(Some("**"), Some("**"))).unwrap();
+        // H = AABBB
+        let mut input = "[He](#He)**llo** ".repeat(50_000);
+        input.push_str(&"[world".repeat(50_000));
+        let segments = s.segments(&Vec::from_iter(input.chars()));
+        assert_eq!(segments.len(), 150_001);
+        let output = s.reveal(&Vec::from_iter(input.chars()), &codec);
+        assert_eq!(output.unwrap().len(), 100_000);
+    }
+
+    #[test]
+    fn disguise_a_large_input() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::template("[{}](#{})").unwrap(),
+            Marker::new(Some("**"), Some("**"))).unwrap();
+        let secret = Vec::from_iter("HELLO ".repeat(10_000).chars());
+        let public = Vec::from_iter("Hello world* ".repeat(30_000).chars());
+        let disguised = s.disguise(&secret, &public, &codec).unwrap();
+        assert!(String::from_iter(disguised[..18].iter()).starts_with("[He](#He)**llo**"));
+        assert!(s.reveal(&disguised, &codec).unwrap().starts_with(&Vec::from_iter("HELLO".repeat(10_000).chars())));
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::default();
+        let output = s.disguise(&['H'], &Vec::from_iter("Hi 42".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 5, available: 2, unit: CapacityUnit::Letters }));
+    }
+
+    #[test]
+    #[allow(clippy::partialeq_to_none)]
     fn markers_creation() {
         let m1 = Marker::new(None, None);
         assert!(m1.start_marker() == &None);
@@ -317,6 +1169,7 @@ mod markdown_tests {
 
     #[test]
     fn steganographer_creation_failure() {
+        // Markers that contain each other are allowed
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("*"),
@@ -324,7 +1177,7 @@ mod markdown_tests {
             Marker::new(
                 Some("**"),
                 Some("**")));
-        assert!(res.is_err());
+        assert!(res.is_ok());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("*"),
@@ -332,7 +1185,7 @@ mod markdown_tests {
             Marker::new(
                 Some("@"),
                 Some("**")));
-        assert!(res.is_err());
+        assert!(res.is_ok());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("!"),
@@ -340,7 +1193,7 @@ mod markdown_tests {
             Marker::new(
                 Some("**"),
                 Some("@")));
-        assert!(res.is_err());
+        assert!(res.is_ok());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("**"),
@@ -348,7 +1201,7 @@ mod markdown_tests {
             Marker::new(
                 Some("*"),
                 Some("*")));
-        assert!(res.is_err());
+        assert!(res.is_ok());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("**"),
@@ -356,7 +1209,7 @@ mod markdown_tests {
             Marker::new(
                 Some("*"),
                 Some("!")));
-        assert!(res.is_err());
+        assert!(res.is_ok());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("@"),
@@ -364,7 +1217,7 @@ mod markdown_tests {
             Marker::new(
                 Some("!"),
                 Some("*")));
-        assert!(res.is_err());
+        assert!(res.is_ok());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("**"),
@@ -373,10 +1226,33 @@ mod markdown_tests {
                 Some("**"),
                 Some("**")));
         assert!(res.is_err());
+        let res = MarkdownSteganographer::new(
+            Marker::new(
+                Some("**"),
+                Some("!")),
+            Marker::new(
+                Some("**"),
+                Some("@")));
+        assert!(res.is_ok());
+        let res = MarkdownSteganographer::new(
+            Marker::new(
+                Some(""),
+                Some("")),
+            Marker::new(
+                Some("*"),
+                Some("*")));
+        assert!(res.is_err());
         let res = MarkdownSteganographer::new(
             Marker::empty(),
             Marker::empty());
         assert!(res.is_err());
+        // The start and end of an asymmetric marker cannot be told apart
+        let res = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(
+                Some("("),
+                Some("((")));
+        assert!(res.is_err());
         let res = MarkdownSteganographer::new(
             Marker::new(
                 Some("**"),
@@ -525,4 +1401,28 @@ mod markdown_tests {
         let string = String::from_iter(output.unwrap().iter());
         assert!(string.starts_with("MYSECRET"));
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "grapheme-clusters")]
+    #[test]
+    fn disguise_does_not_split_grapheme_clusters() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::new(
+            Marker::empty(),
+            Marker::new(
+                Some("*"),
+                Some("*"))).unwrap();
+
+        let public = "The\u{301} cafe\u{301} is\u{301} a public message that contains a secret one";
+        let output = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("T*h*e\u{301} *caf*e\u{301} *is\u{301}* a"));
+        let output = s.reveal(
+            &Vec::from_iter(string.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::char;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// A style of the latin letters, as defined in the Unicode block _Mathematical Alphanumeric Symbols_.
+///
+/// Only the styles that define all the 52 latin letters without gaps are included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "profiles", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum LetterStyle {
+    /// The ASCII letters.
+    Plain,
+    Bold,
+    BoldItalic,
+    SansSerif,
+    SansSerifBold,
+    SansSerifItalic,
+    SansSerifBoldItalic,
+    Monospace,
+}
+
+const STYLES: [LetterStyle; 8] = [
+    LetterStyle::Plain,
+    LetterStyle::Bold,
+    LetterStyle::BoldItalic,
+    LetterStyle::SansSerif,
+    LetterStyle::SansSerifBold,
+    LetterStyle::SansSerifItalic,
+    LetterStyle::SansSerifBoldItalic,
+    LetterStyle::Monospace,
+];
+
+impl LetterStyle {
+    // The code point of the capital `A` of the style. The small `a` follows the capital `Z`.
+    fn first_code_point(&self) -> u32 {
+        match self {
+            LetterStyle::Plain => 'A' as u32,
+            LetterStyle::Bold => 0x1D400,
+            LetterStyle::BoldItalic => 0x1D468,
+            LetterStyle::SansSerif => 0x1D5A0,
+            LetterStyle::SansSerifBold => 0x1D5D4,
+            LetterStyle::SansSerifItalic => 0x1D608,
+            LetterStyle::SansSerifBoldItalic => 0x1D63C,
+            LetterStyle::Monospace => 0x1D670,
+        }
+    }
+
+    /// Applies the style to an ASCII letter. Returns `None` if the given `char` is not an ASCII letter.
+    pub fn apply(&self, c: char) -> Option<char> {
+        let offset = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            _ => return None,
+        };
+        match self {
+            LetterStyle::Plain => Some(c),
+            _ => char::from_u32(self.first_code_point() + offset),
+        }
+    }
+
+    /// Finds the style of a letter and returns it along with the plain ASCII letter.
+    ///
+    /// Returns `None` if the given `char` is not a letter of any of the known styles.
+    pub fn classify(c: char) -> Option<(LetterStyle, char)> {
+        if c.is_ascii_alphabetic() {
+            return Some((LetterStyle::Plain, c));
+        }
+        STYLES.iter()
+            .filter(|style| style != &&LetterStyle::Plain)
+            .find_map(|style| {
+                let first = style.first_code_point();
+                let cp = c as u32;
+                if cp >= first && cp < first + 52 {
+                    let offset = cp - first;
+                    let plain = if offset < 26 { 'A' as u32 + offset } else { 'a' as u32 + offset - 26 };
+                    char::from_u32(plain).map(|plain| (*style, plain))
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// Applies steganography based on the style of the letters, using the Unicode _Mathematical Alphanumeric Symbols_.
+///
+/// E.g. Plain letters for Bacon's element A and bold letters for Bacon's element B. The output looks like the styled
+/// texts that are common in social media posts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MathAlphanumericSteganographer {
+    a_style: LetterStyle,
+    b_style: LetterStyle,
+}
+
+impl MathAlphanumericSteganographer {
+    pub fn new(a_style: LetterStyle, b_style: LetterStyle) -> errors::Result<MathAlphanumericSteganographer> {
+        if a_style == b_style {
+            Err(BaconError::SteganographerError(format!("Cannot use the style {:?} for both A and B", a_style)))
+        } else {
+            Ok(MathAlphanumericSteganographer { a_style, b_style })
+        }
+    }
+
+    pub fn a_style(&self) -> LetterStyle {
+        self.a_style
+    }
+
+    pub fn b_style(&self) -> LetterStyle {
+        self.b_style
+    }
+}
+
+impl Default for MathAlphanumericSteganographer {
+    /// Plain letters for A and bold letters for B.
+    fn default() -> MathAlphanumericSteganographer {
+        MathAlphanumericSteganographer {
+            a_style: LetterStyle::Plain,
+            b_style: LetterStyle::Bold,
+        }
+    }
+}
+
+impl Steganographer for MathAlphanumericSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(public.iter()
+            .filter(|pc| LetterStyle::classify(**pc).is_some())
+            .count())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let available_size = self.capacity(public)?;
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if available_size < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: available_size,
+                unit: CapacityUnit::Letters,
+            })
+        } else {
+            let mut encoded = codec.encode(secret).into_iter();
+
+            let disguised = public.iter()
+                .map(|pc| {
+                    match LetterStyle::classify(*pc) {
+                        Some((_, plain)) => {
+                            match encoded.next() {
+                                Some(ref elem) if codec.is_a(elem) => self.a_style.apply(plain).unwrap_or(*pc),
+                                Some(ref elem) if codec.is_b(elem) => self.b_style.apply(plain).unwrap_or(*pc),
+                                _ => *pc,
+                            }
+                        }
+                        None => *pc,
+                    }
+                })
+                .collect();
+
+            Ok(disguised)
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = input.iter()
+            .filter_map(|elem| LetterStyle::classify(*elem))
+            .filter_map(|(style, _)| {
+                if style == self.a_style {
+                    Some(codec.a())
+                } else if style == self.b_style {
+                    Some(codec.b())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(input.iter().enumerate()
+            .filter_map(|(position, elem)| {
+                match LetterStyle::classify(*elem) {
+                    Some((style, _)) if style == self.a_style => Some(Slot::new(position, SegmentKind::A)),
+                    Some((style, _)) if style == self.b_style => Some(Slot::new(position, SegmentKind::B)),
+                    _ => None,
+                }
+            })
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod math_alphanumeric_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn apply_and_classify_styles() {
+        assert_eq!(LetterStyle::Bold.apply('A'), Some('𝐀'));
+        assert_eq!(LetterStyle::Bold.apply('z'), Some('𝐳'));
+        assert_eq!(LetterStyle::SansSerif.apply('h'), Some('𝗁'));
+        assert_eq!(LetterStyle::Monospace.apply('1'), None);
+        assert_eq!(LetterStyle::classify('𝐳'), Some((LetterStyle::Bold, 'z')));
+        assert_eq!(LetterStyle::classify('𝙯'), Some((LetterStyle::SansSerifBoldItalic, 'z')));
+        assert_eq!(LetterStyle::classify('q'), Some((LetterStyle::Plain, 'q')));
+        assert_eq!(LetterStyle::classify('é'), None);
+        for style in STYLES.iter() {
+            for c in ('a'..='z').chain('A'..='Z') {
+                assert_eq!(LetterStyle::classify(style.apply(c).unwrap()), Some((*style, c)));
+            }
+        }
+    }
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(MathAlphanumericSteganographer::new(LetterStyle::Bold, LetterStyle::Bold).is_err());
+    }
+
+    #[test]
+    fn disguise_a_secret_to_a_char_array() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MathAlphanumericSteganographer::default();
+        let public = "This is a public message that contains a secret one";
+        let output = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "T𝐡i𝐬 𝐢𝐬 a 𝐩𝐮b𝐥ic m𝐞ss𝐚ge tha𝐭 c𝐨ntains 𝐚 se𝐜re𝐭 one");
+    }
+
+    #[test]
+    fn reveal_a_secret_from_a_char_array() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MathAlphanumericSteganographer::new(LetterStyle::SansSerif, LetterStyle::SansSerifBold).unwrap();
+        let public = "This is a public message that contains a secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+}
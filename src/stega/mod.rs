@@ -11,7 +11,415 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+pub mod acrostic;
+#[cfg(feature = "extended-steganography")]
+pub mod css_class;
+pub mod direction_mark;
+#[cfg(feature = "extended-steganography")]
+pub mod dom;
+pub mod hex_case;
+pub mod html_entity;
+pub mod hybrid;
+pub mod inverted;
+pub mod jpeg;
+pub mod leet;
 pub mod letter_case;
+pub mod lsb;
 pub mod markdown;
+pub mod morse;
+pub mod math_alphanumeric;
+pub mod null_cipher;
+pub mod percent_encoding;
+#[cfg(feature = "png-steganography")]
+pub mod png_text;
+pub mod protected;
+pub mod punctuation;
+#[cfg(feature = "regex-steganography")]
+pub mod regex;
+pub mod scattered;
+pub mod sentence_punctuation;
+pub mod source_code;
 #[cfg(feature = "extended-steganography")]
-pub mod tags;
\ No newline at end of file
+pub mod tags;
+pub mod validated;
+pub mod whitespace;
+pub mod word_parity;
+pub mod zero_width;
+#[cfg(feature = "xml-steganography")]
+pub mod xml;
+
+use crate::{BaconCodec, errors};
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::random::Random;
+
+/// How the steganographers that surround the carrier characters with markup, like the
+/// [MarkdownSteganographer](markdown/struct.MarkdownSteganographer.html), align the markup to the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "profiles", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum Wrapping {
+    /// Every alphabetic character carries the next element. Adjacent characters with the same element share their markup.
+    #[default]
+    Characters,
+    /// Whole words carry runs of the same element, like `*is*` or `**that**`. A word that cannot carry the next elements
+    /// as a whole is left unmarked, or, if the unmarked characters carry elements as well, it is marked per character.
+    /// The disguise fails if the words of the public input cannot carry all the elements.
+    Words,
+    /// The carriers are chosen so that the markup is minimal: every run of the same element is carried by the first
+    /// letters of a word that can hold the whole run, so that it needs a single pair of markers. The runs that are longer
+    /// than the words are split. A word is skipped only if the words that follow it can still carry all the elements, and
+    /// the disguise fails if the public input cannot carry them. Needs markup for both elements, otherwise it is the same
+    /// as `Characters`.
+    MinimumMarkup,
+}
+
+impl Wrapping {
+    // Assigns the next elements, starting from `index`, to the units of a text. Returns the element that every unit
+    // carries (true for A), or None for the units that are not carriers.
+    pub(crate) fn assign(&self, units: &[&[char]], elements: &[bool], index: &mut usize, unmarked_carry: bool) -> Vec<Option<bool>> {
+        if *self == Wrapping::MinimumMarkup && !unmarked_carry {
+            return Self::assign_minimum_markup(units, elements, index);
+        }
+        let mut assigned = Vec::with_capacity(units.len());
+        let mut start = 0;
+        while start < units.len() {
+            let end = (start..units.len()).find(|unit| !units[*unit][0].is_alphabetic()).unwrap_or(units.len());
+            let whole_word = elements.get(*index..*index + end - start)
+                .map(|run| run.iter().all(|elem| *elem == run[0]))
+                .unwrap_or(false);
+            if *self == Wrapping::Words && !whole_word && !unmarked_carry {
+                assigned.extend(std::iter::repeat_n(None, end - start));
+            } else {
+                for _ in start..end {
+                    let elem = elements.get(*index).cloned();
+                    if elem.is_some() {
+                        *index += 1;
+                    }
+                    assigned.push(elem);
+                }
+            }
+            // The unit that ends the word is not a carrier
+            if end < units.len() {
+                assigned.push(None);
+            }
+            start = end + 1;
+        }
+        assigned
+    }
+
+    // Places every run of the same element in the first stretch of letters that can hold it, splitting the runs that
+    // do not fit in any stretch across the next stretches. A stretch is skipped only if the letters that follow it can
+    // still carry all the remaining elements.
+    fn assign_minimum_markup(units: &[&[char]], elements: &[bool], index: &mut usize) -> Vec<Option<bool>> {
+        // The stretches of consecutive letters, as (start, length), from a position of the text
+        let stretches = |from: usize| {
+            let mut position = from;
+            std::iter::from_fn(move || {
+                let start = (position..units.len()).find(|unit| units[*unit][0].is_alphabetic())?;
+                let end = (start..units.len()).find(|unit| !units[*unit][0].is_alphabetic()).unwrap_or(units.len());
+                position = end;
+                Some((start, end - start))
+            })
+        };
+        // The letters of the text from a position
+        let letters = |from: usize| units[from..].iter().filter(|unit| unit[0].is_alphabetic()).count();
+        let mut assigned = vec![None; units.len()];
+        let mut position = 0;
+        while *index < elements.len() {
+            let elem = elements[*index];
+            let run = elements[*index..].iter().take_while(|other| **other == elem).count();
+            let remaining = elements.len() - *index;
+            let stretch = stretches(position)
+                .find(|(start, len)| *len >= run && letters(*start) >= remaining)
+                .or_else(|| stretches(position).next());
+            let (start, len) = match stretch {
+                Some(stretch) => stretch,
+                None => break,
+            };
+            let placed = run.min(len);
+            assigned[start..start + placed].iter_mut().for_each(|unit| *unit = Some(elem));
+            *index += placed;
+            position = start + placed;
+        }
+        assigned
+    }
+
+    // Fails if the words, or the stretches of the minimum markup, could not carry all the elements.
+    pub(crate) fn check_carried(&self, carried: usize, required: usize) -> errors::Result<()> {
+        if *self != Wrapping::Characters && carried < required {
+            Err(BaconError::InsufficientCapacity { required, available: carried, unit: CapacityUnit::Carriers })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// What the steganographers that support it, like the [LetterCaseSteganographer](letter_case/struct.LetterCaseSteganographer.html),
+/// write to the carriers that follow the encoded secret. The reveal removes the padding that the disguise added.
+///
+/// The padding starts with a group that the codec does not use, like `BBBBB` for the codecs of 24 or 26 letters, which
+/// marks the end of the secret. If the codec uses all the groups, nothing is padded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "profiles", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum Padding {
+    /// The carriers that follow the secret are left as they are in the public input.
+    #[default]
+    None,
+    /// The secret is repeated until all the carriers are used.
+    Repeat,
+    /// Random letters, which are reproduced from the seed.
+    RandomLetters(u64),
+    /// Groups that the codec does not use.
+    Nulls,
+}
+
+impl Padding {
+    // Fills the groups with the padding, until they have `capacity` elements.
+    pub(crate) fn pad<AB>(&self, groups: &mut Groups, capacity: usize, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) {
+        if *self != Padding::None && groups.len() < capacity {
+            let missing = capacity - groups.len();
+            let padding: Vec<bool> = PaddingSource::new(groups, codec).elements(*self).take(missing).collect();
+            groups.extend(padding);
+        }
+    }
+
+    // Decodes the groups of a padded disguise, without the padding, which starts with the first unused group.
+    pub(crate) fn unpad<AB>(&self, groups: &Groups, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> Vec<char> {
+        let group_size = codec.encoded_group_size();
+        let patterns = groups.complete();
+        let end = match (self, null_pattern(codec)) {
+            // The elements that are read one by one, like by a carrier, are not groups of a padded secret
+            _ if group_size == 1 => return codec.decode_groups(groups),
+            (Padding::None, _) | (_, None) => return codec.decode_groups(groups),
+            // The secret used all the carriers if there is no end
+            (_, Some(null)) => patterns.iter().position(|pattern| *pattern == null).unwrap_or(patterns.len()),
+        };
+        codec.decode_groups(&Groups::from_patterns(patterns[..end].to_vec(), group_size))
+    }
+}
+
+// What the padding needs from the codec and the encoded secret, so that the padding can be written after the codec is
+// gone, like by the streams, which do not know the number of the carriers in advance.
+pub(crate) struct PaddingSource {
+    secret: Vec<bool>,
+    // The elements of the letters `A` to `Z`
+    letters: Vec<Vec<bool>>,
+    null: Vec<bool>,
+}
+
+impl PaddingSource {
+    pub(crate) fn new<AB>(groups: &Groups, codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> PaddingSource {
+        PaddingSource {
+            secret: groups.elements().collect(),
+            letters: (b'A'..=b'Z')
+                .map(|letter| codec.encode_elem(&(letter as char)).iter().map(|elem| codec.is_a(elem)).collect())
+                .collect(),
+            null: null_pattern(codec)
+                .map(|pattern| Groups::from_patterns(vec![pattern], codec.encoded_group_size()).elements().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    // The endless elements of the padding, which start with the null group, or no elements if nothing is padded.
+    pub(crate) fn elements(&self, padding: Padding) -> PaddingElements {
+        let padding = if self.null.is_empty() { Padding::None } else { padding };
+        let (repeated, random) = match padding {
+            Padding::None => (Vec::new(), None),
+            Padding::Repeat => ([&self.secret[..], &self.null[..]].concat(), None),
+            Padding::RandomLetters(seed) => (Vec::new(), Some(Random::new(seed))),
+            Padding::Nulls => (self.null.clone(), None),
+        };
+        PaddingElements {
+            end: if padding == Padding::None { Vec::new() } else { self.null.clone() },
+            repeated,
+            letters: self.letters.clone(),
+            random: random.filter(|_| self.letters.iter().any(|letter| !letter.is_empty())),
+            index: 0,
+        }
+    }
+}
+
+// The elements of a padding: the end of the secret, followed by elements that repeat, or by the elements of random letters.
+pub(crate) struct PaddingElements {
+    // The elements of the end of the secret that are not returned yet
+    end: Vec<bool>,
+    // The elements that repeat, or the elements of the current random letter
+    repeated: Vec<bool>,
+    letters: Vec<Vec<bool>>,
+    random: Option<Random>,
+    index: usize,
+}
+
+impl Iterator for PaddingElements {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if !self.end.is_empty() {
+            return Some(self.end.remove(0));
+        }
+        match self.random.as_mut() {
+            Some(random) => {
+                while self.index >= self.repeated.len() {
+                    self.repeated = self.letters[random.below(26)].clone();
+                    self.index = 0;
+                }
+            }
+            None if self.repeated.is_empty() => return None,
+            None => self.index %= self.repeated.len(),
+        }
+        self.index += 1;
+        Some(self.repeated[self.index - 1])
+    }
+}
+
+// The largest group that the codec does not encode any character to.
+fn null_pattern<AB>(codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> Option<u8> {
+    let group_size = codec.encoded_group_size();
+    (0..1u16 << group_size).rev()
+        .map(|pattern| pattern as u8)
+        .find(|pattern| Groups::from_encoded(&codec.encode_elem(&codec.decode_pattern(*pattern)), codec).complete() != [*pattern])
+}
+
+/// Splits the input to the units that the steganographers process.
+///
+/// With the feature `grapheme-clusters`, a unit is an extended grapheme cluster, so that combining marks, emoji
+/// and ZWJ sequences are never split. Otherwise, every `char` is a unit.
+#[cfg(feature = "grapheme-clusters")]
+pub(crate) fn units(input: &[char]) -> Vec<&[char]> {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let string: String = input.iter().collect();
+    let mut start = 0;
+    string.graphemes(true)
+        .map(|grapheme| {
+            let end = start + grapheme.chars().count();
+            let unit = &input[start..end];
+            start = end;
+            unit
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "grapheme-clusters"))]
+pub(crate) fn units(input: &[char]) -> Vec<&[char]> {
+    input.chunks(1).collect()
+}
+
+/// The first characters of the units of a string, which are found lazily.
+#[cfg(feature = "grapheme-clusters")]
+pub(crate) type UnitStarts<'a> = std::iter::FilterMap<unicode_segmentation::Graphemes<'a>, fn(&str) -> Option<char>>;
+
+#[cfg(not(feature = "grapheme-clusters"))]
+pub(crate) type UnitStarts<'a> = std::str::Chars<'a>;
+
+#[cfg(feature = "grapheme-clusters")]
+pub(crate) fn unit_starts(s: &str) -> UnitStarts<'_> {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    s.graphemes(true).filter_map(|grapheme| grapheme.chars().next())
+}
+
+#[cfg(not(feature = "grapheme-clusters"))]
+pub(crate) fn unit_starts(s: &str) -> UnitStarts<'_> {
+    s.chars()
+}
+
+/// Counts the units of a string that start with an alphabetic character.
+#[cfg(feature = "grapheme-clusters")]
+pub(crate) fn alphabetic_units(s: &str) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    s.graphemes(true)
+        .filter(|grapheme| grapheme.chars().next().map(char::is_alphabetic).unwrap_or(false))
+        .count()
+}
+
+#[cfg(not(feature = "grapheme-clusters"))]
+pub(crate) fn alphabetic_units(s: &str) -> usize {
+    s.chars().filter(|c| c.is_alphabetic()).count()
+}
+
+/// Splits the input to words (runs of alphabetic characters) and the text between them.
+///
+/// Returns `(is_word, text)` tuples.
+pub(crate) fn split_words(input: &[char]) -> Vec<(bool, String)> {
+    let mut tokens: Vec<(bool, String)> = Vec::new();
+    for c in input {
+        let is_word = c.is_alphabetic();
+        match tokens.last_mut() {
+            Some((last_is_word, text)) if *last_is_word == is_word => text.push(*c),
+            _ => tokens.push((is_word, c.to_string())),
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod stega_tests {
+    use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+
+    use super::*;
+
+    #[test]
+    fn split_to_units() {
+        let input: Vec<char> = "ab".chars().collect();
+        assert_eq!(units(&input), vec![&['a'][..], &['b'][..]]);
+        assert_eq!(alphabetic_units("a1 b"), 2);
+    }
+
+    #[test]
+    fn pad_and_unpad() {
+        let codec = CharCodec::new('a', 'b');
+        for padding in [Padding::Repeat, Padding::RandomLetters(42), Padding::Nulls] {
+            let mut groups = codec.encode_groups(&['H', 'I']);
+            padding.pad(&mut groups, 33, &codec);
+            assert_eq!(groups.len(), 33);
+            assert_eq!(padding.unpad(&groups, &codec), vec!['H', 'I'], "{:?}", padding);
+        }
+        let mut groups = codec.encode_groups(&['H', 'I']);
+        Padding::Repeat.pad(&mut groups, 23, &codec);
+        // H = AABBB, I = ABAAA, followed by the end of the secret
+        assert_eq!(groups.complete(), &[7, 8, 31, 7]);
+        assert_eq!(groups.incomplete(), Some(0b01000));
+        let mut groups = codec.encode_groups(&['H', 'I']);
+        Padding::Nulls.pad(&mut groups, 20, &codec);
+        assert_eq!(groups.complete(), &[7, 8, 31, 31]);
+        Padding::None.pad(&mut groups, 40, &codec);
+        assert_eq!(groups.len(), 20);
+        assert_eq!(null_pattern(&codec), Some(31));
+        assert_eq!(null_pattern(&CharCodecV2::new('a', 'b')), Some(31));
+    }
+
+    #[test]
+    fn unpad_the_secrets_that_look_like_padding() {
+        let codec = CharCodecV2::new('a', 'b');
+        for (padding, secret) in [
+            (Padding::Repeat, "LLEL"),
+            (Padding::Repeat, "CC"),
+            (Padding::Repeat, "RR"),
+            (Padding::Repeat, "HAHA"),
+            (Padding::Repeat, "DEAD"),
+            (Padding::RandomLetters(3), "J"),
+            (Padding::Nulls, "Z"),
+        ] {
+            let secret: Vec<char> = secret.chars().collect();
+            // From the capacity that the secret uses exactly
+            for capacity in secret.len() * 5..60 {
+                let mut groups = codec.encode_groups(&secret);
+                padding.pad(&mut groups, capacity, &codec);
+                assert_eq!(padding.unpad(&groups, &codec), secret, "{:?} {}", padding, capacity);
+            }
+        }
+    }
+
+    #[cfg(feature = "grapheme-clusters")]
+    #[test]
+    fn split_to_grapheme_clusters() {
+        let input: Vec<char> = "e\u{301}x👨\u{200d}👩".chars().collect();
+        let units = units(&input);
+        assert_eq!(units.len(), 3);
+        assert_eq!(units[0], &['e', '\u{301}'][..]);
+        assert_eq!(units[2].len(), 3);
+        assert_eq!(alphabetic_units("e\u{301}x"), 2);
+    }
+}
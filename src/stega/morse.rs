@@ -0,0 +1,229 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::ops::Range;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+// The International Morse Code of the letters, the digits and some punctuation
+const CODES: [(char, &str); 44] = [
+    ('A', ".-"), ('B', "-..."), ('C', "-.-."), ('D', "-.."), ('E', "."), ('F', "..-."), ('G', "--."), ('H', "...."),
+    ('I', ".."), ('J', ".---"), ('K', "-.-"), ('L', ".-.."), ('M', "--"), ('N', "-."), ('O', "---"), ('P', ".--."),
+    ('Q', "--.-"), ('R', ".-."), ('S', "..."), ('T', "-"), ('U', "..-"), ('V', "...-"), ('W', ".--"), ('X', "-..-"),
+    ('Y', "-.--"), ('Z', "--.."), ('0', "-----"), ('1', ".----"), ('2', "..---"), ('3', "...--"), ('4', "....-"),
+    ('5', "....."), ('6', "-...."), ('7', "--..."), ('8', "---.."), ('9', "----."), ('.', ".-.-.-"), (',', "--..--"),
+    ('?', "..--.."), ('/', "-..-."), ('=', "-...-"), ('+', ".-.-."), ('@', ".--.-."), ('!', "-.-.--"),
+];
+
+/// Transcribes a text to Morse code, with the letters separated by single spaces and the words by ` / `.
+///
+/// The characters that do not have a Morse code are skipped.
+pub fn to_morse(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter_map(|c| CODES.iter().find(|(letter, _)| *letter == c.to_ascii_uppercase()))
+                .map(|(_, code)| *code)
+                .collect::<Vec<&str>>()
+                .join(" ")
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<String>>()
+        .join(" / ")
+}
+
+/// Reads the text of a Morse transcript, regardless of its spacing. Unknown codes are read as `?`.
+pub fn from_morse(transcript: &[char]) -> String {
+    let mut text = String::new();
+    for token in tokenize(transcript) {
+        match token {
+            MorseToken::Code(range) => {
+                let code: String = transcript[range].iter().collect();
+                text.push(CODES.iter().find(|(_, known)| *known == code).map(|(letter, _)| *letter).unwrap_or('?'));
+            }
+            MorseToken::WordBreak(_) => text.push(' '),
+            MorseToken::Gap(_) | MorseToken::Other(_) => {}
+        }
+    }
+    text
+}
+
+/// A token of a Morse transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MorseToken {
+    /// The dots and dashes of a letter.
+    Code(Range<usize>),
+    /// The spaces between two tokens.
+    Gap(Range<usize>),
+    /// A `/` or a `|` that separates words.
+    WordBreak(Range<usize>),
+    /// Any other character, like a line feed.
+    Other(Range<usize>),
+}
+
+impl MorseToken {
+    pub fn range(&self) -> &Range<usize> {
+        match self {
+            MorseToken::Code(range) | MorseToken::Gap(range) | MorseToken::WordBreak(range) | MorseToken::Other(range) => range,
+        }
+    }
+}
+
+/// Splits a Morse transcript to its tokens.
+pub fn tokenize(transcript: &[char]) -> Vec<MorseToken> {
+    let mut tokens: Vec<MorseToken> = Vec::new();
+    for (position, c) in transcript.iter().enumerate() {
+        let extends = match (tokens.last_mut(), c) {
+            (Some(MorseToken::Code(range)), '.') | (Some(MorseToken::Code(range)), '-') | (Some(MorseToken::Gap(range)), ' ') => {
+                range.end = position + 1;
+                true
+            }
+            _ => false,
+        };
+        if !extends {
+            let range = position..position + 1;
+            tokens.push(match c {
+                '.' | '-' => MorseToken::Code(range),
+                ' ' => MorseToken::Gap(range),
+                '/' | '|' => MorseToken::WordBreak(range),
+                _ => MorseToken::Other(range),
+            });
+        }
+    }
+    tokens
+}
+
+/// Applies steganography based on the spacing of the letters of a Morse transcript, like the logs of radio amateurs.
+///
+/// A single space between two letters of a word carries the Bacon's element A and a double space carries the Bacon's
+/// element B. The spaces around word breaks are kept. A cover transcript can be created with [to_morse](fn.to_morse.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MorseSteganographer;
+
+impl MorseSteganographer {
+    pub fn new() -> MorseSteganographer {
+        MorseSteganographer
+    }
+
+    // The gaps of one or two spaces between two letters.
+    fn gaps(&self, input: &[char]) -> Vec<Range<usize>> {
+        tokenize(input).windows(3)
+            .filter_map(|window| match window {
+                [MorseToken::Code(_), MorseToken::Gap(gap), MorseToken::Code(_)] if gap.len() <= 2 => Some(gap.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Default for MorseSteganographer {
+    fn default() -> MorseSteganographer {
+        MorseSteganographer::new()
+    }
+}
+
+impl Steganographer for MorseSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.gaps(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let gaps = self.gaps(public);
+        let encoded = codec.encode(secret);
+        if gaps.len() < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available: gaps.len(),
+                unit: CapacityUnit::Spaces,
+            });
+        }
+        let mut disguised = Vec::with_capacity(public.len() + encoded.len());
+        let mut copied = 0;
+        for (gap, elem) in gaps.iter().zip(encoded.iter()) {
+            disguised.extend_from_slice(&public[copied..gap.start]);
+            disguised.extend_from_slice(if codec.is_a(elem) { &[' '] } else { &[' ', ' '] });
+            copied = gap.end;
+        }
+        disguised.extend_from_slice(&public[copied..]);
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = self.gaps(input).iter()
+            .map(|gap| if gap.len() == 1 { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(self.gaps(input).iter()
+            .map(|gap| Slot::new(gap.start, if gap.len() == 1 { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod morse_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn transcribe() {
+        let transcript = to_morse("CQ de W1AW");
+        assert_eq!(transcript, "-.-. --.- / -.. . / .-- .---- .- .--");
+        assert_eq!(from_morse(&Vec::from_iter(transcript.chars())), "CQ DE W1AW");
+        assert_eq!(from_morse(&Vec::from_iter("...  ---\n...... .".chars())), "SO?E");
+        let tokens = tokenize(&Vec::from_iter("-. / .".chars()));
+        assert_eq!(tokens, vec![
+            MorseToken::Code(0..2),
+            MorseToken::Gap(2..3),
+            MorseToken::WordBreak(3..4),
+            MorseToken::Gap(4..5),
+            MorseToken::Code(5..6),
+        ]);
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MorseSteganographer::new();
+        let public = Vec::from_iter(to_morse("CQ CQ CQ de W1AW W1AW K").chars());
+        assert_eq!(s.capacity(&public).unwrap(), 10);
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()),
+                   "-.-. --.- / -.-. --.- / -.-.  --.- / -..  . / .--  .---- .-  .-- / .-- .---- .- .-- / -.-");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['H', 'I']);
+        assert_eq!(from_morse(&disguised), "CQ CQ CQ DE W1AW W1AW K");
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let public = Vec::from_iter(to_morse("CQ de W1AW").chars());
+        assert_eq!(MorseSteganographer::new().disguise(&['H', 'I'], &public, &codec), Err(BaconError::InsufficientCapacity {
+            required: 10,
+            available: 5,
+            unit: CapacityUnit::Spaces,
+        }));
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::BaconError;
+use crate::random::Random;
+use crate::stega::split_words;
+use crate::wordlist::WordList;
+
+/// A null cipher that produces one word per Bacon's element.
+///
+/// The words that carry the Bacon's element A are chosen from one word list and the words that carry the
+/// Bacon's element B from another, disjoint one. The choice among the words of a list is pseudo-random and depends on a seed.
+///
+/// The disguise does not use the public input: the output consists only of words of the two lists.
+/// The reveal classifies every word of the input and ignores the words that belong to none of the lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullCipherSteganographer {
+    a_words: WordList,
+    b_words: WordList,
+    seed: u64,
+}
+
+impl NullCipherSteganographer {
+    pub fn new(a_words: WordList, b_words: WordList) -> errors::Result<NullCipherSteganographer> {
+        if a_words.is_empty() || b_words.is_empty() {
+            Err(BaconError::SteganographerError("The word lists should not be empty".to_string()))
+        } else if let Some(word) = a_words.words().iter().find(|a| b_words.words().iter().any(|b| b.to_lowercase() == a.to_lowercase())) {
+            Err(BaconError::SteganographerError(format!("The word lists should be disjoint. {:?} is found in both", word)))
+        } else {
+            Ok(NullCipherSteganographer { a_words, b_words, seed: 0 })
+        }
+    }
+
+    /// Sets the seed for the selection of the words. The same seed always produces the same output.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the number of Bacon's elements that the input carries.
+    pub fn carried_elements(&self, input: &[char]) -> usize {
+        split_words(input).iter()
+            .filter(|(is_word, word)| *is_word && self.classify(word).is_some())
+            .count()
+    }
+
+    /// Returns the number of words that are needed to disguise the secret.
+    pub fn required_words<AB>(&self, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> usize {
+        secret.iter().filter(|c| c.is_alphabetic()).count() * codec.encoded_group_size()
+    }
+
+    // Returns Some(true) for a word of the A list, Some(false) for a word of the B list and None otherwise.
+    fn classify(&self, word: &str) -> Option<bool> {
+        let lowercase = word.to_lowercase();
+        if self.a_words.words().iter().any(|a| a.to_lowercase() == lowercase) {
+            Some(true)
+        } else if self.b_words.words().iter().any(|b| b.to_lowercase() == lowercase) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl Steganographer for NullCipherSteganographer {
+    type T = char;
+
+    /// The output is generated, so the capacity is `usize::MAX`. See
+    /// [carried_elements](struct.NullCipherSteganographer.html#method.carried_elements) for the elements that an input carries.
+    fn capacity(&self, _public: &[char]) -> errors::Result<usize> {
+        Ok(usize::MAX)
+    }
+
+    fn disguise<AB>(&self, secret: &[char], _public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let mut random = Random::new(self.seed);
+        let words: Vec<&str> = codec.encode(secret).iter()
+            .map(|elem| {
+                let list = if codec.is_a(elem) { self.a_words.words() } else { self.b_words.words() };
+                list[random.below(list.len())].as_str()
+            })
+            .collect();
+        Ok(words.join(" ").chars().collect())
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = split_words(input).iter()
+            .filter(|(is_word, _)| *is_word)
+            .filter_map(|(_, word)| self.classify(word))
+            .map(|a| if a { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+}
+
+#[cfg(test)]
+mod null_cipher_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    fn steganographer() -> NullCipherSteganographer {
+        NullCipherSteganographer::new(
+            WordList::parse("sun moon star sky"),
+            WordList::parse("river lake sea ocean")).unwrap()
+    }
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(NullCipherSteganographer::new(WordList::default(), WordList::parse("sea")).is_err());
+        assert!(NullCipherSteganographer::new(WordList::parse("sun Sea"), WordList::parse("sea")).is_err());
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = steganographer().with_seed(11);
+        let secret = ['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'];
+        let disguised = s.disguise(&secret, &[], &codec).unwrap();
+        assert_eq!(s.carried_elements(&disguised), s.required_words(&secret, &codec));
+        assert_eq!(disguised, s.disguise(&secret, &[], &codec).unwrap());
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "MYSECRET");
+    }
+
+    #[test]
+    fn reveal_ignores_unknown_words() {
+        let codec = CharCodec::new('a', 'b');
+        let s = steganographer();
+        // H = AABBB
+        let public = "Sun, and the moon; a river by the lake, the sea!";
+        let output = s.reveal(&Vec::from_iter(public.chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "H");
+        assert_eq!(s.carried_elements(&Vec::from_iter(public.chars())), 5);
+        assert_eq!(Steganographer::capacity(&s, &Vec::from_iter(public.chars())), Ok(usize::MAX));
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::ops::Range;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// The kind of a percent-encoded byte of a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeKind {
+    /// The hexadecimal digits are decimal digits only, like `%20`, so the escape has no case.
+    Numeric,
+    /// The first hexadecimal digit that is a letter is uppercase, like `%2F`.
+    Uppercase,
+    /// The first hexadecimal digit that is a letter is lowercase, like `%2f`.
+    Lowercase,
+}
+
+/// A percent-encoded byte of a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Escape {
+    position: usize,
+    byte: u8,
+    kind: EscapeKind,
+}
+
+impl Escape {
+    /// The position of the `%` in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The byte that is encoded.
+    pub fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    pub fn kind(&self) -> EscapeKind {
+        self.kind
+    }
+
+    /// Whether the encoded byte is an unreserved character of [RFC 3986](https://tools.ietf.org/html/rfc3986#section-2.3),
+    /// which does not need to be encoded.
+    pub fn is_unreserved(&self) -> bool {
+        is_unreserved(self.byte as char)
+    }
+}
+
+/// Finds the URLs of the input, like `https://example.com/a%2Fb`.
+///
+/// A URL starts with a scheme that is followed by `://` and ends before whitespace, `<`, `>` or `"`.
+pub fn urls(input: &[char]) -> Vec<Range<usize>> {
+    let mut urls = Vec::new();
+    let mut index = 0;
+    while index + 3 <= input.len() {
+        if input[index..].starts_with(&[':', '/', '/']) {
+            let start = input[..index].iter()
+                .rposition(|c| !(c.is_ascii_alphanumeric() || *c == '+' || *c == '.' || *c == '-'))
+                .map(|position| position + 1)
+                .unwrap_or(0);
+            let start = (start..index).find(|position| input[*position].is_ascii_alphabetic()).unwrap_or(index);
+            let end = input[index..].iter()
+                .position(|c| c.is_whitespace() || *c == '<' || *c == '>' || *c == '"')
+                .map(|position| index + position)
+                .unwrap_or(input.len());
+            if start < index {
+                urls.push(start..end);
+            }
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+    urls
+}
+
+/// Parses and classifies the percent-encoded bytes of the URLs of the input.
+pub fn escapes(input: &[char]) -> Vec<Escape> {
+    urls(input).into_iter()
+        .flat_map(|url| url.clone().filter(move |position| input[*position] == '%' && position + 3 <= url.end))
+        .filter_map(|position| escape_at(input, position))
+        .collect()
+}
+
+fn escape_at(input: &[char], position: usize) -> Option<Escape> {
+    let digits = input.get(position + 1..position + 3)?;
+    let high = digits[0].to_digit(16)?;
+    let low = digits[1].to_digit(16)?;
+    let kind = match digits.iter().find(|digit| digit.is_ascii_alphabetic()) {
+        Some(digit) if digit.is_ascii_uppercase() => EscapeKind::Uppercase,
+        Some(_) => EscapeKind::Lowercase,
+        None => EscapeKind::Numeric,
+    };
+    Some(Escape { position, byte: (high * 16 + low) as u8, kind })
+}
+
+pub(crate) fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_' || c == '~'
+}
+
+// A place of a URL that carries a Bacon's element.
+enum Site {
+    // An escape that has a case, which is A if it is uppercase
+    Case(usize, bool),
+    // An unreserved character, which is A if it is literal and B if it is percent-encoded
+    Unreserved(usize, bool),
+}
+
+impl Site {
+    fn position(&self) -> usize {
+        match self {
+            Site::Case(position, _) | Site::Unreserved(position, _) => *position,
+        }
+    }
+
+    fn is_a(&self) -> bool {
+        match self {
+            Site::Case(_, a) | Site::Unreserved(_, a) => *a,
+        }
+    }
+}
+
+/// Applies steganography based on the case of the hexadecimal digits of the percent-encoded bytes of URLs.
+///
+/// An escape with uppercase digits, like `%2F`, carries the Bacon's element A and an escape with lowercase digits,
+/// like `%2f`, carries the Bacon's element B. Escapes with decimal digits only, like `%20`, are skipped.
+///
+/// Optionally, the unreserved characters of the path, the query and the fragment of the URLs carry elements too:
+/// a literal character carries the Bacon's element A and a percent-encoded one, like `%61` for `a`, carries the
+/// Bacon's element B. Both forms are equivalent, according to [RFC 3986](https://tools.ietf.org/html/rfc3986#section-6.2.2.2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PercentEncodingSteganographer {
+    unreserved: bool,
+}
+
+impl PercentEncodingSteganographer {
+    /// Creates a `PercentEncodingSteganographer` that uses the case of the escapes only.
+    pub fn new() -> PercentEncodingSteganographer {
+        PercentEncodingSteganographer { unreserved: false }
+    }
+
+    /// Whether the optional encoding of the unreserved characters carries elements too.
+    pub fn with_unreserved(mut self, unreserved: bool) -> Self {
+        self.set_unreserved(unreserved);
+        self
+    }
+
+    pub fn set_unreserved(&mut self, unreserved: bool) {
+        self.unreserved = unreserved;
+    }
+
+    pub fn unreserved(&self) -> bool {
+        self.unreserved
+    }
+
+    fn sites(&self, input: &[char]) -> Vec<Site> {
+        let mut sites = Vec::new();
+        for url in urls(input) {
+            // The path starts after the authority
+            let authority = url.start + input[url.clone()].iter().position(|c| *c == ':').unwrap_or(0) + 3;
+            let path = (authority..url.end)
+                .find(|position| matches!(input[*position], '/' | '?' | '#'))
+                .unwrap_or(url.end);
+            let mut position = url.start;
+            while position < url.end {
+                let escape = if input[position] == '%' && position + 3 <= url.end {
+                    escape_at(input, position)
+                } else {
+                    None
+                };
+                match escape {
+                    Some(escape) if self.unreserved && position >= path && escape.is_unreserved() => {
+                        sites.push(Site::Unreserved(position, false));
+                        position += 3;
+                    }
+                    Some(escape) => {
+                        if escape.kind != EscapeKind::Numeric {
+                            sites.push(Site::Case(position, escape.kind == EscapeKind::Uppercase));
+                        }
+                        position += 3;
+                    }
+                    None => {
+                        if self.unreserved && position >= path && is_unreserved(input[position]) {
+                            sites.push(Site::Unreserved(position, true));
+                        }
+                        position += 1;
+                    }
+                }
+            }
+        }
+        sites
+    }
+}
+
+impl Default for PercentEncodingSteganographer {
+    fn default() -> PercentEncodingSteganographer {
+        PercentEncodingSteganographer::new()
+    }
+}
+
+impl Steganographer for PercentEncodingSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.sites(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let sites = self.sites(public);
+        let encoded = codec.encode(secret);
+        if sites.len() < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available: sites.len(),
+                unit: CapacityUnit::Carriers,
+            });
+        }
+        let mut disguised = Vec::with_capacity(public.len() + 2 * encoded.len());
+        let mut copied = 0;
+        for (site, elem) in sites.iter().zip(encoded.iter()) {
+            let a = codec.is_a(elem);
+            disguised.extend_from_slice(&public[copied..site.position()]);
+            match site {
+                Site::Case(position, _) => {
+                    disguised.push('%');
+                    disguised.extend(public[position + 1..position + 3].iter()
+                        .map(|digit| if a { digit.to_ascii_uppercase() } else { digit.to_ascii_lowercase() }));
+                    copied = position + 3;
+                }
+                Site::Unreserved(position, true) => {
+                    if a {
+                        disguised.push(public[*position]);
+                    } else {
+                        disguised.extend(format!("%{:02X}", public[*position] as u32).chars());
+                    }
+                    copied = position + 1;
+                }
+                Site::Unreserved(position, false) => {
+                    if a {
+                        let byte = escape_at(public, *position).map(|escape| escape.byte).unwrap_or_default();
+                        disguised.push(byte as char);
+                    } else {
+                        disguised.extend_from_slice(&public[*position..position + 3]);
+                    }
+                    copied = position + 3;
+                }
+            }
+        }
+        disguised.extend_from_slice(&public[copied..]);
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = self.sites(input).iter()
+            .map(|site| if site.is_a() { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(self.sites(input).iter()
+            .map(|site| Slot::new(site.position(), if site.is_a() { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod percent_encoding_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    const PUBLIC: &str = "See https://example.com/a%2Fb%3a%20c?q=%7E%2c%5B%5d and <http://x.org/%C3%A9%e2%82%ac>.";
+
+    #[test]
+    fn parse_escapes() {
+        let input = Vec::from_iter(PUBLIC.chars());
+        let urls: Vec<String> = urls(&input).into_iter().map(|url| String::from_iter(&input[url])).collect();
+        assert_eq!(urls, vec!["https://example.com/a%2Fb%3a%20c?q=%7E%2c%5B%5d", "http://x.org/%C3%A9%e2%82%ac"]);
+        let found = escapes(&input);
+        assert_eq!(found.len(), 12);
+        assert_eq!(found[0], Escape { position: 25, byte: b'/', kind: EscapeKind::Uppercase });
+        assert_eq!(found[1].kind(), EscapeKind::Lowercase);
+        assert_eq!(found[2].kind(), EscapeKind::Numeric);
+        assert!(found[3].is_unreserved());
+        assert!(escapes(&Vec::from_iter("100% sure, %2F".chars())).is_empty());
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PercentEncodingSteganographer::new();
+        let public = Vec::from_iter(PUBLIC.chars());
+        assert_eq!(s.capacity(&public).unwrap(), 10);
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()),
+                   "See https://example.com/a%2Fb%3A%20c?q=%7e%2c%5b%5D and <http://x.org/%c3%A9%E2%82%AC>.");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['H', 'I']);
+    }
+
+    #[test]
+    fn disguise_with_unreserved_characters() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PercentEncodingSteganographer::new().with_unreserved(true);
+        let public = Vec::from_iter("https://example.com/%7Eme/%2fdocs".chars());
+        let disguised = s.disguise(&['H'], &public, &codec).unwrap();
+        // H = AABBB, where %7E is the unreserved ~ and the host is skipped
+        assert_eq!(String::from_iter(disguised.iter()), "https://example.com/~m%65/%2f%64ocs");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[0], 'H');
+        assert!(s.disguise(&['H', 'I'], &public, &codec).is_err());
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::io::{Read, Write};
+
+use flate2::Compression;
+use flate2::Crc;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::Carrier;
+use crate::errors::{BaconError, SharedError};
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// The default keyword of the text chunks.
+pub const DEFAULT_KEYWORD: &str = "Comment";
+
+/// Hides a secret in the textual metadata of a PNG image, without touching the image data.
+///
+/// The secret is disguised in a public text by another steganographer and the disguised text is stored in a text chunk
+/// with an innocuous keyword, like `Comment`. The chunk is a `tEXt` chunk, or a compressed `zTXt` chunk, if the text
+/// is Latin-1. Otherwise, it is an `iTXt` chunk, which contains UTF-8 text.
+///
+/// The disguise replaces the text chunks that have the same keyword. The reveal scans all the text chunks of the image
+/// and reveals the secret from the texts of the chunks that have the keyword, in the order that they appear.
+///
+/// As a [Carrier](../../carrier/trait.Carrier.html) of images, it writes the elements to the texts of the chunks that
+/// have the keyword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PngTextSteganographer<S> {
+    steganographer: S,
+    keyword: String,
+    compressed: bool,
+}
+
+impl<S: Steganographer<T=char>> PngTextSteganographer<S> {
+    /// Creates a `PngTextSteganographer` that disguises the secret with the `steganographer`, using the
+    /// [DEFAULT_KEYWORD](constant.DEFAULT_KEYWORD.html) and uncompressed chunks.
+    pub fn new(steganographer: S) -> PngTextSteganographer<S> {
+        PngTextSteganographer {
+            steganographer,
+            keyword: DEFAULT_KEYWORD.to_string(),
+            compressed: false,
+        }
+    }
+
+    /// Sets the keyword of the text chunk. A keyword contains 1 to 79 printable Latin-1 characters and no leading,
+    /// trailing or consecutive spaces.
+    pub fn with_keyword(mut self, keyword: &str) -> Self {
+        self.set_keyword(keyword);
+        self
+    }
+
+    pub fn set_keyword(&mut self, keyword: &str) {
+        self.keyword = keyword.to_string();
+    }
+
+    /// Sets whether the text is compressed, in a `zTXt` chunk.
+    pub fn with_compression(mut self, compressed: bool) -> Self {
+        self.set_compression(compressed);
+        self
+    }
+
+    pub fn set_compression(&mut self, compressed: bool) {
+        self.compressed = compressed;
+    }
+
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+
+    /// Disguises the _secret_ in the _public_ text and adds the disguised text to the _png_ image, in a text chunk
+    /// before the `IEND` chunk.
+    pub fn disguise<AB>(&self, secret: &[char], public: &[char], png: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<u8>> {
+        let disguised = self.steganographer.disguise(secret, public, codec)?;
+        self.write_text(&disguised.iter().collect::<String>(), png)
+    }
+
+    // Replaces the text chunks of the keyword with a chunk of the text.
+    fn write_text(&self, text: &str, png: &[u8]) -> errors::Result<Vec<u8>> {
+        let keyword = latin1(&self.keyword)
+            .filter(|keyword| is_valid_keyword(keyword))
+            .ok_or_else(|| BaconError::SteganographerError(format!("Invalid PNG keyword: {:?}", self.keyword)))?;
+        let chunk = match (latin1(text), self.compressed) {
+            (Some(text), false) => (*b"tEXt", [&keyword[..], &[0], &text].concat()),
+            (Some(text), true) => (*b"zTXt", [&keyword[..], &[0, 0], &compress(&text)?].concat()),
+            (None, _) => (*b"iTXt", [&keyword[..], &[0, 0, 0, 0, 0], text.as_bytes()].concat()),
+        };
+
+        let chunks = chunks(png)?;
+        if !chunks.iter().any(|chunk| &chunk.kind == b"IEND") {
+            return Err(invalid("The PNG image does not have an IEND chunk", png.len()));
+        }
+        let mut output = Vec::with_capacity(png.len() + chunk.1.len() + 12);
+        output.extend_from_slice(&SIGNATURE);
+        for existing in &chunks {
+            if &existing.kind == b"IEND" {
+                write_chunk(&mut output, &chunk.0, &chunk.1);
+            }
+            let replaced = text_of(existing)?.map(|(keyword, _)| keyword == self.keyword).unwrap_or(false);
+            if !replaced {
+                write_chunk(&mut output, &existing.kind, existing.data);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Reveals the secret that is hidden in the text chunks of the _png_ image that have the keyword.
+    pub fn reveal<AB>(&self, png: &[u8], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let texts = self.texts(png)?;
+        if texts.is_empty() {
+            return Err(invalid(&format!("The PNG image does not have any text chunk with the keyword {}", self.keyword), png.len()));
+        }
+        let text: Vec<char> = texts.concat().chars().collect();
+        self.steganographer.reveal(&text, codec)
+    }
+
+    /// The texts of all the `tEXt`, `zTXt` and `iTXt` chunks of the _png_ image that have the keyword.
+    pub fn texts(&self, png: &[u8]) -> errors::Result<Vec<String>> {
+        let mut texts = Vec::new();
+        for chunk in chunks(png)? {
+            if let Some((keyword, text)) = text_of(&chunk)? {
+                if keyword == self.keyword {
+                    texts.push(text);
+                }
+            }
+        }
+        Ok(texts)
+    }
+}
+
+impl<S: Steganographer<T=char>> Carrier for PngTextSteganographer<S> {
+    type Unit = u8;
+
+    fn capacity(&self, png: &[u8]) -> errors::Result<usize> {
+        Steganographer::capacity(&self.steganographer, &self.cover(png)?)
+    }
+
+    fn write_symbols(&self, symbols: &[bool], png: &[u8]) -> errors::Result<Vec<u8>> {
+        let written = self.steganographer.write_symbols(symbols, &self.cover(png)?)?;
+        self.write_text(&written.iter().collect::<String>(), png)
+    }
+
+    fn read_symbols(&self, png: &[u8]) -> errors::Result<Vec<bool>> {
+        self.steganographer.read_symbols(&self.cover(png)?)
+    }
+}
+
+impl<S: Steganographer<T=char>> PngTextSteganographer<S> {
+    // The texts of the keyword, which are empty if the image does not have any
+    fn cover(&self, png: &[u8]) -> errors::Result<Vec<char>> {
+        Ok(self.texts(png)?.concat().chars().collect())
+    }
+}
+
+// A chunk of a PNG image, at the byte `offset` of its length field.
+struct Chunk<'a> {
+    offset: usize,
+    kind: [u8; 4],
+    data: &'a [u8],
+}
+
+fn invalid(message: &str, position: usize) -> BaconError {
+    BaconError::InvalidInput { message: message.to_string(), position: Some(position), source: None }
+}
+
+// Splits a PNG image to its chunks, verifying their CRCs.
+fn chunks(png: &[u8]) -> errors::Result<Vec<Chunk<'_>>> {
+    if !png.starts_with(&SIGNATURE) {
+        return Err(invalid("Not a PNG image", 0));
+    }
+    let mut chunks = Vec::new();
+    let mut offset = SIGNATURE.len();
+    while offset < png.len() {
+        if png.len() - offset < 12 {
+            return Err(invalid("Truncated PNG chunk", offset));
+        }
+        let length = u32::from_be_bytes([png[offset], png[offset + 1], png[offset + 2], png[offset + 3]]) as usize;
+        if png.len() - offset - 12 < length {
+            return Err(invalid("Truncated PNG chunk", offset));
+        }
+        let kind = [png[offset + 4], png[offset + 5], png[offset + 6], png[offset + 7]];
+        let data = &png[offset + 8..offset + 8 + length];
+        let crc_offset = offset + 8 + length;
+        let expected = u32::from_be_bytes([png[crc_offset], png[crc_offset + 1], png[crc_offset + 2], png[crc_offset + 3]]);
+        if crc(&kind, data) != expected {
+            return Err(invalid("Invalid CRC of PNG chunk", crc_offset));
+        }
+        chunks.push(Chunk { offset, kind, data });
+        offset = crc_offset + 4;
+    }
+    Ok(chunks)
+}
+
+fn crc(kind: &[u8], data: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    crc.sum()
+}
+
+fn write_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    output.extend_from_slice(kind);
+    output.extend_from_slice(data);
+    output.extend_from_slice(&crc(kind, data).to_be_bytes());
+}
+
+// The Latin-1 encoding of the text, if all its characters are Latin-1.
+fn latin1(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .map(|c| if (c as u32) < 256 { Some(c as u8) } else { None })
+        .collect()
+}
+
+fn from_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| *b as char).collect()
+}
+
+fn is_valid_keyword(keyword: &[u8]) -> bool {
+    !keyword.is_empty() && keyword.len() < 80 &&
+        keyword.iter().all(|b| (32..=126).contains(b) || *b >= 161) &&
+        keyword[0] != b' ' && keyword[keyword.len() - 1] != b' ' &&
+        !keyword.windows(2).any(|pair| pair == b"  ")
+}
+
+fn compress(data: &[u8]) -> errors::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress(data: &[u8], offset: usize) -> errors::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    ZlibDecoder::new(data).read_to_end(&mut decompressed)
+        .map_err(|error| BaconError::InvalidInput {
+            message: "Invalid compressed text of PNG chunk".to_string(),
+            position: Some(offset),
+            source: Some(SharedError::new(error)),
+        })?;
+    Ok(decompressed)
+}
+
+// The keyword and the text of a text chunk, or None for the rest of the chunks.
+fn text_of(chunk: &Chunk) -> errors::Result<Option<(String, String)>> {
+    let (keyword, rest) = match chunk.data.iter().position(|b| *b == 0) {
+        Some(index) => (from_latin1(&chunk.data[..index]), &chunk.data[index + 1..]),
+        None => return Ok(None),
+    };
+    match &chunk.kind {
+        b"tEXt" => Ok(Some((keyword, from_latin1(rest)))),
+        b"zTXt" if !rest.is_empty() => Ok(Some((keyword, from_latin1(&decompress(&rest[1..], chunk.offset)?)))),
+        b"iTXt" if rest.len() >= 2 => {
+            let compressed = rest[0] == 1;
+            // Skip the language tag and the translated keyword
+            let mut fields = rest[2..].splitn(3, |b| *b == 0);
+            let text = match (fields.next(), fields.next(), fields.next()) {
+                (Some(_), Some(_), Some(text)) => text,
+                _ => return Err(invalid("Invalid iTXt chunk", chunk.offset)),
+            };
+            let text = if compressed { decompress(text, chunk.offset)? } else { text.to_vec() };
+            Ok(Some((keyword, String::from_utf8(text).map_err(|error| BaconError::from(error.utf8_error()))?)))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod png_text_tests {
+    use std::iter::FromIterator;
+
+    use crate::carrier;
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::math_alphanumeric::{LetterStyle, MathAlphanumericSteganographer};
+
+    use super::*;
+
+    // A grayscale image of 1x1 pixels
+    fn png() -> Vec<u8> {
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]);
+        write_chunk(&mut png, b"tEXt", b"Software\0GIMP 2.10");
+        write_chunk(&mut png, b"IDAT", &compress(&[0, 0]).unwrap());
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        for compressed in [false, true] {
+            let s = PngTextSteganographer::new(LetterCaseSteganographer::new()).with_compression(compressed);
+            let output = s.disguise(&Vec::from_iter("My secret".chars()), &public, &png(), &codec).unwrap();
+            let kinds: Vec<[u8; 4]> = chunks(&output).unwrap().iter().map(|chunk| chunk.kind).collect();
+            let kind = if compressed { *b"zTXt" } else { *b"tEXt" };
+            assert_eq!(kinds, vec![*b"IHDR", *b"tEXt", *b"IDAT", kind, *b"IEND"]);
+            assert_eq!(s.texts(&output).unwrap(), vec!["tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one".to_string()]);
+            assert!(String::from_iter(s.reveal(&output, &codec).unwrap().iter()).starts_with("MYSECRET"));
+        }
+    }
+
+    #[test]
+    fn disguise_unicode_text() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PngTextSteganographer::new(MathAlphanumericSteganographer::new(LetterStyle::Plain, LetterStyle::Bold).unwrap()).with_keyword("Description");
+        let output = s.disguise(&['H'], &Vec::from_iter("Hello".chars()), &png(), &codec).unwrap();
+        assert_eq!(chunks(&output).unwrap()[3].kind, *b"iTXt");
+        assert_eq!(s.reveal(&output, &codec).unwrap(), vec!['H']);
+    }
+
+    #[test]
+    fn carrier_of_images() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PngTextSteganographer::new(LetterCaseSteganographer::new());
+        let png = s.write_text("Hello world", &png()).unwrap();
+        assert_eq!(Carrier::capacity(&s, &png).unwrap(), 10);
+        let output = carrier::embed(&s, &['H'], &png, &codec).unwrap();
+        // H = AABBB, and the text of the keyword is replaced
+        assert_eq!(s.texts(&output).unwrap(), vec!["heLLO world".to_string()]);
+        assert_eq!(chunks(&output).unwrap().len(), 5);
+        assert_eq!(carrier::extract(&s, &output, &codec).unwrap(), vec!['H', 'A']);
+    }
+
+    #[test]
+    fn invalid_images() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PngTextSteganographer::new(LetterCaseSteganographer::new());
+        assert!(matches!(s.reveal(&png(), &codec), Err(BaconError::InvalidInput { .. })));
+        assert!(matches!(s.reveal(b"GIF89a", &codec), Err(BaconError::InvalidInput { position: Some(0), .. })));
+        let mut corrupted = png();
+        corrupted[40] ^= 1;
+        assert!(matches!(s.reveal(&corrupted, &codec), Err(BaconError::InvalidInput { .. })));
+        let s = s.with_keyword(" Comment");
+        assert!(matches!(s.disguise(&['H'], &['h'; 5], &png(), &codec), Err(BaconError::SteganographerError(_))));
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::ops::Range;
+
+#[cfg(feature = "regex-steganography")]
+use regex::Regex;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::Carrier;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::stega::percent_encoding;
+
+/// A region of the public text that may not be altered.
+#[derive(Debug, Clone)]
+pub enum Region {
+    /// The characters at the positions of the range.
+    Range(Range<usize>),
+    /// The URLs, as they are found by [urls](../percent_encoding/fn.urls.html).
+    Urls,
+    /// The fenced code blocks and the code spans of Markdown, which are delimited by backticks.
+    Code,
+    /// The matches of a regular expression, like the proper names of a text. (needs the feature `regex-steganography`)
+    #[cfg(feature = "regex-steganography")]
+    Pattern(Regex),
+}
+
+impl Region {
+    /// Creates a region of the matches of a regular expression.
+    #[cfg(feature = "regex-steganography")]
+    pub fn pattern(pattern: &str) -> errors::Result<Region> {
+        Regex::new(pattern)
+            .map(Region::Pattern)
+            .map_err(|error| BaconError::SteganographerError(format!("Invalid pattern {:?}: {}", pattern, error)))
+    }
+
+    // The ranges of the region in the input.
+    fn ranges(&self, input: &[char]) -> Vec<Range<usize>> {
+        match self {
+            Region::Range(range) => {
+                let clamped = range.start.min(input.len())..range.end.min(input.len());
+                vec![clamped]
+            }
+            Region::Urls => percent_encoding::urls(input),
+            Region::Code => code(input),
+            #[cfg(feature = "regex-steganography")]
+            Region::Pattern(regex) => {
+                let text: String = input.iter().collect();
+                let offsets: Vec<usize> = text.char_indices().map(|(offset, _)| offset).collect();
+                let position = |offset: usize| offsets.binary_search(&offset).unwrap_or_else(|position| position);
+                regex.find_iter(&text)
+                    .map(|found| position(found.start())..position(found.end()))
+                    .collect()
+            }
+        }
+    }
+}
+
+// The ranges of the code that is delimited by runs of backticks of the same length.
+fn code(input: &[char]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut position = 0;
+    while position < input.len() {
+        if input[position] != '`' {
+            position += 1;
+            continue;
+        }
+        let ticks = input[position..].iter().take_while(|c| **c == '`').count();
+        let mut end = position + ticks;
+        let mut closing = None;
+        while end < input.len() {
+            let run = input[end..].iter().take_while(|c| **c == '`').count();
+            if run == ticks {
+                closing = Some(end + run);
+                break;
+            }
+            end += run.max(1);
+        }
+        match closing {
+            Some(closing) => {
+                ranges.push(position..closing);
+                position = closing;
+            }
+            None => position += ticks,
+        }
+    }
+    ranges
+}
+
+/// Protects regions of the public text from another steganographer, so that they remain verbatim.
+///
+/// The disguise does not alter the characters of the [Region](enum.Region.html)s and the reveal skips them
+/// identically. The rest of the text is disguised in segments, as the wrapped steganographer
+/// [carries](../../carrier/trait.Carrier.html) them.
+///
+/// The regions should be found in the disguised text as they are found in the public text. Since the disguise may
+/// change the length of the text, ranges of positions are suitable for steganographers that replace characters,
+/// like the [LetterCaseSteganographer](../letter_case/struct.LetterCaseSteganographer.html).
+#[derive(Debug, Clone)]
+pub struct ProtectedSteganographer<S> {
+    steganographer: S,
+    regions: Vec<Region>,
+}
+
+impl<S: Steganographer<T=char>> ProtectedSteganographer<S> {
+    /// Creates a `ProtectedSteganographer` that wraps the `steganographer`, without regions.
+    pub fn new(steganographer: S) -> ProtectedSteganographer<S> {
+        ProtectedSteganographer { steganographer, regions: Vec::new() }
+    }
+
+    /// Adds a region that may not be altered.
+    pub fn with_region(mut self, region: Region) -> Self {
+        self.protect(region);
+        self
+    }
+
+    pub fn protect(&mut self, region: Region) {
+        self.regions.push(region);
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// The wrapped steganographer.
+    pub fn inner(&self) -> &S {
+        &self.steganographer
+    }
+
+    /// The sorted and merged ranges of the protected characters of the input.
+    pub fn protected_ranges(&self, input: &[char]) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = self.regions.iter()
+            .flat_map(|region| region.ranges(input))
+            .filter(|range| range.start < range.end)
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    // The segments of the input, along with whether they are protected.
+    fn segments(&self, input: &[char]) -> Vec<(Range<usize>, bool)> {
+        let mut segments = Vec::new();
+        let mut position = 0;
+        for range in self.protected_ranges(input) {
+            if position < range.start {
+                segments.push((position..range.start, false));
+            }
+            position = range.end;
+            segments.push((range, true));
+        }
+        if position < input.len() {
+            segments.push((position..input.len(), false));
+        }
+        segments
+    }
+}
+
+impl<S: Steganographer<T=char>> Steganographer for ProtectedSteganographer<S> {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        let mut capacity = 0usize;
+        for (range, protected) in self.segments(public) {
+            if !protected {
+                capacity = capacity.saturating_add(Carrier::capacity(&self.steganographer, &public[range])?);
+            }
+        }
+        Ok(capacity)
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let symbols: Vec<bool> = codec.encode(secret).iter().map(|elem| codec.is_a(elem)).collect();
+        let available = Steganographer::capacity(self, public)?;
+        if available < symbols.len() {
+            return Err(BaconError::InsufficientCapacity { required: symbols.len(), available, unit: CapacityUnit::Carriers });
+        }
+        let mut disguised = Vec::with_capacity(public.len());
+        let mut written = 0;
+        for (range, protected) in self.segments(public) {
+            let segment = &public[range];
+            let count = if protected {
+                0
+            } else {
+                Carrier::capacity(&self.steganographer, segment)?.min(symbols.len() - written)
+            };
+            if count == 0 {
+                disguised.extend_from_slice(segment);
+            } else {
+                disguised.extend(self.steganographer.write_symbols(&symbols[written..written + count], segment)?);
+                written += count;
+            }
+        }
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let mut encoded = Vec::new();
+        for (range, protected) in self.segments(input) {
+            if !protected {
+                encoded.extend(self.steganographer.read_symbols(&input[range])?.into_iter()
+                    .map(|is_a| if is_a { codec.a() } else { codec.b() }));
+            }
+        }
+        Ok(codec.decode(&encoded))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        let stripped = self.steganographer.strip(input)?;
+        if stripped.len() == input.len() {
+            // The whole input is stripped at once, so that the context of the segments is kept, like the sentences of
+            // the letter case. The protected characters are restored afterwards.
+            let mut restored = stripped;
+            for range in self.protected_ranges(input) {
+                restored[range.clone()].copy_from_slice(&input[range]);
+            }
+            Ok(restored)
+        } else {
+            let mut restored = Vec::with_capacity(stripped.len());
+            for (range, protected) in self.segments(input) {
+                if protected {
+                    restored.extend_from_slice(&input[range]);
+                } else {
+                    restored.extend(self.steganographer.strip(&input[range])?);
+                }
+            }
+            Ok(restored)
+        }
+    }
+}
+
+#[cfg(test)]
+mod protected_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn find_code() {
+        let input = Vec::from_iter("Run `cargo test` or ``a ` b``, then ```\nfn main() {}\n``` and ` unclosed".chars());
+        let found: Vec<String> = code(&input).into_iter().map(|range| String::from_iter(&input[range])).collect();
+        assert_eq!(found, vec!["`cargo test`", "``a ` b``", "```\nfn main() {}\n```"]);
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ProtectedSteganographer::new(LetterCaseSteganographer::new())
+            .with_region(Region::Urls)
+            .with_region(Region::Code)
+            .with_region(Region::Range(0..4));
+        let public = Vec::from_iter("Read https://example.com and run `make all` now, it is simple.".chars());
+        assert_eq!(s.protected_ranges(&public), vec![0..4, 5..24, 33..43]);
+        assert_eq!(Steganographer::capacity(&s, &public).unwrap(), 19);
+        let disguised = s.disguise(&['H', 'I'], &public, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()), "Read https://example.com anD RUn `make all` Now, it is simple.");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I']);
+        assert!(s.disguise(&['H', 'I', 'J', 'K'], &public, &codec).is_err());
+    }
+
+    #[cfg(feature = "regex-steganography")]
+    #[test]
+    fn protect_the_matches_of_a_pattern() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ProtectedSteganographer::new(LetterCaseSteganographer::new())
+            .with_region(Region::pattern(r"\bΑθήνα|Bacon\b").unwrap());
+        let public = Vec::from_iter("Αθήνα of Francis Bacon".chars());
+        assert_eq!(s.protected_ranges(&public), vec![0..5, 17..22]);
+        let disguised = s.disguise(&['H'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "Αθήνα of FRAncis Bacon");
+        assert!(Region::pattern("(").is_err());
+    }
+}
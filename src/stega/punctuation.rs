@@ -0,0 +1,220 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// The default substitution table: straight vs curly apostrophe, hyphen vs en-dash and straight vs curly quotes.
+///
+/// The curly closing quote `”` is written as the opening quote `“` at the start of the input or after whitespace or
+/// an opening bracket, and both of them carry the Bacon's element B.
+pub const DEFAULT_SUBSTITUTIONS: [(char, char); 3] = [
+    ('\'', '\u{2019}'),
+    ('-', '\u{2013}'),
+    ('"', '\u{201D}'),
+];
+
+/// Applies steganography based on the form of typographically equivalent punctuation characters.
+///
+/// Each entry of the substitution table is a pair of punctuation forms. Wherever either form appears in the public input,
+/// the first form carries the Bacon's element A and the second form carries the Bacon's element B. A second form of `”`
+/// is written as `“` where a quotation opens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PunctuationSteganographer {
+    substitutions: Vec<(char, char)>,
+}
+
+impl PunctuationSteganographer {
+    /// Creates a `PunctuationSteganographer` with a substitution table of `(A form, B form)` pairs.
+    ///
+    /// Every `char` may appear only once in the table and may not be alphanumeric or whitespace. A table with the form
+    /// `”` may not contain `“`, which is its opening form.
+    pub fn new(substitutions: &[(char, char)]) -> errors::Result<PunctuationSteganographer> {
+        let mut seen: Vec<char> = Vec::new();
+        for &(a, b) in substitutions {
+            for c in [a, b].iter().chain(opening_form(b).iter()) {
+                if c.is_alphanumeric() || c.is_whitespace() {
+                    return Err(BaconError::SteganographerError(format!("Cannot use {:?} as a punctuation form", c)));
+                }
+                if seen.contains(c) {
+                    return Err(BaconError::SteganographerError(format!("The punctuation form {:?} is defined more than once", c)));
+                }
+                seen.push(*c);
+            }
+        }
+        if substitutions.is_empty() {
+            Err(BaconError::SteganographerError("The substitution table should not be empty".to_string()))
+        } else {
+            Ok(PunctuationSteganographer { substitutions: substitutions.to_vec() })
+        }
+    }
+
+    pub fn substitutions(&self) -> &[(char, char)] {
+        &self.substitutions
+    }
+
+    // Returns the substitution pair that the `char` belongs to.
+    fn pair_of(&self, c: &char) -> Option<&(char, char)> {
+        self.substitutions.iter().find(|(a, b)| a == c || b == c || opening_form(*b) == Some(*c))
+    }
+}
+
+// The form of a closing quote where a quotation opens.
+fn opening_form(c: char) -> Option<char> {
+    if c == '\u{201D}' { Some('\u{201C}') } else { None }
+}
+
+// Whether a quotation opens at the position: at the start of the input or after whitespace or an opening bracket.
+fn is_opening(public: &[char], position: usize) -> bool {
+    position == 0 || public[position - 1].is_whitespace() || ['(', '[', '{'].contains(&public[position - 1])
+}
+
+impl Default for PunctuationSteganographer {
+    /// Uses the [DEFAULT_SUBSTITUTIONS](constant.DEFAULT_SUBSTITUTIONS.html).
+    fn default() -> PunctuationSteganographer {
+        PunctuationSteganographer { substitutions: DEFAULT_SUBSTITUTIONS.to_vec() }
+    }
+}
+
+impl Steganographer for PunctuationSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(public.iter()
+            .filter(|pc| self.pair_of(pc).is_some())
+            .count())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let available_size = self.capacity(public)?;
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if available_size < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: available_size,
+                unit: CapacityUnit::PunctuationCharacters,
+            })
+        } else {
+            let mut encoded = codec.encode(secret).into_iter();
+
+            let disguised = public.iter()
+                .enumerate()
+                .map(|(position, pc)| {
+                    match self.pair_of(pc) {
+                        Some(&(a, b)) => {
+                            match encoded.next() {
+                                Some(ref elem) if codec.is_a(elem) => a,
+                                Some(ref elem) if codec.is_b(elem) => match opening_form(b) {
+                                    Some(opening) if is_opening(public, position) => opening,
+                                    _ => b,
+                                },
+                                _ => *pc,
+                            }
+                        }
+                        None => *pc,
+                    }
+                })
+                .collect();
+
+            Ok(disguised)
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = input.iter()
+            .filter_map(|elem| {
+                self.pair_of(elem)
+                    .map(|(a, _)| if a == elem { codec.a() } else { codec.b() })
+            })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(input.iter().enumerate()
+            .filter_map(|(position, elem)| {
+                self.pair_of(elem)
+                    .map(|(a, _)| Slot::new(position, if a == elem { SegmentKind::A } else { SegmentKind::B }))
+            })
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod punctuation_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(PunctuationSteganographer::new(&[]).is_err());
+        assert!(PunctuationSteganographer::new(&[('-', 'a')]).is_err());
+        assert!(PunctuationSteganographer::new(&[('-', ' ')]).is_err());
+        assert!(PunctuationSteganographer::new(&[('-', '-')]).is_err());
+        assert!(PunctuationSteganographer::new(&[('-', '\u{2013}'), ('\u{2013}', '\u{2014}')]).is_err());
+        assert!(PunctuationSteganographer::new(&[('-', '\u{2013}'), (',', '\u{201A}')]).is_ok());
+        assert!(PunctuationSteganographer::new(&[('"', '\u{201D}'), ('\u{201C}', '\u{201E}')]).is_err());
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PunctuationSteganographer::default();
+        let output = s.disguise(
+            &['H', 'i'],
+            &Vec::from_iter("It's a well-known fact".chars()),
+            &codec);
+        assert!(output.is_err())
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PunctuationSteganographer::default();
+        let public = "It's a well-known, \"long-standing\" and so-called high-quality, sure-fire tradition: \
+        we don't, can't and won't stop. It's self-evident.";
+        let output = s.disguise(
+            &['H', 'i'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let disguised = output.unwrap();
+        let string = String::from_iter(disguised.iter());
+        // H = AABBB, I = ABAAA
+        assert_eq!(string, "It's a well-known, \u{201C}long\u{2013}standing\u{201D} and so-called high\u{2013}quality, sure-fire tradition: \
+        we don't, can't and won't stop. It's self-evident.");
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("HI"));
+    }
+    #[test]
+    fn opening_and_closing_quotes() {
+        let codec = CharCodec::new('a', 'b');
+        let s = PunctuationSteganographer::new(&[('"', '\u{201D}')]).unwrap();
+        // Z = BABBB
+        let public = Vec::from_iter("\"Hi\" or (\"hello\") \"there\" she said".chars());
+        let disguised = s.disguise(&['Z'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "\u{201C}Hi\" or (\u{201C}hello\u{201D}) \u{201C}there\" she said");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[0], 'Z');
+        assert_eq!(s.mapping(&disguised).unwrap().slots()[0].kind(), SegmentKind::B);
+    }
+}
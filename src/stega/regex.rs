@@ -0,0 +1,218 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use regex::{Captures, Regex};
+
+use crate::{BaconCodec, carrier, errors, Steganographer};
+use crate::carrier::{Segment, SegmentKind};
+use crate::errors::BaconError;
+use crate::mapping::Mapping;
+use crate::stega::markdown::MarkdownSteganographer;
+
+/// The name of the capture group that contains the characters that a pattern marks.
+pub const TEXT_GROUP: &str = "text";
+
+/// Applies steganography based on regular expressions that match the marked spans of the input.
+///
+/// Contrary to the fixed strings of the [markers](../markdown/struct.Marker.html), the patterns can express markup
+/// like `<em>` elements with arbitrary attributes, or can distinguish `_text_` from `__text__`.
+///
+/// The characters that a pattern marks are the ones of the capture group named `text`. If the pattern does not have
+/// such a group, the first capture group is used, or else the whole match.
+/// When both patterns match, the match that starts first is used. If they start at the same position, the longest match is used.
+///
+/// The patterns cannot produce any output, so the disguise is done by a
+/// [MarkdownSteganographer](../markdown/struct.MarkdownSteganographer.html) that should be set with
+/// [with_disguise_steganographer](struct.RegexSteganographer.html#method.with_disguise_steganographer).
+#[derive(Debug, Clone)]
+pub struct RegexSteganographer {
+    a_pattern: Regex,
+    b_pattern: Regex,
+    disguise_steganographer: Option<MarkdownSteganographer>,
+}
+
+impl RegexSteganographer {
+    pub fn new(a_pattern: &str, b_pattern: &str) -> errors::Result<RegexSteganographer> {
+        if a_pattern == b_pattern {
+            return Err(BaconError::SteganographerError("The A and B patterns should be different".to_string()));
+        }
+        Ok(RegexSteganographer {
+            a_pattern: compile(a_pattern)?,
+            b_pattern: compile(b_pattern)?,
+            disguise_steganographer: None,
+        })
+    }
+
+    /// Sets the steganographer that disguises the secrets. Its markers should be matched by the patterns.
+    pub fn with_disguise_steganographer(mut self, steganographer: MarkdownSteganographer) -> Self {
+        self.disguise_steganographer = Some(steganographer);
+        self
+    }
+
+    pub fn set_disguise_steganographer(&mut self, steganographer: MarkdownSteganographer) {
+        self.disguise_steganographer = Some(steganographer);
+    }
+
+    /// Returns the classified segments of the input. The ranges of the marked segments are the ones of the marked characters,
+    /// so the rest of the matches is not contained in any segment.
+    pub fn segments(&self, input: &[char]) -> Vec<Segment> {
+        let input: String = input.iter().collect();
+        let mut segments = Vec::new();
+        let mut offset = 0;
+        let mut position = 0;
+
+        while position <= input.len() {
+            let a_captures = self.a_pattern.captures_at(&input, position).map(|captures| (SegmentKind::A, captures));
+            let b_captures = self.b_pattern.captures_at(&input, position).map(|captures| (SegmentKind::B, captures));
+            let (kind, captures) = match (a_captures, b_captures) {
+                (Some(a), Some(b)) => {
+                    let (a_match, b_match) = (a.1.get(0).unwrap(), b.1.get(0).unwrap());
+                    if b_match.start() < a_match.start() || (b_match.start() == a_match.start() && b_match.end() > a_match.end()) {
+                        b
+                    } else {
+                        a
+                    }
+                }
+                (Some(captures), None) | (None, Some(captures)) => captures,
+                (None, None) => break,
+            };
+            let whole = captures.get(0).unwrap();
+            if whole.as_str().is_empty() {
+                // Empty matches do not mark anything
+                position = whole.end() + input[whole.end()..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+                continue;
+            }
+            let text = marked_text(&captures);
+            if offset < whole.start() {
+                segments.push(Segment::new(SegmentKind::Other, &input[offset..whole.start()], Some(offset..whole.start())));
+            }
+            segments.push(Segment::new(kind, text.as_str(), Some(text.range())));
+            offset = whole.end();
+            position = whole.end();
+        }
+        if offset < input.len() {
+            segments.push(Segment::new(SegmentKind::Other, &input[offset..], Some(offset..input.len())));
+        }
+        segments
+    }
+}
+
+fn compile(pattern: &str) -> errors::Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|error| BaconError::SteganographerError(format!("Invalid pattern {:?}: {}", pattern, error)))
+}
+
+fn marked_text<'t>(captures: &Captures<'t>) -> regex::Match<'t> {
+    captures.name(TEXT_GROUP)
+        .or_else(|| captures.get(1))
+        .or_else(|| captures.get(0))
+        .unwrap()
+}
+
+impl Steganographer for RegexSteganographer {
+    type T = char;
+
+    /// The capacity of the disguise steganographer, or 0 without one.
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        match self.disguise_steganographer {
+            Some(ref steganographer) => steganographer.capacity(public),
+            None => Ok(0),
+        }
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        match self.disguise_steganographer {
+            Some(ref steganographer) => steganographer.disguise(secret, public, codec),
+            None => Err(BaconError::SteganographerError(
+                "The RegexSteganographer needs a disguise steganographer in order to disguise a secret".to_string())),
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        Ok(carrier::decode(&self.segments(input), codec))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::from_segments(input, &self.segments(input)))
+    }
+}
+
+#[cfg(test)]
+mod regex_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::markdown::Marker;
+
+    use super::*;
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(RegexSteganographer::new("<em>(.*?)</em>", "<em>(.*?)</em>").is_err());
+        assert!(RegexSteganographer::new("<em>(.*?</em>", "<b>(.*?)</b>").is_err());
+        assert!(RegexSteganographer::new("<em>(.*?)</em>", "<b>(.*?)</b>").is_ok());
+    }
+
+    #[test]
+    fn reveal_elements_with_attributes() {
+        let codec = CharCodec::new('a', 'b');
+        let s = RegexSteganographer::new(
+            r#"<em\b[^>]*>(?P<text>.*?)</em>"#,
+            r#"<strong\b[^>]*>(?P<text>.*?)</strong>"#).unwrap();
+        // H = AABBB
+        let input = "<em class=\"x\">He</em><strong id=\"y\">l</strong><strong>lo</strong> world";
+        let output = s.reveal(&Vec::from_iter(input.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+    }
+
+    #[test]
+    fn reveal_the_longest_match() {
+        let codec = CharCodec::new('a', 'b');
+        let s = RegexSteganographer::new(r"_([^_]+)_", r"__([^_]+)__").unwrap();
+        // H = AABBB
+        let input = "_He___llo__ world";
+        let output = s.reveal(&Vec::from_iter(input.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+    }
+
+    #[test]
+    fn segments_of_the_input() {
+        let s = RegexSteganographer::new(r"\*([^*]+)\*", r"\d+").unwrap();
+        let segments = s.segments(&Vec::from_iter("ab *cd* 42".chars()));
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::Other, "ab ", Some(0..3)),
+            Segment::new(SegmentKind::A, "cd", Some(4..6)),
+            Segment::new(SegmentKind::Other, " ", Some(7..8)),
+            Segment::new(SegmentKind::B, "42", Some(8..10)),
+        ]);
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = RegexSteganographer::new(r"__([^_]+)__", r"_([^_]+)_").unwrap();
+        let public = "This is a public message that contains a secret one";
+        assert!(s.disguise(&['H'], &Vec::from_iter(public.chars()), &codec).is_err());
+
+        let s = s.with_disguise_steganographer(MarkdownSteganographer::new(
+            Marker::new(Some("__"), Some("__")),
+            Marker::new(Some("_"), Some("_"))).unwrap());
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+}
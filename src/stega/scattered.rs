@@ -0,0 +1,170 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::Carrier;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::random::Random;
+
+/// Scatters the encoded secret pseudo-randomly among the carriers of another steganographer.
+///
+/// Instead of the first carriers of the public input, the carriers of the elements of the encoded secret are selected
+/// by a seeded permutation of all the carriers, so that the hidden data is spread throughout the text. The carriers
+/// that are not selected keep their original element. The same seed is needed to reveal the secret.
+///
+/// The wrapped steganographer should find the same number of carriers in the public input and in its disguised output,
+/// like the [LetterCaseSteganographer](../letter_case/struct.LetterCaseSteganographer.html), or the
+/// [MarkdownSteganographer](../markdown/struct.MarkdownSteganographer.html) with an unmarked Bacon's element.
+/// All the carriers of the wrapped steganographer are read and written, so its padding is not used.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScatteredSteganographer<S> {
+    steganographer: S,
+    seed: u64,
+}
+
+impl<S: Steganographer> ScatteredSteganographer<S> {
+    /// Creates a `ScatteredSteganographer` that wraps the `steganographer`, with the seed 0.
+    pub fn new(steganographer: S) -> ScatteredSteganographer<S> {
+        ScatteredSteganographer { steganographer, seed: 0 }
+    }
+
+    /// Sets the seed of the selection of the carriers.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Sets the seed of the selection of the carriers to the hash of a passphrase.
+    pub fn with_passphrase(mut self, passphrase: &str) -> Self {
+        self.set_passphrase(passphrase);
+        self
+    }
+
+    pub fn set_passphrase(&mut self, passphrase: &str) {
+        self.seed = seed_of(passphrase);
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The wrapped steganographer.
+    pub fn inner(&self) -> &S {
+        &self.steganographer
+    }
+}
+
+// The 64-bit FNV-1a hash of the passphrase, which is stable across platforms and versions.
+fn seed_of(passphrase: &str) -> u64 {
+    passphrase.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+impl<S: Steganographer<T=char>> Steganographer for ScatteredSteganographer<S> {
+    type T = char;
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let mut elements = Carrier::read_symbols(&self.steganographer, public)?;
+        let encoded = codec.encode(secret);
+        if elements.len() < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available: elements.len(),
+                unit: CapacityUnit::Carriers,
+            });
+        }
+        let permutation = Random::new(self.seed).permutation(elements.len());
+        for (elem, index) in encoded.iter().zip(permutation) {
+            elements[index] = codec.is_a(elem);
+        }
+        Carrier::write_symbols(&self.steganographer, &elements, public)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let symbols = Carrier::read_symbols(&self.steganographer, input)?;
+        let gathered: Vec<AB> = Random::new(self.seed).permutation(symbols.len()).into_iter()
+            .map(|index| if symbols[index] { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&gathered))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        self.steganographer.strip(input)
+    }
+}
+
+#[cfg(test)]
+mod scattered_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::stega::markdown::{MarkdownSteganographer, Marker};
+    use crate::stega::Padding;
+
+    use super::*;
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ScatteredSteganographer::new(LetterCaseSteganographer::new()).with_passphrase("open sesame");
+        let public = Vec::from_iter("This is a public message that contains a secret one. ".repeat(3).chars());
+        let disguised = s.disguise(&Vec::from_iter("My secret".chars()), &public, &codec).unwrap();
+        let output = String::from_iter(disguised.iter());
+        assert_eq!(output.to_lowercase(), String::from_iter(public.iter()).to_lowercase());
+        // The 40 elements of the secret are spread throughout the 126 letters of the text
+        assert!(output.split(". ").nth(2).unwrap().chars().any(|c| c.is_uppercase()));
+        assert!(String::from_iter(s.reveal(&disguised, &codec).unwrap().iter()).starts_with("MYSECRET"));
+
+        let wrong = ScatteredSteganographer::new(LetterCaseSteganographer::new()).with_passphrase("open sesame!");
+        assert!(!String::from_iter(wrong.reveal(&disguised, &codec).unwrap().iter()).starts_with("MYSECRET"));
+        assert!(!String::from_iter(LetterCaseSteganographer::new().reveal(&disguised, &codec).unwrap().iter()).starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_markdown() {
+        let codec = CharCodec::new('a', 'b');
+        let markdown = MarkdownSteganographer::new(Marker::empty(), Marker::new(Some("*"), Some("*"))).unwrap();
+        let s = ScatteredSteganographer::new(markdown).with_seed(7);
+        let public = Vec::from_iter("This is a public message that contains a secret one".chars());
+        let disguised = s.disguise(&Vec::from_iter("My secret".chars()), &public, &codec).unwrap();
+        assert!(String::from_iter(s.reveal(&disguised, &codec).unwrap().iter()).starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_with_a_padded_steganographer() {
+        let codec = CharCodec::new('a', 'b');
+        // The groups of the public texts are all AAAAA, which the padding of repeats would remove, or all BBBBB, which
+        // the padding of nulls would remove
+        let public = "this is a public message that contains a secret one";
+        for (padding, public) in [(Padding::Repeat, public.to_string()), (Padding::Nulls, public.to_uppercase())] {
+            let s = ScatteredSteganographer::new(LetterCaseSteganographer::new().with_padding(padding)).with_seed(7);
+            let disguised = s.disguise(&['H', 'I'], &Vec::from_iter(public.chars()), &codec).unwrap();
+            assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I'], "{:?}", padding);
+        }
+    }
+
+    #[test]
+    fn insufficient_carriers() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ScatteredSteganographer::new(LetterCaseSteganographer::new());
+        assert_eq!(s.disguise(&['H', 'I'], &Vec::from_iter("Hello".chars()), &codec), Err(BaconError::InsufficientCapacity {
+            required: 10,
+            available: 5,
+            unit: CapacityUnit::Carriers,
+        }));
+    }
+}
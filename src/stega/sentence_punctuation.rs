@@ -0,0 +1,320 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+// The abbreviations whose period does not end a sentence, in lowercase.
+const ABBREVIATIONS: [&str; 14] = ["mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "e.g", "i.e", "no", "fig"];
+// The characters that may close a sentence after its final punctuation, like quotes and parentheses.
+const CLOSING: [char; 5] = ['"', '\'', ')', '\u{201D}', '\u{2019}'];
+// The characters that may open a sentence before its first letter.
+const OPENING: [char; 4] = ['"', '\'', '(', '\u{201C}'];
+// The conjunctions that end the lists.
+const CONJUNCTIONS: [&str; 2] = ["and", "or"];
+
+/// Applies steganography based on stylistic choices at the end of the sentences and the lists, which are
+/// used in formal documents interchangeably.
+///
+/// In document order, the carriers are:
+///
+/// * The spaces after a sentence: a single space carries the Bacon's element A and two spaces carry the Bacon's element B.
+///   A sentence ends with `.`, `!` or `?`, optionally followed by closing quotes or parentheses, and the next sentence
+///   starts with a capital letter. The periods of abbreviations, like `Dr.` or `e.g.`, and initials do not end a sentence.
+/// * The comma before the conjunction that ends a list, like `a, b and c`: without the Oxford comma it carries the
+///   Bacon's element A and with it, like `a, b, and c`, the Bacon's element B. A list contains a comma before its last item.
+///
+/// The carriers are few, so the steganographer is meant for very short secrets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentencePunctuationSteganographer {
+    sentence_spacing: bool,
+    oxford_comma: bool,
+}
+
+// A place of the input that carries a Bacon's element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Site {
+    // The run of spaces that follows a sentence.
+    Spacing { start: usize, len: usize },
+    // The position of the Oxford comma of a list, or of the space before the conjunction if the comma is absent.
+    Comma { position: usize, present: bool },
+}
+
+impl Site {
+    fn position(&self) -> usize {
+        match self {
+            Site::Spacing { start, .. } => *start,
+            Site::Comma { position, .. } => *position,
+        }
+    }
+
+    fn is_a(&self) -> bool {
+        match self {
+            Site::Spacing { len, .. } => *len == 1,
+            Site::Comma { present, .. } => !present,
+        }
+    }
+}
+
+impl SentencePunctuationSteganographer {
+    /// Creates a `SentencePunctuationSteganographer` that uses both the spaces after the sentences and the Oxford commas.
+    pub fn new() -> SentencePunctuationSteganographer {
+        SentencePunctuationSteganographer {
+            sentence_spacing: true,
+            oxford_comma: true,
+        }
+    }
+
+    /// Defines whether the spaces after the sentences carry elements.
+    pub fn with_sentence_spacing(mut self, sentence_spacing: bool) -> Self {
+        self.set_sentence_spacing(sentence_spacing);
+        self
+    }
+
+    pub fn set_sentence_spacing(&mut self, sentence_spacing: bool) {
+        self.sentence_spacing = sentence_spacing;
+    }
+
+    /// Defines whether the Oxford commas carry elements.
+    pub fn with_oxford_comma(mut self, oxford_comma: bool) -> Self {
+        self.set_oxford_comma(oxford_comma);
+        self
+    }
+
+    pub fn set_oxford_comma(&mut self, oxford_comma: bool) {
+        self.oxford_comma = oxford_comma;
+    }
+
+    // Tokenizes the input and returns the sites that carry elements, in the order of their positions.
+    //
+    // The sites do not depend on the elements that they carry, so that the disguise does not change the sites that the
+    // reveal finds.
+    fn sites(&self, input: &[char]) -> Vec<Site> {
+        let mut sites = Vec::new();
+        // The start of the part of the sentence that may contain the items of a list
+        let mut segment_start = 0;
+        for i in 0..input.len() {
+            let c = input[i];
+            if c == '.' || c == '!' || c == '?' {
+                if self.sentence_spacing {
+                    sites.extend(Self::sentence_spacing(input, i));
+                }
+                segment_start = i + 1;
+            } else if c == ';' || c == ':' {
+                segment_start = i + 1;
+            } else if let Some(conjunction) = Self::conjunction_at(input, i) {
+                if self.oxford_comma {
+                    sites.extend(Self::oxford_comma(input, segment_start, i));
+                }
+                segment_start = i + conjunction.len();
+            }
+        }
+        sites
+    }
+
+    // The spaces that follow the sentence that ends with the punctuation at `end`.
+    fn sentence_spacing(input: &[char], end: usize) -> Option<Site> {
+        let word_start = (0..end).rev()
+            .find(|i| !input[*i].is_alphabetic() && input[*i] != '.')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let word: String = input[word_start..end].iter().collect::<String>().to_lowercase();
+        let initial = end - word_start == 1 && input[word_start].is_uppercase();
+        if input[end] == '.' && (initial || ABBREVIATIONS.contains(&word.as_str())) {
+            return None;
+        }
+        let start = (end + 1..input.len()).find(|i| !CLOSING.contains(&input[*i])).unwrap_or(input.len());
+        let next = (start..input.len()).find(|i| input[*i] != ' ').unwrap_or(input.len());
+        let sentence = (next..input.len()).find(|i| !OPENING.contains(&input[*i])).unwrap_or(input.len());
+        let len = next - start;
+        if (len == 1 || len == 2) && sentence < input.len() && input[sentence].is_uppercase() {
+            Some(Site::Spacing { start, len })
+        } else {
+            None
+        }
+    }
+
+    // The conjunction that starts at `i` as a word, after a space.
+    fn conjunction_at(input: &[char], i: usize) -> Option<&'static str> {
+        if i == 0 || input[i - 1] != ' ' {
+            return None;
+        }
+        CONJUNCTIONS.iter().cloned().find(|conjunction| {
+            let end = i + conjunction.len();
+            end < input.len() && !input[end].is_alphanumeric() && input[i..end].iter().cloned().eq(conjunction.chars())
+        })
+    }
+
+    // The Oxford comma of the list whose conjunction starts at `conjunction`, if the segment before it is a list.
+    fn oxford_comma(input: &[char], segment_start: usize, conjunction: usize) -> Option<Site> {
+        let space = conjunction - 1;
+        let present = space > segment_start && input[space - 1] == ',';
+        let items_end = if present { space - 1 } else { space };
+        if input[segment_start..items_end].contains(&',') {
+            Some(Site::Comma { position: items_end, present })
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SentencePunctuationSteganographer {
+    fn default() -> SentencePunctuationSteganographer {
+        SentencePunctuationSteganographer::new()
+    }
+}
+
+impl Steganographer for SentencePunctuationSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.sites(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let sites = self.sites(public);
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if sites.len() < secret_size * codec.encoded_group_size() {
+            return Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: sites.len(),
+                unit: CapacityUnit::Carriers,
+            });
+        }
+
+        let mut disguised = Vec::with_capacity(public.len() + sites.len());
+        let mut cursor = 0;
+        for (site, elem) in sites.iter().zip(codec.encode(secret)) {
+            disguised.extend_from_slice(&public[cursor..site.position()]);
+            let is_a = codec.is_a(&elem);
+            match *site {
+                Site::Spacing { start, len } => {
+                    disguised.extend_from_slice(if is_a { &[' '] } else { &[' ', ' '] });
+                    cursor = start + len;
+                }
+                Site::Comma { position, present } => {
+                    if !is_a {
+                        disguised.push(',');
+                    }
+                    cursor = if present { position + 1 } else { position };
+                }
+            }
+        }
+        disguised.extend_from_slice(&public[cursor..]);
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = self.sites(input).iter()
+            .map(|site| if site.is_a() { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    /// Writes all the carriers in the style of the Bacon's element A: a single space after the sentences and no Oxford commas.
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        let mut stripped = Vec::with_capacity(input.len());
+        let mut cursor = 0;
+        for site in self.sites(input) {
+            stripped.extend_from_slice(&input[cursor..site.position()]);
+            cursor = match site {
+                Site::Spacing { start, len } => {
+                    stripped.push(' ');
+                    start + len
+                }
+                Site::Comma { position, present } => if present { position + 1 } else { position },
+            };
+        }
+        stripped.extend_from_slice(&input[cursor..]);
+        Ok(stripped)
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(self.sites(input).iter()
+            .map(|site| Slot::new(site.position(), if site.is_a() { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod sentence_punctuation_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    const PUBLIC: &str = "We met Dr. Smith at noon. He brought apples, pears and plums. We left at once! \
+    The report lists costs, risks, and benefits. \"It works.\" She agreed, and J. Doe signed it. \
+    They sold tea, coffee or juice. It rained. The end.";
+
+    #[test]
+    fn sites_of_the_input() {
+        let s = SentencePunctuationSteganographer::new();
+        let input = Vec::from_iter(PUBLIC.chars());
+        let kinds: Vec<SegmentKind> = s.mapping(&input).unwrap().slots().iter().map(|slot| slot.kind()).collect();
+        // `Dr.`, `J.` and the clause `She agreed, and` are not carriers
+        assert_eq!(kinds, vec![
+            SegmentKind::A, SegmentKind::A, SegmentKind::A, SegmentKind::A, SegmentKind::B, SegmentKind::A,
+            SegmentKind::A, SegmentKind::A, SegmentKind::A, SegmentKind::A, SegmentKind::A,
+        ]);
+        assert_eq!(s.capacity(&input).unwrap(), 11);
+        assert_eq!(s.clone().with_oxford_comma(false).capacity(&input).unwrap(), 8);
+        assert_eq!(s.with_sentence_spacing(false).capacity(&input).unwrap(), 3);
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SentencePunctuationSteganographer::new();
+        let public = Vec::from_iter(PUBLIC.chars());
+        // H = AABBB, I = ABAAA
+        let disguised = s.disguise(&['H', 'i'], &public, &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        assert_eq!(string, "We met Dr. Smith at noon. He brought apples, pears and plums.  We left at once!  \
+    The report lists costs, risks, and benefits. \"It works.\"  She agreed, and J. Doe signed it. \
+    They sold tea, coffee or juice. It rained. The end.");
+        assert!(String::from_iter(s.reveal(&disguised, &codec).unwrap().iter()).starts_with("HI"));
+        assert_eq!(s.strip(&disguised).unwrap(), s.strip(&public).unwrap());
+        assert_eq!(s.capacity(&disguised).unwrap(), s.capacity(&public).unwrap());
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_oxford_commas() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SentencePunctuationSteganographer::new().with_sentence_spacing(false);
+        let public = Vec::from_iter("Red, green and blue. Up, down, and out. Salt, pepper and oil. Cats, dogs and birds. Now, then or never.".chars());
+        // Z = BABBB
+        let disguised = s.disguise(&['Z'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()),
+                   "Red, green, and blue. Up, down and out. Salt, pepper, and oil. Cats, dogs, and birds. Now, then, or never.");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['Z']);
+        assert_eq!(String::from_iter(s.strip(&disguised).unwrap().iter()),
+                   "Red, green and blue. Up, down and out. Salt, pepper and oil. Cats, dogs and birds. Now, then or never.");
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SentencePunctuationSteganographer::new();
+        let output = s.disguise(&['H', 'i'], &Vec::from_iter("One. Two. Three, four and five.".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 10, available: 3, unit: CapacityUnit::Carriers }));
+    }
+}
@@ -0,0 +1,702 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fmt;
+use std::ops::Range;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+
+/// A place of a code file where a [FormattingRule](trait.FormattingRule.html) makes a stylistic choice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Site {
+    range: Range<usize>,
+    a: bool,
+}
+
+impl Site {
+    /// Creates a `Site` of the characters in the `range`, which currently carry the Bacon's element A if `a` is true,
+    /// or B otherwise. The range may be empty, for choices that insert characters.
+    pub fn new(range: Range<usize>, a: bool) -> Site {
+        Site { range, a }
+    }
+
+    pub fn range(&self) -> &Range<usize> {
+        &self.range
+    }
+
+    /// Whether the site carries the Bacon's element A.
+    pub fn is_a(&self) -> bool {
+        self.a
+    }
+}
+
+/// A stylistic choice of a code file, that does not change the meaning of the code.
+///
+/// A rule should find the same sites in the code after any of them is rewritten. The rules are `Send` and `Sync`, so
+/// that the steganographer can be shared across threads.
+pub trait FormattingRule: Send + Sync {
+    /// Finds the sites of the choice in the _code_, in the order that they appear.
+    fn sites(&self, code: &[char]) -> Vec<Site>;
+
+    /// The characters that replace the characters of a site, to carry the Bacon's element A if `a` is true, or B otherwise.
+    fn rewrite(&self, site: &[char], a: bool) -> Vec<char>;
+}
+
+/// The lexical conventions of a programming language, that the [FormattingRule](trait.FormattingRule.html)s need
+/// in order to skip strings and comments.
+///
+/// Strings and block comments may span lines. Block comments do not nest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Syntax {
+    quotes: Vec<char>,
+    line_comment: String,
+    block_comment: Option<(String, String)>,
+    char_literals: bool,
+    raw_strings: bool,
+    brace_lists: bool,
+}
+
+impl Syntax {
+    /// Creates a `Syntax` with the characters that delimit strings and the start of line comments.
+    pub fn new(quotes: &[char], line_comment: &str) -> Syntax {
+        Syntax {
+            quotes: quotes.to_vec(),
+            line_comment: line_comment.to_string(),
+            block_comment: None,
+            char_literals: false,
+            raw_strings: false,
+            brace_lists: false,
+        }
+    }
+
+    /// The start and the end of block comments, like `/*` and `*/`.
+    pub fn with_block_comment(mut self, start: &str, end: &str) -> Self {
+        self.set_block_comment(start, end);
+        self
+    }
+
+    pub fn set_block_comment(&mut self, start: &str, end: &str) {
+        self.block_comment = if start.is_empty() || end.is_empty() {
+            None
+        } else {
+            Some((start.to_string(), end.to_string()))
+        };
+    }
+
+    /// Whether single quotes delimit character literals, like `'"'` or `'\''`, instead of strings. A single quote that
+    /// does not close a character literal, like the one of the lifetime `'a`, is ignored.
+    pub fn with_char_literals(mut self, char_literals: bool) -> Self {
+        self.set_char_literals(char_literals);
+        self
+    }
+
+    pub fn set_char_literals(&mut self, char_literals: bool) {
+        self.char_literals = char_literals;
+    }
+
+    /// Whether the raw strings of Rust, like `r"..."`, `r#"..."#` or `br"..."`, are recognized.
+    pub fn with_raw_strings(mut self, raw_strings: bool) -> Self {
+        self.set_raw_strings(raw_strings);
+        self
+    }
+
+    pub fn set_raw_strings(&mut self, raw_strings: bool) {
+        self.raw_strings = raw_strings;
+    }
+
+    /// Whether braces always delimit lists, like the dictionaries of Python, instead of blocks of statements.
+    pub fn with_brace_lists(mut self, brace_lists: bool) -> Self {
+        self.set_brace_lists(brace_lists);
+        self
+    }
+
+    pub fn set_brace_lists(&mut self, brace_lists: bool) {
+        self.brace_lists = brace_lists;
+    }
+
+    /// Rust, with strings in double quotes, raw strings, character literals and `//` and `/* */` comments.
+    pub fn rust() -> Syntax {
+        Syntax::new(&['"'], "//")
+            .with_block_comment("/*", "*/")
+            .with_char_literals(true)
+            .with_raw_strings(true)
+    }
+
+    /// Python, with strings in double or single quotes, `#` comments and braces for dictionaries and sets.
+    pub fn python() -> Syntax {
+        Syntax::new(&['"', '\''], "#").with_brace_lists(true)
+    }
+
+    /// JavaScript, with strings in double quotes, single quotes or backticks and `//` and `/* */` comments.
+    pub fn javascript() -> Syntax {
+        Syntax::new(&['"', '\'', '`'], "//").with_block_comment("/*", "*/")
+    }
+
+    pub fn quotes(&self) -> &[char] {
+        &self.quotes
+    }
+
+    pub fn line_comment(&self) -> &str {
+        &self.line_comment
+    }
+
+    pub fn block_comment(&self) -> Option<(&str, &str)> {
+        self.block_comment.as_ref().map(|(start, end)| (start.as_str(), end.as_str()))
+    }
+
+    pub fn char_literals(&self) -> bool {
+        self.char_literals
+    }
+
+    pub fn raw_strings(&self) -> bool {
+        self.raw_strings
+    }
+
+    pub fn brace_lists(&self) -> bool {
+        self.brace_lists
+    }
+
+    // The number of the `#` of the raw string that starts at the `index`, if any.
+    fn raw_string_at(&self, code: &[char], index: usize) -> Option<usize> {
+        let is_word = |position: Option<usize>| position.and_then(|position| code.get(position))
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_');
+        let prefixed = index.checked_sub(1).map(|before| code[before] == 'b' && !is_word(before.checked_sub(1))).unwrap_or(false);
+        if !self.raw_strings || code[index] != 'r' || (is_word(index.checked_sub(1)) && !prefixed) {
+            return None;
+        }
+        let hashes = code[index + 1..].iter().take_while(|c| **c == '#').count();
+        if code.get(index + 1 + hashes) == Some(&'"') { Some(hashes) } else { None }
+    }
+
+    // The end of the character literal that starts at the `index`, if any.
+    fn char_literal_at(&self, code: &[char], index: usize) -> Option<usize> {
+        if !self.char_literals || code[index] != '\'' {
+            return None;
+        }
+        match code.get(index + 1) {
+            // The longest escape is like `\u{10FFFF}`
+            Some('\\') => code.get(index + 3..).unwrap_or(&[]).iter().take(10).position(|c| *c == '\'').map(|end| index + 4 + end),
+            Some('\n') | None => None,
+            Some(_) if code.get(index + 2) == Some(&'\'') => Some(index + 3),
+            Some(_) => None,
+        }
+    }
+
+    fn is_list(&self, open: char) -> bool {
+        open == '(' || open == '[' || (open == '{' && self.brace_lists)
+    }
+}
+
+// An open bracket and the number of the commas that were found directly in it.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    open: char,
+    commas: usize,
+}
+
+// A line of code, from the `start` to the `end` before its line feed.
+struct Line {
+    start: usize,
+    indent_end: usize,
+    // The end of the last character that is not whitespace, excluding comments
+    code_end: usize,
+    // Whether the line starts inside a string or a block comment
+    in_string: bool,
+    // The innermost open bracket at the start and at the end of the line
+    frame_at_start: Option<Frame>,
+    frame_at_end: Option<Frame>,
+}
+
+// The lines of a code file and its strings, as (range, quote) pairs. Raw strings are not included, as their quotes
+// cannot change.
+struct Scan {
+    lines: Vec<Line>,
+    strings: Vec<(Range<usize>, char)>,
+}
+
+// A token that may span lines.
+#[derive(Clone, Copy)]
+enum Open {
+    // A string that starts at a position with a quote
+    String(usize, char),
+    // A raw string with a number of `#`
+    RawString(usize),
+    BlockComment,
+}
+
+fn scan(code: &[char], syntax: &Syntax) -> Scan {
+    let comment: Vec<char> = syntax.line_comment.chars().collect();
+    let (block_start, block_end): (Vec<char>, Vec<char>) = syntax.block_comment()
+        .map(|(start, end)| (start.chars().collect(), end.chars().collect()))
+        .unwrap_or_default();
+    let mut lines = Vec::new();
+    let mut strings = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut open: Option<Open> = None;
+    let mut index = 0;
+    while index <= code.len() {
+        let start = index;
+        let in_string = open.is_some();
+        let frame_at_start = frames.last().cloned();
+        let mut code_end = start;
+        while index < code.len() && code[index] != '\n' {
+            let c = code[index];
+            match open {
+                Some(Open::String(string_start, quote)) => {
+                    if c == '\\' {
+                        index += 1;
+                    } else if c == quote {
+                        strings.push((string_start..index + 1, quote));
+                        open = None;
+                    }
+                    code_end = index + 1;
+                }
+                Some(Open::RawString(hashes)) => {
+                    if c == '"' && code.get(index + 1..index + 1 + hashes).is_some_and(|end| end.iter().all(|c| *c == '#')) {
+                        index += hashes;
+                        open = None;
+                    }
+                    code_end = index + 1;
+                }
+                Some(Open::BlockComment) => {
+                    if code[index..].starts_with(&block_end) {
+                        index += block_end.len() - 1;
+                        open = None;
+                    }
+                }
+                None if !comment.is_empty() && code[index..].starts_with(&comment) => {
+                    index = code[index..].iter().position(|c| *c == '\n').map(|end| index + end).unwrap_or(code.len());
+                    break;
+                }
+                None if !block_start.is_empty() && code[index..].starts_with(&block_start) => {
+                    index += block_start.len() - 1;
+                    open = Some(Open::BlockComment);
+                }
+                None => {
+                    if let Some(hashes) = syntax.raw_string_at(code, index) {
+                        index += 1 + hashes;
+                        open = Some(Open::RawString(hashes));
+                    } else if let Some(end) = syntax.char_literal_at(code, index) {
+                        index = end - 1;
+                    } else if syntax.quotes.contains(&c) {
+                        open = Some(Open::String(index, c));
+                    } else if c == '(' || c == '[' || c == '{' {
+                        frames.push(Frame { open: c, commas: 0 });
+                    } else if c == ')' || c == ']' || c == '}' {
+                        frames.pop();
+                    } else if c == ',' {
+                        if let Some(frame) = frames.last_mut() {
+                            frame.commas += 1;
+                        }
+                    }
+                    if !c.is_whitespace() {
+                        code_end = index + 1;
+                    }
+                }
+            }
+            index += 1;
+        }
+        let indent_end = code[start..index].iter()
+            .position(|c| *c != ' ' && *c != '\t')
+            .map(|position| start + position)
+            .unwrap_or(index);
+        lines.push(Line {
+            start,
+            indent_end,
+            code_end: code_end.max(indent_end),
+            in_string,
+            frame_at_start,
+            frame_at_end: frames.last().cloned(),
+        });
+        index += 1;
+    }
+    Scan { lines, strings }
+}
+
+/// Uses the quotes of the strings that contain neither quote nor escape sequences.
+///
+/// E.g. `"text"` for Bacon's element A and `'text'` for Bacon's element B.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteRule {
+    syntax: Syntax,
+    a: char,
+    b: char,
+}
+
+impl QuoteRule {
+    /// Creates a `QuoteRule` with the quote of the Bacon's element A and the quote of the Bacon's element B, which
+    /// should be distinct quotes of the _syntax_.
+    pub fn new(syntax: Syntax, a: char, b: char) -> errors::Result<QuoteRule> {
+        if a == b || !syntax.quotes.contains(&a) || !syntax.quotes.contains(&b) {
+            Err(BaconError::SteganographerError(format!("Cannot use the quotes {:?} and {:?} of the syntax {:?}", a, b, syntax.quotes)))
+        } else {
+            Ok(QuoteRule { syntax, a, b })
+        }
+    }
+}
+
+impl FormattingRule for QuoteRule {
+    fn sites(&self, code: &[char]) -> Vec<Site> {
+        scan(code, &self.syntax).strings.into_iter()
+            .filter(|(range, quote)| {
+                (*quote == self.a || *quote == self.b) && code[range.start + 1..range.end - 1].iter()
+                    .all(|c| *c != self.a && *c != self.b && *c != '\\' && *c != '\n')
+            })
+            .map(|(range, quote)| Site::new(range, quote == self.a))
+            .collect()
+    }
+
+    fn rewrite(&self, site: &[char], a: bool) -> Vec<char> {
+        let quote = if a { self.a } else { self.b };
+        let mut rewritten = site.to_vec();
+        rewritten[0] = quote;
+        rewritten[site.len() - 1] = quote;
+        rewritten
+    }
+}
+
+/// Uses the presence of a trailing comma after the last item of the lists that span lines and close on a line of
+/// their own.
+///
+/// E.g. A trailing comma for Bacon's element A and no trailing comma for Bacon's element B.
+///
+/// Only lists with more than one item are used, so that a parenthesized expression never becomes a tuple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailingCommaRule {
+    syntax: Syntax,
+}
+
+impl TrailingCommaRule {
+    pub fn new(syntax: Syntax) -> TrailingCommaRule {
+        TrailingCommaRule { syntax }
+    }
+}
+
+impl FormattingRule for TrailingCommaRule {
+    fn sites(&self, code: &[char]) -> Vec<Site> {
+        let lines = scan(code, &self.syntax).lines;
+        lines.windows(2)
+            .filter_map(|pair| {
+                let (last, closing) = (&pair[0], &pair[1]);
+                let frame = last.frame_at_end?;
+                let closes = closing.indent_end < code.len()
+                    && !closing.in_string
+                    && matches!((frame.open, code[closing.indent_end]), ('(', ')') | ('[', ']') | ('{', '}'));
+                if !closes || !self.syntax.is_list(frame.open) || last.code_end == last.indent_end {
+                    return None;
+                }
+                let present = code[last.code_end - 1] == ',';
+                let items_separated = if present { frame.commas > 1 } else { frame.commas > 0 };
+                if code[last.code_end - 1] == frame.open || !items_separated {
+                    None
+                } else if present {
+                    Some(Site::new(last.code_end - 1..last.code_end, true))
+                } else {
+                    Some(Site::new(last.code_end..last.code_end, false))
+                }
+            })
+            .collect()
+    }
+
+    fn rewrite(&self, _site: &[char], a: bool) -> Vec<char> {
+        if a { vec![','] } else { Vec::new() }
+    }
+}
+
+/// Uses the indentation of the continuation lines, which are the lines that start inside a list, where the
+/// indentation does not change the meaning of the code.
+///
+/// E.g. Indentation with spaces for Bacon's element A and with tabs for Bacon's element B.
+///
+/// Only indentation of spaces that is a multiple of the width of a tab, or of tabs only, is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContinuationIndentRule {
+    syntax: Syntax,
+    width: usize,
+}
+
+impl ContinuationIndentRule {
+    /// Creates a `ContinuationIndentRule` that replaces every tab with `width` spaces.
+    pub fn new(syntax: Syntax, width: usize) -> errors::Result<ContinuationIndentRule> {
+        if width == 0 {
+            Err(BaconError::SteganographerError("The width of a tab should be positive".to_string()))
+        } else {
+            Ok(ContinuationIndentRule { syntax, width })
+        }
+    }
+}
+
+impl FormattingRule for ContinuationIndentRule {
+    fn sites(&self, code: &[char]) -> Vec<Site> {
+        scan(code, &self.syntax).lines.into_iter()
+            .filter(|line| {
+                !line.in_string
+                    && line.indent_end > line.start
+                    && line.code_end > line.indent_end
+                    && line.frame_at_start.map(|frame| self.syntax.is_list(frame.open)).unwrap_or(false)
+            })
+            .filter_map(|line| {
+                let indentation = &code[line.start..line.indent_end];
+                if indentation.iter().all(|c| *c == ' ') && indentation.len().is_multiple_of(self.width) {
+                    Some(Site::new(line.start..line.indent_end, true))
+                } else if indentation.iter().all(|c| *c == '\t') {
+                    Some(Site::new(line.start..line.indent_end, false))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn rewrite(&self, site: &[char], a: bool) -> Vec<char> {
+        let levels = if site[0] == '\t' { site.len() } else { site.len() / self.width };
+        if a { vec![' '; levels * self.width] } else { vec!['\t'; levels] }
+    }
+}
+
+/// Applies steganography based on stylistic choices of a code file, like the quotes of the strings, the trailing
+/// commas of the lists and the indentation of the continuation lines.
+///
+/// The choices are made by pluggable [FormattingRule](trait.FormattingRule.html)s, so that the rules of any language
+/// can be used. The sites of all the rules carry the Bacon's elements in the order that they appear in the code.
+pub struct SourceCodeSteganographer {
+    rules: Vec<Box<dyn FormattingRule>>,
+}
+
+impl SourceCodeSteganographer {
+    /// Creates a `SourceCodeSteganographer` without rules.
+    pub fn new() -> SourceCodeSteganographer {
+        SourceCodeSteganographer { rules: Vec::new() }
+    }
+
+    /// The trailing commas and the continuation indentation of Rust code, with tabs of 4 spaces.
+    pub fn rust() -> SourceCodeSteganographer {
+        SourceCodeSteganographer::new()
+            .with_rule(TrailingCommaRule::new(Syntax::rust()))
+            .with_rule(ContinuationIndentRule { syntax: Syntax::rust(), width: 4 })
+    }
+
+    /// The quotes, the trailing commas and the continuation indentation of Python code, with tabs of 4 spaces.
+    pub fn python() -> SourceCodeSteganographer {
+        SourceCodeSteganographer::new()
+            .with_rule(QuoteRule { syntax: Syntax::python(), a: '"', b: '\'' })
+            .with_rule(TrailingCommaRule::new(Syntax::python()))
+            .with_rule(ContinuationIndentRule { syntax: Syntax::python(), width: 4 })
+    }
+
+    /// The quotes, the trailing commas and the continuation indentation of JavaScript code, with tabs of 2 spaces.
+    pub fn javascript() -> SourceCodeSteganographer {
+        SourceCodeSteganographer::new()
+            .with_rule(QuoteRule { syntax: Syntax::javascript(), a: '"', b: '\'' })
+            .with_rule(TrailingCommaRule::new(Syntax::javascript()))
+            .with_rule(ContinuationIndentRule { syntax: Syntax::javascript(), width: 2 })
+    }
+
+    /// Adds a rule.
+    pub fn with_rule<R: FormattingRule + 'static>(mut self, rule: R) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    pub fn add_rule<R: FormattingRule + 'static>(&mut self, rule: R) {
+        self.rules.push(Box::new(rule));
+    }
+
+    // The sites of all the rules, along with the rule of each, in order. Sites that overlap earlier ones are ignored.
+    fn sites(&self, code: &[char]) -> Vec<(Site, &dyn FormattingRule)> {
+        let mut sites: Vec<(Site, &dyn FormattingRule)> = self.rules.iter()
+            .flat_map(|rule| rule.sites(code).into_iter().map(move |site| (site, rule.as_ref())))
+            .collect();
+        sites.sort_by_key(|(site, _)| (site.range.start, site.range.end));
+        let mut end = 0;
+        sites.retain(|(site, _)| {
+            let separate = site.range.start >= end;
+            if separate {
+                end = site.range.end.max(site.range.start + 1);
+            }
+            separate
+        });
+        sites
+    }
+}
+
+impl fmt::Debug for SourceCodeSteganographer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SourceCodeSteganographer")
+            .field("rules", &self.rules.len())
+            .finish()
+    }
+}
+
+impl Default for SourceCodeSteganographer {
+    fn default() -> SourceCodeSteganographer {
+        SourceCodeSteganographer::new()
+    }
+}
+
+impl Steganographer for SourceCodeSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.sites(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let sites = self.sites(public);
+        let encoded = codec.encode(secret);
+        if sites.len() < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available: sites.len(),
+                unit: CapacityUnit::Carriers,
+            });
+        }
+        let mut disguised = Vec::with_capacity(public.len() + encoded.len());
+        let mut copied = 0;
+        for ((site, rule), elem) in sites.iter().zip(encoded.iter()) {
+            disguised.extend_from_slice(&public[copied..site.range.start]);
+            disguised.extend(rule.rewrite(&public[site.range.clone()], codec.is_a(elem)));
+            copied = site.range.end;
+        }
+        disguised.extend_from_slice(&public[copied..]);
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = self.sites(input).iter()
+            .map(|(site, _)| if site.a { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(self.sites(input).iter()
+            .map(|(site, _)| Slot::new(site.range.start, if site.a { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod source_code_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    const PYTHON: &str = "\
+names = [
+    \"alice\",
+    'bob',  # the [builder]
+    \"carol\"
+]
+print(names, \"it's\")
+value = (
+    1
+)
+call(
+\tfirst,
+\tsecond,
+)
+";
+
+    #[test]
+    fn find_the_sites() {
+        let code = Vec::from_iter(PYTHON.chars());
+        let quotes = QuoteRule::new(Syntax::python(), '"', '\'').unwrap();
+        let found: Vec<(String, bool)> = quotes.sites(&code).iter()
+            .map(|site| (String::from_iter(&code[site.range().clone()]), site.is_a()))
+            .collect();
+        assert_eq!(found, vec![
+            ("\"alice\"".to_string(), true),
+            ("'bob'".to_string(), false),
+            ("\"carol\"".to_string(), true),
+        ]);
+        // The list of a single item is not used
+        let commas = TrailingCommaRule::new(Syntax::python()).sites(&code);
+        assert_eq!(commas.len(), 2);
+        assert_eq!(commas[0], Site::new(62..62, false));
+        assert!(commas[1].is_a());
+        let indentation = ContinuationIndentRule::new(Syntax::python(), 4).unwrap().sites(&code);
+        assert_eq!(indentation.iter().map(|site| site.is_a()).collect::<Vec<bool>>(), vec![true, true, true, true, false, false]);
+        assert_eq!(SourceCodeSteganographer::python().capacity(&code).unwrap(), 11);
+    }
+
+    #[test]
+    fn skip_char_literals_raw_strings_and_block_comments() {
+        // The list in the string is not used, nor the brackets of the raw strings and the comment
+        let code = Vec::from_iter("\
+let quote = '\"';
+let text = \"(
+    a, b
+)\";
+let items = [
+    r\"(c, d\",
+    r#\"e \" f\"#, /* ( */
+    b'\\''
+];
+".chars());
+        let commas = TrailingCommaRule::new(Syntax::rust()).sites(&code);
+        assert_eq!(commas.len(), 1);
+        let end = commas[0].range().start;
+        assert_eq!(String::from_iter(&code[end - 5..end + 2]), "b'\\''\n]");
+        let indentation = ContinuationIndentRule::new(Syntax::rust(), 4).unwrap().sites(&code);
+        assert_eq!(indentation.len(), 3);
+        assert!(indentation.iter().all(|site| code[site.range().end] != 'a'));
+        // A single quote that is not a character literal is ignored
+        let code = Vec::from_iter("fn f<'a>(x: &'a str) -> [&'a str; 2] {\n    [\n        x,\n        x\n    ]\n}\n".chars());
+        assert_eq!(TrailingCommaRule::new(Syntax::rust()).sites(&code).len(), 1);
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SourceCodeSteganographer::python();
+        let code = Vec::from_iter(PYTHON.chars());
+        let disguised = s.disguise(&['H', 'I'], &code, &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()), "\
+names = [
+    \"alice\",
+\t'bob',  # the [builder]
+\t\"carol\"
+]
+print(names, \"it's\")
+value = (
+    1
+)
+call(
+    first,
+    second,
+)
+");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[..2], ['H', 'I']);
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let code = Vec::from_iter(PYTHON.chars());
+        assert_eq!(SourceCodeSteganographer::rust().disguise(&['H', 'I'], &code, &codec), Err(BaconError::InsufficientCapacity {
+            required: 10,
+            available: 8,
+            unit: CapacityUnit::Carriers,
+        }));
+        assert!(QuoteRule::new(Syntax::rust(), '"', '\'').is_err());
+        assert!(ContinuationIndentRule::new(Syntax::rust(), 0).is_err());
+    }
+}
@@ -11,31 +11,182 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use html5ever::parse_document;
+use std::cell::Cell;
+use std::io;
+
+use html5ever::{LocalName, Namespace, parse_document, parse_fragment, QualName};
 use html5ever::rcdom::{Handle, NodeData, RcDom};
+use html5ever::serialize::{Serialize, Serializer, TraversalScope};
 use html5ever::tendril::TendrilSink;
 
-use crate::{BaconCodec, errors, Steganographer};
+use crate::{BaconCodec, carrier, errors, Steganographer};
+use crate::carrier::{Segment, SegmentKind};
+use crate::errors::{BaconError, CapacityUnit};
+use crate::stega::{alphabetic_units, units, Wrapping};
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// The maximum depth of the nested elements of a parsed input. The elements are traversed recursively, so deeper
+/// inputs are rejected with [BaconError::InvalidInput](../../errors/enum.BaconError.html#variant.InvalidInput).
+pub const MAX_NESTING_DEPTH: usize = 512;
+
+/// Selects the elements that match a local name and a set of required attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSelector {
+    name: String,
+    attributes: Vec<(String, String)>,
+}
+
+impl TagSelector {
+    pub fn new(name: &str) -> TagSelector {
+        TagSelector {
+            name: name.to_lowercase(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Requires the element to have an attribute with the given value.
+    ///
+    /// For the `class` attribute, the value is matched against each one of the classes of the element.
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_lowercase(), value.to_string()));
+        self
+    }
+
+    /// Creates a `TagSelector` from a start node, like `<b class="a">`.
+    ///
+    /// Returns `None` if the start node cannot be parsed.
+    pub fn parse(start_node: &str) -> Option<TagSelector> {
+        let inner = start_node.trim().strip_prefix('<')?.strip_suffix('>')?.trim();
+        let name_end = inner.find(|c: char| c.is_whitespace()).unwrap_or(inner.len());
+        let name = &inner[..name_end];
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == ':') {
+            return None;
+        }
+        let mut selector = TagSelector::new(name);
+        let mut rest = inner[name_end..].trim_start();
+        while !rest.is_empty() {
+            let attr_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+            let attr_name = &rest[..attr_end];
+            rest = rest[attr_end..].trim_start();
+            let value = if let Some(after_eq) = rest.strip_prefix('=') {
+                let after_eq = after_eq.trim_start();
+                let quote = after_eq.chars().next().filter(|c| c == &'"' || c == &'\'');
+                let (value, remaining) = match quote {
+                    Some(q) => {
+                        let end = after_eq[1..].find(q)? + 1;
+                        (&after_eq[1..end], &after_eq[end + 1..])
+                    }
+                    None => {
+                        let end = after_eq.find(|c: char| c.is_whitespace()).unwrap_or(after_eq.len());
+                        (&after_eq[..end], &after_eq[end..])
+                    }
+                };
+                rest = remaining.trim_start();
+                value
+            } else {
+                ""
+            };
+            if attr_name.is_empty() {
+                return None;
+            }
+            selector = selector.with_attribute(attr_name, value);
+        }
+        Some(selector)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    fn qual_name(&self, namespace: &str) -> QualName {
+        QualName::new(None, Namespace::from(namespace), LocalName::from(self.name.as_str()))
+    }
+
+    /// Tests whether an element with the given local name and attributes matches the selector.
+    pub fn matches(&self, name: &str, attributes: &[(String, String)]) -> bool {
+        self.name == name.to_lowercase() &&
+            self.attributes.iter().all(|(required_name, required_value)| {
+                attributes.iter().any(|(attr_name, attr_value)| {
+                    attr_name.to_lowercase() == *required_name &&
+                        (attr_value == required_value ||
+                            (required_name == "class" && attr_value.split_whitespace().any(|class| class == required_value)))
+                })
+            })
+    }
+}
+
+/// Defines how the markup is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// An HTML document. The markup is placed in `<html>` and `<body>` elements if they are missing.
+    #[default]
+    Document,
+    /// An HTML fragment, as the content of a `<body>` element.
+    Fragment,
+    /// An XML document.
+    Xml,
+}
+
+impl ParseMode {
+    // The namespace of the elements that are created during disguise.
+    fn namespace(&self) -> &'static str {
+        match self {
+            ParseMode::Xml => "",
+            _ => HTML_NAMESPACE,
+        }
+    }
+}
+
+/// Defines which tag applies to the text of an A element that is nested in a B element, or vice versa.
+///
+/// Elements that match neither the A nor the B tag are transparent: their text inherits the tag of their ancestors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NestingPolicy {
+    /// The tag of the innermost element applies.
+    #[default]
+    InnermostWins,
+    /// The tag of the outermost element applies.
+    OutermostWins,
+    /// The reveal fails.
+    Error,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Tag {
     start_node: Option<String>,
     end_node: Option<String>,
+    selector: Option<TagSelector>,
 }
 
 impl Tag {
+    const EMPTY: Tag = Tag {
+        start_node: None,
+        end_node: None,
+        selector: None,
+    };
+
+    /// Creates a new `Tag`. The [TagSelector](struct.TagSelector.html) that is used during reveal is parsed from the `start_node`.
     pub fn new(start_node: Option<&str>, end_node: Option<&str>) -> Tag {
         Tag {
             start_node: start_node.map(|node| node.to_string()),
             end_node: end_node.map(|node| node.to_string()),
+            selector: start_node.and_then(TagSelector::parse),
         }
     }
 
     pub fn empty() -> Tag {
-        Tag {
-            start_node: None,
-            end_node: None,
-        }
+        Tag::EMPTY
+    }
+
+    /// Overrides the [TagSelector](struct.TagSelector.html) that is used during reveal.
+    pub fn with_selector(mut self, selector: TagSelector) -> Self {
+        self.selector = Some(selector);
+        self
     }
 
     pub fn start_node(&self) -> &Option<String> {
@@ -46,6 +197,10 @@ impl Tag {
         &self.end_node
     }
 
+    pub fn selector(&self) -> Option<&TagSelector> {
+        self.selector.as_ref()
+    }
+
     pub fn start_node_string(&self) -> String {
         self.start_node().clone().unwrap_or("".to_string())
     }
@@ -55,10 +210,104 @@ impl Tag {
     }
 }
 
+/// Builds a [SimpleTagSteganographer](struct.SimpleTagSteganographer.html).
+///
+/// Both tags should be set and they should be different.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimpleTagSteganographerBuilder {
+    a_tag: Option<Tag>,
+    b_tag: Option<Tag>,
+    optimize_disguise: bool,
+    embed_in_document: bool,
+    parse_mode: ParseMode,
+    nesting_policy: NestingPolicy,
+    wrapping: Wrapping,
+}
+
+impl Default for SimpleTagSteganographerBuilder {
+    fn default() -> SimpleTagSteganographerBuilder {
+        SimpleTagSteganographerBuilder {
+            a_tag: None,
+            b_tag: None,
+            optimize_disguise: true,
+            embed_in_document: false,
+            parse_mode: ParseMode::Document,
+            nesting_policy: NestingPolicy::InnermostWins,
+            wrapping: Wrapping::Characters,
+        }
+    }
+}
+
+impl SimpleTagSteganographerBuilder {
+    /// Sets the tag of the Bacon's element A. It may be [empty](struct.Tag.html#method.empty).
+    pub fn with_a_tag(mut self, tag: Tag) -> Self {
+        self.a_tag = Some(tag);
+        self
+    }
+
+    /// Sets the tag of the Bacon's element B. It may be [empty](struct.Tag.html#method.empty).
+    pub fn with_b_tag(mut self, tag: Tag) -> Self {
+        self.b_tag = Some(tag);
+        self
+    }
+
+    /// Whether consecutive characters with the same tag are surrounded by a single pair of tags. The default is true.
+    pub fn with_optimize_disguise(mut self, optimize_disguise: bool) -> Self {
+        self.optimize_disguise = optimize_disguise;
+        self
+    }
+
+    /// See [SimpleTagSteganographer::embed_in_document](struct.SimpleTagSteganographer.html#method.embed_in_document).
+    pub fn with_embed_in_document(mut self, embed_in_document: bool) -> Self {
+        self.embed_in_document = embed_in_document;
+        self
+    }
+
+    /// See [SimpleTagSteganographer::with_parse_mode](struct.SimpleTagSteganographer.html#method.with_parse_mode).
+    pub fn with_parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// See [SimpleTagSteganographer::with_nesting_policy](struct.SimpleTagSteganographer.html#method.with_nesting_policy).
+    pub fn with_nesting_policy(mut self, nesting_policy: NestingPolicy) -> Self {
+        self.nesting_policy = nesting_policy;
+        self
+    }
+
+    /// See [SimpleTagSteganographer::with_wrapping](struct.SimpleTagSteganographer.html#method.with_wrapping).
+    pub fn with_wrapping(mut self, wrapping: Wrapping) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    pub fn build(self) -> errors::Result<SimpleTagSteganographer> {
+        let (a_tag, b_tag) = match (self.a_tag, self.b_tag) {
+            (Some(a_tag), Some(b_tag)) => (a_tag, b_tag),
+            _ => return Err(BaconError::SteganographerError("Both the A and the B tags should be set".to_string())),
+        };
+        if a_tag.start_node() == b_tag.start_node() && a_tag.end_node() == b_tag.end_node() {
+            return Err(BaconError::SteganographerError(format!("Cannot use the same tag {:?} for both A and B", a_tag)));
+        }
+        let mut steganographer = SimpleTagSteganographer::new(a_tag, b_tag)
+            .with_parse_mode(self.parse_mode)
+            .with_nesting_policy(self.nesting_policy)
+            .with_wrapping(self.wrapping);
+        steganographer.set_optimize_disguise(self.optimize_disguise);
+        steganographer.set_embed_in_document(self.embed_in_document);
+        Ok(steganographer)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct SimpleTagSteganographer {
     a_tag: Tag,
     b_tag: Tag,
     optimize_disguise: bool,
+    embed_in_document: bool,
+    parse_mode: ParseMode,
+    nesting_policy: NestingPolicy,
+    wrapping: Wrapping,
 }
 
 impl SimpleTagSteganographer {
@@ -67,9 +316,19 @@ impl SimpleTagSteganographer {
             a_tag,
             b_tag,
             optimize_disguise: true,
+            embed_in_document: false,
+            parse_mode: ParseMode::Document,
+            nesting_policy: NestingPolicy::InnermostWins,
+            wrapping: Wrapping::Characters,
         }
     }
 
+    /// Creates a [SimpleTagSteganographerBuilder](struct.SimpleTagSteganographerBuilder.html).
+    pub fn builder() -> SimpleTagSteganographerBuilder {
+        SimpleTagSteganographerBuilder::default()
+    }
+
+    #[deprecated(note = "Use SimpleTagSteganographer::builder().with_optimize_disguise(false) or set_optimize_disguise(false)")]
     pub fn no_optimize_disguise_output(mut self) -> Self {
         self.set_optimize_disguise(false);
         self
@@ -79,137 +338,479 @@ impl SimpleTagSteganographer {
         self.optimize_disguise = b;
     }
 
-    fn parse(&self, handle: &Handle) -> Vec<ParsedInputElement> {
+    /// Treats the public input of the disguise as markup.
+    ///
+    /// By default, the public input is treated as plain text and characters like `<` and `&` are escaped.
+    ///
+    /// The public input is parsed according to the [ParseMode](enum.ParseMode.html) and only the characters of its
+    /// text nodes are surrounded with tags. The markup is preserved and the result is the serialized input.
+    /// In HTML, the text of elements that cannot contain markup (e.g. `script`, `style` or `title`) is not used.
+    pub fn embed_in_document(mut self) -> Self {
+        self.set_embed_in_document(true);
+        self
+    }
+
+    pub fn set_embed_in_document(&mut self, b: bool) {
+        self.embed_in_document = b;
+    }
+
+    /// Sets how the input of the reveal, and the public input of the disguise if it is
+    /// [embedded in a document](struct.SimpleTagSteganographer.html#method.embed_in_document), are parsed.
+    /// The default is [ParseMode::Document](enum.ParseMode.html#variant.Document).
+    pub fn with_parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.set_parse_mode(parse_mode);
+        self
+    }
+
+    pub fn set_parse_mode(&mut self, parse_mode: ParseMode) {
+        self.parse_mode = parse_mode;
+    }
+
+    /// Sets how the reveal treats A and B elements that are nested in each other.
+    /// The default is [NestingPolicy::InnermostWins](enum.NestingPolicy.html#variant.InnermostWins).
+    pub fn with_nesting_policy(mut self, nesting_policy: NestingPolicy) -> Self {
+        self.set_nesting_policy(nesting_policy);
+        self
+    }
+
+    pub fn set_nesting_policy(&mut self, nesting_policy: NestingPolicy) {
+        self.nesting_policy = nesting_policy;
+    }
+
+    /// Sets how the tags are aligned to the words of the public text.
+    /// The default is [Wrapping::Characters](../enum.Wrapping.html#variant.Characters).
+    pub fn with_wrapping(mut self, wrapping: Wrapping) -> Self {
+        self.set_wrapping(wrapping);
+        self
+    }
+
+    pub fn set_wrapping(&mut self, wrapping: Wrapping) {
+        self.wrapping = wrapping;
+    }
+
+    fn parse_input(&self, input: &str) -> errors::Result<ParsedInput> {
+        parse_input(input, self.parse_mode)
+    }
+
+    fn serialize<T: Serialize>(&self, node: &T) -> errors::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        let result = match self.parse_mode {
+            ParseMode::Xml => xml5ever::serialize::serialize(&mut output, node, Default::default()),
+            _ => html5ever::serialize(&mut output, node, Default::default()),
+        };
+        result
+            .map(|_| output)
+            .map_err(BaconError::from)
+    }
+
+    fn is_raw_text(&self, name: &LocalName) -> bool {
+        is_raw_text(self.parse_mode, name)
+    }
+
+    // Splits the text into runs of characters, along with the tag that should surround each run.
+    // `elements` contains the Bacon's elements to disguise (true for A) and `index` the next one to use.
+    fn runs(&self, text: &[char], elements: &[bool], index: &mut usize) -> Vec<(Option<&Tag>, String)> {
+        let mut runs: Vec<(Option<&Tag>, String)> = Vec::new();
+        let units = units(text);
+        let unmarked_carry = self.a_tag.start_node().is_none() || self.b_tag.start_node().is_none();
+        let assigned = self.wrapping.assign(&units, elements, index, unmarked_carry);
+        for (unit, elem) in units.into_iter().zip(assigned) {
+            let tag = elem.map(|is_a| if is_a { &self.a_tag } else { &self.b_tag });
+            match runs.last_mut() {
+                // Adjacent elements of the same tag are merged. Comparing the tags instead of their nodes keeps
+                // A and B elements apart, even if they share the same end node (e.g. `<b class="a">` and `<b class="b">`).
+                Some((last_tag, last_text)) if *last_tag == tag && (tag.is_none() || self.optimize_disguise) => {
+                    last_text.extend(unit.iter())
+                }
+                _ => runs.push((tag, unit.iter().collect())),
+            }
+        }
+        runs
+    }
+
+    fn classify(&self, name: &LocalName, attributes: &[(String, String)]) -> SegmentKind {
+        let a_match = self.a_tag.selector().filter(|selector| selector.matches(name, attributes));
+        let b_match = self.b_tag.selector().filter(|selector| selector.matches(name, attributes));
+        match (a_match, b_match) {
+            // When both match, the more specific selector wins
+            (Some(a), Some(b)) if b.attributes().len() > a.attributes().len() => SegmentKind::B,
+            (Some(_), _) => SegmentKind::A,
+            (None, Some(_)) => SegmentKind::B,
+            (None, None) => SegmentKind::Other,
+        }
+    }
+
+    fn contains_tags(&self, handle: &Handle) -> bool {
+        let is_tag = match handle.data {
+            NodeData::Element { ref name, ref attrs, .. } => {
+                self.classify(&name.local, &attributes(&attrs.borrow())) != SegmentKind::Other
+            }
+            _ => false,
+        };
+        is_tag || handle.children.borrow().iter().any(|child| self.contains_tags(child))
+    }
+
+    fn disguise_document(&self, elements: &[bool], public: &[char]) -> errors::Result<Vec<char>> {
+        if [&self.a_tag, &self.b_tag].iter().any(|tag| tag.start_node().is_some() && tag.selector().is_none()) {
+            return Err(BaconError::SteganographerError(
+                "Cannot embed in a document tags with start nodes that cannot be parsed".to_string()));
+        }
+        let parsed = self.parse_input(&public.iter().collect::<String>())?;
+        let root = parsed.root.clone();
+        if self.contains_tags(&root) {
+            return Err(BaconError::SteganographerError(
+                "The public document already contains elements that match the A or B tags".to_string()));
+        }
+
+        let embedding = DocumentEmbedding {
+            steganographer: self,
+            root,
+            elements,
+            index: Cell::new(0),
+        };
+        let serialized = self.serialize(&embedding)?;
+        self.wrapping.check_carried(embedding.index.get(), elements.len())?;
+        String::from_utf8(serialized)
+            .map(|string| string.chars().collect())
+            .map_err(|error| BaconError::from(error.utf8_error()))
+    }
+
+    /// Classifies the text of the input to segments. The segments do not have ranges, since they are the text nodes
+    /// of the parsed input.
+    pub fn segments(&self, input: &[char]) -> errors::Result<Vec<Segment>> {
+        self.parse_str(&input.iter().collect::<String>())
+    }
+
+    /// Classifies the text of the input to segments, like [segments](#method.segments), without panicking for any input.
+    ///
+    /// This is the entry point for untrusted input: the inputs whose elements are nested deeper than
+    /// [MAX_NESTING_DEPTH](constant.MAX_NESTING_DEPTH.html) are rejected, instead of overflowing the stack.
+    pub fn parse_str(&self, input: &str) -> errors::Result<Vec<Segment>> {
+        self.parse(&self.parse_input(input)?.root)
+    }
+
+    fn parse(&self, handle: &Handle) -> errors::Result<Vec<Segment>> {
         let mut acc = Vec::new();
-        self.do_parse(handle, &mut acc, None);
-        acc
+        self.do_parse(handle, &mut acc, None)?;
+        debug_event!(segments = acc.len(), "parsed tags");
+        Ok(acc)
+    }
+
+    fn tag_of(&self, kind: SegmentKind) -> &Tag {
+        if kind == SegmentKind::A { &self.a_tag } else { &self.b_tag }
     }
 
-    fn do_parse(&self, handle: &Handle, acc: &mut Vec<ParsedInputElement>, parent_element_type: Option<ParsedInputType>) {
+    // Applies the nesting policy to the type of an element and the type that is inherited by its ancestors.
+    fn nested_type(&self, element_type: SegmentKind, inherited: Option<SegmentKind>) -> errors::Result<SegmentKind> {
+        match (element_type, inherited) {
+            (element_type, None) | (element_type, Some(SegmentKind::Other)) => Ok(element_type),
+            (SegmentKind::Other, Some(inherited)) => Ok(inherited),
+            (element_type, Some(inherited)) => {
+                trace_event!(?element_type, ?inherited, policy = ?self.nesting_policy, "nested tags");
+                match self.nesting_policy {
+                    NestingPolicy::InnermostWins => Ok(element_type),
+                    NestingPolicy::OutermostWins => Ok(inherited),
+                    NestingPolicy::Error if element_type == inherited => Ok(element_type),
+                    NestingPolicy::Error => Err(BaconError::UnexpectedMarker {
+                        expected: self.tag_of(inherited).start_node_string(),
+                        found: self.tag_of(element_type).start_node_string(),
+                    }),
+                }
+            }
+        }
+    }
+
+    fn do_parse(&self, handle: &Handle, acc: &mut Vec<Segment>, parent_element_type: Option<SegmentKind>) -> errors::Result<()> {
         let mut current_element_type = None;
 
         match handle.data {
             NodeData::Text { ref contents } => {
                 match parent_element_type {
-                    Some(ParsedInputType::A) => acc.push(ParsedInputElement::new(contents.borrow().to_string(), ParsedInputType::A)),
-                    Some(ParsedInputType::B) => acc.push(ParsedInputElement::new(contents.borrow().to_string(), ParsedInputType::B)),
-                    Some(ParsedInputType::Other) => {
-                        if self.a_tag.start_node.is_none() {
-                            acc.push(ParsedInputElement::new(contents.borrow().to_string(), ParsedInputType::A))
-                        } else if self.b_tag.start_node.is_none() {
-                            acc.push(ParsedInputElement::new(contents.borrow().to_string(), ParsedInputType::B))
-                        }
-                    }
+                    Some(SegmentKind::Other) if self.a_tag.start_node.is_none() => acc.push(Segment::new(SegmentKind::A, &contents.borrow(), None)),
+                    Some(SegmentKind::Other) if self.b_tag.start_node.is_none() => acc.push(Segment::new(SegmentKind::B, &contents.borrow(), None)),
+                    Some(kind) => acc.push(Segment::new(kind, &contents.borrow(), None)),
                     None => { /* ignore */ }
                 }
             }
+            // The text of elements that cannot contain markup does not carry any Bacon's elements
             NodeData::Element {
                 ref name,
+                ref attrs,
                 ..
-            } => {
-                let name = format!("<{}>", name.local);
-                if name == self.a_tag.start_node_string() {
-                    current_element_type = Some(ParsedInputType::A);
-                } else if name == self.b_tag.start_node_string() {
-                    current_element_type = Some(ParsedInputType::B);
-                } else {
-                    current_element_type = Some(ParsedInputType::Other);
-                }
+            } if !self.is_raw_text(&name.local) => {
+                let element_type = self.classify(&name.local, &attributes(&attrs.borrow()));
+                current_element_type = Some(self.nested_type(element_type, parent_element_type)?);
             }
             _ => { /* ignore */ }
         }
 
         for child in handle.children.borrow().iter() {
-            self.do_parse(&child, acc, current_element_type.clone());
+            self.do_parse(child, acc, current_element_type)?;
         }
+        Ok(())
+    }
+}
+
+impl Default for SimpleTagSteganographer {
+    /// Uses `<b>` elements for the Bacon's element A and `<i>` elements for the Bacon's element B.
+    fn default() -> SimpleTagSteganographer {
+        SimpleTagSteganographer::new(Tag::new(Some("<b>"), Some("</b>")), Tag::new(Some("<i>"), Some("</i>")))
     }
 }
 
 impl Steganographer for SimpleTagSteganographer {
     type T = char;
 
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        if self.embed_in_document {
+            Ok(self.segments(public)?.iter().map(|segment| alphabetic_units(segment.text())).sum())
+        } else {
+            Ok(alphabetic_units(&public.iter().collect::<String>()))
+        }
+    }
+
     fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
-        let encoded = codec.encode(secret);
-
-        let mut disguised = String::new();
-        let mut i = 0;
-
-        for pc in public {
-            if pc.is_alphabetic() {
-                let opt = encoded.get(i);
-                if opt.is_some() && codec.is_a(opt.unwrap()) {
-                    disguised.push_str(&format!("{}{}{}",
-                                                self.a_tag.start_node_string(),
-                                                pc.clone(),
-                                                self.a_tag.end_node_string()));
-                    i = i + 1;
-                } else if opt.is_some() && codec.is_b(opt.unwrap()) {
-                    disguised.push_str(&format!("{}{}{}",
-                                                self.b_tag.start_node_string(),
-                                                pc.clone(),
-                                                self.b_tag.end_node_string()));
-                    i = i + 1;
-                } else {
-                    disguised.push(pc.clone())
-                }
-            } else {
-                disguised.push(pc.clone())
-            }
+        enter_span!("disguise", steganographer = "tags", embed_in_document = self.embed_in_document);
+        let elements: Vec<bool> = codec.encode(secret).iter()
+            .map(|elem| codec.is_a(elem))
+            .collect();
+        let available = Steganographer::capacity(self, public)?;
+        if available < elements.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: elements.len(),
+                available,
+                unit: CapacityUnit::Letters,
+            });
         }
 
-        if self.optimize_disguise {
-            Ok(disguised
-                .replace(&format!("{}{}", self.a_tag.end_node_string(), self.a_tag.start_node_string()), "")
-                .replace(&format!("{}{}", self.b_tag.end_node_string(), self.b_tag.start_node_string()), "")
-                .chars().collect())
-        } else {
-            Ok(disguised.chars().collect())
+        if self.embed_in_document {
+            return self.disguise_document(&elements, public);
+        }
+
+        // The public input is plain text, which is escaped by the serializer
+        let mut disguised = Vec::new();
+        let mut index = 0;
+        for (tag, text) in self.runs(public, &elements, &mut index) {
+            let tag = tag.unwrap_or(&Tag::EMPTY);
+            disguised.extend_from_slice(tag.start_node_string().as_bytes());
+            disguised.extend(self.serialize(&EscapedText(&text))?);
+            disguised.extend_from_slice(tag.end_node_string().as_bytes());
         }
+        self.wrapping.check_carried(index, elements.len())?;
+
+        String::from_utf8(disguised)
+            .map(|string| string.chars().collect())
+            .map_err(|error| BaconError::from(error.utf8_error()))
     }
 
     fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
-        let input_iter: Vec<String> = input.iter().map(|ch| ch.to_string()).collect();
-        let dom = parse_document(RcDom::default(), Default::default()).from_iter(input_iter);
+        enter_span!("reveal", steganographer = "tags");
+        Ok(carrier::decode(&self.segments(input)?, codec))
+    }
 
-        let encoded: Vec<AB> = self.parse(&dom.document).iter()
-            .map(|elem| {
-                if elem.tp == ParsedInputType::A {
-                    let v: Vec<AB> = elem.string.chars()
-                        .filter(|sc| sc.is_alphabetic())
-                        .map(|_| codec.a())
-                        .collect();
-                    v
-                } else {
-                    let v: Vec<AB> = elem.string.chars()
-                        .filter(|sc| sc.is_alphabetic())
-                        .map(|_| codec.b())
-                        .collect();
-                    v
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        if self.embed_in_document {
+            let parsed = self.parse_input(&input.iter().collect::<String>())?;
+            let stripping = DocumentStripping { steganographer: self, root: parsed.root.clone() };
+            String::from_utf8(self.serialize(&stripping)?)
+                .map(|string| string.chars().collect())
+                .map_err(|error| BaconError::from(error.utf8_error()))
+        } else {
+            // The disguised input is the plain text of the segments, surrounded by the tags
+            Ok(self.segments(input)?.iter().flat_map(|segment| segment.text().chars()).collect())
+        }
+    }
+}
+
+// Parses the input, whose root is the node whose children are the parsed input.
+pub(crate) fn parse_input(input: &str, parse_mode: ParseMode) -> errors::Result<ParsedInput> {
+    let parsed = match parse_mode {
+        ParseMode::Document => ParsedInput::new(parse_document(RcDom::default(), Default::default()).one(input).document),
+        ParseMode::Fragment => {
+            let context = QualName::new(None, Namespace::from(HTML_NAMESPACE), LocalName::from("body"));
+            let dom = parse_fragment(RcDom::default(), Default::default(), context, Vec::new()).one(input);
+            // The fragment is placed in an `<html>` element
+            let root = dom.document.children.borrow().first().cloned();
+            ParsedInput { root: root.unwrap_or_else(|| dom.document.clone()), document: dom.document }
+        }
+        ParseMode::Xml => ParsedInput::new(xml5ever::driver::parse_document(RcDom::default(), Default::default()).one(input).document),
+    };
+    parsed.check_depth()?;
+    Ok(parsed)
+}
+
+// Elements whose text is not parsed as markup.
+pub(crate) fn is_raw_text(parse_mode: ParseMode, name: &str) -> bool {
+    parse_mode != ParseMode::Xml &&
+        ["script", "style", "xmp", "iframe", "noembed", "noframes", "noscript", "plaintext", "textarea", "title"]
+            .contains(&name)
+}
+
+// A parsed input, along with the document that owns its nodes.
+//
+// The nodes do not implement a non-recursive drop, so dropping deeply nested nodes would overflow the stack. Instead,
+// the nodes are detached from their parents and dropped one by one.
+pub(crate) struct ParsedInput {
+    document: Handle,
+    pub(crate) root: Handle,
+}
+
+impl ParsedInput {
+    fn new(document: Handle) -> ParsedInput {
+        ParsedInput { root: document.clone(), document }
+    }
+
+    fn check_depth(&self) -> errors::Result<()> {
+        let mut nodes = vec![(self.root.clone(), 0)];
+        while let Some((node, depth)) = nodes.pop() {
+            if depth > MAX_NESTING_DEPTH {
+                return Err(BaconError::InvalidInput {
+                    message: format!("The elements are nested deeper than {}", MAX_NESTING_DEPTH),
+                    position: None,
+                    source: None,
+                });
+            }
+            nodes.extend(node.children.borrow().iter().map(|child| (child.clone(), depth + 1)));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ParsedInput {
+    fn drop(&mut self) {
+        let mut nodes = vec![self.document.clone()];
+        while let Some(node) = nodes.pop() {
+            nodes.append(&mut node.children.borrow_mut());
+            if let NodeData::Element { template_contents: Some(ref contents), .. } = node.data {
+                nodes.push(contents.clone());
+            }
+        }
+    }
+}
+
+pub(crate) fn attributes(attrs: &[html5ever::Attribute]) -> Vec<(String, String)> {
+    attrs.iter()
+        .map(|attr| (attr.name.local.to_string(), attr.value.to_string()))
+        .collect()
+}
+
+// Serializes plain text.
+struct EscapedText<'a>(&'a str);
+
+impl<'a> Serialize for EscapedText<'a> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S, _traversal_scope: TraversalScope) -> io::Result<()> {
+        serializer.write_text(self.0)
+    }
+}
+
+// Serializes a parsed document, surrounding the characters of its text nodes with the A and B tags.
+struct DocumentEmbedding<'a> {
+    steganographer: &'a SimpleTagSteganographer,
+    root: Handle,
+    elements: &'a [bool],
+    index: Cell<usize>,
+}
+
+impl<'a> DocumentEmbedding<'a> {
+    fn serialize_node<S: Serializer>(&self, handle: &Handle, serializer: &mut S, raw_text: bool) -> io::Result<()> {
+        match handle.data {
+            NodeData::Element { ref name, ref attrs, .. } => {
+                serializer.start_elem(name.clone(), attrs.borrow().iter().map(|attr| (&attr.name, &attr.value[..])))?;
+                for child in handle.children.borrow().iter() {
+                    self.serialize_node(child, serializer, self.steganographer.is_raw_text(&name.local))?;
                 }
-            })
-            .flat_map(|m| m)
-            .collect();
-        Ok(codec.decode(&encoded))
+                serializer.end_elem(name.clone())
+            }
+            NodeData::Text { ref contents } if !raw_text => {
+                let text: Vec<char> = contents.borrow().chars().collect();
+                let mut index = self.index.get();
+                for (tag, run) in self.steganographer.runs(&text, self.elements, &mut index) {
+                    match tag.and_then(|tag| tag.selector()) {
+                        Some(selector) => {
+                            let attribute_names: Vec<QualName> = selector.attributes().iter()
+                                .map(|(name, _)| QualName::new(None, Namespace::from(""), LocalName::from(name.as_str())))
+                                .collect();
+                            let attrs = attribute_names.iter()
+                                .zip(selector.attributes().iter())
+                                .map(|(name, (_, value))| (name, value.as_str()));
+                            let name = selector.qual_name(self.steganographer.parse_mode.namespace());
+                            serializer.start_elem(name.clone(), attrs)?;
+                            serializer.write_text(&run)?;
+                            serializer.end_elem(name)?;
+                        }
+                        None => serializer.write_text(&run)?,
+                    }
+                }
+                self.index.set(index);
+                Ok(())
+            }
+            NodeData::Text { ref contents } => serializer.write_text(&contents.borrow()),
+            NodeData::Document => {
+                for child in handle.children.borrow().iter() {
+                    self.serialize_node(child, serializer, raw_text)?;
+                }
+                Ok(())
+            }
+            NodeData::Doctype { ref name, .. } => serializer.write_doctype(name),
+            NodeData::Comment { ref contents } => serializer.write_comment(contents),
+            NodeData::ProcessingInstruction { ref target, ref contents } => serializer.write_processing_instruction(target, contents),
+        }
+    }
+}
+
+impl<'a> Serialize for DocumentEmbedding<'a> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S, _traversal_scope: TraversalScope) -> io::Result<()> {
+        for child in self.root.children.borrow().iter() {
+            self.serialize_node(child, serializer, false)?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct ParsedInputElement {
-    string: String,
-    tp: ParsedInputType,
+// Serializes a parsed document, replacing the elements that match the A and B tags with their children.
+struct DocumentStripping<'a> {
+    steganographer: &'a SimpleTagSteganographer,
+    root: Handle,
 }
 
-impl ParsedInputElement {
-    fn new(string: String, tp: ParsedInputType) -> ParsedInputElement {
-        ParsedInputElement { string, tp }
+impl<'a> DocumentStripping<'a> {
+    fn serialize_node<S: Serializer>(&self, handle: &Handle, serializer: &mut S) -> io::Result<()> {
+        match handle.data {
+            NodeData::Element { ref name, ref attrs, .. } => {
+                let is_tag = self.steganographer.classify(&name.local, &attributes(&attrs.borrow())) != SegmentKind::Other;
+                if !is_tag {
+                    serializer.start_elem(name.clone(), attrs.borrow().iter().map(|attr| (&attr.name, &attr.value[..])))?;
+                }
+                for child in handle.children.borrow().iter() {
+                    self.serialize_node(child, serializer)?;
+                }
+                if is_tag {
+                    Ok(())
+                } else {
+                    serializer.end_elem(name.clone())
+                }
+            }
+            NodeData::Text { ref contents } => serializer.write_text(&contents.borrow()),
+            NodeData::Document => {
+                for child in handle.children.borrow().iter() {
+                    self.serialize_node(child, serializer)?;
+                }
+                Ok(())
+            }
+            NodeData::Doctype { ref name, .. } => serializer.write_doctype(name),
+            NodeData::Comment { ref contents } => serializer.write_comment(contents),
+            NodeData::ProcessingInstruction { ref target, ref contents } => serializer.write_processing_instruction(target, contents),
+        }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-enum ParsedInputType {
-    A,
-    B,
-    Other,
+impl<'a> Serialize for DocumentStripping<'a> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S, _traversal_scope: TraversalScope) -> io::Result<()> {
+        for child in self.root.children.borrow().iter() {
+            self.serialize_node(child, serializer)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -225,6 +826,108 @@ mod tag_tests {
     use super::*;
 
     #[test]
+    fn parse_tag_selectors() {
+        assert_eq!(TagSelector::parse("<b>"), Some(TagSelector::new("b")));
+        assert_eq!(TagSelector::parse("<B class=\"a b\" data-x='1' hidden>"),
+                   Some(TagSelector::new("b").with_attribute("class", "a b").with_attribute("data-x", "1").with_attribute("hidden", "")));
+        assert_eq!(TagSelector::parse("<span id=x>"), Some(TagSelector::new("span").with_attribute("id", "x")));
+        assert_eq!(TagSelector::parse("b"), None);
+        assert_eq!(TagSelector::parse("<b class=\"a>"), None);
+        assert_eq!(Tag::new(Some("<i>"), Some("</i>")).selector(), Some(&TagSelector::new("i")));
+    }
+
+    #[test]
+    fn tag_selectors_match() {
+        let selector = TagSelector::new("b").with_attribute("class", "a");
+        assert!(selector.matches("b", &[("class".to_string(), "x a".to_string())]));
+        assert!(selector.matches("B", &[("id".to_string(), "1".to_string()), ("CLASS".to_string(), "a".to_string())]));
+        assert!(!selector.matches("b", &[("class".to_string(), "ab".to_string())]));
+        assert!(!selector.matches("i", &[("class".to_string(), "a".to_string())]));
+        assert!(!selector.matches("b", &[]));
+    }
+
+    #[test]
+    fn disguise_and_reveal_with_attribute_differentiated_tags() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::new(
+                Some("<b class=\"a\">"),
+                Some("</b>")),
+            Tag::new(
+                Some("<b class=\"b\">"),
+                Some("</b>")));
+        // H = AABBB
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("Hello world".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "<b class=\"a\">He</b><b class=\"b\">llo</b> world");
+
+        let public = "This is a public message that contains a secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_and_reveal_whole_words() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::builder()
+            .with_a_tag(Tag::new(Some("<i>"), Some("</i>")))
+            .with_b_tag(Tag::new(Some("<b>"), Some("</b>")))
+            .with_wrapping(Wrapping::Words)
+            .build().unwrap();
+        // H = AABBB
+        let output = s.disguise_str("H", "Here is my plan: I go", &codec).unwrap();
+        assert_eq!(output, "Here <i>is</i> <b>my</b> plan: <b>I</b> go");
+        assert_eq!(s.reveal_str(&output, &codec).unwrap(), "H");
+        let public = "I do not know if he is in or out, so we go up to see if it is a good day. ".repeat(20);
+        let output = s.disguise_str("My secret", &public, &codec).unwrap();
+        assert!(s.reveal_str(&output, &codec).unwrap().starts_with("MYSECRET"));
+        assert!(s.disguise_str("H", "Hello", &codec).is_err());
+    }
+
+    #[test]
+    fn reveal_with_explicit_selectors() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::new(
+                Some("<b>"),
+                Some("</b>")),
+            Tag::new(
+                Some("<b class=\"y\">"),
+                Some("</b>"))
+                .with_selector(TagSelector::new("b").with_attribute("data-kind", "y")));
+        // H = AABBB
+        let public = "<b>He</b><b data-kind=\"y\" class=\"z\">llo</b> world";
+        let output = s.reveal(&Vec::from_iter(public.chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "H");
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::default();
+        let output = s.disguise(&['H'], &Vec::from_iter("Hi 42".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 5, available: 2, unit: CapacityUnit::Letters }));
+        let s = SimpleTagSteganographer::builder()
+            .with_a_tag(Tag::new(Some("<b>"), Some("</b>")))
+            .with_b_tag(Tag::new(Some("<i>"), Some("</i>")))
+            .with_embed_in_document(true)
+            .build()
+            .unwrap();
+        let output = s.disguise(&['H'], &Vec::from_iter("<p>Hi</p><p>42</p>".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 5, available: 2, unit: CapacityUnit::Letters }));
+    }
+
+    #[test]
+    #[allow(clippy::partialeq_to_none)]
     fn tags_creation() {
         let tag1 = Tag::new(None, None);
         assert!(tag1.start_node() == &None);
@@ -302,15 +1005,42 @@ mod tag_tests {
         assert!(string == "<i>T</i><b>h</b><i>i</i><b>s</b> <b>is</b> <i>a</i> <b>pu</b><i>b</i><b>l</b><i>ic</i> <i>m</i><b>e</b><i>ss</i><b>a</b><i>ge</i> <i>tha</i><b>t</b> <i>c</i><b>o</b><i>ntains</i> <b>a</b> <i>se</i><b>c</b><i>re</i><b>t</b> <i>o</i>ne");
     }
 
+    #[test]
+    fn common_traits() {
+        let s = SimpleTagSteganographer::default();
+        assert_eq!(s.clone(), SimpleTagSteganographer::builder()
+            .with_a_tag(Tag::new(Some("<b>"), Some("</b>")))
+            .with_b_tag(Tag::new(Some("<i>"), Some("</i>")))
+            .build()
+            .unwrap());
+        assert!(format!("{:?}", s).contains("<i>"));
+    }
+
+    #[test]
+    fn build_a_steganographer() {
+        let b_tag = Tag::new(Some("<b>"), Some("</b>"));
+        assert!(SimpleTagSteganographer::builder().with_b_tag(b_tag.clone()).build().is_err());
+        assert!(SimpleTagSteganographer::builder().with_a_tag(b_tag.clone()).with_b_tag(b_tag.clone()).build().is_err());
+        let s = SimpleTagSteganographer::builder()
+            .with_a_tag(Tag::new(Some("<i>"), Some("</i>")))
+            .with_b_tag(b_tag)
+            .with_embed_in_document(true)
+            .with_parse_mode(ParseMode::Fragment)
+            .build()
+            .unwrap();
+        assert!(s.embed_in_document && s.optimize_disguise);
+        assert_eq!(s.parse_mode, ParseMode::Fragment);
+    }
+
     #[test]
     fn disguise_a_secret_to_a_char_array_no_output_optimization() {
         let codec = CharCodec::new('a', 'b');
-        let s = SimpleTagSteganographer::new(
-            Tag::empty(),
-            Tag::new(
-                Some("<b>"),
-                Some("</b>")))
-            .no_optimize_disguise_output();
+        let s = SimpleTagSteganographer::builder()
+            .with_a_tag(Tag::empty())
+            .with_b_tag(Tag::new(Some("<b>"), Some("</b>")))
+            .with_optimize_disguise(false)
+            .build()
+            .unwrap();
 
         let public = "This is a public message that contains a secret one";
         let output = s.disguise(
@@ -335,8 +1065,7 @@ mod tag_tests {
             &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
             &Vec::from_iter(public.chars()),
             &codec);
-        let string = String::from_iter(output.unwrap().iter());
-        assert!(string == "S<b>h</b>o<b>rt</b> <b>p</b>u<b>bl</b>i<b>c</b>");
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 40, available: 11, unit: CapacityUnit::Letters }));
     }
 
     #[test]
@@ -410,6 +1139,211 @@ mod tag_tests {
         assert!(string.starts_with("MYSECRET"));
     }
 
+    #[test]
+    fn disguise_escapes_the_public_text() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::empty(),
+            Tag::new(
+                Some("<b>"),
+                Some("</b>")));
+        // H = AABBB
+        let public = "1 < 2 & Hello <i>world</i> > 0";
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "1 &lt; 2 &amp; He<b>llo</b> &lt;i&gt;world&lt;/i&gt; &gt; 0");
+
+        let public = "<This> is a public & message that contains <a> secret one";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_in_a_document() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::new(
+                Some("<b class=\"a\">"),
+                Some("</b>")),
+            Tag::new(
+                Some("<b class=\"b\">"),
+                Some("</b>")))
+            .embed_in_document();
+        // H = AABBB
+        let public = "<!DOCTYPE html><html><head><title>Title</title><script>var x;</script></head>\
+        <body><p id=\"p\">H<i>el</i>l</p><!-- note --><p>o world</p></body></html>";
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "<!DOCTYPE html><html><head><title>Title</title><script>var x;</script></head>\
+        <body><p id=\"p\"><b class=\"a\">H</b><i><b class=\"a\">e</b><b class=\"b\">l</b></i><b class=\"b\">l</b></p>\
+        <!-- note --><p><b class=\"b\">o</b> world</p></body></html>");
+        let output = s.reveal(&Vec::from_iter(string.chars()), &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+        let stripped = s.strip(&Vec::from_iter(string.chars())).unwrap();
+        assert_eq!(String::from_iter(stripped.iter()), public);
+    }
+
+    #[test]
+    fn disguise_and_reveal_in_a_document_with_an_empty_tag() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::empty(),
+            Tag::new(
+                Some("<em>"),
+                Some("</em>")))
+            .embed_in_document();
+        let public = "<html><head><title>A title</title><style>p { color: red; }</style></head>\
+        <body><h1>This is a public message</h1><p>that <a href=\"x\">contains</a> a secret one</p></body></html>";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let string = String::from_iter(disguised.iter());
+        assert!(string.contains("<a href=\"x\">"));
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn disguise_in_a_document_failure() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::empty(),
+            Tag::new(
+                Some("<b>"),
+                Some("</b>")))
+            .embed_in_document();
+        assert!(s.disguise(&['H'], &Vec::from_iter("<p>Hello <b>world</b></p>".chars()), &codec).is_err());
+
+        let s = SimpleTagSteganographer::new(
+            Tag::empty(),
+            Tag::new(
+                Some("b"),
+                Some("</b>")))
+            .embed_in_document();
+        assert!(s.disguise(&['H'], &Vec::from_iter("<p>Hello world</p>".chars()), &codec).is_err());
+    }
+
+    #[test]
+    fn disguise_and_reveal_in_a_fragment() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::new(
+                Some("<b class=\"a\">"),
+                Some("</b>")),
+            Tag::new(
+                Some("<b class=\"b\">"),
+                Some("</b>")))
+            .embed_in_document()
+            .with_parse_mode(ParseMode::Fragment);
+        // H = AABBB
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("<p>Hello <i>world</i></p>".chars()),
+            &codec).unwrap();
+        let string = String::from_iter(output.iter());
+        assert_eq!(string, "<p><b class=\"a\">He</b><b class=\"b\">llo</b> <i>world</i></p>");
+        let output = s.reveal(&output, &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+    }
+
+    #[test]
+    fn disguise_and_reveal_in_xml() {
+        let codec = CharCodec::new('a', 'b');
+        let s = SimpleTagSteganographer::new(
+            Tag::empty(),
+            Tag::new(
+                Some("<em>"),
+                Some("</em>")))
+            .embed_in_document()
+            .with_parse_mode(ParseMode::Xml);
+        // H = AABBB
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("<note><title>Hello</title><body>4 &amp; 2</body></note>".chars()),
+            &codec).unwrap();
+        let string = String::from_iter(output.iter());
+        assert_eq!(string, "<note><title>He<em>llo</em></title><body>4 &amp; 2</body></note>");
+        let output = s.reveal(&output, &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+
+        let public = "<doc><p>This is a public message</p><p>that contains a secret one</p></doc>";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn reveal_nested_tags() {
+        let codec = CharCodec::new('a', 'b');
+        let tags = || SimpleTagSteganographer::new(
+            Tag::new(
+                Some("<b>"),
+                Some("</b>")),
+            Tag::new(
+                Some("<i>"),
+                Some("</i>")));
+        // H = AABBB, Z = BABBB, A = AAAAA
+        let public = Vec::from_iter("<b>H<span>e</span><i>llo</i></b> <i>w<b><u>o</u></b>rld</i>".chars());
+
+        let output = tags().reveal(&public, &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "HZ");
+
+        let output = tags().with_nesting_policy(NestingPolicy::OutermostWins).reveal(&public, &codec);
+        assert!(String::from_iter(output.unwrap().iter()).starts_with('A'));
+
+        let output = tags().with_nesting_policy(NestingPolicy::Error).reveal(&public, &codec);
+        assert_eq!(output, Err(BaconError::UnexpectedMarker { expected: "<b>".to_string(), found: "<i>".to_string() }));
+
+        let public = Vec::from_iter("<b>H<b>e</b></b><i>llo</i> world".chars());
+        let output = tags().with_nesting_policy(NestingPolicy::Error).reveal(&public, &codec);
+        assert_eq!(String::from_iter(output.unwrap().iter()), "H");
+    }
+
+    #[test]
+    fn parse_deeply_nested_elements() {
+        let s = SimpleTagSteganographer::default();
+        let nested = format!("{}<b>He</b>", "<span>".repeat(100_000));
+        assert!(matches!(s.parse_str(&nested), Err(BaconError::InvalidInput { .. })));
+        assert!(matches!(s.clone().embed_in_document().strip(&Vec::from_iter(nested.chars())), Err(BaconError::InvalidInput { .. })));
+
+        let nested = format!("{}<b>He</b>", "<span>".repeat(MAX_NESTING_DEPTH / 2));
+        assert_eq!(s.parse_str(&nested).unwrap(), vec![Segment::new(SegmentKind::A, "He", None)]);
+    }
+
+    #[test]
+    fn segments_of_the_input() {
+        let s = SimpleTagSteganographer::new(
+            Tag::new(
+                Some("<b>"),
+                Some("</b>")),
+            Tag::new(
+                Some("<i>"),
+                Some("</i>")));
+        let segments = s.segments(&Vec::from_iter("<b>He</b>l<i>lo</i>".chars())).unwrap();
+        assert_eq!(segments, vec![
+            Segment::new(SegmentKind::A, "He", None),
+            Segment::new(SegmentKind::Other, "l", None),
+            Segment::new(SegmentKind::B, "lo", None),
+        ]);
+    }
+
     #[test]
     fn parse_a_document_to_tags() {
         let document = "<grandparent><parent>childB1</parent>childA<parent>childB2</parent></grandparent>";
@@ -420,10 +1354,10 @@ mod tag_tests {
             Tag::new(
                 Some("<parent>"),
                 Some("</parent>")));
-        let parse_result = s.parse(&dom.document);
+        let parse_result = s.parse(&dom.document).unwrap();
         assert_eq!(parse_result.len(), 3);
-        assert!(parse_result.contains(&ParsedInputElement::new("childB1".to_string(), ParsedInputType::B)));
-        assert!(parse_result.contains(&ParsedInputElement::new("childB2".to_string(), ParsedInputType::B)));
-        assert!(parse_result.contains(&ParsedInputElement::new("childA".to_string(), ParsedInputType::A)));
+        assert!(parse_result.contains(&Segment::new(SegmentKind::B, "childB1", None)));
+        assert!(parse_result.contains(&Segment::new(SegmentKind::B, "childB2", None)));
+        assert!(parse_result.contains(&Segment::new(SegmentKind::A, "childA", None)));
     }
 }
\ No newline at end of file
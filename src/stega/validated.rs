@@ -0,0 +1,237 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::Carrier;
+use crate::errors::{BaconError, CapacityUnit};
+
+/// Keeps the output of another steganographer valid in its host format, like HTML, JSON or Markdown.
+///
+/// The validator is a function that tells whether a text is valid, like a parser or a renderer of the format. The
+/// carriers of the wrapped steganographer are chosen one by one in the [stripped](../../trait.Steganographer.html#method.strip)
+/// input: a carrier is used only if the text, along with the elements of the carriers that are chosen before it, remains
+/// valid with either Bacon's element in it. Otherwise, the carrier is skipped and the next one is tried. This way, every
+/// chosen element is validated along with the rest, and the reveal chooses the same carriers by validating the stripped
+/// disguised input with the elements that it reads.
+///
+/// The rest of the carriers keep the elements of the public input. If these make the whole disguised output invalid,
+/// the chosen elements are written to the stripped input instead, exactly as they were validated.
+///
+/// The wrapped steganographer should find the same number of carriers in the public input and in its disguised output,
+/// like the [LetterCaseSteganographer](../letter_case/struct.LetterCaseSteganographer.html). Since every carrier is
+/// validated in a whole text, the disguise and the reveal take quadratic time in the length of the input.
+#[derive(Debug, Clone)]
+pub struct ValidatedSteganographer<S, F> {
+    steganographer: S,
+    validator: F,
+}
+
+// The carriers that are chosen in a stripped input, along with the elements of the stripped input, in which the chosen
+// carriers have their elements.
+struct Choice {
+    stripped: Vec<char>,
+    symbols: Vec<bool>,
+    carriers: Vec<usize>,
+}
+
+impl<S, F> ValidatedSteganographer<S, F>
+    where S: Steganographer<T=char>, F: Fn(&[char]) -> bool {
+    /// Creates a `ValidatedSteganographer` that wraps the `steganographer` and keeps the texts that the `validator`
+    /// accepts valid.
+    pub fn new(steganographer: S, validator: F) -> ValidatedSteganographer<S, F> {
+        ValidatedSteganographer { steganographer, validator }
+    }
+
+    /// The wrapped steganographer.
+    pub fn inner(&self) -> &S {
+        &self.steganographer
+    }
+
+    /// Whether the validator accepts the input.
+    pub fn is_valid(&self, input: &[char]) -> bool {
+        (self.validator)(input)
+    }
+
+    /// The indexes of the carriers of the input that carry elements, as they are chosen by the reveal.
+    ///
+    /// Fails if the stripped input is not valid.
+    pub fn usable_carriers(&self, input: &[char]) -> errors::Result<Vec<usize>> {
+        let read = self.steganographer.read_symbols(input)?;
+        Ok(self.choose(input, |index| read.get(index).cloned())?.carriers)
+    }
+
+    // Chooses the carriers of the stripped input, in order, until `element` returns no element for a chosen carrier.
+    fn choose<E>(&self, input: &[char], mut element: E) -> errors::Result<Choice>
+        where E: FnMut(usize) -> Option<bool> {
+        let stripped = self.steganographer.strip(input)?;
+        if !self.is_valid(&stripped) {
+            return Err(invalid_input("The stripped input is not valid"));
+        }
+        let mut symbols = self.steganographer.read_symbols(&stripped)?;
+        let mut carriers = Vec::new();
+        for index in 0..symbols.len() {
+            let original = symbols[index];
+            let mut valid = true;
+            for elem in [true, false] {
+                symbols[index] = elem;
+                valid = valid && self.is_valid(&self.steganographer.write_symbols(&symbols[..=index], &stripped)?);
+            }
+            symbols[index] = original;
+            if valid {
+                match element(index) {
+                    Some(elem) => {
+                        symbols[index] = elem;
+                        carriers.push(index);
+                    }
+                    None => break,
+                }
+            }
+        }
+        Ok(Choice { stripped, symbols, carriers })
+    }
+}
+
+fn invalid_input(message: &str) -> BaconError {
+    BaconError::InvalidInput { message: message.to_string(), position: None, source: None }
+}
+
+impl<S, F> Steganographer for ValidatedSteganographer<S, F>
+    where S: Steganographer<T=char>, F: Fn(&[char]) -> bool {
+    type T = char;
+
+    /// The number of the carriers that are chosen for the elements of the public input itself. Since the carriers are
+    /// validated along with the elements that are chosen before them, a secret may find a different number of them.
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(self.usable_carriers(public)?.len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        if !self.is_valid(public) {
+            return Err(invalid_input("The public input is not valid"));
+        }
+        let encoded = codec.encode(secret);
+        let mut elements = encoded.iter().map(|elem| codec.is_a(elem));
+        let choice = self.choose(public, |_| elements.next())?;
+        if choice.carriers.len() < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available: choice.carriers.len(),
+                unit: CapacityUnit::Carriers,
+            });
+        }
+        let written = choice.carriers.last().map_or(0, |index| index + 1);
+        let mut symbols = self.steganographer.read_symbols(public)?;
+        if symbols.len() < written {
+            return Err(BaconError::SteganographerError("The public input and its stripped form have different carriers".to_string()));
+        }
+        for index in choice.carriers.iter() {
+            symbols[*index] = choice.symbols[*index];
+        }
+        let disguised = self.steganographer.write_symbols(&symbols[..written], public)?;
+        if self.is_valid(&disguised) {
+            return Ok(disguised);
+        }
+        // The carriers that were not chosen are invalid along with the chosen ones in the public input
+        let disguised = self.steganographer.write_symbols(&choice.symbols[..written], &choice.stripped)?;
+        if self.is_valid(&disguised) {
+            Ok(disguised)
+        } else {
+            Err(BaconError::SteganographerError("The steganographer does not write the elements as they were validated".to_string()))
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let read = self.steganographer.read_symbols(input)?;
+        let encoded: Vec<AB> = self.choose(input, |index| read.get(index).cloned())?.carriers.into_iter()
+            .map(|index| if read[index] { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        self.steganographer.strip(input)
+    }
+}
+
+#[cfg(test)]
+mod validated_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    // The literals of JSON, like true and null, are lowercase, while the strings may have any case.
+    fn json_literals(input: &[char]) -> bool {
+        let mut in_string = false;
+        for c in input {
+            if *c == '"' {
+                in_string = !in_string;
+            } else if !in_string && c.is_uppercase() {
+                return false;
+            }
+        }
+        true
+    }
+
+    // No two uppercase letters in a row.
+    fn no_shouting(input: &[char]) -> bool {
+        input.windows(2).all(|pair| !(pair[0].is_uppercase() && pair[1].is_uppercase()))
+    }
+
+    #[test]
+    fn disguise_and_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ValidatedSteganographer::new(LetterCaseSteganographer::new(), json_literals);
+        let public = Vec::from_iter(r#"{"to": "bob", "urgent": true, "body": "see you at the usual place", "cc": null}"#.chars());
+        assert_eq!(Steganographer::capacity(&s, &public).unwrap(), 38);
+        let disguised = s.disguise(&Vec::from_iter("Sunset".chars()), &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), r#"{"To": "boB", "UrgENt": true, "BOdy": "See yOu aT thE usUal place", "cc": null}"#);
+        assert!(s.is_valid(&disguised));
+        assert!(String::from_iter(s.reveal(&disguised, &codec).unwrap().iter()).starts_with("SUNSET"));
+        assert_eq!(s.strip(&disguised).unwrap(), s.strip(&public).unwrap());
+    }
+
+    #[test]
+    fn disguise_fails() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ValidatedSteganographer::new(LetterCaseSteganographer::new(), json_literals);
+        assert!(matches!(s.disguise(&['H'], &Vec::from_iter(r#"{"a": TRUE}"#.chars()), &codec), Err(BaconError::InvalidInput { .. })));
+        assert_eq!(s.disguise(&['H'], &Vec::from_iter(r#"{"key": true}"#.chars()), &codec), Err(BaconError::InsufficientCapacity {
+            required: 5,
+            available: 3,
+            unit: CapacityUnit::Carriers,
+        }));
+    }
+
+    #[test]
+    fn skip_the_carriers_that_are_invalid_together() {
+        let codec = CharCodec::new('a', 'b');
+        // Every letter may be uppercase alone, but not along with its neighbours
+        let s = ValidatedSteganographer::new(LetterCaseSteganographer::new(), no_shouting);
+        let public = Vec::from_iter("hello world".chars());
+        // Z = BABBB
+        let disguised = s.disguise(&['Z'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "HelLo WoRld");
+        assert!(s.is_valid(&disguised));
+        assert_eq!(s.usable_carriers(&disguised).unwrap()[..5], [0, 2, 3, 5, 7]);
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[0], 'Z');
+        // The uppercase E of the public input is not chosen, but it is invalid next to the chosen H
+        let disguised = s.disguise(&['Z'], &Vec::from_iter("hEllo world".chars()), &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "HelLo WoRld");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap()[0], 'Z');
+    }
+}
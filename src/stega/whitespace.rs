@@ -0,0 +1,228 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::{BaconError, CapacityUnit};
+
+/// Applies steganography based on the number of spaces between words.
+///
+/// By default, a single space carries the Bacon's element A and a double space carries the Bacon's element B.
+/// All the visible characters of the public input are left untouched.
+///
+/// Only runs of spaces that separate two words are used as carriers. Runs that contain other whitespace characters
+/// (e.g. new lines), or appear at the start or the end of the input are left as they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhitespaceSteganographer {
+    a_spaces: usize,
+    b_spaces: usize,
+}
+
+impl WhitespaceSteganographer {
+    pub fn new() -> WhitespaceSteganographer {
+        WhitespaceSteganographer {
+            a_spaces: 1,
+            b_spaces: 2,
+        }
+    }
+
+    /// Creates a `WhitespaceSteganographer` that uses `a_spaces` spaces for the element A and `b_spaces` spaces for the element B.
+    pub fn with_spaces(a_spaces: usize, b_spaces: usize) -> errors::Result<WhitespaceSteganographer> {
+        if a_spaces == 0 || b_spaces == 0 {
+            Err(BaconError::SteganographerError("The number of spaces should be greater than zero".to_string()))
+        } else if a_spaces == b_spaces {
+            Err(BaconError::SteganographerError(format!("Cannot use {} spaces for both A and B", a_spaces)))
+        } else {
+            Ok(WhitespaceSteganographer { a_spaces, b_spaces })
+        }
+    }
+
+    pub fn a_spaces(&self) -> usize {
+        self.a_spaces
+    }
+
+    pub fn b_spaces(&self) -> usize {
+        self.b_spaces
+    }
+
+    // Returns the start index and the length of each run of spaces that separates two words.
+    fn space_runs(input: &[char]) -> Vec<(usize, usize)> {
+        let mut runs = Vec::new();
+        let mut i = 0;
+        while i < input.len() {
+            if input[i].is_whitespace() {
+                let start = i;
+                while i < input.len() && input[i].is_whitespace() {
+                    i += 1;
+                }
+                let between_words = start > 0 && i < input.len();
+                if between_words && input[start..i].iter().all(|c| c == &' ') {
+                    runs.push((start, i - start));
+                }
+            } else {
+                i += 1;
+            }
+        }
+        runs
+    }
+}
+
+impl Default for WhitespaceSteganographer {
+    fn default() -> WhitespaceSteganographer {
+        WhitespaceSteganographer::new()
+    }
+}
+
+impl Steganographer for WhitespaceSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(Self::space_runs(public).len())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let runs = Self::space_runs(public);
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if runs.len() < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: runs.len(),
+                unit: CapacityUnit::Spaces,
+            })
+        } else {
+            let encoded = codec.encode(secret);
+
+            let mut disguised: Vec<char> = Vec::new();
+            let mut last = 0;
+
+            for (&(start, len), elem) in runs.iter().zip(encoded.iter()) {
+                disguised.extend_from_slice(&public[last..start]);
+                let spaces = if codec.is_a(elem) { self.a_spaces } else { self.b_spaces };
+                disguised.extend(std::iter::repeat_n(' ', spaces));
+                last = start + len;
+            }
+            disguised.extend_from_slice(&public[last..]);
+
+            Ok(disguised)
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = Self::space_runs(input).iter()
+            .filter_map(|&(_, len)| {
+                if len == self.a_spaces {
+                    Some(codec.a())
+                } else if len == self.b_spaces {
+                    Some(codec.b())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    // The runs of spaces that carry an element are replaced by a single space.
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        let mut stripped = Vec::with_capacity(input.len());
+        let mut last = 0;
+        for (start, len) in Self::space_runs(input) {
+            if len == self.a_spaces || len == self.b_spaces {
+                stripped.extend_from_slice(&input[last..start]);
+                stripped.push(' ');
+                last = start + len;
+            }
+        }
+        stripped.extend_from_slice(&input[last..]);
+        Ok(stripped)
+    }
+}
+
+#[cfg(test)]
+mod whitespace_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    const PUBLIC: &str = "Far out in the uncharted backwaters of the unfashionable end of the western spiral arm of the Galaxy \
+    lies a small unregarded yellow sun. Orbiting this at a distance of roughly ninety-two million miles is an utterly \
+    insignificant little blue green planet whose ape-descended life forms are so amazingly primitive that they still \
+    think digital watches are a pretty neat idea.";
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(WhitespaceSteganographer::with_spaces(1, 1).is_err());
+        assert!(WhitespaceSteganographer::with_spaces(0, 1).is_err());
+        assert!(WhitespaceSteganographer::with_spaces(2, 1).is_ok());
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = WhitespaceSteganographer::new();
+        let output = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter("This is a short public message".chars()),
+            &codec);
+        assert!(output.is_err())
+    }
+
+    #[test]
+    fn disguise_a_secret_to_a_char_array() {
+        let codec = CharCodec::new('a', 'b');
+        let s = WhitespaceSteganographer::new();
+        let output = s.disguise(
+            &['H', 'i'],
+            &Vec::from_iter("One two three four five six seven eight nine ten eleven".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        // H = AABBB, I = ABAAA
+        assert_eq!(string, "One two three  four  five  six seven  eight nine ten eleven");
+    }
+
+    #[test]
+    fn disguise_keeps_other_whitespace() {
+        let codec = CharCodec::new('a', 'b');
+        let s = WhitespaceSteganographer::new();
+        let output = s.disguise(
+            &['B'],
+            &Vec::from_iter(" One\ttwo three\nfour five six seven eight ".chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        // B = AAAAB
+        assert_eq!(string, " One\ttwo three\nfour five six seven  eight ");
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = WhitespaceSteganographer::with_spaces(2, 1).unwrap();
+        let output = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(PUBLIC.chars()),
+            &codec);
+        let disguised = output.unwrap();
+        let visible = |chars: &[char]| chars.iter().filter(|c| !c.is_whitespace()).cloned().collect::<Vec<char>>();
+        assert_eq!(visible(&disguised), visible(&Vec::from_iter(PUBLIC.chars())));
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+        assert_eq!(s.strip(&disguised).unwrap(), Vec::from_iter(PUBLIC.chars()));
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::{BaconError, CapacityUnit};
+use crate::stega::split_words;
+
+/// A dictionary of groups of synonyms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SynonymDictionary {
+    groups: Vec<Vec<String>>,
+    index: HashMap<String, usize>,
+}
+
+impl SynonymDictionary {
+    pub fn new() -> SynonymDictionary {
+        SynonymDictionary::default()
+    }
+
+    /// Parses a dictionary from text.
+    ///
+    /// Every line defines a group of synonyms, separated by commas. Empty lines and lines starting with `#` are ignored.
+    ///
+    /// E.g.
+    ///
+    /// ```text
+    /// # A comment
+    /// big, large, huge
+    /// quick, fast, rapid
+    /// ```
+    pub fn parse(text: &str) -> errors::Result<SynonymDictionary> {
+        let mut dictionary = SynonymDictionary::new();
+        for line in text.lines().map(|line| line.trim()) {
+            if !line.is_empty() && !line.starts_with('#') {
+                let group: Vec<&str> = line.split(',').map(|word| word.trim()).filter(|word| !word.is_empty()).collect();
+                dictionary.add_group(&group)?;
+            }
+        }
+        Ok(dictionary)
+    }
+
+    /// Reads and parses a dictionary. See [parse](struct.SynonymDictionary.html#method.parse) for the format.
+    pub fn from_reader<R: Read>(mut reader: R) -> errors::Result<SynonymDictionary> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)
+            .map_err(|error| BaconError::GeneralError(format!("Could not read the synonym dictionary: {}", error)))?;
+        Self::parse(&text)
+    }
+
+    /// Adds a group of synonyms. The words should be alphabetic and may belong to one group only.
+    pub fn add_group(&mut self, words: &[&str]) -> errors::Result<()> {
+        let group: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        for word in group.iter() {
+            if word.is_empty() || !word.chars().all(|c| c.is_alphabetic()) {
+                return Err(BaconError::SteganographerError(format!("The synonym {:?} should contain only alphabetic characters", word)));
+            }
+            if self.index.contains_key(word) || group.iter().filter(|w| w == &word).count() > 1 {
+                return Err(BaconError::SteganographerError(format!("The synonym {:?} is defined more than once", word)));
+            }
+        }
+        for word in group.iter() {
+            self.index.insert(word.clone(), self.groups.len());
+        }
+        self.groups.push(group);
+        Ok(())
+    }
+
+    /// Returns the synonyms of a word (including the word itself), or `None` if the word is not in the dictionary.
+    pub fn synonyms(&self, word: &str) -> Option<&[String]> {
+        self.index.get(&word.to_lowercase())
+            .map(|index| self.groups[*index].as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+/// Applies steganography based on the parity of the length of the words.
+///
+/// Words of even length carry the Bacon's element A and words of odd length carry the Bacon's element B.
+/// When the parity of a word needs to flip, the word is substituted with a synonym of the right length.
+///
+/// Only the words of the [SynonymDictionary](struct.SynonymDictionary.html) whose group contains synonyms of both parities
+/// are used as carriers. Any other word is left untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordParitySteganographer {
+    dictionary: SynonymDictionary,
+}
+
+impl WordParitySteganographer {
+    pub fn new(dictionary: SynonymDictionary) -> WordParitySteganographer {
+        WordParitySteganographer { dictionary }
+    }
+
+    pub fn dictionary(&self) -> &SynonymDictionary {
+        &self.dictionary
+    }
+
+    fn is_carrier(&self, word: &str) -> bool {
+        self.dictionary.synonyms(word)
+            .map(|synonyms| {
+                synonyms.iter().any(|s| Self::is_even(s)) && synonyms.iter().any(|s| !Self::is_even(s))
+            })
+            .unwrap_or(false)
+    }
+
+    fn is_even(word: &str) -> bool {
+        word.chars().count().is_multiple_of(2)
+    }
+
+    // Returns a synonym of the word with the requested parity, following the capitalization of the word.
+    fn substitute(&self, word: &str, even: bool) -> String {
+        if Self::is_even(word) == even {
+            return word.to_string();
+        }
+        let synonym = self.dictionary.synonyms(word)
+            .and_then(|synonyms| synonyms.iter().find(|s| Self::is_even(s) == even))
+            .cloned()
+            .unwrap_or_else(|| word.to_string());
+        let mut chars = word.chars();
+        let first_upper = chars.next().map(|c| c.is_uppercase()).unwrap_or(false);
+        let all_upper = first_upper && word.chars().count() > 1 && chars.all(|c| c.is_uppercase());
+        if all_upper {
+            synonym.to_uppercase()
+        } else if first_upper {
+            let mut synonym_chars = synonym.chars();
+            synonym_chars.next()
+                .map(|first| first.to_uppercase().chain(synonym_chars).collect())
+                .unwrap_or(synonym)
+        } else {
+            synonym
+        }
+    }
+}
+
+impl Steganographer for WordParitySteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(split_words(public).iter()
+            .filter(|(is_word, word)| *is_word && self.is_carrier(word))
+            .count())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let tokens = split_words(public);
+        let available_size = self.capacity(public)?;
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if available_size < secret_size * codec.encoded_group_size() {
+            Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: available_size,
+                unit: CapacityUnit::Words,
+            })
+        } else {
+            let mut encoded = codec.encode(secret).into_iter();
+
+            let disguised = tokens.iter()
+                .flat_map(|(is_word, text)| {
+                    let substituted = if *is_word && self.is_carrier(text) {
+                        match encoded.next() {
+                            Some(ref elem) if codec.is_a(elem) => self.substitute(text, true),
+                            Some(ref elem) if codec.is_b(elem) => self.substitute(text, false),
+                            _ => text.clone(),
+                        }
+                    } else {
+                        text.clone()
+                    };
+                    substituted.chars().collect::<Vec<char>>()
+                })
+                .collect();
+
+            Ok(disguised)
+        }
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = split_words(input).iter()
+            .filter(|(is_word, word)| *is_word && self.is_carrier(word))
+            .map(|(_, word)| if Self::is_even(word) { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+}
+
+#[cfg(test)]
+mod word_parity_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    const DICTIONARY: &str = "# Synonyms
+    big, huge
+    small, little, tiny
+    quick, fast
+    dog, mutt
+    cat, kitten
+    said, replied
+    happy, glad
+    old, aged, ancient
+    ";
+
+    #[test]
+    fn parse_a_dictionary() {
+        let dictionary = SynonymDictionary::parse(DICTIONARY).unwrap();
+        assert_eq!(dictionary.synonyms("Huge"), Some(&["big".to_string(), "huge".to_string()][..]));
+        assert!(dictionary.synonyms("house").is_none());
+        assert!(SynonymDictionary::parse("big, large\nlarge, huge").is_err());
+        assert!(SynonymDictionary::parse("big, large1").is_err());
+        assert!(SynonymDictionary::from_reader(DICTIONARY.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn substitute_keeps_capitalization() {
+        let s = WordParitySteganographer::new(SynonymDictionary::parse(DICTIONARY).unwrap());
+        assert_eq!(s.substitute("Big", true), "Huge");
+        assert_eq!(s.substitute("BIG", true), "HUGE");
+        assert_eq!(s.substitute("big", false), "big");
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = WordParitySteganographer::new(SynonymDictionary::parse(DICTIONARY).unwrap());
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter("The big dog said hi to the cow".chars()),
+            &codec);
+        assert!(output.is_err())
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = WordParitySteganographer::new(SynonymDictionary::parse(DICTIONARY).unwrap());
+        let public = "The big dog told the small cat that an old hound is happy. \
+        The quick kitty said that the little dog is glad and large.";
+        let output = s.disguise(
+            &['H', 'i'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let disguised = output.unwrap();
+        let string = String::from_iter(disguised.iter());
+        // H = AABBB, I = ABAAA
+        assert_eq!(string, "The huge mutt told the small cat that an old hound is glad. \
+        The quick kitty said that the little mutt is glad and large.");
+
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("HI"));
+    }
+}
@@ -0,0 +1,379 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use quick_xml::escape::partial_escape;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::name::{Namespace, QName, ResolveResult};
+use quick_xml::reader::NsReader;
+use quick_xml::writer::Writer;
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::errors::{BaconError, CapacityUnit, SharedError};
+use crate::stega::{alphabetic_units, units};
+
+/// A namespace-qualified XML element name, along with the prefix that is used to write it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlName {
+    prefix: String,
+    namespace: String,
+    local_name: String,
+}
+
+impl XmlName {
+    pub fn new(prefix: &str, namespace: &str, local_name: &str) -> errors::Result<XmlName> {
+        let is_ncname = |name: &str| {
+            let mut chars = name.chars();
+            chars.next().map(|c| c.is_alphabetic() || c == '_').unwrap_or(false) &&
+                chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+        };
+        if !is_ncname(prefix) || prefix.to_lowercase().starts_with("xml") {
+            Err(BaconError::SteganographerError(format!("Invalid namespace prefix {:?}", prefix)))
+        } else if !is_ncname(local_name) {
+            Err(BaconError::SteganographerError(format!("Invalid local name {:?}", local_name)))
+        } else if namespace.is_empty() {
+            Err(BaconError::SteganographerError("The namespace should not be empty".to_string()))
+        } else {
+            Ok(XmlName {
+                prefix: prefix.to_string(),
+                namespace: namespace.to_string(),
+                local_name: local_name.to_string(),
+            })
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn local_name(&self) -> &str {
+        &self.local_name
+    }
+
+    fn qualified_name(&self) -> String {
+        format!("{}:{}", self.prefix, self.local_name)
+    }
+
+    fn matches(&self, resolved: &ResolveResult, local_name: &[u8]) -> bool {
+        match resolved {
+            ResolveResult::Bound(Namespace(namespace)) => {
+                *namespace == self.namespace.as_bytes() && local_name == self.local_name.as_bytes()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Applies steganography based on namespace-qualified XML elements that surround the characters.
+///
+/// E.g. Surround a character with `<a:x>` and `</a:x>` for Bacon's element A and with `<b:x>` and `</b:x>`
+/// for Bacon's element B, where the prefixes `a` and `b` are bound to different namespaces.
+///
+/// The public input should be an XML document. Only the characters of its text nodes are surrounded with elements and
+/// the rest of the markup, including the order of the attributes, is preserved. The namespaces are declared in the root element.
+/// The reveal resolves the namespaces, so the elements are recognized regardless of the prefixes that are used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlSteganographer {
+    a_name: XmlName,
+    b_name: XmlName,
+}
+
+impl XmlSteganographer {
+    pub fn new(a_name: XmlName, b_name: XmlName) -> errors::Result<XmlSteganographer> {
+        if a_name.namespace == b_name.namespace && a_name.local_name == b_name.local_name {
+            Err(BaconError::SteganographerError("The A and B elements should have different names".to_string()))
+        } else if a_name.prefix == b_name.prefix && a_name.namespace != b_name.namespace {
+            Err(BaconError::SteganographerError(
+                format!("The prefix {:?} cannot be bound to two different namespaces", a_name.prefix)))
+        } else {
+            Ok(XmlSteganographer { a_name, b_name })
+        }
+    }
+
+    fn name(&self, a: bool) -> &XmlName {
+        if a { &self.a_name } else { &self.b_name }
+    }
+
+    // Returns Some(true) for an A element, Some(false) for a B element and None otherwise.
+    fn classify(&self, resolved: &ResolveResult, local_name: &[u8]) -> Option<bool> {
+        if self.a_name.matches(resolved, local_name) {
+            Some(true)
+        } else if self.b_name.matches(resolved, local_name) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // Returns the text of the document, along with the classification of the elements that surround it.
+    fn segments(&self, input: &str) -> errors::Result<Vec<(Option<bool>, String)>> {
+        let mut reader = NsReader::from_str(input);
+        let mut stack: Vec<Option<bool>> = Vec::new();
+        let mut segments = Vec::new();
+        loop {
+            let event = match reader.read_resolved_event() {
+                Ok(event) => event,
+                Err(error) => return Err(parse_error(error, reader.buffer_position())),
+            };
+            match event {
+                (resolved, Event::Start(start)) => {
+                    let parent = stack.last().cloned().flatten();
+                    stack.push(self.classify(&resolved, start.local_name().as_ref()).or(parent));
+                }
+                (_, Event::End(_)) => {
+                    stack.pop();
+                }
+                (_, Event::Text(text)) if !stack.is_empty() => {
+                    segments.push((stack.last().cloned().flatten(), text.unescape().map_err(xml_error)?.into_owned()));
+                }
+                (_, Event::Eof) => break,
+                _ => { /* ignore */ }
+            }
+        }
+        Ok(segments)
+    }
+
+    // Adds the namespace declarations of the A and B elements to the root element.
+    fn declare_namespaces(&self, root: &mut BytesStart) -> errors::Result<()> {
+        let names = if self.a_name.prefix == self.b_name.prefix { vec![&self.a_name] } else { vec![&self.a_name, &self.b_name] };
+        for name in names {
+            let declaration = format!("xmlns:{}", name.prefix);
+            let existing = root.try_get_attribute(declaration.as_str()).map_err(xml_error)?;
+            match existing {
+                Some(attribute) if attribute.value.as_ref() == name.namespace.as_bytes() => { /* already declared */ }
+                Some(_) => {
+                    return Err(BaconError::SteganographerError(
+                        format!("The prefix {:?} is bound to another namespace in the public input", name.prefix)));
+                }
+                None => root.push_attribute((declaration.as_str(), name.namespace.as_str())),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn xml_error<E: std::fmt::Display>(error: E) -> BaconError {
+    BaconError::SteganographerError(format!("Invalid XML input: {}", error))
+}
+
+fn parse_error(error: quick_xml::Error, position: usize) -> BaconError {
+    BaconError::InvalidInput {
+        message: format!("Invalid XML: {}", error),
+        position: Some(position),
+        source: Some(SharedError::new(error)),
+    }
+}
+
+impl Steganographer for XmlSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        let public: String = public.iter().collect();
+        Ok(self.segments(&public)?.iter().map(|(_, text)| alphabetic_units(text)).sum())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        let public: String = public.iter().collect();
+        let segments = self.segments(&public)?;
+        let available_size: usize = segments.iter().map(|(_, text)| alphabetic_units(text)).sum();
+        let secret_size = secret.iter()
+            .filter(|pc| pc.is_alphabetic())
+            .count();
+
+        BaconError::check_secret(secret)?;
+        if segments.iter().any(|(classification, _)| classification.is_some()) {
+            return Err(BaconError::SteganographerError(
+                "The public input already contains elements that match the A or B elements".to_string()));
+        } else if available_size < secret_size * codec.encoded_group_size() {
+            return Err(BaconError::InsufficientCapacity {
+                required: secret_size * codec.encoded_group_size(),
+                available: available_size,
+                unit: CapacityUnit::Letters,
+            });
+        }
+
+        let elements: Vec<bool> = codec.encode(secret).iter()
+            .map(|elem| codec.is_a(elem))
+            .collect();
+        let mut index = 0;
+        let mut depth = 0;
+        let mut reader = NsReader::from_str(&public);
+        let mut writer = Writer::new(Vec::new());
+
+        loop {
+            let event = reader.read_event().map_err(|error| parse_error(error, reader.buffer_position()))?;
+            match event {
+                Event::Start(mut start) => {
+                    if depth == 0 {
+                        self.declare_namespaces(&mut start)?;
+                    }
+                    depth += 1;
+                    writer.write_event(Event::Start(start)).map_err(xml_error)?;
+                }
+                Event::End(end) => {
+                    depth -= 1;
+                    writer.write_event(Event::End(end)).map_err(xml_error)?;
+                }
+                Event::Text(text) if depth > 0 && index < elements.len() => {
+                    let unescaped: Vec<char> = text.unescape().map_err(xml_error)?.chars().collect();
+                    // Runs of characters, along with the Bacon's element that they carry
+                    let mut runs: Vec<(Option<bool>, String)> = Vec::new();
+                    for unit in units(&unescaped) {
+                        let pc: String = unit.iter().collect();
+                        let element = elements.get(index).filter(|_| unit[0].is_alphabetic()).cloned();
+                        if element.is_some() {
+                            index += 1;
+                        }
+                        match runs.last_mut() {
+                            Some((last, last_text)) if *last == element => last_text.push_str(&pc),
+                            _ => runs.push((element, pc)),
+                        }
+                    }
+                    for (element, run) in runs {
+                        let run_text = BytesText::from_escaped(partial_escape(&run));
+                        match element {
+                            Some(a) => {
+                                let name = self.name(a);
+                                let qualified_name = name.qualified_name();
+                                let (resolved, _) = reader.resolve_element(QName(qualified_name.as_bytes()));
+                                if let ResolveResult::Bound(Namespace(namespace)) = resolved {
+                                    if namespace != name.namespace.as_bytes() {
+                                        return Err(BaconError::SteganographerError(
+                                            format!("The prefix {:?} is bound to another namespace in the public input", name.prefix)));
+                                    }
+                                }
+                                writer.write_event(Event::Start(BytesStart::new(qualified_name.as_str()))).map_err(xml_error)?;
+                                writer.write_event(Event::Text(run_text)).map_err(xml_error)?;
+                                writer.write_event(Event::End(BytesEnd::new(qualified_name.as_str()))).map_err(xml_error)?;
+                            }
+                            None => writer.write_event(Event::Text(run_text)).map_err(xml_error)?,
+                        }
+                    }
+                }
+                Event::Eof => break,
+                other => writer.write_event(other).map_err(xml_error)?,
+            }
+        }
+
+        String::from_utf8(writer.into_inner())
+            .map(|string| string.chars().collect())
+            .map_err(|error| BaconError::from(error.utf8_error()))
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let input: String = input.iter().collect();
+        let encoded: Vec<AB> = self.segments(&input)?.iter()
+            .filter_map(|(classification, text)| classification.map(|a| (a, text)))
+            .flat_map(|(a, text)| {
+                let v: Vec<AB> = (0..alphabetic_units(text))
+                    .map(|_| if a { codec.a() } else { codec.b() })
+                    .collect();
+                v
+            })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+}
+
+#[cfg(test)]
+mod xml_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    fn steganographer() -> XmlSteganographer {
+        XmlSteganographer::new(
+            XmlName::new("n1", "urn:one", "x").unwrap(),
+            XmlName::new("n2", "urn:two", "x").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn steganographer_creation_failure() {
+        assert!(XmlName::new("", "urn:one", "x").is_err());
+        assert!(XmlName::new("xmlns", "urn:one", "x").is_err());
+        assert!(XmlName::new("n1", "", "x").is_err());
+        assert!(XmlName::new("n1", "urn:one", "1x").is_err());
+        assert!(XmlSteganographer::new(
+            XmlName::new("n1", "urn:one", "x").unwrap(),
+            XmlName::new("n2", "urn:one", "x").unwrap()).is_err());
+        assert!(XmlSteganographer::new(
+            XmlName::new("n1", "urn:one", "x").unwrap(),
+            XmlName::new("n1", "urn:two", "y").unwrap()).is_err());
+        assert!(XmlSteganographer::new(
+            XmlName::new("n1", "urn:one", "x").unwrap(),
+            XmlName::new("n1", "urn:one", "y").unwrap()).is_ok());
+    }
+
+    #[test]
+    fn disguise_a_secret_to_a_char_array() {
+        let codec = CharCodec::new('a', 'b');
+        let s = steganographer();
+        // H = AABBB
+        let public = "<?xml version=\"1.0\"?><doc z=\"1\" a=\"2\"><!-- c --><p>Hello &amp; world</p></doc>";
+        let output = s.disguise(
+            &['H'],
+            &Vec::from_iter(public.chars()),
+            &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "<?xml version=\"1.0\"?><doc z=\"1\" a=\"2\" xmlns:n1=\"urn:one\" xmlns:n2=\"urn:two\"><!-- c -->\
+        <p><n1:x>He</n1:x><n2:x>llo</n2:x> &amp; world</p></doc>");
+    }
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = steganographer();
+        let public = "<doc xmlns=\"urn:default\"><p>This is a public message</p><p>that contains a secret one</p></doc>";
+        let disguised = s.disguise(
+            &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'],
+            &Vec::from_iter(public.chars()),
+            &codec).unwrap();
+        let output = s.reveal(&disguised, &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert!(string.starts_with("MYSECRET"));
+    }
+
+    #[test]
+    fn reveal_resolves_the_namespaces() {
+        let codec = CharCodec::new('a', 'b');
+        let s = steganographer();
+        // H = AABBB
+        let public = "<doc xmlns:p=\"urn:one\" xmlns:q=\"urn:two\" xmlns:r=\"urn:other\">\
+        <p:x>He</p:x><q:x>l<b>l</b></q:x><x xmlns=\"urn:two\">o</x><r:x>ignored</r:x> world</doc>";
+        let output = s.reveal(&Vec::from_iter(public.chars()), &codec);
+        let string = String::from_iter(output.unwrap().iter());
+        assert_eq!(string, "H");
+    }
+
+    #[test]
+    fn disguise_failure() {
+        let codec = CharCodec::new('a', 'b');
+        let s = steganographer();
+        match s.disguise(&['H'], &Vec::from_iter("<doc>Hello world</p>".chars()), &codec) {
+            Err(error @ BaconError::InvalidInput { .. }) => {
+                assert!(matches!(error, BaconError::InvalidInput { position: Some(_), .. }));
+                assert!(std::error::Error::source(&error).unwrap().downcast_ref::<quick_xml::Error>().is_some());
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(s.disguise(&['H'], &Vec::from_iter("<doc>Hi</doc>".chars()), &codec).is_err());
+        assert!(s.disguise(&['H'], &Vec::from_iter("<doc xmlns:n1=\"urn:x\">Hello world</doc>".chars()), &codec).is_err());
+        assert!(s.disguise(&['H'], &Vec::from_iter("<doc><p xmlns:n2=\"urn:x\">Hello world</p></doc>".chars()), &codec).is_err());
+        assert!(s.disguise(&['H'], &Vec::from_iter("<doc xmlns:n1=\"urn:one\"><n1:x>Hello</n1:x> world</doc>".chars()), &codec).is_err());
+    }
+}
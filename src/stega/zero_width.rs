@@ -0,0 +1,173 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{BaconCodec, errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::{Mapping, Slot};
+use crate::stega::units;
+
+/// The ZERO WIDTH NON-JOINER, which carries the Bacon's element A.
+pub const ZWNJ: char = '\u{200C}';
+/// The ZERO WIDTH JOINER, which carries the Bacon's element B.
+pub const ZWJ: char = '\u{200D}';
+
+/// Applies steganography based on invisible zero-width characters.
+///
+/// The disguise places a [ZWNJ](constant.ZWNJ.html) for the Bacon's element A or a [ZWJ](constant.ZWJ.html) for the
+/// Bacon's element B after the alphabetic characters of the public input, which are not rendered between the letters
+/// of most scripts. The zero-width characters that already exist inside the grapheme clusters of the public input, like
+/// the joiners of the emoji ZWJ sequences, belong to the text: they are kept and they are not revealed as elements.
+/// The ones that end a cluster, like after a letter, cannot be told apart from the elements and are removed.
+///
+/// Without the feature `grapheme-clusters`, every character is a cluster, so all the existing zero-width characters
+/// are removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZeroWidthSteganographer {}
+
+impl ZeroWidthSteganographer {
+    pub fn new() -> ZeroWidthSteganographer {
+        ZeroWidthSteganographer {}
+    }
+}
+
+fn is_zero_width(c: &char) -> bool {
+    *c == ZWNJ || *c == ZWJ
+}
+
+// The positions of the zero-width characters that end a unit of the input, which are the elements.
+fn element_positions(input: &[char]) -> Vec<usize> {
+    let mut end = 0;
+    units(input).iter()
+        .filter_map(|unit| {
+            end += unit.len();
+            unit.last().filter(|c| is_zero_width(c)).map(|_| end - 1)
+        })
+        .collect()
+}
+
+impl Steganographer for ZeroWidthSteganographer {
+    type T = char;
+
+    fn capacity(&self, public: &[char]) -> errors::Result<usize> {
+        Ok(units(public).iter().filter(|unit| unit[0].is_alphabetic()).count())
+    }
+
+    fn disguise<AB>(&self, secret: &[char], public: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+        BaconError::check_secret(secret)?;
+        let encoded = codec.encode(secret);
+        let available = Steganographer::capacity(self, public)?;
+        if available < encoded.len() {
+            return Err(BaconError::InsufficientCapacity {
+                required: encoded.len(),
+                available,
+                unit: CapacityUnit::Letters,
+            });
+        }
+
+        let mut encoded = encoded.iter();
+        let mut disguised = Vec::with_capacity(public.len() + available);
+        for unit in units(public) {
+            // The zero-width characters that end the unit would be revealed as elements
+            let end = unit.iter().rposition(|c| !is_zero_width(c)).map_or(0, |last| last + 1);
+            disguised.extend_from_slice(&unit[..end]);
+            if unit[0].is_alphabetic() {
+                if let Some(elem) = encoded.next() {
+                    disguised.push(if codec.is_a(elem) { ZWNJ } else { ZWJ });
+                }
+            }
+        }
+        Ok(disguised)
+    }
+
+    fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=Self::T>) -> errors::Result<Vec<char>> {
+        let encoded: Vec<AB> = element_positions(input).into_iter()
+            .map(|position| if input[position] == ZWNJ { codec.a() } else { codec.b() })
+            .collect();
+        Ok(codec.decode(&encoded))
+    }
+
+    fn strip(&self, input: &[char]) -> errors::Result<Vec<char>> {
+        let mut elements = element_positions(input).into_iter().peekable();
+        Ok(input.iter().enumerate()
+            .filter(|(position, _)| elements.next_if_eq(position).is_none())
+            .map(|(_, c)| *c)
+            .collect())
+    }
+
+    fn mapping(&self, input: &[char]) -> errors::Result<Mapping> {
+        Ok(Mapping::new(element_positions(input).into_iter()
+            .map(|position| Slot::new(position, if input[position] == ZWNJ { SegmentKind::A } else { SegmentKind::B }))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod zero_width_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+
+    use super::*;
+
+    #[test]
+    fn disguise_and_reveal_a_secret() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ZeroWidthSteganographer::new();
+        let public = "This is a public message";
+        let disguised = s.disguise(&['H', 'i'], &Vec::from_iter(public.chars()), &codec).unwrap();
+        // H = AABBB, I = ABAAA
+        assert_eq!(String::from_iter(disguised.iter()),
+                   "T\u{200C}h\u{200C}i\u{200D}s\u{200D} i\u{200D}s\u{200C} a\u{200D} p\u{200C}u\u{200C}b\u{200C}lic message");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['H', 'I']);
+        assert_eq!(String::from_iter(s.strip(&disguised).unwrap().iter()), public);
+        assert_eq!(s.mapping(&disguised).unwrap().slots().len(), 10);
+    }
+
+    #[test]
+    fn existing_zero_width_characters_are_removed() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ZeroWidthSteganographer::new();
+        let public = Vec::from_iter("\u{200D}Hello\u{200C}".chars());
+        // A = AAAAA
+        let disguised = s.disguise(&['A'], &public, &codec).unwrap();
+        assert_eq!(String::from_iter(disguised.iter()), "H\u{200C}e\u{200C}l\u{200C}l\u{200C}o\u{200C}");
+        assert_eq!(s.reveal(&disguised, &codec).unwrap(), vec!['A']);
+    }
+
+    #[cfg(feature = "grapheme-clusters")]
+    #[test]
+    fn the_joiners_of_the_emoji_are_kept() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ZeroWidthSteganographer::new();
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let public = format!("Hello {} family, how are you?", family);
+        let disguised = s.disguise_str("Hi", &public, &codec).unwrap();
+        assert!(disguised.contains(family));
+        assert_eq!(s.reveal_str(&disguised, &codec).unwrap(), "HI");
+        let disguised = Vec::from_iter(disguised.chars());
+        assert_eq!(String::from_iter(s.strip(&disguised).unwrap().iter()), public);
+        assert_eq!(s.mapping(&disguised).unwrap().slots().len(), 10);
+        // Nothing is revealed from the joiners of the public input
+        assert_eq!(s.reveal_str(&public, &codec).unwrap(), "");
+    }
+
+    #[test]
+    fn disguise_fails_because_of_public_message_length() {
+        let codec = CharCodec::new('a', 'b');
+        let s = ZeroWidthSteganographer::new();
+        let output = s.disguise(&['H'], &Vec::from_iter("Hi 42".chars()), &codec);
+        assert_eq!(output, Err(BaconError::InsufficientCapacity { required: 5, available: 2, unit: CapacityUnit::Letters }));
+    }
+}
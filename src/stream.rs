@@ -0,0 +1,487 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Streaming steganography, for inputs that are too large to be held in memory.
+//!
+//! The streams use the letter case channel of the [LetterCaseSteganographer](../stega/letter_case/struct.LetterCaseSteganographer.html):
+//! lowercase characters carry the Bacon's element A and capital characters carry the Bacon's element B.
+//! The units of the text are processed as they arrive, so the disguised text is the same as the one of the
+//! steganographer, including its padding.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::str;
+
+use crate::{BaconCodec, errors};
+use crate::codecs::groups::Groups;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::session::RevealSession;
+use crate::stega::{PaddingElements, PaddingSource, units};
+use crate::stega::letter_case::LetterCaseSteganographer;
+
+// Decodes UTF-8 input that arrives in arbitrary chunks, keeping the bytes of any incomplete character for the next chunk.
+#[derive(Default)]
+pub(crate) struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    // Appends the characters of the chunk that are complete to `output`.
+    pub(crate) fn decode(&mut self, chunk: &[u8], output: &mut String) -> io::Result<()> {
+        self.pending.extend_from_slice(chunk);
+        let valid_up_to = match str::from_utf8(&self.pending) {
+            Ok(s) => s.len(),
+            // An incomplete character at the end of the chunk
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+        };
+        output.push_str(str::from_utf8(&self.pending[..valid_up_to]).unwrap());
+        self.pending.drain(..valid_up_to);
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "The input ends with an incomplete UTF-8 character"))
+        }
+    }
+}
+
+// The state of a streaming disguise: the elements of the encoded secret, the elements of the padding that follows it
+// and the index of the next carrier.
+pub(crate) struct DisguiseState {
+    steganographer: LetterCaseSteganographer,
+    decoder: Utf8Decoder,
+    // The characters of the last unit, which may continue in the next chunk
+    pending: Vec<char>,
+    elements: Groups,
+    source: PaddingSource,
+    padding: PaddingElements,
+    index: usize,
+}
+
+impl DisguiseState {
+    pub(crate) fn new<AB>(secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<DisguiseState> {
+        BaconError::check_secret(secret)?;
+        let steganographer = LetterCaseSteganographer::new();
+        let elements = codec.encode_groups(secret);
+        let source = PaddingSource::new(&elements, codec);
+        Ok(DisguiseState {
+            padding: source.elements(steganographer.padding()),
+            steganographer,
+            decoder: Utf8Decoder::default(),
+            pending: Vec::new(),
+            elements,
+            source,
+            index: 0,
+        })
+    }
+
+    pub(crate) fn set_steganographer(&mut self, steganographer: LetterCaseSteganographer) {
+        self.padding = self.source.elements(steganographer.padding());
+        self.steganographer = steganographer;
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.elements.len().saturating_sub(self.index)
+    }
+
+    // Disguises the public text of the chunk and returns the disguised text of the units that are complete.
+    pub(crate) fn disguise(&mut self, chunk: &[u8]) -> io::Result<String> {
+        let mut public = String::new();
+        self.decoder.decode(chunk, &mut public)?;
+        self.pending.extend(public.chars());
+        let complete = units(&self.pending).split_last()
+            .map(|(_, complete)| complete.iter().map(|unit| unit.len()).sum())
+            .unwrap_or(0);
+        Ok(self.disguise_pending(complete))
+    }
+
+    // Disguises the first `len` pending characters, which end at the end of a unit.
+    fn disguise_pending(&mut self, len: usize) -> String {
+        let public: Vec<char> = self.pending.drain(..len).collect();
+        let (elements, index, padding) = (&self.elements, &mut self.index, &mut self.padding);
+        let mut disguised = String::new();
+        self.steganographer.write_units(&units(&public), || {
+            *index += 1;
+            elements.is_a(*index - 1).or_else(|| padding.next())
+        }, &mut disguised);
+        disguised
+    }
+
+    // Disguises and returns the last unit, once the whole input is written.
+    pub(crate) fn flush(&mut self) -> io::Result<String> {
+        self.decoder.finish()?;
+        Ok(self.disguise_pending(self.pending.len()))
+    }
+
+    // Verifies that the secret is fully disguised.
+    pub(crate) fn finish(&self) -> io::Result<()> {
+        if self.remaining() > 0 {
+            Err(io::Error::other(BaconError::InsufficientCapacity {
+                required: self.elements.len(),
+                available: self.index,
+                unit: CapacityUnit::Letters,
+            }))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Disguises a secret in the public text that is written to it, writing the disguised text to an inner writer.
+///
+/// The secret is encoded when the writer is created and its elements are applied to the public text as it arrives.
+/// The last unit of the text that is written is held back, since it may continue in the next write.
+/// [finish](struct.BaconDisguiseWriter.html#method.finish) should be called after the whole public text is written,
+/// in order to write the last unit and verify that the secret is fully disguised.
+pub struct BaconDisguiseWriter<W: Write> {
+    inner: W,
+    state: DisguiseState,
+}
+
+impl<W: Write> BaconDisguiseWriter<W> {
+    pub fn new<AB>(inner: W, secret: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<BaconDisguiseWriter<W>> {
+        Ok(BaconDisguiseWriter {
+            inner,
+            state: DisguiseState::new(secret, codec)?,
+        })
+    }
+
+    /// Sets the steganographer that defines the carrier characters.
+    pub fn with_steganographer(mut self, steganographer: LetterCaseSteganographer) -> Self {
+        self.set_steganographer(steganographer);
+        self
+    }
+
+    pub fn set_steganographer(&mut self, steganographer: LetterCaseSteganographer) {
+        self.state.set_steganographer(steganographer);
+    }
+
+    /// Returns the number of Bacon's elements that are not disguised yet.
+    pub fn remaining(&self) -> usize {
+        self.state.remaining()
+    }
+
+    /// Flushes the inner writer and returns it.
+    ///
+    /// Fails if the written public text ends with an incomplete UTF-8 character, or if it was too short for the secret.
+    pub fn finish(mut self) -> io::Result<W> {
+        let disguised = self.state.flush()?;
+        self.inner.write_all(disguised.as_bytes())?;
+        self.state.finish()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for BaconDisguiseWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let disguised = self.state.disguise(buf)?;
+        self.inner.write_all(disguised.as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes the characters that it is extended with to an inner writer, encoded as UTF-8, so that it can be the sink of
+/// [disguise_to](../trait.Steganographer.html#method.disguise_to).
+///
+/// The characters are written one by one, so a buffered writer is preferable. The first error of the inner writer
+/// is kept and the characters that follow it are discarded. [finish](struct.CharWriter.html#method.finish) returns it.
+pub struct CharWriter<W: Write> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> CharWriter<W> {
+    pub fn new(inner: W) -> CharWriter<W> {
+        CharWriter { inner, error: None }
+    }
+
+    /// Flushes the inner writer and returns it, or the first error that occurred while writing.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Extend<char> for CharWriter<W> {
+    fn extend<I: IntoIterator<Item=char>>(&mut self, iter: I) {
+        let mut buf = [0u8; 4];
+        for c in iter {
+            if self.error.is_some() {
+                return;
+            }
+            if let Err(error) = self.inner.write_all(c.encode_utf8(&mut buf).as_bytes()) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+// The state of a streaming reveal: the session that keeps the group that is not complete yet and the characters that
+// are decoded but not consumed yet.
+pub(crate) struct RevealState<'a, AB> {
+    decoder: Utf8Decoder,
+    codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>,
+    session: RevealSession<'a, LetterCaseSteganographer, AB>,
+    revealed: VecDeque<char>,
+}
+
+impl<'a, AB> RevealState<'a, AB> {
+    pub(crate) fn new(codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> RevealState<'a, AB> {
+        RevealState {
+            decoder: Utf8Decoder::default(),
+            codec,
+            session: RevealSession::new(LetterCaseSteganographer::new(), codec),
+            revealed: VecDeque::new(),
+        }
+    }
+
+    // The steganographer is set before the input is read, so the session starts over.
+    pub(crate) fn set_steganographer(&mut self, steganographer: LetterCaseSteganographer) {
+        self.session = RevealSession::new(steganographer, self.codec);
+    }
+
+    // Classifies the carrier text of the chunk and decodes every group that is completed.
+    pub(crate) fn reveal(&mut self, chunk: &[u8]) -> io::Result<()> {
+        let mut input = String::new();
+        self.decoder.decode(chunk, &mut input)?;
+        let input: Vec<char> = input.chars().collect();
+        let revealed = self.session.reveal(&input).map_err(io::Error::other)?;
+        self.revealed.extend(revealed);
+        Ok(())
+    }
+
+    pub(crate) fn next_revealed(&mut self) -> Option<char> {
+        self.revealed.pop_front()
+    }
+
+    // The elements of an incomplete group at the end of the input are not decoded.
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        self.decoder.finish()
+    }
+}
+
+/// Reveals the secret that is hidden in the text of an inner reader, as the text arrives.
+///
+/// The reader is an iterator over the characters of the secret. Every character is returned as soon as the
+/// Bacon's elements of its group are read, so that secrets can be extracted from live streams. An incomplete group
+/// at the end of the input is ignored.
+pub struct BaconRevealReader<'a, R: Read, AB> {
+    inner: R,
+    state: RevealState<'a, AB>,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl<'a, R: Read, AB> BaconRevealReader<'a, R, AB> {
+    pub fn new(inner: R, codec: &'a dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> BaconRevealReader<'a, R, AB> {
+        BaconRevealReader {
+            inner,
+            state: RevealState::new(codec),
+            buffer: vec![0; 8192],
+            finished: false,
+        }
+    }
+
+    /// Sets the steganographer that defines the carrier characters.
+    pub fn with_steganographer(mut self, steganographer: LetterCaseSteganographer) -> Self {
+        self.set_steganographer(steganographer);
+        self
+    }
+
+    pub fn set_steganographer(&mut self, steganographer: LetterCaseSteganographer) {
+        self.state.set_steganographer(steganographer);
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<'a, R: Read, AB> Iterator for BaconRevealReader<'a, R, AB> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<io::Result<char>> {
+        loop {
+            if let Some(c) = self.state.next_revealed() {
+                return Some(Ok(c));
+            } else if self.finished {
+                return None;
+            }
+            let read = match self.inner.read(&mut self.buffer) {
+                Ok(read) => read,
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Some(Err(error)),
+            };
+            let result = if read == 0 {
+                self.finished = true;
+                self.state.finish()
+            } else {
+                self.state.reveal(&self.buffer[..read])
+            };
+            if let Err(error) = result {
+                self.finished = true;
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::Padding;
+    use crate::Steganographer;
+
+    use super::*;
+
+    #[test]
+    fn disguise_a_secret_to_a_writer() {
+        let codec = CharCodec::new('a', 'b');
+        let mut writer = BaconDisguiseWriter::new(Vec::new(), &['M', 'y', ' ', 's', 'e', 'c', 'r', 'e', 't'], &codec).unwrap();
+        for chunk in "This is a public message that contains a secret one".as_bytes().chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.remaining(), 0);
+        let output = writer.finish().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one");
+    }
+
+    #[test]
+    fn disguise_multi_byte_characters_that_are_split() {
+        let codec = CharCodec::new('a', 'b');
+        let public = "Ηλιόλουστη μέρα";
+        let mut writer = BaconDisguiseWriter::new(Vec::new(), &['H'], &codec).unwrap();
+        for byte in public.as_bytes() {
+            writer.write_all(&[*byte]).unwrap();
+        }
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(output, "ηλΙΌΛουστη μέρα");
+        let revealed = LetterCaseSteganographer::new().reveal(&Vec::from_iter(output.chars()), &codec).unwrap();
+        assert!(String::from_iter(revealed.iter()).starts_with('H'));
+    }
+
+    #[test]
+    fn reveal_a_secret_from_a_reader() {
+        let codec = CharCodec::new('a', 'b');
+        let input = "tHiS IS a PUbLic mEssAge thaT cOntains A seCreT one";
+        let reader = BaconRevealReader::new(input.as_bytes(), &codec);
+        let revealed: io::Result<String> = reader.collect();
+        assert_eq!(revealed.unwrap(), "MYSECRET");
+    }
+
+    #[test]
+    fn reveal_every_group_as_soon_as_it_is_read() {
+        let codec = CharCodec::new('a', 'b');
+        // A reader that returns one byte per read
+        struct Bytes<'b>(&'b [u8]);
+        impl<'b> Read for Bytes<'b> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let (first, rest) = self.0.split_at(self.0.len().min(1));
+                buf[..first.len()].copy_from_slice(first);
+                self.0 = rest;
+                Ok(first.len())
+            }
+        }
+        // H = AABBB
+        let input = "heLLO wOrld, ηλΙΌΛ";
+        let mut reader = BaconRevealReader::new(Bytes(input.as_bytes()), &codec);
+        assert_eq!(reader.next().unwrap().unwrap(), 'H');
+        assert_eq!(reader.into_inner().0, " wOrld, ηλΙΌΛ".as_bytes());
+
+        let reader = BaconRevealReader::new(Bytes(input.as_bytes()), &codec);
+        let revealed: io::Result<String> = reader.collect();
+        assert_eq!(revealed.unwrap(), "HIH");
+    }
+
+    #[test]
+    fn reveal_from_a_reader_failure() {
+        let codec = CharCodec::new('a', 'b');
+        let mut reader = BaconRevealReader::new("heLLO wörld".as_bytes().split_at(8).0, &codec);
+        assert_eq!(reader.next().unwrap().unwrap(), 'H');
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn disguise_to_a_writer_like_the_steganographer() {
+        let codec = CharCodec::new('a', 'b');
+        let secret = ['H', 'I'];
+        // The accent of the second é is a combining mark, which is split from its letter by the chunks
+        let public = "Déjà vu: a cafe\u{301} in the street, with a view to the sea and the old harbour";
+        let steganographers = [
+            LetterCaseSteganographer::new(),
+            LetterCaseSteganographer::new().with_padding(Padding::Repeat),
+            LetterCaseSteganographer::new().with_padding(Padding::RandomLetters(7)),
+            LetterCaseSteganographer::new().with_padding(Padding::Nulls),
+        ];
+        for steganographer in steganographers.iter() {
+            let expected = steganographer.disguise(&secret, &Vec::from_iter(public.chars()), &codec).unwrap();
+            let mut writer = BaconDisguiseWriter::new(Vec::new(), &secret, &codec).unwrap()
+                .with_steganographer(steganographer.clone());
+            for chunk in public.as_bytes().chunks(3) {
+                writer.write_all(chunk).unwrap();
+            }
+            let disguised = String::from_utf8(writer.finish().unwrap()).unwrap();
+            assert_eq!(disguised, String::from_iter(expected.iter()), "{:?}", steganographer.padding());
+        }
+    }
+
+    #[test]
+    fn disguise_to_a_writer_failure() {
+        let codec = CharCodec::new('a', 'b');
+        assert!(BaconDisguiseWriter::new(Vec::new(), &['1'], &codec).is_err());
+
+        let mut writer = BaconDisguiseWriter::new(Vec::new(), &['H'], &codec).unwrap();
+        writer.write_all(b"Hell").unwrap();
+        // The last unit may continue in the next chunk, so it is disguised by finish
+        assert_eq!(writer.remaining(), 2);
+        assert!(writer.finish().is_err());
+
+        let mut writer = BaconDisguiseWriter::new(Vec::new(), &['H'], &codec).unwrap();
+        writer.write_all("Hello wörld".as_bytes().split_at(8).0).unwrap();
+        assert!(writer.finish().is_err());
+
+        let mut writer = BaconDisguiseWriter::new(Vec::new(), &['H'], &codec).unwrap();
+        assert!(writer.write_all(&[b'H', 0xff, b'i']).is_err());
+    }
+
+    #[test]
+    fn disguise_to_a_char_writer() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let mut writer = CharWriter::new(Vec::new());
+        s.disguise_to(&['H'], &Vec::from_iter("Hello wörld".chars()), &mut writer, &codec).unwrap();
+        assert_eq!(String::from_utf8(writer.finish().unwrap()).unwrap(), "heLLO wörld");
+
+        let mut buf = [0u8; 3];
+        let mut writer = CharWriter::new(&mut buf[..]);
+        writer.extend("wörld".chars());
+        assert!(writer.finish().is_err());
+    }
+}
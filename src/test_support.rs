@@ -0,0 +1,291 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for property-testing the integrations of the crate (needs the feature `test-support`).
+//!
+//! A [Generator](struct.Generator.html) produces random secrets, cover texts and marker pairs. The inputs are
+//! reproducible: the same seed always generates the same inputs, so that a failing case can be replayed.
+//! [roundtrip_property](fn.roundtrip_property.html) checks that a [Scheme](../registry/struct.Scheme.html) reveals every
+//! secret that it disguises.
+//!
+//! ```rust
+//! use bacon_cipher::registry::Registry;
+//! use bacon_cipher::test_support::roundtrip_property;
+//!
+//! let scheme = Registry::default().scheme("markdown:*/**").unwrap();
+//! assert_eq!(roundtrip_property(&scheme), Ok(()));
+//! ```
+
+use std::fmt;
+
+use crate::errors::BaconError;
+use crate::random::Random;
+use crate::registry::Scheme;
+use crate::stega::markdown::{MarkdownSteganographer, Marker};
+
+/// The number of random cases that [roundtrip_property](fn.roundtrip_property.html) checks.
+pub const ROUNDTRIP_CASES: usize = 64;
+
+const MAX_SECRET_LEN: usize = 16;
+const MAX_WORD_LEN: usize = 8;
+const MAX_SENTENCE_WORDS: usize = 10;
+// The cover text is doubled this many times at most, until it has the capacity for the secret
+const MAX_COVER_GROWTH: usize = 8;
+
+const SYMMETRIC_MARKERS: [&str; 7] = ["*", "**", "_", "__", "~~", "`", "=="];
+const ASYMMETRIC_MARKERS: [(&str, &str); 4] = [("{", "}"), ("((", "))"), ("<sub>", "</sub>"), ("<sup>", "</sup>")];
+const TEMPLATES: [&str; 3] = ["[{}](#{})", "[{}](https://example.com)", "![{}](image.png)"];
+
+/// Generates random inputs, reproducibly from a seed.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    random: Random,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator { random: Random::new(seed) }
+    }
+
+    /// A random secret of `len` characters, which are ASCII letters and spaces.
+    pub fn secret(&mut self, len: usize) -> Vec<char> {
+        (0..len)
+            .map(|_| match self.random.below(27) {
+                26 => ' ',
+                letter if self.random.below(2) == 0 => (b'a' + letter as u8) as char,
+                letter => (b'A' + letter as u8) as char,
+            })
+            .collect()
+    }
+
+    /// A random cover text that contains exactly `letters` alphabetic characters.
+    ///
+    /// The text consists of sentences of lowercase words, which start with a capital letter and end with a period. The
+    /// words are separated with spaces and, sometimes, with commas or hyphens. Some words contain an apostrophe, like
+    /// `word's`, so that the text carries punctuation as well.
+    pub fn cover_text(&mut self, letters: usize) -> Vec<char> {
+        let mut text = Vec::new();
+        let mut remaining = letters;
+        let mut sentence_words = 0;
+        while remaining > 0 {
+            let word_len = (1 + self.random.below(MAX_WORD_LEN)).min(remaining);
+            if sentence_words > 0 {
+                match self.random.below(6) {
+                    0 => text.extend(&[',', ' ']),
+                    1 => text.push('-'),
+                    _ => text.push(' '),
+                }
+            }
+            let apostrophe = if word_len > 2 && self.random.below(4) == 0 { Some(word_len - 1) } else { None };
+            for i in 0..word_len {
+                if apostrophe == Some(i) {
+                    text.push('\'');
+                }
+                let letter = (b'a' + self.random.below(26) as u8) as char;
+                text.push(if i == 0 && sentence_words == 0 { letter.to_ascii_uppercase() } else { letter });
+            }
+            remaining -= word_len;
+            sentence_words += 1;
+            if remaining == 0 || sentence_words > self.random.below(MAX_SENTENCE_WORDS) {
+                text.push('.');
+                if remaining > 0 {
+                    text.push(' ');
+                }
+                sentence_words = 0;
+            }
+        }
+        text
+    }
+
+    /// A random pair of markers for the A and B elements, which creates a
+    /// [MarkdownSteganographer](../stega/markdown/struct.MarkdownSteganographer.html).
+    ///
+    /// Each marker is symmetric, asymmetric, a template or, for one of the two, empty.
+    pub fn marker_pair(&mut self) -> (Marker, Marker) {
+        loop {
+            let a_marker = self.marker(true);
+            let b_marker = self.marker(!a_marker.is_empty());
+            if MarkdownSteganographer::new(a_marker.clone(), b_marker.clone()).is_ok() {
+                return (a_marker, b_marker);
+            }
+        }
+    }
+
+    fn marker(&mut self, allow_empty: bool) -> Marker {
+        match self.random.below(if allow_empty { 4 } else { 3 }) {
+            0 => {
+                let marker = SYMMETRIC_MARKERS[self.random.below(SYMMETRIC_MARKERS.len())];
+                Marker::new(Some(marker), Some(marker))
+            }
+            1 => {
+                let (start, end) = ASYMMETRIC_MARKERS[self.random.below(ASYMMETRIC_MARKERS.len())];
+                Marker::new(Some(start), Some(end))
+            }
+            2 => Marker::template(TEMPLATES[self.random.below(TEMPLATES.len())])
+                .expect("The templates contain the placeholder"),
+            _ => Marker::empty(),
+        }
+    }
+}
+
+/// A secret and a cover text, for which a scheme does not reveal the secret that it disguised.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample {
+    secret: Vec<char>,
+    public: Vec<char>,
+    error: BaconError,
+}
+
+impl Counterexample {
+    pub fn secret(&self) -> &[char] {
+        &self.secret
+    }
+
+    pub fn public(&self) -> &[char] {
+        &self.public
+    }
+
+    /// The error of the disguise, or the [RoundTripMismatch](../errors/enum.BaconError.html#variant.RoundTripMismatch)
+    /// of the reveal.
+    pub fn error(&self) -> &BaconError {
+        &self.error
+    }
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The secret {:?} in the public text {:?} fails: {}",
+               self.secret.iter().collect::<String>(), self.public.iter().collect::<String>(), self.error)
+    }
+}
+
+/// Checks that the scheme reveals the secrets that it disguises, for [ROUNDTRIP_CASES](constant.ROUNDTRIP_CASES.html)
+/// random secrets. See [roundtrip_property_with](fn.roundtrip_property_with.html).
+pub fn roundtrip_property(scheme: &Scheme) -> Result<(), Counterexample> {
+    roundtrip_property_with(scheme, &mut Generator::new(0), ROUNDTRIP_CASES)
+}
+
+/// Checks that the scheme reveals the secrets that it disguises, for `cases` random secrets of the generator.
+///
+/// The cover text of each secret is generated with enough [capacity](../registry/struct.Scheme.html#method.capacity)
+/// for it, and the round trip is checked with [Scheme::verify](../registry/struct.Scheme.html#method.verify). The first
+/// case that fails is returned.
+pub fn roundtrip_property_with(scheme: &Scheme, generator: &mut Generator, cases: usize) -> Result<(), Counterexample> {
+    for _ in 0..cases {
+        let len = 1 + generator.random.below(MAX_SECRET_LEN);
+        let secret = generator.secret(len);
+        let public = cover_with_capacity(scheme, generator, &secret);
+        if let Err(error) = scheme.verify(&secret, &public) {
+            return Err(Counterexample { secret, public, error });
+        }
+    }
+    Ok(())
+}
+
+// Generates a cover text, doubling its letters until the scheme can carry the secret in it.
+fn cover_with_capacity(scheme: &Scheme, generator: &mut Generator, secret: &[char]) -> Vec<char> {
+    let required = scheme.required_capacity(secret);
+    let mut letters = required.max(1);
+    let mut public = generator.cover_text(letters);
+    for _ in 0..MAX_COVER_GROWTH {
+        match scheme.capacity(&public) {
+            Ok(capacity) if capacity < required => {
+                letters *= 2;
+                public = generator.cover_text(letters);
+            }
+            _ => break,
+        }
+    }
+    public
+}
+
+#[cfg(test)]
+mod test_support_tests {
+    use crate::{BaconCodec, errors, Steganographer};
+    use crate::codecs::char_codec::CharCodec;
+    use crate::registry::Registry;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+
+    use super::*;
+
+    #[test]
+    fn generated_inputs() {
+        let mut generator = Generator::new(42);
+        let secret = generator.secret(100);
+        assert_eq!(secret.len(), 100);
+        assert!(secret.iter().all(|c| c.is_ascii_alphabetic() || *c == ' '));
+        assert_eq!(secret, Generator::new(42).secret(100));
+
+        for letters in 0..50 {
+            let cover = generator.cover_text(letters);
+            assert_eq!(cover.iter().filter(|c| c.is_alphabetic()).count(), letters);
+            assert!(letters == 0 || (cover[0].is_uppercase() && cover[cover.len() - 1] == '.'));
+        }
+
+        for _ in 0..50 {
+            let (a_marker, b_marker) = generator.marker_pair();
+            assert!(MarkdownSteganographer::new(a_marker, b_marker).is_ok());
+        }
+    }
+
+    #[test]
+    fn roundtrip_of_the_default_schemes() {
+        let registry = Registry::default();
+        // The plugins that the tests register globally are not checked
+        let mut configs = vec!["letter-case", "markdown", "markdown:*/**", "markdown:[{}](#{})/", "html-entity",
+                               "math-alphanumeric", "punctuation", "whitespace", "zero-width"];
+        if cfg!(feature = "extended-steganography") {
+            configs.push("tag:<b>/<i>");
+        }
+        let schemes = configs.into_iter().map(|config| registry.scheme(config).unwrap());
+        for scheme in schemes {
+            assert_eq!(roundtrip_property(&scheme), Ok(()), "{}", scheme.name());
+        }
+    }
+
+    #[test]
+    fn roundtrip_of_random_markers() {
+        let mut generator = Generator::new(7);
+        for _ in 0..10 {
+            let (a_marker, b_marker) = generator.marker_pair();
+            let steganographer = MarkdownSteganographer::new(a_marker, b_marker).unwrap();
+            let scheme = Scheme::new("markdown", steganographer, Box::new(CharCodec::new('A', 'B')));
+            assert_eq!(roundtrip_property_with(&scheme, &mut generator, 8), Ok(()));
+        }
+    }
+
+    // Leaves the public text untouched, so that it never reveals the secret.
+    struct UntouchedSteganographer(LetterCaseSteganographer);
+
+    impl Steganographer for UntouchedSteganographer {
+        type T = char;
+
+        fn disguise<AB>(&self, _: &[char], public: &[char], _: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+            Ok(public.to_vec())
+        }
+
+        fn reveal<AB>(&self, input: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Vec<char>> {
+            self.0.reveal(input, codec)
+        }
+    }
+
+    #[test]
+    fn counterexample_of_a_broken_scheme() {
+        let scheme = Scheme::new("untouched", UntouchedSteganographer(LetterCaseSteganographer::new()), Box::new(CharCodec::new('A', 'B')));
+        let counterexample = roundtrip_property(&scheme).unwrap_err();
+        assert!(matches!(counterexample.error(), BaconError::RoundTripMismatch { .. }));
+        assert_eq!(counterexample.public().iter().filter(|c| c.is_alphabetic()).count(), scheme.required_capacity(counterexample.secret()));
+        assert!(counterexample.to_string().starts_with("The secret"));
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compile time assertions that the codecs, the steganographers and the schemes are `Send` and `Sync`, so that they can
+// be configured once and shared across the threads of a server.
+
+use crate::codecs::char_codec::{CharCodec, CharCodecV2};
+use crate::codecs::format::GroupFormat;
+use crate::codecs::groups::Groups;
+use crate::covergen::CoverGenerator;
+use crate::dynamic::DynSteganographer;
+use crate::envelope::Envelope;
+use crate::registry::{Codec, Registry, Scheme};
+use crate::stega::acrostic::AcrosticSteganographer;
+#[cfg(feature = "extended-steganography")]
+use crate::stega::css_class::CssClassSteganographer;
+use crate::stega::direction_mark::DirectionMarkSteganographer;
+#[cfg(feature = "extended-steganography")]
+use crate::stega::dom::{DomChannelSteganographer, ElementData};
+use crate::stega::hex_case::HexCaseSteganographer;
+use crate::stega::html_entity::HtmlEntitySteganographer;
+use crate::stega::hybrid::HybridSteganographer;
+use crate::stega::inverted::Inverted;
+use crate::stega::jpeg::JpegSteganographer;
+use crate::stega::leet::LeetSteganographer;
+use crate::stega::letter_case::LetterCaseSteganographer;
+use crate::stega::lsb::LsbSteganographer;
+use crate::stega::markdown::MarkdownSteganographer;
+use crate::stega::math_alphanumeric::MathAlphanumericSteganographer;
+use crate::stega::morse::MorseSteganographer;
+use crate::stega::null_cipher::NullCipherSteganographer;
+use crate::stega::percent_encoding::PercentEncodingSteganographer;
+#[cfg(feature = "png-steganography")]
+use crate::stega::png_text::PngTextSteganographer;
+use crate::stega::protected::ProtectedSteganographer;
+use crate::stega::punctuation::PunctuationSteganographer;
+#[cfg(feature = "regex-steganography")]
+use crate::stega::regex::RegexSteganographer;
+use crate::stega::sentence_punctuation::SentencePunctuationSteganographer;
+use crate::stega::scattered::ScatteredSteganographer;
+use crate::stega::source_code::SourceCodeSteganographer;
+#[cfg(feature = "extended-steganography")]
+use crate::stega::tags::SimpleTagSteganographer;
+use crate::stega::validated::ValidatedSteganographer;
+use crate::stega::whitespace::WhitespaceSteganographer;
+use crate::stega::word_parity::WordParitySteganographer;
+use crate::stega::zero_width::ZeroWidthSteganographer;
+use crate::watermark::Watermark;
+#[cfg(feature = "xml-steganography")]
+use crate::stega::xml::XmlSteganographer;
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+    assert_send_sync::<CharCodec<char>>();
+    assert_send_sync::<CharCodecV2<char>>();
+    assert_send_sync::<Codec>();
+    assert_send_sync::<GroupFormat>();
+    assert_send_sync::<Groups>();
+
+    assert_send_sync::<AcrosticSteganographer>();
+    #[cfg(feature = "extended-steganography")]
+    assert_send_sync::<CssClassSteganographer>();
+    assert_send_sync::<DirectionMarkSteganographer>();
+    #[cfg(feature = "extended-steganography")]
+    assert_send_sync::<DomChannelSteganographer<fn(&ElementData) -> Option<bool>>>();
+    assert_send_sync::<HexCaseSteganographer>();
+    assert_send_sync::<HtmlEntitySteganographer>();
+    assert_send_sync::<HybridSteganographer>();
+    assert_send_sync::<Inverted<LetterCaseSteganographer>>();
+    assert_send_sync::<JpegSteganographer<LetterCaseSteganographer>>();
+    assert_send_sync::<LeetSteganographer>();
+    assert_send_sync::<LetterCaseSteganographer>();
+    assert_send_sync::<LsbSteganographer>();
+    assert_send_sync::<MarkdownSteganographer>();
+    assert_send_sync::<MathAlphanumericSteganographer>();
+    assert_send_sync::<MorseSteganographer>();
+    assert_send_sync::<NullCipherSteganographer>();
+    assert_send_sync::<PercentEncodingSteganographer>();
+    #[cfg(feature = "png-steganography")]
+    assert_send_sync::<PngTextSteganographer<LetterCaseSteganographer>>();
+    assert_send_sync::<ProtectedSteganographer<LetterCaseSteganographer>>();
+    assert_send_sync::<PunctuationSteganographer>();
+    #[cfg(feature = "regex-steganography")]
+    assert_send_sync::<RegexSteganographer>();
+    assert_send_sync::<ScatteredSteganographer<LetterCaseSteganographer>>();
+    assert_send_sync::<SentencePunctuationSteganographer>();
+    assert_send_sync::<SourceCodeSteganographer>();
+    #[cfg(feature = "extended-steganography")]
+    assert_send_sync::<SimpleTagSteganographer>();
+    assert_send_sync::<ValidatedSteganographer<LetterCaseSteganographer, fn(&[char]) -> bool>>();
+    assert_send_sync::<WhitespaceSteganographer>();
+    assert_send_sync::<WordParitySteganographer>();
+    assert_send_sync::<ZeroWidthSteganographer>();
+    #[cfg(feature = "xml-steganography")]
+    assert_send_sync::<XmlSteganographer>();
+    assert_send_sync::<Box<dyn DynSteganographer>>();
+
+    assert_send_sync::<Scheme>();
+    assert_send_sync::<Registry>();
+    assert_send_sync::<CoverGenerator>();
+    assert_send_sync::<Envelope>();
+    assert_send_sync::<Watermark>();
+};
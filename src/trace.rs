@@ -0,0 +1,109 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Instrumentation with the `tracing` crate, which compiles to nothing without the feature `tracing`.
+//
+// The arguments of the macros are the arguments of the respective `tracing` macros. They are not evaluated at all
+// when the feature is not enabled, so that the instrumentation does not cost anything.
+
+// Emits an event at the debug level.
+macro_rules! debug_event {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)+);
+    };
+}
+
+// Emits an event at the trace level, for the decisions that are taken for every part of the input.
+macro_rules! trace_event {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)+);
+    };
+}
+
+// Enters a span at the debug level, until the end of the enclosing block.
+macro_rules! enter_span {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($arg)+).entered();
+    };
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod trace_tests {
+    use std::fmt;
+    use std::iter::FromIterator;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{Event, Metadata, Subscriber};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::markdown::MarkdownSteganographer;
+    use crate::Steganographer;
+
+    // Collects the messages of the events.
+    struct Messages(Arc<Mutex<Vec<String>>>);
+
+    struct MessageVisitor<'a>(&'a mut String);
+
+    impl<'a> Visit for MessageVisitor<'a> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Messages {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event) {
+            let mut message = String::new();
+            event.record(&mut MessageVisitor(&mut message));
+            self.0.lock().unwrap().push(message);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn trace_a_reveal() {
+        let codec = CharCodec::new('a', 'b');
+        let s = MarkdownSteganographer::default();
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let revealed = tracing::subscriber::with_default(Messages(messages.clone()), || {
+            s.reveal(&Vec::from_iter("**He**llo **wor".chars()), &codec).unwrap()
+        });
+        // A complete group and an incomplete one
+        assert_eq!(revealed.len(), 2);
+        assert_eq!(*messages.lock().unwrap(), vec![
+            "closed marker", "unterminated marker", "parsed markdown", "decoding segments", "decoding",
+        ]);
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Renders a carrier text with the characters that carry the Bacon's elements A and B highlighted.
+//!
+//! The characters are classified by the [mapping](../trait.Steganographer.html#method.mapping) of a steganographer.
+//!
+//! ```rust
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//! use bacon_cipher::visualize::{self, Format};
+//!
+//! let s = LetterCaseSteganographer::new();
+//! let input: Vec<char> = "heLLO!".chars().collect();
+//! let html = visualize::visualize(&s, &input, Format::Html).unwrap();
+//! assert_eq!(html, "<mark class=\"bacon-a\">he</mark><mark class=\"bacon-b\">LLO</mark>!");
+//! ```
+use crate::{errors, Steganographer};
+use crate::carrier::SegmentKind;
+use crate::mapping::Mapping;
+use crate::stega::units;
+
+const ANSI_A: &str = "\u{1b}[30;46m";
+const ANSI_B: &str = "\u{1b}[30;43m";
+const ANSI_RESET: &str = "\u{1b}[0m";
+
+/// The format of the rendered text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// ANSI escape sequences for terminals: a cyan background for A and a yellow background for B.
+    Ansi,
+    /// HTML, with the characters surrounded by `<mark class="bacon-a">` and `<mark class="bacon-b">`. The rest of the text is escaped.
+    Html,
+}
+
+impl Format {
+    fn start(&self, kind: SegmentKind) -> &'static str {
+        match (self, kind) {
+            (Format::Ansi, SegmentKind::A) => ANSI_A,
+            (Format::Ansi, _) => ANSI_B,
+            (Format::Html, SegmentKind::A) => "<mark class=\"bacon-a\">",
+            (Format::Html, _) => "<mark class=\"bacon-b\">",
+        }
+    }
+
+    fn end(&self) -> &'static str {
+        match self {
+            Format::Ansi => ANSI_RESET,
+            Format::Html => "</mark>",
+        }
+    }
+
+    fn push_text(&self, output: &mut String, text: &[char]) {
+        for c in text {
+            match (self, c) {
+                (Format::Html, '&') => output.push_str("&amp;"),
+                (Format::Html, '<') => output.push_str("&lt;"),
+                (Format::Html, '>') => output.push_str("&gt;"),
+                (Format::Html, '"') => output.push_str("&quot;"),
+                _ => output.push(*c),
+            }
+        }
+    }
+}
+
+/// Renders the input, highlighting the characters of the slots of the mapping.
+///
+/// Adjacent characters that carry the same element are highlighted together. Characters that do not carry any element are not highlighted.
+pub fn render(input: &[char], mapping: &Mapping, format: Format) -> String {
+    let mut output = String::new();
+    let mut open: Option<SegmentKind> = None;
+    let mut position = 0;
+    for unit in units(input) {
+        let kind = mapping.slot_at(position).map(|slot| slot.kind());
+        if open.is_some() && open != kind {
+            output.push_str(format.end());
+            open = None;
+        }
+        if let (None, Some(kind)) = (open, kind) {
+            output.push_str(format.start(kind));
+            open = Some(kind);
+        }
+        format.push_text(&mut output, unit);
+        position += unit.len();
+    }
+    if open.is_some() {
+        output.push_str(format.end());
+    }
+    output
+}
+
+/// Renders the input, highlighting the characters that carry elements according to the mapping of the steganographer.
+pub fn visualize<S: Steganographer<T=char>>(steganographer: &S, input: &[char], format: Format) -> errors::Result<String> {
+    Ok(render(input, &steganographer.mapping(input)?, format))
+}
+
+#[cfg(test)]
+mod visualize_tests {
+    use std::iter::FromIterator;
+
+    use crate::mapping::Slot;
+    use crate::stega::markdown::{MarkdownSteganographer, Marker};
+
+    use super::*;
+
+    #[test]
+    fn render_ansi() {
+        let mapping = Mapping::new(vec![Slot::new(0, SegmentKind::A), Slot::new(1, SegmentKind::B), Slot::new(3, SegmentKind::B)]);
+        let output = render(&Vec::from_iter("ab c".chars()), &mapping, Format::Ansi);
+        assert_eq!(output, "\u{1b}[30;46ma\u{1b}[0m\u{1b}[30;43mb\u{1b}[0m \u{1b}[30;43mc\u{1b}[0m");
+    }
+
+    #[test]
+    fn render_html() {
+        let s = MarkdownSteganographer::new(Marker::empty(), Marker::new(Some("*"), Some("*"))).unwrap();
+        let input = Vec::from_iter("He*llo* <w>".chars());
+        assert_eq!(visualize(&s, &input, Format::Html).unwrap(),
+                   "<mark class=\"bacon-a\">He</mark>*<mark class=\"bacon-b\">llo</mark>* &lt;<mark class=\"bacon-a\">w</mark>&gt;");
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watermarks that identify the copies of a distributed document.
+//!
+//! A [Watermark](struct.Watermark.html) is a short identifier, like the name of the recipient of a copy, that is
+//! embedded repeatedly by any [Carrier](../carrier/trait.Carrier.html), so that even an excerpt of a leaked copy carries
+//! it. The detector finds every complete copy of the identifier, at any offset.
+//!
+//! ```rust
+//! use bacon_cipher::codecs::char_codec::CharCodec;
+//! use bacon_cipher::stega::letter_case::LetterCaseSteganographer;
+//! use bacon_cipher::watermark::Watermark;
+//!
+//! let codec = CharCodec::new('a', 'b');
+//! let s = LetterCaseSteganographer::new();
+//! let public: Vec<char> = "It is a truth universally acknowledged, that a single man in possession of a good fortune".chars().collect();
+//! let watermark = Watermark::new(&['B', 'O', 'B'], &codec).unwrap();
+//! let marked = watermark.embed(&s, &public).unwrap();
+//! // An excerpt of the marked copy
+//! let detection = watermark.detect(&s, &marked[20..]).unwrap();
+//! assert!(detection.is_present());
+//! assert!(!Watermark::new(&['E', 'V', 'E'], &codec).unwrap().detect(&s, &marked).unwrap().is_present());
+//! ```
+use crate::{BaconCodec, errors};
+use crate::carrier::Carrier;
+use crate::errors::{BaconError, CapacityUnit};
+use crate::mapping::Mapping;
+
+/// An identifier that is embedded repeatedly in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watermark {
+    symbols: Vec<bool>,
+}
+
+impl Watermark {
+    /// Creates a `Watermark` of the identifier, encoded with the codec. Fails if the identifier cannot be encoded or
+    /// is empty.
+    pub fn new<AB>(identifier: &[char], codec: &dyn BaconCodec<ABTYPE=AB, CONTENT=char>) -> errors::Result<Watermark> {
+        BaconError::check_secret(identifier)?;
+        let symbols: Vec<bool> = codec.encode(identifier).iter().map(|elem| codec.is_a(elem)).collect();
+        if symbols.is_empty() {
+            Err(BaconError::GeneralError("The identifier of a watermark cannot be empty".to_string()))
+        } else {
+            Ok(Watermark { symbols })
+        }
+    }
+
+    /// The elements of the encoded identifier, which are true for the Bacon's element A.
+    pub fn symbols(&self) -> &[bool] {
+        &self.symbols
+    }
+
+    /// Writes the identifier repeatedly to all the carriers of the public input. Fails if the public input cannot carry
+    /// a single copy of it.
+    pub fn embed<C>(&self, carrier: &C, public: &[C::Unit]) -> errors::Result<Vec<C::Unit>>
+        where C: Carrier + ?Sized {
+        let available = carrier.capacity(public)?;
+        if available < self.symbols.len() {
+            return Err(BaconError::InsufficientCapacity { required: self.symbols.len(), available, unit: CapacityUnit::Carriers });
+        }
+        // The capacity of generating carriers is unlimited, so that they carry a single copy
+        let len = if available == usize::MAX { self.symbols.len() } else { available };
+        let symbols: Vec<bool> = self.symbols.iter().cloned().cycle().take(len).collect();
+        carrier.write_symbols(&symbols, public)
+    }
+
+    /// Reads the elements of the input and finds the complete copies of the identifier.
+    pub fn detect<C>(&self, carrier: &C, input: &[C::Unit]) -> errors::Result<Detection>
+        where C: Carrier + ?Sized {
+        let read = carrier.read_symbols(input)?;
+        let occurrences = read.windows(self.symbols.len())
+            .enumerate()
+            .filter(|(_, window)| *window == self.symbols.as_slice())
+            .map(|(index, _)| index)
+            .collect();
+        Ok(Detection { occurrences, elements: read.len() })
+    }
+}
+
+/// The copies of a [Watermark](struct.Watermark.html) that are found in an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    occurrences: Vec<usize>,
+    elements: usize,
+}
+
+impl Detection {
+    /// Returns true if at least one complete copy of the identifier is found.
+    pub fn is_present(&self) -> bool {
+        !self.occurrences.is_empty()
+    }
+
+    /// The indexes of the elements of the input at which the copies of the identifier start.
+    pub fn occurrences(&self) -> &[usize] {
+        &self.occurrences
+    }
+
+    /// The number of the elements that the input carries.
+    pub fn elements(&self) -> usize {
+        self.elements
+    }
+
+    /// The positions of the input at which the copies start, according to the [Mapping](../mapping/struct.Mapping.html)
+    /// of the input, like the one of [Steganographer::mapping](../trait.Steganographer.html#method.mapping).
+    pub fn locations(&self, mapping: &Mapping) -> Vec<usize> {
+        self.occurrences.iter()
+            .filter_map(|index| mapping.slots().get(*index))
+            .map(|slot| slot.position())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod watermark_tests {
+    use std::iter::FromIterator;
+
+    use crate::codecs::char_codec::CharCodec;
+    use crate::stega::letter_case::LetterCaseSteganographer;
+    use crate::Steganographer;
+
+    use super::*;
+
+    const PUBLIC: &str = "It is a truth universally acknowledged, that a single man in possession of a good fortune, \
+    must be in want of a wife.";
+
+    #[test]
+    fn embed_and_detect() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        let public = Vec::from_iter(PUBLIC.chars());
+        let watermark = Watermark::new(&['B', 'O', 'B'], &codec).unwrap();
+        let marked = watermark.embed(&s, &public).unwrap();
+        let detection = watermark.detect(&s, &marked).unwrap();
+        // 92 letters carry 6 copies of the 15 elements of the identifier
+        assert_eq!(detection.elements(), 92);
+        assert_eq!(detection.occurrences(), &[0, 15, 30, 45, 60, 75]);
+        let locations = detection.locations(&s.mapping(&marked).unwrap());
+        assert_eq!(locations[..2], [0, 19]);
+
+        // An excerpt that starts in the middle of a copy
+        let detection = watermark.detect(&s, &marked[4..60]).unwrap();
+        assert_eq!(detection.occurrences()[0], 12);
+        assert!(!watermark.detect(&s, &public).unwrap().is_present());
+    }
+
+    #[test]
+    fn invalid_watermarks() {
+        let codec = CharCodec::new('a', 'b');
+        let s = LetterCaseSteganographer::new();
+        assert!(Watermark::new(&[], &codec).is_err());
+        assert!(Watermark::new(&['4'], &codec).is_err());
+        let watermark = Watermark::new(&['B', 'O', 'B'], &codec).unwrap();
+        assert!(matches!(watermark.embed(&s, &Vec::from_iter("Too short".chars())),
+            Err(BaconError::InsufficientCapacity { required: 15, available: 8, .. })));
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2019 astonbitecode
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::io::Read;
+
+use crate::errors::{self, BaconError};
+
+/// A list of words, used by the steganographers and generators that produce text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordList {
+    words: Vec<String>,
+}
+
+impl WordList {
+    /// Creates a `WordList` from the given words. Words that are empty or contain non alphabetic characters are ignored.
+    pub fn new<S: AsRef<str>>(words: &[S]) -> WordList {
+        let mut list = WordList::default();
+        for word in words {
+            list.push(word.as_ref());
+        }
+        list
+    }
+
+    /// Parses a `WordList` from text.
+    ///
+    /// The words are separated by whitespace. Lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> WordList {
+        let mut list = WordList::default();
+        text.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.starts_with('#'))
+            .flat_map(|line| line.split_whitespace())
+            .for_each(|word| list.push(word));
+        list
+    }
+
+    /// Reads and parses a `WordList`. See [parse](struct.WordList.html#method.parse) for the format.
+    pub fn from_reader<R: Read>(mut reader: R) -> errors::Result<WordList> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)
+            .map_err(|error| BaconError::GeneralError(format!("Could not read the word list: {}", error)))?;
+        Ok(Self::parse(&text))
+    }
+
+    fn push(&mut self, word: &str) {
+        if !word.is_empty() && word.chars().all(|c| c.is_alphabetic()) && !self.words.iter().any(|w| w == word) {
+            self.words.push(word.to_string());
+        }
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the words that satisfy the given predicate.
+    pub fn filter<P: Fn(&str) -> bool>(&self, predicate: P) -> Vec<&str> {
+        self.words.iter()
+            .map(|word| word.as_str())
+            .filter(|word| predicate(word))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod wordlist_tests {
+    use super::*;
+
+    #[test]
+    fn parse_a_word_list() {
+        let list = WordList::parse("# Comment\nalpha beta\n gamma  beta\ndelta1\n");
+        assert_eq!(list.words(), &["alpha".to_string(), "beta".to_string(), "gamma".to_string()]);
+        assert_eq!(list.filter(|word| word.starts_with('g')), vec!["gamma"]);
+        assert_eq!(WordList::from_reader("one two".as_bytes()).unwrap().len(), 2);
+    }
+}